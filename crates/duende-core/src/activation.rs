@@ -0,0 +1,540 @@
+//! Socket activation - listening sockets bound before the daemon starts.
+//!
+//! Implements the `LISTEN_FDS` protocol of `sd_listen_fds(3)`: listeners
+//! are inherited as file descriptors 3, 4, ... with `LISTEN_FDS` giving
+//! their count, `LISTEN_FDNAMES` their colon-separated names and
+//! `LISTEN_PID` the process they are meant for. systemd and the native
+//! adapter both pass listeners this way, so a daemon reads them the same
+//! way under either.
+//!
+//! # Toyota Way: Heijunka (平準化)
+//! The listening socket belongs to the supervisor, not the daemon process.
+//! A restart swaps the process while clients keep queueing on the socket.
+
+use std::io;
+use std::net::{SocketAddr, TcpListener, UdpSocket};
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+use std::sync::OnceLock;
+
+use crate::config::{ListenAddress, SocketConfig};
+use crate::error::Result;
+
+/// First inherited listener file descriptor (`SD_LISTEN_FDS_START`).
+pub const LISTEN_FDS_START: i32 = 3;
+
+// =============================================================================
+// Listener
+// =============================================================================
+
+/// A listening socket.
+///
+/// Sockets are in blocking mode; call
+/// [`set_nonblocking`](Self::set_nonblocking) before handing one to an
+/// async runtime (e.g. `tokio::net::TcpListener::from_std`).
+#[derive(Debug)]
+pub enum Listener {
+    /// TCP stream listener.
+    Tcp(TcpListener),
+    /// UDP socket.
+    Udp(UdpSocket),
+    /// Unix stream listener.
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    /// Binds a listener on `addr`.
+    ///
+    /// A stale Unix socket file left behind by a previous run is replaced.
+    ///
+    /// # Errors
+    /// Returns an error if the address cannot be bound.
+    pub fn bind(addr: &ListenAddress) -> io::Result<Self> {
+        match addr {
+            ListenAddress::Tcp(addr) => TcpListener::bind(addr).map(Self::Tcp),
+            ListenAddress::Udp(addr) => UdpSocket::bind(addr).map(Self::Udp),
+            #[cfg(unix)]
+            ListenAddress::Unix(path) => {
+                use std::os::unix::fs::FileTypeExt;
+
+                if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+                    std::fs::remove_file(path)?;
+                }
+                UnixListener::bind(path).map(Self::Unix)
+            }
+            #[cfg(not(unix))]
+            ListenAddress::Unix(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unix sockets are not supported on this platform",
+            )),
+        }
+    }
+
+    /// Returns true if the listener is bound to `addr`.
+    ///
+    /// Port 0 matches any port: the kernel picked one at bind time.
+    #[must_use]
+    pub fn is_bound_to(&self, addr: &ListenAddress) -> bool {
+        let same = |bound: SocketAddr, addr: &SocketAddr| {
+            bound.ip() == addr.ip() && (addr.port() == 0 || bound.port() == addr.port())
+        };
+        match (self, addr) {
+            (Self::Tcp(l), ListenAddress::Tcp(addr)) => l.local_addr().is_ok_and(|a| same(a, addr)),
+            (Self::Udp(s), ListenAddress::Udp(addr)) => s.local_addr().is_ok_and(|a| same(a, addr)),
+            #[cfg(unix)]
+            (Self::Unix(l), ListenAddress::Unix(path)) => l
+                .local_addr()
+                .is_ok_and(|a| a.as_pathname() == Some(path.as_path())),
+            _ => false,
+        }
+    }
+
+    /// Creates an independent handle to the same socket.
+    ///
+    /// # Errors
+    /// Returns an error if the descriptor cannot be duplicated.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Self::Tcp(l) => l.try_clone().map(Self::Tcp),
+            Self::Udp(s) => s.try_clone().map(Self::Udp),
+            #[cfg(unix)]
+            Self::Unix(l) => l.try_clone().map(Self::Unix),
+        }
+    }
+
+    /// Moves the socket into or out of non-blocking mode.
+    ///
+    /// # Errors
+    /// Returns an error if the mode cannot be changed.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        match self {
+            Self::Tcp(l) => l.set_nonblocking(nonblocking),
+            Self::Udp(s) => s.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            Self::Unix(l) => l.set_nonblocking(nonblocking),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl std::os::fd::AsFd for Listener {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        match self {
+            Self::Tcp(l) => l.as_fd(),
+            Self::Udp(s) => s.as_fd(),
+            Self::Unix(l) => l.as_fd(),
+        }
+    }
+}
+
+// =============================================================================
+// Listeners
+// =============================================================================
+
+/// Named listening sockets handed to a daemon.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let Some(Listener::Tcp(http)) = ctx.listeners_mut().take("http") else {
+///     return Err(DaemonError::init("no http listener"));
+/// };
+/// http.set_nonblocking(true)?;
+/// let http = tokio::net::TcpListener::from_std(http)?;
+/// ```
+#[derive(Debug, Default)]
+pub struct Listeners {
+    /// Listeners in descriptor order, by name.
+    entries: Vec<(String, Listener)>,
+}
+
+impl Listeners {
+    /// Creates an empty set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds every configured socket.
+    ///
+    /// # Errors
+    /// Returns an error naming the first socket that cannot be bound.
+    pub fn bind(sockets: &[SocketConfig]) -> Result<Self> {
+        let mut listeners = Self::new();
+        for socket in sockets {
+            let listener = Listener::bind(&socket.listen).map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!(
+                        "failed to bind socket {} ({}): {e}",
+                        socket.name, socket.listen
+                    ),
+                )
+            })?;
+            listeners.push(socket.name.clone(), listener);
+        }
+        Ok(listeners)
+    }
+
+    /// Rebinds unless the set already matches `sockets` exactly.
+    ///
+    /// Keeps existing sockets across restarts; only a changed socket
+    /// configuration closes and rebinds them.
+    ///
+    /// # Errors
+    /// Returns an error if a socket cannot be bound.
+    pub fn ensure_bound(&mut self, sockets: &[SocketConfig]) -> Result<()> {
+        if self.matches(sockets) {
+            return Ok(());
+        }
+        // Close first so unchanged addresses can be bound again
+        self.entries.clear();
+        *self = Self::bind(sockets)?;
+        Ok(())
+    }
+
+    /// Returns true if the set holds exactly `sockets`, in order.
+    #[must_use]
+    pub fn matches(&self, sockets: &[SocketConfig]) -> bool {
+        self.entries.len() == sockets.len()
+            && self
+                .entries
+                .iter()
+                .zip(sockets)
+                .all(|((name, l), s)| *name == s.name && l.is_bound_to(&s.listen))
+    }
+
+    /// Adds a listener.
+    pub fn push(&mut self, name: impl Into<String>, listener: Listener) {
+        self.entries.push((name.into(), listener));
+    }
+
+    /// Returns the first listener with the given name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Listener> {
+        self.entries.iter().find(|(n, _)| n == name).map(|(_, l)| l)
+    }
+
+    /// Removes and returns the first listener with the given name.
+    pub fn take(&mut self, name: &str) -> Option<Listener> {
+        let index = self.entries.iter().position(|(n, _)| n == name)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Iterates over `(name, listener)` pairs in descriptor order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Listener)> {
+        self.entries.iter().map(|(n, l)| (n.as_str(), l))
+    }
+
+    /// Returns the listener names in descriptor order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(n, _)| n.as_str())
+    }
+
+    /// Returns the number of listeners.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no listeners.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Duplicates every listener.
+    ///
+    /// # Errors
+    /// Returns an error if a descriptor cannot be duplicated.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(n, l)| Ok((n.clone(), l.try_clone()?)))
+            .collect::<io::Result<_>>()?;
+        Ok(Self { entries })
+    }
+
+    /// Returns the `LISTEN_FDNAMES` value for this set.
+    #[must_use]
+    pub fn fd_names(&self) -> String {
+        self.names().collect::<Vec<_>>().join(":")
+    }
+
+    /// Names listeners after the configured socket bound to the same address.
+    ///
+    /// systemd passes a single `FileDescriptorName` per socket unit, so with
+    /// several sockets the names alone cannot tell them apart.
+    pub fn resolve_names(&mut self, sockets: &[SocketConfig]) {
+        for socket in sockets {
+            if self.get(&socket.name).is_some() {
+                continue;
+            }
+            let configured = |name: &str| sockets.iter().any(|s| s.name == name);
+            if let Some(entry) = self
+                .entries
+                .iter_mut()
+                .find(|(n, l)| !configured(n) && l.is_bound_to(&socket.listen))
+            {
+                entry.0.clone_from(&socket.name);
+            }
+        }
+    }
+
+    /// Returns the listeners inherited by this process.
+    ///
+    /// The environment is read once; later calls return the same set. Use
+    /// [`try_clone`](Self::try_clone) to get owned handles.
+    pub fn inherited() -> &'static Self {
+        static INHERITED: OnceLock<Listeners> = OnceLock::new();
+
+        INHERITED.get_or_init(|| match Self::from_env() {
+            Ok(listeners) => listeners,
+            Err(e) => {
+                tracing::warn!(error = %e, "ignoring inherited listeners");
+                Self::new()
+            }
+        })
+    }
+
+    /// Adopts listeners passed under the `LISTEN_FDS` protocol.
+    #[cfg(unix)]
+    fn from_env() -> io::Result<Self> {
+        let var = |key| std::env::var(key).ok();
+        let names = parse_listen_env(
+            var("LISTEN_PID").as_deref(),
+            var("LISTEN_FDS").as_deref(),
+            var("LISTEN_FDNAMES").as_deref(),
+            std::process::id(),
+        )?;
+
+        let mut listeners = Self::new();
+        for (fd, name) in (LISTEN_FDS_START..).zip(names) {
+            listeners.push(name, adopt_fd(fd)?);
+        }
+        Ok(listeners)
+    }
+
+    /// Socket activation is not available off Unix.
+    #[cfg(not(unix))]
+    #[allow(clippy::unnecessary_wraps)]
+    fn from_env() -> io::Result<Self> {
+        Ok(Self::new())
+    }
+}
+
+/// Parses the `LISTEN_*` variables into one name per inherited descriptor.
+///
+/// Returns no names when the variables are absent or meant for another
+/// process. Unnamed descriptors are called `unknown`, as by systemd.
+fn parse_listen_env(
+    listen_pid: Option<&str>,
+    listen_fds: Option<&str>,
+    listen_fdnames: Option<&str>,
+    pid: u32,
+) -> io::Result<Vec<String>> {
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+    let (Some(listen_pid), Some(listen_fds)) = (listen_pid, listen_fds) else {
+        return Ok(Vec::new());
+    };
+    let listen_pid: u32 = listen_pid
+        .parse()
+        .map_err(|_| invalid(format!("invalid LISTEN_PID: {listen_pid}")))?;
+    if listen_pid != pid {
+        return Ok(Vec::new());
+    }
+    let count: usize = listen_fds
+        .parse()
+        .map_err(|_| invalid(format!("invalid LISTEN_FDS: {listen_fds}")))?;
+
+    let mut names: Vec<String> = listen_fdnames
+        .filter(|n| !n.is_empty())
+        .map(|n| n.split(':').map(ToString::to_string).collect())
+        .unwrap_or_default();
+    if names.len() > count {
+        return Err(invalid(format!(
+            "LISTEN_FDNAMES has {} names for {count} descriptors",
+            names.len()
+        )));
+    }
+    names.resize(count, "unknown".to_string());
+    Ok(names)
+}
+
+/// Takes ownership of an inherited socket descriptor.
+#[cfg(unix)]
+#[allow(unsafe_code)]
+fn adopt_fd(fd: std::os::fd::RawFd) -> io::Result<Listener> {
+    use std::os::fd::{FromRawFd, OwnedFd};
+
+    let check = |ret: libc::c_int| {
+        if ret < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(ret)
+        }
+    };
+
+    let mut socket_type: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    // SAFETY: SO_TYPE writes a c_int into the buffer of the given length.
+    check(unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_TYPE,
+            (&raw mut socket_type).cast(),
+            &raw mut len,
+        )
+    })
+    .map_err(|e| io::Error::new(e.kind(), format!("inherited fd {fd} is not a socket: {e}")))?;
+
+    // SAFETY: sockaddr_storage is valid when zeroed and large enough for
+    // any address family; getsockname writes at most `len` bytes.
+    let mut addr: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    check(unsafe { libc::getsockname(fd, (&raw mut addr).cast(), &raw mut len) })?;
+
+    // Not inherited by our own children
+    // SAFETY: fcntl on a descriptor we are about to own.
+    check(unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) })?;
+
+    // SAFETY: LISTEN_PID names this process, so the descriptor was passed
+    // to us and nothing else in the process owns it; adoption runs once.
+    let owned = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    match (i32::from(addr.ss_family), socket_type) {
+        (libc::AF_UNIX, libc::SOCK_STREAM) => Ok(Listener::Unix(owned.into())),
+        (libc::AF_INET | libc::AF_INET6, libc::SOCK_STREAM) => Ok(Listener::Tcp(owned.into())),
+        (libc::AF_INET | libc::AF_INET6, libc::SOCK_DGRAM) => Ok(Listener::Udp(owned.into())),
+        (family, socket_type) => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("inherited fd {fd} has unsupported family {family} / type {socket_type}"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loopback() -> SocketAddr {
+        "127.0.0.1:0".parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_listen_env() {
+        let names = parse_listen_env(Some("42"), Some("3"), Some("http:admin"), 42).unwrap();
+        assert_eq!(names, ["http", "admin", "unknown"]);
+
+        let names = parse_listen_env(Some("42"), Some("1"), None, 42).unwrap();
+        assert_eq!(names, ["unknown"]);
+    }
+
+    #[test]
+    fn test_parse_listen_env_other_process() {
+        assert!(
+            parse_listen_env(Some("7"), Some("2"), None, 42)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            parse_listen_env(None, Some("2"), None, 42)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(parse_listen_env(None, None, None, 42).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_listen_env_invalid() {
+        assert!(parse_listen_env(Some("x"), Some("1"), None, 42).is_err());
+        assert!(parse_listen_env(Some("42"), Some("-1"), None, 42).is_err());
+        assert!(parse_listen_env(Some("42"), Some("1"), Some("a:b"), 42).is_err());
+    }
+
+    #[test]
+    fn test_bind_and_lookup() {
+        let sockets = [
+            SocketConfig::new("http", ListenAddress::Tcp(loopback())),
+            SocketConfig::new("stats", ListenAddress::Udp(loopback())),
+        ];
+        let mut listeners = Listeners::bind(&sockets).unwrap();
+
+        assert_eq!(listeners.len(), 2);
+        assert_eq!(listeners.fd_names(), "http:stats");
+        assert!(matches!(listeners.get("http"), Some(Listener::Tcp(_))));
+        assert!(matches!(listeners.take("stats"), Some(Listener::Udp(_))));
+        assert!(listeners.get("stats").is_none());
+    }
+
+    #[test]
+    fn test_ensure_bound_keeps_matching_sockets() {
+        let mut listeners =
+            Listeners::bind(&[SocketConfig::new("http", ListenAddress::Tcp(loopback()))]).unwrap();
+        let Some(Listener::Tcp(l)) = listeners.get("http") else {
+            panic!("expected tcp listener");
+        };
+        let bound = l.local_addr().unwrap();
+
+        // Same address: the socket is kept
+        let sockets = [SocketConfig::new("http", ListenAddress::Tcp(bound))];
+        assert!(listeners.matches(&sockets));
+        listeners.ensure_bound(&sockets).unwrap();
+        let Some(Listener::Tcp(l)) = listeners.get("http") else {
+            panic!("expected tcp listener");
+        };
+        assert_eq!(l.local_addr().unwrap(), bound);
+
+        // Changed configuration: rebound
+        let sockets = [SocketConfig::new("api", ListenAddress::Tcp(loopback()))];
+        listeners.ensure_bound(&sockets).unwrap();
+        assert_eq!(listeners.fd_names(), "api");
+    }
+
+    #[test]
+    fn test_resolve_names_by_address() {
+        let mut listeners = Listeners::new();
+        listeners.push(
+            "svc.socket",
+            Listener::bind(&ListenAddress::Tcp(loopback())).unwrap(),
+        );
+        let Some(Listener::Tcp(l)) = listeners.get("svc.socket") else {
+            panic!("expected tcp listener");
+        };
+        let bound = l.local_addr().unwrap();
+
+        listeners.resolve_names(&[
+            SocketConfig::new("other", ListenAddress::Udp(loopback())),
+            SocketConfig::new("http", ListenAddress::Tcp(bound)),
+        ]);
+        assert_eq!(listeners.fd_names(), "http");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_listener_replaces_stale_socket() {
+        let path = std::env::temp_dir().join(format!("duende-{}.sock", crate::DaemonId::new()));
+        let addr = ListenAddress::Unix(path.clone());
+
+        let first = Listener::bind(&addr).unwrap();
+        assert!(first.is_bound_to(&addr));
+        drop(first);
+
+        // The socket file outlives the listener; binding again must succeed
+        let second = Listener::bind(&addr).unwrap();
+        let clone = second.try_clone().unwrap();
+        assert!(clone.is_bound_to(&addr));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_inherited_without_environment() {
+        // The test runner is not socket-activated
+        assert!(Listeners::inherited().is_empty());
+    }
+}
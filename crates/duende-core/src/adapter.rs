@@ -11,6 +11,7 @@ use std::time::Duration;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+use crate::config::DaemonConfig;
 use crate::daemon::Daemon;
 use crate::platform::Platform;
 use crate::types::{DaemonId, DaemonStatus, Signal};
@@ -187,6 +188,11 @@ pub enum HandleData {
     Native {
         /// Process ID.
         pid: u32,
+        /// Start time of the process in clock ticks since boot
+        /// (`/proc/<pid>/stat` field 22), if known. Tells the process
+        /// apart from a later one that reuses its PID.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start_time: Option<u64>,
     },
 }
 
@@ -261,8 +267,25 @@ impl DaemonHandle {
         Self {
             id,
             platform: Platform::Native,
-            handle_data: HandleData::Native { pid },
+            handle_data: HandleData::Native {
+                pid,
+                start_time: None,
+            },
+        }
+    }
+
+    /// Records the start time of a native process; see
+    /// [`HandleData::Native`]. Other handles are returned unchanged.
+    #[must_use]
+    pub const fn with_start_time(mut self, start_time: Option<u64>) -> Self {
+        if let HandleData::Native {
+            start_time: ref mut recorded,
+            ..
+        } = self.handle_data
+        {
+            *recorded = start_time;
         }
+        self
     }
 
     /// Returns the daemon ID.
@@ -314,7 +337,16 @@ impl DaemonHandle {
     #[must_use]
     pub fn pid(&self) -> Option<u32> {
         match &self.handle_data {
-            HandleData::Wos { pid } | HandleData::Native { pid } => Some(*pid),
+            HandleData::Wos { pid } | HandleData::Native { pid, .. } => Some(*pid),
+            _ => None,
+        }
+    }
+
+    /// Returns the start time of a native process, if recorded.
+    #[must_use]
+    pub const fn start_time(&self) -> Option<u64> {
+        match self.handle_data {
+            HandleData::Native { start_time, .. } => start_time,
             _ => None,
         }
     }
@@ -344,7 +376,7 @@ impl fmt::Display for DaemonHandle {
                 write!(f, "pepita:{}@cid{}", vm_id, vsock_cid)
             }
             HandleData::Wos { pid } => write!(f, "wos:pid{}", pid),
-            HandleData::Native { pid } => write!(f, "native:pid{}", pid),
+            HandleData::Native { pid, .. } => write!(f, "native:pid{}", pid),
         }
     }
 }
@@ -437,8 +469,10 @@ impl TracerHandle {
 /// # Toyota Way: Standardized Work
 /// Every platform implements the same lifecycle contract:
 /// - spawn: Create and start daemon
+/// - spawn_with_config: Create and start daemon from its configuration
 /// - signal: Send signal to daemon
 /// - status: Query daemon status
+/// - reattach: Resume control of a daemon spawned by an earlier process
 /// - attach_tracer: Attach renacer tracer
 #[async_trait]
 pub trait PlatformAdapter: Send + Sync {
@@ -451,6 +485,20 @@ pub trait PlatformAdapter: Send + Sync {
     /// Returns an error if the daemon cannot be spawned.
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> PlatformResult<DaemonHandle>;
 
+    /// Spawns a daemon on this platform using its configuration.
+    ///
+    /// Unlike [`spawn`](Self::spawn), which only knows the daemon's identity,
+    /// this honors `binary_path`, `args`, `env`, `working_dir`, `user`/`group`
+    /// and `resources` from the config, so the real daemon binary is executed.
+    ///
+    /// # Errors
+    /// Returns an error if the daemon cannot be spawned.
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> PlatformResult<DaemonHandle>;
+
     /// Sends a signal to a daemon.
     ///
     /// # Errors
@@ -463,6 +511,21 @@ pub trait PlatformAdapter: Send + Sync {
     /// Returns an error if the status cannot be determined.
     async fn status(&self, handle: &DaemonHandle) -> PlatformResult<DaemonStatus>;
 
+    /// Resumes control of a daemon spawned by an earlier process.
+    ///
+    /// Used after a manager restart with a persisted handle. Returns the
+    /// daemon's current status; once reattached, the handle can be used
+    /// with the other methods again. The default queries
+    /// [`status`](Self::status), which suffices for platforms whose state
+    /// lives outside this process (systemd units, launchd labels,
+    /// containers).
+    ///
+    /// # Errors
+    /// Returns an error if the status cannot be determined.
+    async fn reattach(&self, handle: &DaemonHandle) -> PlatformResult<DaemonStatus> {
+        self.status(handle).await
+    }
+
     /// Attaches a tracer to a running daemon.
     ///
     /// # Errors
@@ -705,7 +768,10 @@ mod tests {
                 vsock_cid: 1,
             },
             HandleData::Wos { pid: 1 },
-            HandleData::Native { pid: 1 },
+            HandleData::Native {
+                pid: 1,
+                start_time: Some(1),
+            },
         ];
 
         for variant in variants {
@@ -3,6 +3,7 @@
 //! Provides daemon management via container runtimes (Docker, Podman, containerd).
 
 use crate::adapter::{DaemonHandle, PlatformAdapter, PlatformError, PlatformResult, TracerHandle};
+use crate::config::{DaemonConfig, RestartPolicy};
 use crate::daemon::Daemon;
 use crate::platform::Platform;
 use crate::types::{DaemonStatus, FailureReason, Signal};
@@ -117,6 +118,114 @@ impl ContainerAdapter {
         format!("duende-{}", daemon_name.replace(' ', "-").replace('_', "-"))
     }
 
+    /// Builds `run` arguments for the configured runtime from the config.
+    ///
+    /// The image comes from `platform.container_image`, falling back to the
+    /// adapter's default image.
+    fn run_args(&self, container_name: &str, config: &DaemonConfig) -> Vec<String> {
+        let res = &config.resources;
+        let image = config
+            .platform
+            .container_image
+            .as_deref()
+            .unwrap_or(&self.default_image);
+
+        // Sorted for deterministic argument order
+        let mut env: Vec<_> = config.env.iter().collect();
+        env.sort();
+
+        let user = config.user.as_ref().map(|user| match config.group {
+            Some(ref group) => format!("{}:{}", user, group),
+            None => user.clone(),
+        });
+
+        let mut args = vec!["run".to_string(), "-d".to_string()];
+
+        match self.runtime {
+            ContainerRuntime::Docker | ContainerRuntime::Podman => {
+                args.push("--name".to_string());
+                args.push(container_name.to_string());
+                args.push("--restart".to_string());
+                args.push(Self::restart_value(config.restart).to_string());
+
+                for (key, value) in env {
+                    args.push("-e".to_string());
+                    args.push(format!("{}={}", key, value));
+                }
+                if let Some(ref dir) = config.working_dir {
+                    args.push("-w".to_string());
+                    args.push(dir.display().to_string());
+                }
+                if let Some(user) = user {
+                    args.push("--user".to_string());
+                    args.push(user);
+                }
+                if res.memory_bytes > 0 {
+                    args.push(format!("--memory={}", res.memory_bytes));
+                    args.push(format!(
+                        "--memory-swap={}",
+                        res.memory_swap_bytes.max(res.memory_bytes)
+                    ));
+                }
+                if res.cpu_quota_percent > 0.0 {
+                    args.push(format!("--cpus={}", res.cpu_quota_percent / 100.0));
+                }
+                args.push(format!("--cpu-shares={}", res.cpu_shares));
+                if res.pids_max > 0 {
+                    args.push(format!("--pids-limit={}", res.pids_max));
+                }
+                if res.open_files_max > 0 {
+                    args.push(format!(
+                        "--ulimit=nofile={}:{}",
+                        res.open_files_max, res.open_files_max
+                    ));
+                }
+                if res.lock_memory {
+                    args.push("--cap-add=IPC_LOCK".to_string());
+                    args.push("--ulimit=memlock=-1:-1".to_string());
+                }
+                args.push(image.to_string());
+            }
+            ContainerRuntime::Containerd => {
+                // containerd uses ctr with different syntax: image, then ID
+                for (key, value) in env {
+                    args.push("--env".to_string());
+                    args.push(format!("{}={}", key, value));
+                }
+                if let Some(ref dir) = config.working_dir {
+                    args.push("--cwd".to_string());
+                    args.push(dir.display().to_string());
+                }
+                if let Some(user) = user {
+                    args.push("--user".to_string());
+                    args.push(user);
+                }
+                if res.memory_bytes > 0 {
+                    args.push(format!("--memory-limit={}", res.memory_bytes));
+                }
+                if res.cpu_quota_percent > 0.0 {
+                    args.push(format!("--cpus={}", res.cpu_quota_percent / 100.0));
+                }
+                args.push(image.to_string());
+                args.push(container_name.to_string());
+            }
+        }
+
+        args.push(config.binary_path.display().to_string());
+        args.extend(config.args.iter().cloned());
+        args
+    }
+
+    /// Maps a config restart policy to the docker/podman `--restart` value.
+    const fn restart_value(policy: RestartPolicy) -> &'static str {
+        match policy {
+            RestartPolicy::Never => "no",
+            RestartPolicy::OnFailure => "on-failure:5",
+            RestartPolicy::Always => "always",
+            RestartPolicy::UnlessStopped => "unless-stopped",
+        }
+    }
+
     /// Maps Signal to container kill signal name.
     fn signal_name(sig: Signal) -> &'static str {
         match sig {
@@ -210,37 +319,23 @@ impl PlatformAdapter for ContainerAdapter {
     }
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> PlatformResult<DaemonHandle> {
-        let daemon_name = daemon.name().to_string();
+        // Without a config there is no command to run; keep the container
+        // alive with a placeholder loop. Use `spawn_with_config` for real daemons.
+        let mut config = DaemonConfig::new(daemon.name(), "/bin/sh");
+        config.args = vec!["-c".to_string(), "while true; do sleep 1; done".to_string()];
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> PlatformResult<DaemonHandle> {
         let daemon_id = daemon.id();
-        let container_name = Self::container_name(&daemon_name);
+        let container_name = Self::container_name(daemon.name());
 
-        // Build container run command
         let mut cmd = Command::new(self.runtime.command());
-
-        match self.runtime {
-            ContainerRuntime::Docker | ContainerRuntime::Podman => {
-                cmd.arg("run")
-                    .arg("-d") // detached
-                    .arg("--name")
-                    .arg(&container_name)
-                    .arg("--restart")
-                    .arg("on-failure:5")
-                    .arg(&self.default_image)
-                    .arg("/bin/sh")
-                    .arg("-c")
-                    .arg("while true; do sleep 1; done"); // placeholder command
-            }
-            ContainerRuntime::Containerd => {
-                // containerd uses ctr with different syntax
-                cmd.arg("run")
-                    .arg("-d")
-                    .arg(&self.default_image)
-                    .arg(&container_name)
-                    .arg("/bin/sh")
-                    .arg("-c")
-                    .arg("while true; do sleep 1; done");
-            }
-        }
+        cmd.args(self.run_args(&container_name, config));
 
         let output = cmd.output().await.map_err(|e| {
             PlatformError::spawn_failed(format!(
@@ -570,4 +665,59 @@ mod tests {
         assert_eq!(adapter.runtime(), ContainerRuntime::Podman);
         assert_eq!(adapter.default_image(), "ubuntu:22.04");
     }
+
+    #[test]
+    fn test_run_args_docker_from_config() {
+        let adapter = ContainerAdapter::docker();
+        let mut config = DaemonConfig::new("api", "/usr/bin/api-server");
+        config.args = vec!["--port".to_string(), "8080".to_string()];
+        config
+            .env
+            .insert("RUST_LOG".to_string(), "info".to_string());
+        config.working_dir = Some("/srv/api".into());
+        config.user = Some("api".to_string());
+        config.group = Some("www".to_string());
+        config.platform.container_image = Some("api:1.2".to_string());
+
+        let args = adapter.run_args("duende-api", &config);
+        let has = |s: &str| args.iter().any(|a| a == s);
+
+        assert!(has("RUST_LOG=info"));
+        assert!(has("/srv/api"));
+        assert!(has("api:www"));
+        assert!(has("--memory=536870912"));
+        assert!(has("--cpus=1"));
+        assert!(has("--pids-limit=100"));
+        assert!(has("--ulimit=nofile=1024:1024"));
+        assert!(!has("--cap-add=IPC_LOCK"));
+
+        // Image, then the command and its arguments
+        let image = args.iter().position(|a| a == "api:1.2").unwrap();
+        assert_eq!(
+            &args[image + 1..],
+            ["/usr/bin/api-server", "--port", "8080"]
+        );
+    }
+
+    #[test]
+    fn test_run_args_containerd_uses_default_image() {
+        let adapter = ContainerAdapter::containerd();
+        let config = DaemonConfig::new("api", "/usr/bin/api-server");
+
+        let args = adapter.run_args("duende-api", &config);
+        let image = args
+            .iter()
+            .position(|a| a == "docker.io/library/alpine:latest")
+            .unwrap();
+        assert_eq!(&args[image + 1..], ["duende-api", "/usr/bin/api-server"]);
+    }
+
+    #[test]
+    fn test_restart_value() {
+        assert_eq!(ContainerAdapter::restart_value(RestartPolicy::Never), "no");
+        assert_eq!(
+            ContainerAdapter::restart_value(RestartPolicy::UnlessStopped),
+            "unless-stopped"
+        );
+    }
 }
@@ -3,6 +3,7 @@
 //! Provides daemon management via launchd plist files and launchctl.
 
 use crate::adapter::{DaemonHandle, PlatformAdapter, PlatformError, PlatformResult, TracerHandle};
+use crate::config::DaemonConfig;
 use crate::daemon::Daemon;
 use crate::platform::Platform;
 use crate::types::{DaemonStatus, FailureReason, Signal};
@@ -121,8 +122,86 @@ impl LaunchdAdapter {
         self.plist_dir.join(format!("{}.plist", label))
     }
 
-    /// Generates a plist XML for a daemon.
-    fn generate_plist(label: &str, daemon_name: &str, program: &str) -> String {
+    /// Generates a plist XML for a daemon from its configuration.
+    ///
+    /// Maps `binary_path`/`args` to `ProgramArguments`, `env` to
+    /// `EnvironmentVariables`, `working_dir`, `user`/`group`, and resource
+    /// limits to `SoftResourceLimits`/`HardResourceLimits`.
+    fn generate_plist(label: &str, config: &DaemonConfig) -> String {
+        let daemon_name = xml_escape(&config.name);
+        let res = &config.resources;
+
+        let mut program_args = format!(
+            "        <string>{}</string>\n",
+            xml_escape(&config.binary_path.display().to_string())
+        );
+        for arg in &config.args {
+            program_args.push_str(&format!("        <string>{}</string>\n", xml_escape(arg)));
+        }
+
+        let mut extra = String::new();
+
+        if !config.env.is_empty() {
+            // Sorted for deterministic output
+            let mut env: Vec<_> = config.env.iter().collect();
+            env.sort();
+            extra.push_str("    <key>EnvironmentVariables</key>\n    <dict>\n");
+            for (key, value) in env {
+                extra.push_str(&format!(
+                    "        <key>{}</key>\n        <string>{}</string>\n",
+                    xml_escape(key),
+                    xml_escape(value)
+                ));
+            }
+            extra.push_str("    </dict>\n");
+        }
+        if let Some(ref dir) = config.working_dir {
+            extra.push_str(&format!(
+                "    <key>WorkingDirectory</key>\n    <string>{}</string>\n",
+                xml_escape(&dir.display().to_string())
+            ));
+        }
+        if let Some(ref user) = config.user {
+            extra.push_str(&format!(
+                "    <key>UserName</key>\n    <string>{}</string>\n",
+                xml_escape(user)
+            ));
+        }
+        if let Some(ref group) = config.group {
+            extra.push_str(&format!(
+                "    <key>GroupName</key>\n    <string>{}</string>\n",
+                xml_escape(group)
+            ));
+        }
+
+        let mut limits = String::new();
+        if res.memory_bytes > 0 {
+            limits.push_str(&format!(
+                "        <key>ResidentSetSize</key>\n        <integer>{}</integer>\n",
+                res.memory_bytes
+            ));
+        }
+        if res.open_files_max > 0 {
+            limits.push_str(&format!(
+                "        <key>NumberOfFiles</key>\n        <integer>{}</integer>\n",
+                res.open_files_max
+            ));
+        }
+        if res.pids_max > 0 {
+            limits.push_str(&format!(
+                "        <key>NumberOfProcesses</key>\n        <integer>{}</integer>\n",
+                res.pids_max
+            ));
+        }
+        if !limits.is_empty() {
+            extra.push_str(&format!(
+                "    <key>SoftResourceLimits</key>\n    <dict>\n{limits}    </dict>\n"
+            ));
+            extra.push_str(&format!(
+                "    <key>HardResourceLimits</key>\n    <dict>\n{limits}    </dict>\n"
+            ));
+        }
+
         format!(
             r#"<?xml version="1.0" encoding="UTF-8"?>
 <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
@@ -132,9 +211,8 @@ impl LaunchdAdapter {
     <string>{label}</string>
     <key>ProgramArguments</key>
     <array>
-        <string>{program}</string>
-    </array>
-    <key>RunAtLoad</key>
+{program_args}    </array>
+{extra}    <key>RunAtLoad</key>
     <true/>
     <key>KeepAlive</key>
     <dict>
@@ -143,6 +221,8 @@ impl LaunchdAdapter {
     </dict>
     <key>ThrottleInterval</key>
     <integer>5</integer>
+    <key>ExitTimeOut</key>
+    <integer>{exit_timeout}</integer>
     <key>StandardOutPath</key>
     <string>/tmp/duende-{daemon_name}.out.log</string>
     <key>StandardErrorPath</key>
@@ -150,8 +230,10 @@ impl LaunchdAdapter {
 </dict>
 </plist>
 "#,
-            label = label,
-            program = program,
+            label = xml_escape(label),
+            program_args = program_args,
+            extra = extra,
+            exit_timeout = config.shutdown_timeout.as_secs().max(1),
             daemon_name = daemon_name
         )
     }
@@ -204,6 +286,15 @@ impl LaunchdAdapter {
     }
 }
 
+/// Escapes text for inclusion in plist XML.
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
 impl Default for LaunchdAdapter {
     fn default() -> Self {
         Self::new()
@@ -217,6 +308,17 @@ impl PlatformAdapter for LaunchdAdapter {
     }
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> PlatformResult<DaemonHandle> {
+        // Without a config there is no program to run; use /usr/bin/true as a
+        // placeholder. Use `spawn_with_config` to run the real daemon.
+        let config = DaemonConfig::new(daemon.name(), "/usr/bin/true");
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> PlatformResult<DaemonHandle> {
         let daemon_name = daemon.name().to_string();
         let daemon_id = daemon.id();
         let label = Self::service_label(&daemon_name);
@@ -230,8 +332,7 @@ impl PlatformAdapter for LaunchdAdapter {
             })?;
 
         // Generate and write plist file
-        // For now, use /bin/true as placeholder - real implementation would use daemon config
-        let plist_content = Self::generate_plist(&label, &daemon_name, "/usr/bin/true");
+        let plist_content = Self::generate_plist(&label, config);
         tokio::fs::write(&plist_path, &plist_content)
             .await
             .map_err(|e| {
@@ -453,12 +554,32 @@ mod tests {
 
     #[test]
     fn test_plist_generation() {
-        let plist = LaunchdAdapter::generate_plist("com.duende.test", "test", "/usr/bin/test");
+        let config = DaemonConfig::new("test", "/usr/bin/test");
+        let plist = LaunchdAdapter::generate_plist("com.duende.test", &config);
         assert!(plist.contains("com.duende.test"));
         assert!(plist.contains("/usr/bin/test"));
         assert!(plist.contains("KeepAlive"));
     }
 
+    #[test]
+    fn test_plist_generation_from_config() {
+        let mut config = DaemonConfig::new("api", "/usr/local/bin/api");
+        config.args = vec!["--port".to_string(), "8080".to_string()];
+        config.env.insert("MODE".to_string(), "a&b".to_string());
+        config.working_dir = Some(PathBuf::from("/srv/api"));
+        config.user = Some("_api".to_string());
+
+        let plist = LaunchdAdapter::generate_plist("com.duende.api", &config);
+        assert!(plist.contains("<string>--port</string>"));
+        assert!(plist.contains("<string>8080</string>"));
+        assert!(plist.contains("<key>MODE</key>"));
+        assert!(plist.contains("<string>a&amp;b</string>"));
+        assert!(plist.contains("<key>WorkingDirectory</key>"));
+        assert!(plist.contains("<key>UserName</key>"));
+        assert!(plist.contains("<key>SoftResourceLimits</key>"));
+        assert!(plist.contains("<key>NumberOfFiles</key>"));
+    }
+
     #[test]
     fn test_domain_target_system() {
         assert_eq!(LaunchdDomain::System.target(), "system");
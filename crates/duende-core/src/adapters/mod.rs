@@ -24,6 +24,8 @@ pub use container::{ContainerAdapter, ContainerRuntime};
 #[cfg(target_os = "macos")]
 pub use launchd::{LaunchdAdapter as LaunchdAdapterImpl, LaunchdDomain};
 pub use native::NativeAdapter;
+#[cfg(unix)]
+pub use native::{apply_credentials, apply_resource_limits};
 pub use pepita::PepitaAdapter;
 #[cfg(target_os = "linux")]
 pub use systemd::SystemdAdapter as SystemdAdapterImpl;
@@ -50,6 +52,7 @@ pub use systemd::SystemdAdapter;
 #[cfg(not(target_os = "linux"))]
 mod systemd_stub {
     use super::*;
+    use crate::config::DaemonConfig;
 
     /// Linux systemd adapter stub (non-Linux platforms).
     ///
@@ -97,6 +100,14 @@ mod systemd_stub {
             Err(PlatformError::not_supported(Platform::Linux, "spawn"))
         }
 
+        async fn spawn_with_config(
+            &self,
+            _daemon: Box<dyn Daemon>,
+            _config: &DaemonConfig,
+        ) -> PlatformResult<DaemonHandle> {
+            Err(PlatformError::not_supported(Platform::Linux, "spawn"))
+        }
+
         async fn signal(&self, _handle: &DaemonHandle, _sig: Signal) -> PlatformResult<()> {
             Err(PlatformError::not_supported(Platform::Linux, "signal"))
         }
@@ -129,6 +140,7 @@ pub use launchd::LaunchdAdapter;
 #[cfg(not(target_os = "macos"))]
 mod launchd_stub {
     use super::*;
+    use crate::config::DaemonConfig;
 
     /// macOS launchd adapter stub (non-macOS platforms).
     ///
@@ -176,6 +188,14 @@ mod launchd_stub {
             Err(PlatformError::not_supported(Platform::MacOS, "spawn"))
         }
 
+        async fn spawn_with_config(
+            &self,
+            _daemon: Box<dyn Daemon>,
+            _config: &DaemonConfig,
+        ) -> PlatformResult<DaemonHandle> {
+            Err(PlatformError::not_supported(Platform::MacOS, "spawn"))
+        }
+
         async fn signal(&self, _handle: &DaemonHandle, _sig: Signal) -> PlatformResult<()> {
             Err(PlatformError::not_supported(Platform::MacOS, "signal"))
         }
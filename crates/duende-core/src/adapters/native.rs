@@ -4,32 +4,150 @@
 //! Unix-like system without requiring systemd, launchd, or containers.
 
 use std::collections::HashMap;
+#[cfg(unix)]
+use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 
+#[cfg(unix)]
+use crate::activation::LISTEN_FDS_START;
+use crate::activation::Listeners;
 use crate::adapter::{DaemonHandle, PlatformAdapter, PlatformError, PlatformResult, TracerHandle};
+use crate::config::{DaemonConfig, ResourceConfig};
 use crate::daemon::Daemon;
+#[cfg(unix)]
+use crate::notify::{NotifyListener, NotifyState};
 use crate::platform::Platform;
+use crate::trace::TraceContext;
 use crate::types::{DaemonId, DaemonStatus, FailureReason, Signal};
 
 /// Native process adapter.
 ///
 /// Uses fork/exec to spawn daemon processes. This is the fallback adapter
 /// when no platform-specific integration is available.
+///
+/// Sockets declared in the config are bound by the adapter and passed to
+/// the process under the `LISTEN_FDS` protocol. They stay open across
+/// respawns of the same daemon until [`close_listeners`](Self::close_listeners).
 pub struct NativeAdapter {
     /// Running processes indexed by daemon ID.
     processes: Arc<Mutex<HashMap<DaemonId, ProcessState>>>,
+    /// Listening sockets held for socket-activated daemons.
+    listeners: Mutex<HashMap<DaemonId, Listeners>>,
 }
 
 /// State for a running native process.
 struct ProcessState {
-    /// The child process handle.
-    child: Child,
+    /// The child process handle; `None` for a reattached process.
+    child: Option<Child>,
+    /// Process ID.
+    pid: u32,
+    /// Start time of the process, to tell it from a reused PID.
+    start_time: Option<u64>,
+    /// Notify socket of a spawned process.
+    notify: Option<NotifyChannel>,
+}
+
+/// Notifications received from a native process.
+#[derive(Debug, Default)]
+struct NotifyRecord {
+    /// `READY=1` received.
+    ready: bool,
+    /// Last `STATUS=` line.
+    status: Option<String>,
+    /// Last `WATCHDOG=1` ping.
+    last_watchdog: Option<Instant>,
+    /// The watchdog deadline was missed and the process aborted.
+    watchdog_expired: bool,
+}
+
+/// Notify socket of a native process; stops listening when dropped.
+struct NotifyChannel {
+    /// The process stays `Starting` until it sends `READY=1`.
+    awaits_ready: bool,
+    /// What the process reported.
+    record: Arc<Mutex<NotifyRecord>>,
+    /// Receives notifications; owns the listener.
+    task: JoinHandle<()>,
+    /// Aborts the process when it misses its watchdog deadline.
+    watchdog: Option<JoinHandle<()>>,
+}
+
+#[cfg(unix)]
+impl NotifyChannel {
+    /// Binds a notify socket for the daemon and starts recording.
+    fn bind(id: DaemonId, awaits_ready: bool) -> std::io::Result<(Self, PathBuf)> {
+        let listener = NotifyListener::for_daemon(id)?;
+        let path = listener.path().to_path_buf();
+        let record = Arc::new(Mutex::new(NotifyRecord::default()));
+
+        let task = tokio::spawn({
+            let record = Arc::clone(&record);
+            async move {
+                while let Ok(states) = listener.recv().await {
+                    let mut record = record.lock().await;
+                    for state in states {
+                        match state {
+                            NotifyState::Ready => record.ready = true,
+                            NotifyState::Status(status) => record.status = Some(status),
+                            NotifyState::Watchdog => record.last_watchdog = Some(Instant::now()),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        });
+
+        let channel = Self {
+            awaits_ready,
+            record,
+            task,
+            watchdog: None,
+        };
+        Ok((channel, path))
+    }
+
+    /// Aborts `pid` with SIGABRT, as systemd, once it goes `interval`
+    /// without a `WATCHDOG=1` ping. Spawn time counts as the first ping.
+    fn enforce_watchdog(&mut self, pid: u32, interval: Duration) {
+        use nix::sys::signal::{Signal as NixSignal, kill};
+        use nix::unistd::Pid;
+
+        let record = Arc::clone(&self.record);
+        let started = Instant::now();
+
+        self.watchdog = Some(tokio::spawn(async move {
+            loop {
+                let last = record.lock().await.last_watchdog.unwrap_or(started);
+                let expires = last + interval;
+                if Instant::now() < expires {
+                    tokio::time::sleep_until(expires.into()).await;
+                    continue;
+                }
+
+                tracing::error!(pid = pid, "watchdog deadline missed; aborting process");
+                record.lock().await.watchdog_expired = true;
+                #[allow(clippy::cast_possible_wrap)] // PID always fits in i32 on Unix
+                let _ = kill(Pid::from_raw(pid as i32), NixSignal::SIGABRT);
+                return;
+            }
+        }));
+    }
+}
+
+impl Drop for NotifyChannel {
+    fn drop(&mut self) {
+        self.task.abort();
+        if let Some(ref watchdog) = self.watchdog {
+            watchdog.abort();
+        }
+    }
 }
 
 impl NativeAdapter {
@@ -38,6 +156,7 @@ impl NativeAdapter {
     pub fn new() -> Self {
         Self {
             processes: Arc::new(Mutex::new(HashMap::new())),
+            listeners: Mutex::new(HashMap::new()),
         }
     }
 
@@ -45,6 +164,43 @@ impl NativeAdapter {
     pub async fn process_count(&self) -> usize {
         self.processes.lock().await.len()
     }
+
+    /// Returns the names of the listening sockets held for a daemon.
+    pub async fn listener_names(&self, id: DaemonId) -> Vec<String> {
+        self.listeners
+            .lock()
+            .await
+            .get(&id)
+            .map(|l| l.names().map(ToString::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the last `STATUS=` line a daemon sent.
+    pub async fn status_text(&self, id: DaemonId) -> Option<String> {
+        let record = self.notify_record(id).await?;
+        record.lock().await.status.clone()
+    }
+
+    /// Returns when a daemon last pinged its watchdog.
+    pub async fn last_watchdog(&self, id: DaemonId) -> Option<Instant> {
+        let record = self.notify_record(id).await?;
+        record.lock().await.last_watchdog
+    }
+
+    /// Returns the notification record of a running daemon.
+    async fn notify_record(&self, id: DaemonId) -> Option<Arc<Mutex<NotifyRecord>>> {
+        let processes = self.processes.lock().await;
+        let channel = processes.get(&id)?.notify.as_ref()?;
+        Some(Arc::clone(&channel.record))
+    }
+
+    /// Closes the listening sockets held for a daemon.
+    ///
+    /// Call once the daemon is stopped for good; until then its sockets
+    /// keep accepting connections for the next process.
+    pub async fn close_listeners(&self, id: DaemonId) {
+        self.listeners.lock().await.remove(&id);
+    }
 }
 
 impl Default for NativeAdapter {
@@ -60,41 +216,107 @@ impl PlatformAdapter for NativeAdapter {
     }
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> PlatformResult<DaemonHandle> {
+        // Without a config there is no binary to exec, so run a long-lived
+        // placeholder process. Use `spawn_with_config` to run the real daemon.
+        let mut config = DaemonConfig::new(daemon.name(), "/bin/sleep");
+        config.args = vec!["3600".to_string()];
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> PlatformResult<DaemonHandle> {
         let id = daemon.id();
         let name = daemon.name().to_string();
 
-        // For native adapter, we spawn a simple test process
-        // In production, this would exec the actual daemon binary
-        // For now, return NotSupported since we can't properly spawn
-        // a daemon from a trait object (we'd need the binary path)
-
-        // This is a stub - in real implementation:
-        // 1. Get binary path from daemon config
-        // 2. Fork and exec the binary
-        // 3. Track the PID
-
-        // For testing purposes, we create a placeholder child process
         #[cfg(unix)]
         {
-            let child = Command::new("/bin/sleep")
-                .arg("3600") // Sleep for an hour (will be killed on shutdown)
+            // Held until spawned: the child inherits the descriptors
+            let mut listeners = self.listeners.lock().await;
+            let mut cmd = if config.sockets.is_empty() {
+                let mut cmd = Command::new(&config.binary_path);
+                cmd.args(&config.args);
+                cmd
+            } else {
+                let bound = listeners.entry(id).or_default();
+                bound
+                    .ensure_bound(&config.sockets)
+                    .map_err(|e| PlatformError::spawn_failed(e.to_string()))?;
+                socket_activated_command(config, bound)
+            };
+            // Children join the spawner's trace through TRACEPARENT
+            cmd.envs(&config.env)
+                .envs(TraceContext::for_spawn(&config.env).env_vars())
                 .stdin(Stdio::null())
                 .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .spawn()
-                .map_err(|e| PlatformError::spawn_failed(format!("failed to spawn: {e}")))?;
+                .stderr(Stdio::null());
+
+            if let Some(ref cwd) = config.working_dir {
+                cmd.current_dir(cwd);
+            }
+
+            apply_credentials(&mut cmd, config)?;
+            apply_resource_limits(&mut cmd, &config.resources);
+
+            // Same protocol as under systemd: NOTIFY_SOCKET, WATCHDOG_USEC
+            let mut notify = match NotifyChannel::bind(id, config.notify) {
+                Ok((channel, path)) => {
+                    cmd.env("NOTIFY_SOCKET", path);
+                    Some(channel)
+                }
+                Err(e) if config.notify => {
+                    return Err(PlatformError::spawn_failed(format!(
+                        "failed to bind notify socket: {e}"
+                    )));
+                }
+                Err(e) => {
+                    tracing::warn!(daemon = %name, error = %e, "no notify socket");
+                    None
+                }
+            };
+            let watchdog = config.health_check.watchdog;
+            if !watchdog.is_zero() {
+                cmd.env("WATCHDOG_USEC", watchdog.as_micros().to_string());
+            }
+
+            let child = cmd.spawn().map_err(|e| {
+                PlatformError::spawn_failed(format!(
+                    "failed to spawn {}: {e}",
+                    config.binary_path.display()
+                ))
+            })?;
+            drop(listeners);
 
             let pid = child
                 .id()
                 .ok_or_else(|| PlatformError::spawn_failed("failed to get PID"))?;
 
-            let handle = DaemonHandle::native(id, pid);
+            let start_time = process_start_time(pid);
+            let handle = DaemonHandle::native(id, pid).with_start_time(start_time);
 
-            let state = ProcessState { child };
+            if let Some(ref mut channel) = notify
+                && !watchdog.is_zero()
+            {
+                channel.enforce_watchdog(pid, watchdog);
+            }
+
+            let state = ProcessState {
+                child: Some(child),
+                pid,
+                start_time,
+                notify,
+            };
 
             self.processes.lock().await.insert(id, state);
 
-            tracing::info!(daemon = %name, pid = pid, "spawned native process");
+            tracing::info!(
+                daemon = %name,
+                pid = pid,
+                binary = %config.binary_path.display(),
+                "spawned native process"
+            );
 
             Ok(handle)
         }
@@ -102,7 +324,7 @@ impl PlatformAdapter for NativeAdapter {
         #[cfg(not(unix))]
         {
             // For non-Unix platforms, return not supported
-            let _ = (daemon, name);
+            let _ = (daemon, name, id, config);
             Err(PlatformError::not_supported(
                 Platform::Native,
                 "spawn (non-Unix)",
@@ -126,6 +348,11 @@ impl PlatformAdapter for NativeAdapter {
             let pid = handle
                 .pid()
                 .ok_or_else(|| PlatformError::signal_failed("no PID in handle"))?;
+            // Not our child: the process may have exited and its PID reused
+            if state.child.is_none() && !process_alive(pid, state.start_time) {
+                processes.remove(&id);
+                return Err(PlatformError::NotFound(id.to_string()));
+            }
 
             let nix_sig = match sig {
                 Signal::Hup => NixSignal::SIGHUP,
@@ -145,7 +372,9 @@ impl PlatformAdapter for NativeAdapter {
 
             // For SIGKILL, clean up immediately
             if sig == Signal::Kill {
-                let _ = state.child.start_kill();
+                if let Some(ref mut child) = state.child {
+                    let _ = child.start_kill();
+                }
                 processes.remove(&id);
             }
 
@@ -170,11 +399,26 @@ impl PlatformAdapter for NativeAdapter {
         let mut processes = self.processes.lock().await;
 
         if let Some(state) = processes.get_mut(&id) {
+            let Some(ref mut child) = state.child else {
+                // Reattached: not our child, so no exit status to collect
+                if process_alive(state.pid, state.start_time) {
+                    return Ok(DaemonStatus::Running);
+                }
+                processes.remove(&id);
+                return Ok(DaemonStatus::Stopped);
+            };
+
             // Try to get exit status without blocking
-            match state.child.try_wait() {
+            match child.try_wait() {
                 Ok(Some(exit_status)) => {
                     // Process has exited
-                    let status = if exit_status.success() {
+                    let hung = match state.notify {
+                        Some(ref n) => n.record.lock().await.watchdog_expired,
+                        None => false,
+                    };
+                    let status = if hung {
+                        DaemonStatus::Failed(FailureReason::HealthCheckTimeout)
+                    } else if exit_status.success() {
                         DaemonStatus::Stopped
                     } else {
                         #[cfg(unix)]
@@ -202,8 +446,16 @@ impl PlatformAdapter for NativeAdapter {
                     Ok(status)
                 }
                 Ok(None) => {
-                    // Process is still running
-                    Ok(DaemonStatus::Running)
+                    // Still running; with notify, not ready before READY=1
+                    let starting = match state.notify {
+                        Some(ref n) if n.awaits_ready => !n.record.lock().await.ready,
+                        _ => false,
+                    };
+                    Ok(if starting {
+                        DaemonStatus::Starting
+                    } else {
+                        DaemonStatus::Running
+                    })
                 }
                 Err(e) => Err(PlatformError::status_failed(format!(
                     "failed to get status: {e}"
@@ -215,6 +467,34 @@ impl PlatformAdapter for NativeAdapter {
         }
     }
 
+    async fn reattach(&self, handle: &DaemonHandle) -> PlatformResult<DaemonStatus> {
+        let id = handle.id();
+        if self.processes.lock().await.contains_key(&id) {
+            return self.status(handle).await;
+        }
+
+        let pid = handle
+            .pid()
+            .ok_or_else(|| PlatformError::status_failed("no PID in handle"))?;
+        // A live PID with another start time is a different process
+        if !process_alive(pid, handle.start_time()) {
+            return Ok(DaemonStatus::Stopped);
+        }
+
+        self.processes.lock().await.insert(
+            id,
+            ProcessState {
+                child: None,
+                pid,
+                start_time: handle.start_time(),
+                notify: None,
+            },
+        );
+        tracing::info!(id = %id, pid = pid, "reattached native process");
+
+        Ok(DaemonStatus::Running)
+    }
+
     async fn attach_tracer(&self, handle: &DaemonHandle) -> PlatformResult<TracerHandle> {
         let id = handle.id();
 
@@ -248,11 +528,201 @@ impl PlatformAdapter for NativeAdapter {
     }
 }
 
+/// Returns true if a process with this PID exists and can be signalled.
+///
+/// With a `start_time`, the process must also have started then: the PID
+/// of an exited process may have been reused.
+#[cfg(unix)]
+fn process_alive(pid: u32, start_time: Option<u64>) -> bool {
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    #[allow(clippy::cast_possible_wrap)] // PID always fits in i32 on Unix
+    let exists = kill(Pid::from_raw(pid as i32), None).is_ok();
+    // Unknown on either side: the PID is all there is to go by
+    exists
+        && match (start_time, process_start_time(pid)) {
+            (Some(expected), Some(actual)) => expected == actual,
+            _ => true,
+        }
+}
+
+/// Returns true if a process with this PID exists and can be signalled.
+#[cfg(not(unix))]
+const fn process_alive(_pid: u32, _start_time: Option<u64>) -> bool {
+    false
+}
+
+/// Returns the start time of a process in clock ticks since boot, from
+/// field 22 of `/proc/<pid>/stat`.
+#[cfg(target_os = "linux")]
+fn process_start_time(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // Field 2 is the command in parentheses, which may contain spaces
+    let (_, fields) = stat.rsplit_once(')')?;
+    // Fields after the command start at field 3
+    fields.split_whitespace().nth(22 - 3)?.parse().ok()
+}
+
+/// Process start times are only read from Linux `/proc`.
+#[cfg(not(target_os = "linux"))]
+const fn process_start_time(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// Builds a command that inherits `listeners` as descriptors 3, 4, ...
+///
+/// `LISTEN_PID` must hold the daemon's own PID, which is only known after
+/// fork; a shell sets it and then execs the daemon in the same process.
+#[cfg(unix)]
+#[allow(unsafe_code)]
+fn socket_activated_command(config: &DaemonConfig, listeners: &Listeners) -> Command {
+    use std::os::fd::{AsFd, AsRawFd, RawFd};
+
+    let mut cmd = Command::new("/bin/sh");
+    cmd.arg("-c")
+        .arg(r#"LISTEN_PID=$$; export LISTEN_PID; exec "$0" "$@""#)
+        .arg(&config.binary_path)
+        .args(&config.args)
+        .env("LISTEN_FDS", listeners.len().to_string())
+        .env("LISTEN_FDNAMES", listeners.fd_names());
+
+    let fds: Vec<RawFd> = listeners
+        .iter()
+        .map(|(_, l)| l.as_fd().as_raw_fd())
+        .collect();
+    // Allocated before fork: pre_exec must not allocate
+    let mut moved: Vec<RawFd> = vec![-1; fds.len()];
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    let count = fds.len() as RawFd;
+
+    // SAFETY: the closure runs between fork and exec. It only calls
+    // fcntl(2) and dup2(2), which are async-signal-safe, and does not
+    // allocate. The descriptors stay open in the parent until spawn returns.
+    unsafe {
+        cmd.pre_exec(move || {
+            // Move every source above the target range first, so placing
+            // one listener cannot close another that sits on its target
+            for (src, dst) in fds.iter().zip(moved.iter_mut()) {
+                *dst = libc::fcntl(*src, libc::F_DUPFD_CLOEXEC, LISTEN_FDS_START + count);
+                if *dst < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            // dup2 clears close-on-exec on the target
+            for (target, src) in (LISTEN_FDS_START..).zip(moved.iter()) {
+                if libc::dup2(*src, target) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+    cmd
+}
+
+/// Resolves `user`/`group` from the config and applies them to the command.
+///
+/// Accepts either names (looked up in the user/group database) or numeric IDs.
+/// When only `user` is given, the user's primary group is used.
+///
+/// # Errors
+/// Returns [`PlatformError::Config`] if a user or group name is unknown.
+#[cfg(unix)]
+pub fn apply_credentials(cmd: &mut Command, config: &DaemonConfig) -> PlatformResult<()> {
+    use nix::unistd::{Group, User};
+
+    if let Some(ref user) = config.user {
+        let (uid, primary_gid) = if let Ok(uid) = user.parse::<u32>() {
+            (uid, None)
+        } else {
+            let entry = User::from_name(user)
+                .map_err(|e| PlatformError::Config(format!("failed to look up user {user}: {e}")))?
+                .ok_or_else(|| PlatformError::Config(format!("unknown user: {user}")))?;
+            (entry.uid.as_raw(), Some(entry.gid.as_raw()))
+        };
+        cmd.uid(uid);
+        if config.group.is_none() {
+            if let Some(gid) = primary_gid {
+                cmd.gid(gid);
+            }
+        }
+    }
+
+    if let Some(ref group) = config.group {
+        let gid = match group.parse::<u32>() {
+            Ok(gid) => gid,
+            Err(_) => Group::from_name(group)
+                .map_err(|e| {
+                    PlatformError::Config(format!("failed to look up group {group}: {e}"))
+                })?
+                .ok_or_else(|| PlatformError::Config(format!("unknown group: {group}")))?
+                .gid
+                .as_raw(),
+        };
+        cmd.gid(gid);
+    }
+
+    Ok(())
+}
+
+/// Applies per-process resource limits (rlimits) to the command.
+///
+/// Native processes have no cgroup, so only limits expressible as rlimits are
+/// enforced: `address_space_bytes`, if set, and `open_files_max`. Memory,
+/// CPU, I/O and PID limits require cgroups and are enforced by the systemd
+/// and container adapters; `memory_bytes` limits memory in use, which no
+/// rlimit does (`RLIMIT_AS` caps reserved address space instead).
+///
+/// Only soft limits are set, clamped to the inherited hard limit: an
+/// unprivileged manager never fails the spawn by trying to raise them, and
+/// the daemon can still raise its own soft limit up to the hard one.
+#[cfg(unix)]
+#[allow(unsafe_code)]
+pub fn apply_resource_limits(cmd: &mut Command, resources: &ResourceConfig) {
+    use nix::sys::resource::{Resource, getrlimit, setrlimit};
+
+    let clamp = |resource: Resource, requested: u64| -> Option<(u64, u64)> {
+        if requested == 0 {
+            return None;
+        }
+        let (_, hard) = getrlimit(resource).ok()?;
+        Some((requested.min(hard), hard))
+    };
+
+    let address_space = clamp(Resource::RLIMIT_AS, resources.address_space_bytes);
+    let open_files = clamp(Resource::RLIMIT_NOFILE, resources.open_files_max);
+    // mlockall() in the daemon needs RLIMIT_MEMLOCK raised to the hard limit.
+    let memlock = if resources.lock_memory {
+        getrlimit(Resource::RLIMIT_MEMLOCK)
+            .ok()
+            .map(|(_, hard)| hard)
+    } else {
+        None
+    };
+
+    // SAFETY: the closure runs between fork and exec. It only calls
+    // setrlimit(2), which is async-signal-safe, and does not allocate.
+    unsafe {
+        cmd.pre_exec(move || {
+            if let Some((soft, hard)) = address_space {
+                setrlimit(Resource::RLIMIT_AS, soft, hard)?;
+            }
+            if let Some((soft, hard)) = open_files {
+                setrlimit(Resource::RLIMIT_NOFILE, soft, hard)?;
+            }
+            if let Some(limit) = memlock {
+                setrlimit(Resource::RLIMIT_MEMLOCK, limit, limit)?;
+            }
+            Ok(())
+        });
+    }
+}
+
 #[cfg(test)]
 #[cfg(unix)]
 mod tests {
     use super::*;
-    use crate::config::DaemonConfig;
     use crate::daemon::{Daemon, DaemonContext};
     use crate::error::Result;
     use crate::metrics::DaemonMetrics;
@@ -337,6 +807,290 @@ mod tests {
         assert!(status.is_terminal());
     }
 
+    #[tokio::test]
+    async fn test_native_adapter_spawn_with_config() {
+        let adapter = NativeAdapter::new();
+        let dir = std::env::temp_dir().join(format!("duende-native-{}", DaemonId::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // The daemon writes its env, argument and cwd to a file in its cwd
+        let mut config = DaemonConfig::new("test-daemon", "/bin/sh");
+        config.args = vec![
+            "-c".to_string(),
+            r#"echo "$GREETING $0 $(pwd)" > out.txt"#.to_string(),
+            "from-args".to_string(),
+        ];
+        config
+            .env
+            .insert("GREETING".to_string(), "hello".to_string());
+        config.working_dir = Some(dir.clone());
+
+        let handle = adapter
+            .spawn_with_config(Box::new(TestDaemon::new()), &config)
+            .await
+            .unwrap();
+
+        for _ in 0..100 {
+            if adapter.status(&handle).await.unwrap().is_terminal() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let out = std::fs::read_to_string(dir.join("out.txt")).unwrap();
+        let cwd = dir.canonicalize().unwrap();
+        assert_eq!(out.trim(), format!("hello from-args {}", cwd.display()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Returns the soft and hard limit named `name` in a process's
+    /// `/proc/<pid>/limits`; `unlimited` is `u64::MAX`.
+    #[cfg(target_os = "linux")]
+    fn proc_limit(pid: &str, name: &str) -> (u64, u64) {
+        let limits = std::fs::read_to_string(format!("/proc/{pid}/limits")).unwrap();
+        let line = limits.lines().find(|line| line.starts_with(name)).unwrap();
+        let mut values = line[name.len()..]
+            .split_whitespace()
+            .map(|value| value.parse().unwrap_or(u64::MAX));
+        (values.next().unwrap(), values.next().unwrap())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_native_adapter_address_space_limit_only_when_set() {
+        let adapter = NativeAdapter::new();
+        let mut config = DaemonConfig::new("test-daemon", "/bin/sleep");
+        config.args = vec!["5".to_string()];
+        let (_, hard) = proc_limit("self", "Max address space");
+
+        // The memory limit is not an address-space limit
+        let handle = adapter
+            .spawn_with_config(Box::new(TestDaemon::new()), &config)
+            .await
+            .unwrap();
+        let pid = handle.pid().unwrap().to_string();
+        assert_eq!(
+            proc_limit(&pid, "Max address space"),
+            proc_limit("self", "Max address space")
+        );
+        adapter.signal(&handle, Signal::Kill).await.unwrap();
+
+        config.resources.address_space_bytes = 1 << 40;
+        let handle = adapter
+            .spawn_with_config(Box::new(TestDaemon::new()), &config)
+            .await
+            .unwrap();
+        let pid = handle.pid().unwrap().to_string();
+        assert_eq!(
+            proc_limit(&pid, "Max address space"),
+            (hard.min(1 << 40), hard)
+        );
+        adapter.signal(&handle, Signal::Kill).await.unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_native_adapter_open_files_keeps_hard_limit() {
+        let adapter = NativeAdapter::new();
+        let mut config = DaemonConfig::new("test-daemon", "/bin/sleep");
+        config.args = vec!["5".to_string()];
+        config.resources.open_files_max = 64;
+        let (_, hard) = proc_limit("self", "Max open files");
+
+        let handle = adapter
+            .spawn_with_config(Box::new(TestDaemon::new()), &config)
+            .await
+            .unwrap();
+        let pid = handle.pid().unwrap().to_string();
+        assert_eq!(proc_limit(&pid, "Max open files"), (hard.min(64), hard));
+        adapter.signal(&handle, Signal::Kill).await.unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_native_adapter_socket_activation() {
+        use crate::config::{ListenAddress, SocketConfig};
+
+        let adapter = NativeAdapter::new();
+        let daemon = TestDaemon::new();
+        let id = daemon.id();
+        let dir = std::env::temp_dir().join(format!("duende-sockets-{}", DaemonId::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Reports the LISTEN_* variables and what fds 3 and 4 are
+        let mut config = DaemonConfig::new("test-daemon", "/bin/sh");
+        config.args = vec![
+            "-c".to_string(),
+            r#"echo "$LISTEN_PID $$ $LISTEN_FDS $LISTEN_FDNAMES" > out.txt;
+               readlink /proc/$$/fd/3 /proc/$$/fd/4 >> out.txt"#
+                .to_string(),
+        ];
+        config.working_dir = Some(dir.clone());
+        config.sockets = vec![
+            SocketConfig::new("http", ListenAddress::Tcp("127.0.0.1:0".parse().unwrap())),
+            SocketConfig::new("stats", ListenAddress::Udp("127.0.0.1:0".parse().unwrap())),
+        ];
+
+        let wait_exit = |handle: DaemonHandle| {
+            let adapter = &adapter;
+            async move {
+                for _ in 0..100 {
+                    if adapter.status(&handle).await.unwrap().is_terminal() {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            }
+        };
+
+        let handle = adapter
+            .spawn_with_config(
+                Box::new(TestDaemon {
+                    id,
+                    ..TestDaemon::new()
+                }),
+                &config,
+            )
+            .await
+            .unwrap();
+        wait_exit(handle.clone()).await;
+
+        let out = std::fs::read_to_string(dir.join("out.txt")).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        let vars: Vec<&str> = lines[0].split(' ').collect();
+        assert_eq!(vars[0], vars[1], "LISTEN_PID must be the daemon's PID");
+        assert_eq!(vars[2..], ["2", "http:stats"]);
+        assert!(lines[1].starts_with("socket:"));
+        assert!(lines[2].starts_with("socket:"));
+
+        // The sockets survive the process and are reused on respawn
+        assert_eq!(adapter.listener_names(id).await, ["http", "stats"]);
+        let first = lines[1].to_string();
+        let handle = adapter
+            .spawn_with_config(
+                Box::new(TestDaemon {
+                    id,
+                    ..TestDaemon::new()
+                }),
+                &config,
+            )
+            .await
+            .unwrap();
+        wait_exit(handle).await;
+        let out = std::fs::read_to_string(dir.join("out.txt")).unwrap();
+        assert_eq!(out.lines().nth(1), Some(first.as_str()));
+
+        adapter.close_listeners(id).await;
+        assert!(adapter.listener_names(id).await.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_native_adapter_notify_socket() {
+        use crate::notify::Notifier;
+
+        let adapter = NativeAdapter::new();
+        let daemon = TestDaemon::new();
+        let id = daemon.id();
+        let dir = std::env::temp_dir().join(format!("duende-notify-{}", DaemonId::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut config = DaemonConfig::new("test-daemon", "/bin/sh");
+        config.args = vec![
+            "-c".to_string(),
+            r#"echo "$NOTIFY_SOCKET $WATCHDOG_USEC" > out.tmp && mv out.tmp out.txt; sleep 5"#
+                .to_string(),
+        ];
+        config.working_dir = Some(dir.clone());
+        config.notify = true;
+        config.health_check.watchdog = Duration::from_secs(2);
+
+        let handle = adapter
+            .spawn_with_config(Box::new(daemon), &config)
+            .await
+            .unwrap();
+        let out = dir.join("out.txt");
+        for _ in 0..100 {
+            if out.exists() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let out = std::fs::read_to_string(out).unwrap();
+        let (socket, watchdog) = out.trim().split_once(' ').unwrap();
+        assert_eq!(watchdog, "2000000");
+
+        // Not ready until the process says so
+        assert_eq!(
+            adapter.status(&handle).await.unwrap(),
+            DaemonStatus::Starting
+        );
+
+        // Speak the protocol as the daemon would
+        let notifier = Notifier::new(socket).unwrap();
+        notifier
+            .notify(&[
+                NotifyState::Status("serving".to_string()),
+                NotifyState::Ready,
+            ])
+            .unwrap();
+        notifier.notify(&[NotifyState::Watchdog]).unwrap();
+        for _ in 0..100 {
+            if adapter.last_watchdog(id).await.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(
+            adapter.status(&handle).await.unwrap(),
+            DaemonStatus::Running
+        );
+        assert_eq!(adapter.status_text(id).await.as_deref(), Some("serving"));
+        assert!(adapter.last_watchdog(id).await.is_some());
+
+        adapter.signal(&handle, Signal::Kill).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_native_adapter_watchdog_aborts_silent_process() {
+        let adapter = NativeAdapter::new();
+        let mut config = DaemonConfig::new("test-daemon", "/bin/sleep");
+        config.args = vec!["5".to_string()];
+        config.health_check.watchdog = Duration::from_millis(100);
+
+        let handle = adapter
+            .spawn_with_config(Box::new(TestDaemon::new()), &config)
+            .await
+            .unwrap();
+
+        let mut status = DaemonStatus::Running;
+        for _ in 0..200 {
+            status = adapter.status(&handle).await.unwrap();
+            if status.is_terminal() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(
+            status,
+            DaemonStatus::Failed(FailureReason::HealthCheckTimeout)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_native_adapter_spawn_with_config_unknown_user() {
+        let adapter = NativeAdapter::new();
+        let mut config = DaemonConfig::new("test-daemon", "/bin/true");
+        config.user = Some("duende-no-such-user".to_string());
+
+        let result = adapter
+            .spawn_with_config(Box::new(TestDaemon::new()), &config)
+            .await;
+        assert!(matches!(result, Err(PlatformError::Config(_))));
+    }
+
     #[tokio::test]
     async fn test_native_adapter_graceful_stop() {
         let adapter = NativeAdapter::new();
@@ -352,6 +1106,76 @@ mod tests {
         assert!(status.is_terminal());
     }
 
+    #[tokio::test]
+    async fn test_native_adapter_reattach() {
+        let owner = NativeAdapter::new();
+        let handle = owner.spawn(Box::new(TestDaemon::new())).await.unwrap();
+
+        // A fresh adapter, as after a manager restart
+        let adapter = NativeAdapter::new();
+        assert_eq!(
+            adapter.reattach(&handle).await.unwrap(),
+            DaemonStatus::Running
+        );
+        assert_eq!(adapter.process_count().await, 1);
+        assert_eq!(
+            adapter.status(&handle).await.unwrap(),
+            DaemonStatus::Running
+        );
+
+        adapter.signal(&handle, Signal::Kill).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // Reap the zombie through the owning adapter
+        let _ = owner.status(&handle).await;
+        assert_eq!(
+            adapter.reattach(&handle).await.unwrap(),
+            DaemonStatus::Stopped
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_native_adapter_reattach_rejects_reused_pid() {
+        // This test process stands in for whatever reused the PID
+        let pid = std::process::id();
+        let started = process_start_time(pid).unwrap();
+        let same = DaemonHandle::native(DaemonId::new(), pid).with_start_time(Some(started));
+        let reused = DaemonHandle::native(DaemonId::new(), pid).with_start_time(Some(started + 1));
+
+        let adapter = NativeAdapter::new();
+        assert_eq!(
+            adapter.reattach(&same).await.unwrap(),
+            DaemonStatus::Running
+        );
+        assert_eq!(
+            adapter.reattach(&reused).await.unwrap(),
+            DaemonStatus::Stopped
+        );
+        assert_eq!(adapter.process_count().await, 1);
+
+        // The handle of a spawned process carries its start time
+        let owner = NativeAdapter::new();
+        let handle = owner.spawn(Box::new(TestDaemon::new())).await.unwrap();
+        assert_eq!(
+            handle.start_time(),
+            process_start_time(handle.pid().unwrap())
+        );
+        assert!(handle.start_time().is_some());
+        owner.signal(&handle, Signal::Kill).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_native_adapter_reattach_dead_pid() {
+        let adapter = NativeAdapter::new();
+        let handle = DaemonHandle::native(DaemonId::new(), i32::MAX as u32);
+
+        assert_eq!(
+            adapter.reattach(&handle).await.unwrap(),
+            DaemonStatus::Stopped
+        );
+        assert_eq!(adapter.process_count().await, 0);
+    }
+
     #[tokio::test]
     async fn test_native_adapter_process_count() {
         let adapter = NativeAdapter::new();
@@ -6,6 +6,7 @@
 //! optimized for running single-purpose daemons with minimal overhead.
 
 use crate::adapter::{DaemonHandle, PlatformAdapter, PlatformError, PlatformResult, TracerHandle};
+use crate::config::DaemonConfig;
 use crate::daemon::Daemon;
 use crate::platform::Platform;
 use crate::types::{DaemonStatus, FailureReason, Signal};
@@ -159,6 +160,96 @@ impl PepitaAdapter {
             .unwrap_or(false)
     }
 
+    /// Builds `pepita run` arguments from the config.
+    ///
+    /// `platform.kernel_path`/`rootfs_path` override the adapter defaults and
+    /// `platform.vcpus` overrides the vCPU count derived from the CPU quota.
+    /// The daemon command (if any) follows `--` and is executed by the guest.
+    fn run_args(
+        &self,
+        vm_id: &str,
+        vsock_cid: u32,
+        config: &DaemonConfig,
+    ) -> PlatformResult<Vec<String>> {
+        let kernel = config
+            .platform
+            .kernel_path
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .or_else(|| self.default_kernel.clone())
+            .ok_or_else(|| {
+                PlatformError::Config(
+                    "No kernel image configured. Use with_images() to set kernel path.".into(),
+                )
+            })?;
+
+        let rootfs = config
+            .platform
+            .rootfs_path
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .or_else(|| self.default_rootfs.clone())
+            .ok_or_else(|| {
+                PlatformError::Config(
+                    "No rootfs image configured. Use with_images() to set rootfs path.".into(),
+                )
+            })?;
+
+        let res = &config.resources;
+        let memory_mb = match res.memory_bytes / (1024 * 1024) {
+            0 => 256, // Default 256MB
+            mb => mb,
+        };
+        let cpus = config.platform.vcpus.unwrap_or_else(|| {
+            // Round the quota up to whole vCPUs
+            let quota = (res.cpu_quota_percent / 100.0).ceil();
+            if quota >= 1.0 { quota as u32 } else { 1 }
+        });
+
+        // pepita run --kernel <path> --rootfs <path> --vsock-cid <cid> --memory <mb> --cpus <n>
+        let mut args = vec![
+            "run".to_string(),
+            "--kernel".to_string(),
+            kernel,
+            "--rootfs".to_string(),
+            rootfs,
+            "--vsock-cid".to_string(),
+            vsock_cid.to_string(),
+            "--memory".to_string(),
+            memory_mb.to_string(),
+            "--cpus".to_string(),
+            cpus.to_string(),
+            "--name".to_string(),
+            vm_id.to_string(),
+            "--daemon".to_string(), // Run in background
+        ];
+
+        if config.binary_path.as_os_str().is_empty() {
+            return Ok(args);
+        }
+
+        // Sorted for deterministic argument order
+        let mut env: Vec<_> = config.env.iter().collect();
+        env.sort();
+        for (key, value) in env {
+            args.push("--env".to_string());
+            args.push(format!("{}={}", key, value));
+        }
+        if let Some(ref dir) = config.working_dir {
+            args.push("--workdir".to_string());
+            args.push(dir.display().to_string());
+        }
+        if let Some(ref user) = config.user {
+            args.push("--user".to_string());
+            args.push(user.clone());
+        }
+
+        args.push("--".to_string());
+        args.push(config.binary_path.display().to_string());
+        args.extend(config.args.iter().cloned());
+        Ok(args)
+    }
+
     /// Maps Signal to signal number for vsock command.
     fn signal_number(sig: Signal) -> i32 {
         match sig {
@@ -188,6 +279,16 @@ impl PlatformAdapter for PepitaAdapter {
     }
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> PlatformResult<DaemonHandle> {
+        // Without a config, boot the guest's default init with no daemon command.
+        let config = DaemonConfig::new(daemon.name(), "");
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> PlatformResult<DaemonHandle> {
         // Check prerequisites
         if !Self::kvm_available() {
             return Err(PlatformError::spawn_failed(
@@ -201,40 +302,14 @@ impl PlatformAdapter for PepitaAdapter {
             ));
         }
 
-        let kernel = self.default_kernel.as_ref().ok_or_else(|| {
-            PlatformError::Config(
-                "No kernel image configured. Use with_images() to set kernel path.".into(),
-            )
-        })?;
-
-        let rootfs = self.default_rootfs.as_ref().ok_or_else(|| {
-            PlatformError::Config(
-                "No rootfs image configured. Use with_images() to set rootfs path.".into(),
-            )
-        })?;
-
         let daemon_name = daemon.name().to_string();
         let daemon_id = daemon.id();
         let vm_id = Self::vm_id(&daemon_name);
         let vsock_cid = Self::allocate_cid();
 
-        // Build pepita command
-        // pepita run --kernel <path> --rootfs <path> --vsock-cid <cid> --memory <mb> --cpus <n>
+        let args = self.run_args(&vm_id, vsock_cid, config)?;
         let output = tokio::process::Command::new("pepita")
-            .arg("run")
-            .arg("--kernel")
-            .arg(kernel)
-            .arg("--rootfs")
-            .arg(rootfs)
-            .arg("--vsock-cid")
-            .arg(vsock_cid.to_string())
-            .arg("--memory")
-            .arg("256") // Default 256MB
-            .arg("--cpus")
-            .arg("1")
-            .arg("--name")
-            .arg(&vm_id)
-            .arg("--daemon") // Run in background
+            .args(&args)
             .output()
             .await
             .map_err(|e| PlatformError::spawn_failed(format!("Failed to execute pepita: {}", e)))?;
@@ -635,4 +710,40 @@ mod tests {
         // Result depends on pepita availability, but should not panic
         let _ = result;
     }
+
+    #[test]
+    fn test_run_args_from_config() {
+        let adapter = PepitaAdapter::with_images("/boot/vmlinux", "/images/rootfs.ext4");
+        let mut config = DaemonConfig::new("api", "/usr/bin/api-server");
+        config.args = vec!["--port".to_string(), "8080".to_string()];
+        config
+            .env
+            .insert("RUST_LOG".to_string(), "info".to_string());
+        config.platform.rootfs_path = Some("/images/api.ext4".into());
+        config.platform.vcpus = Some(2);
+
+        let args = adapter.run_args("duende-vm-api", 7, &config).unwrap();
+        let after = |flag: &str| {
+            let pos = args.iter().position(|a| a == flag).unwrap();
+            args[pos + 1].clone()
+        };
+
+        assert_eq!(after("--kernel"), "/boot/vmlinux");
+        assert_eq!(after("--rootfs"), "/images/api.ext4");
+        assert_eq!(after("--memory"), "512");
+        assert_eq!(after("--cpus"), "2");
+        assert_eq!(after("--env"), "RUST_LOG=info");
+        assert_eq!(after("--"), "/usr/bin/api-server");
+        assert_eq!(args.last().map(String::as_str), Some("8080"));
+    }
+
+    #[test]
+    fn test_run_args_requires_kernel() {
+        let adapter = PepitaAdapter::new();
+        let config = DaemonConfig::new("api", "/usr/bin/api-server");
+        assert!(matches!(
+            adapter.run_args("duende-vm-api", 7, &config),
+            Err(PlatformError::Config(_))
+        ));
+    }
 }
@@ -3,8 +3,10 @@
 //! Provides daemon management via systemd transient units.
 
 use crate::adapter::{DaemonHandle, PlatformAdapter, PlatformError, PlatformResult, TracerHandle};
+use crate::config::{DaemonConfig, ListenAddress, RestartPolicy};
 use crate::daemon::Daemon;
 use crate::platform::Platform;
+use crate::trace::{TRACEPARENT_ENV, TRACESTATE_ENV, TraceContext};
 use crate::types::{DaemonStatus, FailureReason, Signal};
 
 use async_trait::async_trait;
@@ -147,6 +149,136 @@ impl SystemdAdapter {
         DaemonStatus::Stopped
     }
 
+    /// Builds `systemd-run` arguments for a transient unit from the config.
+    ///
+    /// Resource limits map to cgroup v2 unit properties (`MemoryMax`,
+    /// `CPUQuota`, `CPUWeight`, `IO*BandwidthMax`, `TasksMax`) plus rlimits
+    /// (`LimitNOFILE`, `LimitMEMLOCK`). `notify` maps to `Type=notify` and
+    /// the health-check watchdog to `WatchdogSec`.
+    fn run_args(unit_name: &str, daemon_name: &str, config: &DaemonConfig) -> Vec<String> {
+        let res = &config.resources;
+        let mut args = vec![
+            "--unit".to_string(),
+            unit_name.to_string(),
+            "--description".to_string(),
+            format!("Duende daemon: {}", daemon_name),
+            "--remain-after-exit".to_string(),
+            "--collect".to_string(),
+        ];
+
+        // Type=notify would block systemd-run until READY=1; report
+        // activating (Starting) instead
+        if config.notify {
+            args.push("--no-block".to_string());
+        }
+
+        if let Some(ref dir) = config.working_dir {
+            args.push(format!("--working-directory={}", dir.display()));
+        }
+        if let Some(ref user) = config.user {
+            args.push(format!("--uid={}", user));
+        }
+        if let Some(ref group) = config.group {
+            args.push(format!("--gid={}", group));
+        }
+
+        // Sorted for deterministic unit properties; the unit joins the
+        // spawner's trace through TRACEPARENT
+        let mut env: Vec<_> = config
+            .env
+            .iter()
+            .filter(|(key, _)| *key != TRACEPARENT_ENV && *key != TRACESTATE_ENV)
+            .collect();
+        env.sort();
+        for (key, value) in env {
+            args.push(format!("--setenv={}={}", key, value));
+        }
+        for (key, value) in TraceContext::for_spawn(&config.env).env_vars() {
+            args.push(format!("--setenv={}={}", key, value));
+        }
+
+        let mut property = |p: String| args.push(format!("--property={}", p));
+
+        if res.memory_bytes > 0 {
+            property(format!("MemoryMax={}", res.memory_bytes));
+            property(format!(
+                "MemorySwapMax={}",
+                res.memory_swap_bytes.saturating_sub(res.memory_bytes)
+            ));
+        }
+        if res.cpu_quota_percent > 0.0 {
+            property(format!("CPUQuota={}%", res.cpu_quota_percent));
+        }
+        // cgroup v2 weight: 1..10000, default 100 (cpu.shares default 1024)
+        property(format!(
+            "CPUWeight={}",
+            (res.cpu_shares.saturating_mul(100) / 1024).clamp(1, 10_000)
+        ));
+        if res.io_read_bps > 0 {
+            property(format!("IOReadBandwidthMax=/ {}", res.io_read_bps));
+        }
+        if res.io_write_bps > 0 {
+            property(format!("IOWriteBandwidthMax=/ {}", res.io_write_bps));
+        }
+        if res.pids_max > 0 {
+            property(format!("TasksMax={}", res.pids_max));
+        }
+        if res.open_files_max > 0 {
+            property(format!("LimitNOFILE={}", res.open_files_max));
+        }
+        if res.lock_memory {
+            property("LimitMEMLOCK=infinity".to_string());
+        }
+
+        property(format!(
+            "TimeoutStopSec={}ms",
+            config.shutdown_timeout.as_millis()
+        ));
+        property(format!("Restart={}", Self::restart_value(config.restart)));
+        property("RestartSec=5".to_string());
+        if config.notify {
+            property("Type=notify".to_string());
+        }
+        if !config.health_check.watchdog.is_zero() {
+            property(format!(
+                "WatchdogSec={}ms",
+                config.health_check.watchdog.as_millis()
+            ));
+        }
+
+        // Sockets go on a transient .socket unit; systemd holds them and
+        // starts the service on the first connection
+        for socket in &config.sockets {
+            let listen = match socket.listen {
+                ListenAddress::Tcp(addr) => format!("ListenStream={addr}"),
+                ListenAddress::Udp(addr) => format!("ListenDatagram={addr}"),
+                ListenAddress::Unix(ref path) => format!("ListenStream={}", path.display()),
+            };
+            args.push(format!("--socket-property={listen}"));
+        }
+        // One name per socket unit; several sockets are told apart by address
+        if let [socket] = config.sockets.as_slice() {
+            args.push(format!(
+                "--socket-property=FileDescriptorName={}",
+                socket.name
+            ));
+        }
+
+        args.push("--".to_string());
+        args.push(config.binary_path.display().to_string());
+        args.extend(config.args.iter().cloned());
+        args
+    }
+
+    /// Maps a config restart policy to the systemd `Restart=` value.
+    const fn restart_value(policy: RestartPolicy) -> &'static str {
+        match policy {
+            RestartPolicy::Never => "no",
+            RestartPolicy::OnFailure => "on-failure",
+            RestartPolicy::Always | RestartPolicy::UnlessStopped => "always",
+        }
+    }
+
     /// Maps Signal to systemctl kill signal name.
     fn signal_name(sig: Signal) -> &'static str {
         match sig {
@@ -177,29 +309,22 @@ impl PlatformAdapter for SystemdAdapter {
     }
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> PlatformResult<DaemonHandle> {
-        let daemon_name = daemon.name().to_string();
+        // Without a config there is no binary to run; use /bin/true as a
+        // placeholder. Use `spawn_with_config` to run the real daemon.
+        let config = DaemonConfig::new(daemon.name(), "/bin/true");
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> PlatformResult<DaemonHandle> {
         let daemon_id = daemon.id();
-        let unit_name = Self::unit_name(&daemon_name);
+        let unit_name = Self::unit_name(daemon.name());
 
-        // Build systemd-run command for transient unit
-        // Note: This creates a simple transient service. For full resource control,
-        // use DaemonConfig passed through a proper spawn_with_config method.
         let mut cmd = self.systemd_run_cmd();
-        cmd.arg("--unit")
-            .arg(&unit_name)
-            .arg("--description")
-            .arg(format!("Duende daemon: {}", daemon_name))
-            .arg("--remain-after-exit")
-            .arg("--collect");
-
-        // Add restart policy
-        cmd.arg("--property=Restart=on-failure")
-            .arg("--property=RestartSec=5");
-
-        // For transient units, we need a command to run.
-        // In a real implementation, this would be configured via DaemonConfig.
-        // For now, use /bin/true as a placeholder for the test harness.
-        cmd.arg("--").arg("/bin/true");
+        cmd.args(Self::run_args(&unit_name, daemon.name(), config));
 
         // Execute systemd-run
         let output = cmd.output().await.map_err(|e| {
@@ -335,6 +460,7 @@ impl SystemdAdapter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
 
     #[test]
     fn test_systemd_adapter_system() {
@@ -412,6 +538,123 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_run_args_from_config() {
+        let mut config = DaemonConfig::new("api", "/usr/bin/api-server");
+        config.args = vec!["--port".to_string(), "8080".to_string()];
+        config
+            .env
+            .insert("RUST_LOG".to_string(), "info".to_string());
+        config.working_dir = Some(PathBuf::from("/srv/api"));
+        config.user = Some("api".to_string());
+        config.group = Some("www".to_string());
+        config.resources.lock_memory = true;
+        config.restart = RestartPolicy::Always;
+
+        let args = SystemdAdapter::run_args("duende-api.service", "api", &config);
+        let has = |s: &str| args.iter().any(|a| a == s);
+
+        assert!(has("--working-directory=/srv/api"));
+        assert!(has("--uid=api"));
+        assert!(has("--gid=www"));
+        assert!(has("--setenv=RUST_LOG=info"));
+        assert!(has("--property=MemoryMax=536870912"));
+        assert!(has("--property=MemorySwapMax=536870912"));
+        assert!(has("--property=CPUQuota=100%"));
+        assert!(has("--property=CPUWeight=100"));
+        assert!(has("--property=TasksMax=100"));
+        assert!(has("--property=LimitNOFILE=1024"));
+        assert!(has("--property=LimitMEMLOCK=infinity"));
+        assert!(has("--property=Restart=always"));
+
+        // Command goes last, after the separator
+        let sep = args.iter().position(|a| a == "--").unwrap();
+        assert_eq!(&args[sep + 1..], ["/usr/bin/api-server", "--port", "8080"]);
+    }
+
+    #[test]
+    fn test_run_args_propagate_trace() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mut config = DaemonConfig::new("api", "/usr/bin/api-server");
+        let args = SystemdAdapter::run_args("duende-api.service", "api", &config);
+        assert!(
+            args.iter()
+                .any(|a| a.starts_with("--setenv=TRACEPARENT=00-"))
+        );
+
+        // A configured parent is passed on once, untouched
+        config
+            .env
+            .insert(TRACEPARENT_ENV.to_string(), traceparent.to_string());
+        let args = SystemdAdapter::run_args("duende-api.service", "api", &config);
+        let traced: Vec<_> = args.iter().filter(|a| a.contains("TRACEPARENT")).collect();
+        assert_eq!(traced, [&format!("--setenv=TRACEPARENT={traceparent}")]);
+    }
+
+    #[test]
+    fn test_run_args_notify_and_watchdog() {
+        let mut config = DaemonConfig::new("api", "/usr/bin/api-server");
+        let args = SystemdAdapter::run_args("duende-api.service", "api", &config);
+        assert!(
+            !args
+                .iter()
+                .any(|a| a.contains("Type=") || a.contains("Watchdog"))
+        );
+        assert!(!args.iter().any(|a| a == "--no-block"));
+
+        config.notify = true;
+        config.health_check.watchdog = Duration::from_secs(10);
+        let args = SystemdAdapter::run_args("duende-api.service", "api", &config);
+        let has = |s: &str| args.iter().any(|a| a == s);
+        assert!(has("--property=Type=notify"));
+        assert!(has("--property=WatchdogSec=10000ms"));
+        assert!(has("--no-block"));
+    }
+
+    #[test]
+    fn test_run_args_sockets() {
+        use crate::config::SocketConfig;
+
+        let mut config = DaemonConfig::new("api", "/usr/bin/api-server");
+        config.sockets = vec![SocketConfig::new(
+            "http",
+            ListenAddress::Tcp("0.0.0.0:8080".parse().unwrap()),
+        )];
+
+        let args = SystemdAdapter::run_args("duende-api.service", "api", &config);
+        let has = |s: &str| args.iter().any(|a| a == s);
+        assert!(has("--socket-property=ListenStream=0.0.0.0:8080"));
+        assert!(has("--socket-property=FileDescriptorName=http"));
+
+        // Several sockets share the unit's name; no FileDescriptorName
+        config.sockets.push(SocketConfig::new(
+            "stats",
+            ListenAddress::Udp("[::1]:8125".parse().unwrap()),
+        ));
+        config.sockets.push(SocketConfig::new(
+            "control",
+            ListenAddress::Unix(PathBuf::from("/run/api.sock")),
+        ));
+        let args = SystemdAdapter::run_args("duende-api.service", "api", &config);
+        let has = |s: &str| args.iter().any(|a| a == s);
+        assert!(has("--socket-property=ListenDatagram=[::1]:8125"));
+        assert!(has("--socket-property=ListenStream=/run/api.sock"));
+        assert!(!args.iter().any(|a| a.contains("FileDescriptorName")));
+    }
+
+    #[test]
+    fn test_restart_value() {
+        assert_eq!(SystemdAdapter::restart_value(RestartPolicy::Never), "no");
+        assert_eq!(
+            SystemdAdapter::restart_value(RestartPolicy::OnFailure),
+            "on-failure"
+        );
+        assert_eq!(
+            SystemdAdapter::restart_value(RestartPolicy::Always),
+            "always"
+        );
+    }
+
     #[test]
     fn test_with_unit_dir() {
         let adapter = SystemdAdapter::with_unit_dir(PathBuf::from("/custom/path"), false);
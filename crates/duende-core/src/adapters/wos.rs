@@ -6,6 +6,7 @@
 //! priority scheduler, process isolation, and IPC via message passing.
 
 use crate::adapter::{DaemonHandle, PlatformAdapter, PlatformError, PlatformResult, TracerHandle};
+use crate::config::DaemonConfig;
 use crate::daemon::Daemon;
 use crate::platform::Platform;
 use crate::types::{DaemonStatus, FailureReason, Signal};
@@ -160,6 +161,59 @@ impl WosAdapter {
             .unwrap_or(false)
     }
 
+    /// Returns the priority for a daemon: `platform.priority` (clamped to
+    /// 0-7) or the adapter default.
+    fn priority_for(&self, config: &DaemonConfig) -> u8 {
+        config
+            .platform
+            .priority
+            .map_or(self.default_priority, |p| p.min(7))
+    }
+
+    /// Builds `wos-ctl spawn` arguments from the config.
+    fn spawn_args(name: &str, pid: u32, priority: u8, config: &DaemonConfig) -> Vec<String> {
+        // wos-ctl spawn --name <name> --priority <level> --pid <pid> [--wasm <path>]
+        let mut args = vec![
+            "spawn".to_string(),
+            "--name".to_string(),
+            name.to_string(),
+            "--priority".to_string(),
+            priority.to_string(),
+            "--pid".to_string(),
+            pid.to_string(),
+        ];
+
+        if config.resources.memory_bytes > 0 {
+            args.push("--memory".to_string());
+            args.push(config.resources.memory_bytes.to_string());
+        }
+
+        if config.binary_path.as_os_str().is_empty() {
+            return args;
+        }
+
+        args.push("--wasm".to_string());
+        args.push(config.binary_path.display().to_string());
+
+        // Sorted for deterministic argument order
+        let mut env: Vec<_> = config.env.iter().collect();
+        env.sort();
+        for (key, value) in env {
+            args.push("--env".to_string());
+            args.push(format!("{}={}", key, value));
+        }
+        if let Some(ref dir) = config.working_dir {
+            args.push("--workdir".to_string());
+            args.push(dir.display().to_string());
+        }
+
+        if !config.args.is_empty() {
+            args.push("--".to_string());
+            args.extend(config.args.iter().cloned());
+        }
+        args
+    }
+
     /// Maps Signal to WOS signal number.
     fn signal_number(sig: Signal) -> i32 {
         match sig {
@@ -221,6 +275,16 @@ impl PlatformAdapter for WosAdapter {
     }
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> PlatformResult<DaemonHandle> {
+        // Without a config there is no module to load; register the process only.
+        let config = DaemonConfig::new(daemon.name(), "");
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> PlatformResult<DaemonHandle> {
         // Check if we're in a WOS environment or have wos-ctl
         if !Self::is_wos_environment() && !Self::wos_ctl_available().await {
             return Err(PlatformError::spawn_failed(
@@ -232,19 +296,12 @@ impl PlatformAdapter for WosAdapter {
         let daemon_name = daemon.name().to_string();
         let daemon_id = daemon.id();
         let pid = Self::allocate_pid();
-        let priority = self.default_priority;
+        let priority = self.priority_for(config);
 
         // If we have wos-ctl, use it to spawn the process
         if Self::wos_ctl_available().await {
-            // wos-ctl spawn --name <name> --priority <level> --wasm <path>
             let output = tokio::process::Command::new("wos-ctl")
-                .arg("spawn")
-                .arg("--name")
-                .arg(&daemon_name)
-                .arg("--priority")
-                .arg(priority.to_string())
-                .arg("--pid")
-                .arg(pid.to_string())
+                .args(Self::spawn_args(&daemon_name, pid, priority, config))
                 .output()
                 .await
                 .map_err(|e| {
@@ -941,4 +998,28 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_spawn_args_from_config() {
+        let adapter = WosAdapter::new();
+        let mut config = DaemonConfig::new("api", "/wasm/api.wasm");
+        config.args = vec!["--port".to_string(), "8080".to_string()];
+        config
+            .env
+            .insert("RUST_LOG".to_string(), "info".to_string());
+        config.platform.priority = Some(9);
+
+        let priority = adapter.priority_for(&config);
+        assert_eq!(priority, 7); // Clamped to the highest level
+
+        let args = WosAdapter::spawn_args("api", 42, priority, &config);
+        let after = |flag: &str| {
+            let pos = args.iter().position(|a| a == flag).unwrap();
+            args[pos + 1].clone()
+        };
+        assert_eq!(after("--priority"), "7");
+        assert_eq!(after("--wasm"), "/wasm/api.wasm");
+        assert_eq!(after("--env"), "RUST_LOG=info");
+        assert_eq!(after("--"), "--port");
+    }
 }
@@ -0,0 +1,466 @@
+//! External health checks - probe a daemon from the outside.
+//!
+//! A daemon spawned as a separate binary cannot answer
+//! [`Daemon::health_check`](crate::daemon::Daemon::health_check). The
+//! manager checks it the way a client would instead: run its control
+//! command, connect to its port or socket, or request its health endpoint.
+//!
+//! # Toyota Way: Genchi Genbutsu (現地現物)
+//! Go and see: a daemon that accepts connections is observed to be up,
+//! not assumed to be.
+
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::config::{CheckKind, ExternalCheck};
+use crate::error::{DaemonError, Result};
+use crate::types::{HealthCheck, HealthStatus, ProbeKind};
+
+/// Pause between retries of a failing check.
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Longest HTTP status line accepted.
+const MAX_STATUS_LINE: usize = 1024;
+
+/// Result of one check attempt; the error describes the failure.
+type Outcome<T = ()> = std::result::Result<T, String>;
+
+/// Runs the checks belonging to `probe`, in order.
+///
+/// Each attempt is bounded by `timeout`, and a failing check is retried up
+/// to `retries` times. Returns `None` if no check belongs to the probe.
+pub async fn run_checks(
+    checks: &[ExternalCheck],
+    probe: ProbeKind,
+    timeout: Duration,
+    retries: u32,
+) -> Option<HealthStatus> {
+    let started = Instant::now();
+    let mut results = Vec::new();
+    for check in checks.iter().filter(|check| check.probe == probe) {
+        results.push(run_with_retries(check, timeout, retries).await);
+    }
+
+    if results.is_empty() {
+        return None;
+    }
+    Some(HealthStatus::from_checks(
+        results,
+        started.elapsed().as_millis() as u64,
+    ))
+}
+
+/// Runs one check, retrying a failure up to `retries` times.
+pub async fn run_with_retries(
+    check: &ExternalCheck,
+    timeout: Duration,
+    retries: u32,
+) -> HealthCheck {
+    let mut attempt = 0;
+    loop {
+        let result = run_check(check, timeout).await;
+        if result.passed || attempt >= retries {
+            return result;
+        }
+        attempt += 1;
+        tracing::debug!(check = %result.name, attempt, "retrying failed health check");
+        tokio::time::sleep(RETRY_DELAY).await;
+    }
+}
+
+/// Runs one attempt of a check within `timeout`.
+pub async fn run_check(check: &ExternalCheck, timeout: Duration) -> HealthCheck {
+    let outcome = tokio::time::timeout(timeout, probe(&check.kind))
+        .await
+        .unwrap_or_else(|_| Err(format!("timed out after {timeout:?}")));
+
+    HealthCheck {
+        name: check.name(),
+        passed: outcome.is_ok(),
+        message: outcome.err(),
+    }
+}
+
+/// Performs a single check.
+async fn probe(kind: &CheckKind) -> Outcome {
+    match kind {
+        CheckKind::Exec { command } => exec(command).await,
+        CheckKind::Tcp { address } => tokio::net::TcpStream::connect(address)
+            .await
+            .map(drop)
+            .map_err(|e| format!("connect to {address} failed: {e}")),
+        CheckKind::Http {
+            url,
+            expected_status,
+        } => http(url, *expected_status).await,
+        CheckKind::Unix { path } => unix(path).await,
+    }
+}
+
+/// Runs a command; passes on exit status 0.
+async fn exec(command: &[String]) -> Outcome {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| "empty command".to_string())?;
+
+    // Killed if the check times out
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("failed to run {program}: {e}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match stderr.lines().map(str::trim).find(|line| !line.is_empty()) {
+        Some(line) => Err(format!("{program} exited with {}: {line}", output.status)),
+        None => Err(format!("{program} exited with {}", output.status)),
+    }
+}
+
+/// Connects to a Unix socket.
+#[cfg(unix)]
+async fn unix(path: &std::path::Path) -> Outcome {
+    tokio::net::UnixStream::connect(path)
+        .await
+        .map(drop)
+        .map_err(|e| format!("connect to {} failed: {e}", path.display()))
+}
+
+/// Connects to a Unix socket.
+#[cfg(not(unix))]
+async fn unix(_path: &std::path::Path) -> Outcome {
+    Err("unix socket checks are not supported on this platform".to_string())
+}
+
+/// Sends an HTTP GET; passes if the response status is `expected`.
+async fn http(url: &str, expected: u16) -> Outcome {
+    let target = HttpTarget::parse(url).map_err(|e| e.to_string())?;
+    let mut stream = tokio::net::TcpStream::connect(&target.address)
+        .await
+        .map_err(|e| format!("connect to {} failed: {e}", target.address))?;
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: duende\r\nConnection: close\r\n\r\n",
+        target.path, target.host
+    );
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("request to {url} failed: {e}"))?;
+
+    let status = read_status(&mut stream).await?;
+    if status == expected {
+        Ok(())
+    } else {
+        Err(format!("HTTP {status}, expected {expected}"))
+    }
+}
+
+// =============================================================================
+// HTTP client
+// =============================================================================
+
+/// Where an HTTP check connects to, parsed from an `http://` URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpTarget {
+    /// `host:port` to connect to; port 80 if the URL has none.
+    pub address: String,
+    /// `Host` header value, as in the URL.
+    pub host: String,
+    /// Request path and query, `/` if the URL has none.
+    pub path: String,
+}
+
+impl HttpTarget {
+    /// Parses an `http://host[:port][/path]` URL.
+    ///
+    /// # Errors
+    /// Returns a configuration error for other schemes (TLS is not
+    /// supported) or a URL without a host.
+    pub fn parse(url: &str) -> Result<Self> {
+        let rest = url.strip_prefix("http://").ok_or_else(|| {
+            DaemonError::config(format!("http check url must start with http://: {url}"))
+        })?;
+        let (host, path) = rest
+            .find(['/', '?'])
+            .map_or((rest, "/"), |i| rest.split_at(i));
+        if host.is_empty() {
+            return Err(DaemonError::config(format!(
+                "http check url has no host: {url}"
+            )));
+        }
+
+        // An IPv6 literal ("[::1]") has colons but no port
+        let has_port = host
+            .rsplit_once(':')
+            .is_some_and(|(_, port)| !port.contains(']'));
+        let address = if has_port {
+            host.to_string()
+        } else {
+            format!("{host}:80")
+        };
+        let path = if path.starts_with('?') {
+            format!("/{path}")
+        } else {
+            path.to_string()
+        };
+
+        Ok(Self {
+            address,
+            host: host.to_string(),
+            path,
+        })
+    }
+}
+
+/// Reads the response status line from `stream` and returns its status
+/// code, leaving the rest of the response unread.
+///
+/// # Errors
+/// Describes the failure if reading fails, the connection closes first,
+/// or the line is longer than 1 KiB or not an HTTP status line.
+pub async fn read_status(stream: &mut tokio::net::TcpStream) -> std::result::Result<u16, String> {
+    let mut line = Vec::new();
+    let mut chunk = [0u8; 256];
+    while !line.contains(&b'\n') {
+        if line.len() > MAX_STATUS_LINE {
+            return Err("HTTP status line too long".to_string());
+        }
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("reading response failed: {e}"))?;
+        if n == 0 {
+            return Err("connection closed before HTTP status line".to_string());
+        }
+        line.extend_from_slice(&chunk[..n]);
+    }
+
+    parse_status_line(&line)
+}
+
+/// Parses the status code out of `HTTP/1.1 200 OK\r\n...`.
+fn parse_status_line(response: &[u8]) -> Outcome<u16> {
+    let line = response.split(|&b| b == b'\n').next().unwrap_or_default();
+    let line = String::from_utf8_lossy(line);
+    let mut parts = line.split_whitespace();
+    match (parts.next(), parts.next().map(str::parse)) {
+        (Some(version), Some(Ok(status))) if version.starts_with("HTTP/") => Ok(status),
+        _ => Err(format!("invalid HTTP status line: {}", line.trim())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::net::TcpListener;
+
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Serves HTTP responses with the given statuses, one per connection,
+    /// repeating the last one.
+    async fn http_server(statuses: &'static [u16]) -> (String, Arc<AtomicU32>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let served = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&served);
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let n = counter.fetch_add(1, Ordering::SeqCst) as usize;
+                let status = statuses[n.min(statuses.len() - 1)];
+
+                let mut request = vec![0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let response = format!("HTTP/1.1 {status} Status\r\nContent-Length: 0\r\n\r\n");
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (format!("http://{addr}/healthz"), served)
+    }
+
+    fn check(kind: CheckKind) -> ExternalCheck {
+        ExternalCheck::new(kind)
+    }
+
+    fn sh(script: &str) -> CheckKind {
+        CheckKind::Exec {
+            command: vec!["/bin/sh".to_string(), "-c".to_string(), script.to_string()],
+        }
+    }
+
+    #[test]
+    fn test_http_target_parse() {
+        let target = HttpTarget::parse("http://127.0.0.1:8080/healthz?deep=1").unwrap();
+        assert_eq!(target.address, "127.0.0.1:8080");
+        assert_eq!(target.host, "127.0.0.1:8080");
+        assert_eq!(target.path, "/healthz?deep=1");
+
+        let target = HttpTarget::parse("http://localhost").unwrap();
+        assert_eq!(target.address, "localhost:80");
+        assert_eq!(target.path, "/");
+
+        assert_eq!(
+            HttpTarget::parse("http://[::1]/x").unwrap().address,
+            "[::1]:80"
+        );
+        assert_eq!(HttpTarget::parse("http://host?a").unwrap().path, "/?a");
+        assert!(HttpTarget::parse("https://localhost/").is_err());
+        assert!(HttpTarget::parse("http:///path").is_err());
+    }
+
+    #[test]
+    fn test_parse_status_line() {
+        assert_eq!(parse_status_line(b"HTTP/1.1 204 No Content\r\n"), Ok(204));
+        assert_eq!(parse_status_line(b"HTTP/1.0 503\r\n"), Ok(503));
+        assert!(parse_status_line(b"SSH-2.0-OpenSSH\r\n").is_err());
+        assert!(parse_status_line(b"HTTP/1.1 abc\r\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tcp_check() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let result = run_check(
+            &check(CheckKind::Tcp {
+                address: address.clone(),
+            }),
+            TIMEOUT,
+        )
+        .await;
+        assert!(result.passed, "{:?}", result.message);
+        assert_eq!(result.name, format!("tcp://{address}"));
+
+        drop(listener);
+        let result = run_check(&check(CheckKind::Tcp { address }), TIMEOUT).await;
+        assert!(!result.passed);
+        assert!(result.message.unwrap().contains("connect to"));
+    }
+
+    #[tokio::test]
+    async fn test_http_check_expected_status() {
+        let (url, _) = http_server(&[200]).await;
+        let ok = CheckKind::Http {
+            url: url.clone(),
+            expected_status: 200,
+        };
+        assert!(run_check(&check(ok), TIMEOUT).await.passed);
+
+        let (url, _) = http_server(&[503]).await;
+        let unavailable = CheckKind::Http {
+            url,
+            expected_status: 200,
+        };
+        let result = run_check(&check(unavailable).with_name("api"), TIMEOUT).await;
+        assert!(!result.passed);
+        assert_eq!(result.name, "api");
+        assert_eq!(result.message.as_deref(), Some("HTTP 503, expected 200"));
+    }
+
+    #[tokio::test]
+    async fn test_http_check_retries() {
+        let (url, served) = http_server(&[503, 200]).await;
+        let kind = CheckKind::Http {
+            url,
+            expected_status: 200,
+        };
+
+        assert!(
+            !run_with_retries(&check(kind.clone()), TIMEOUT, 0)
+                .await
+                .passed
+        );
+        assert!(run_with_retries(&check(kind), TIMEOUT, 1).await.passed);
+        assert_eq!(served.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_http_check_times_out() {
+        // Accepts but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let kind = CheckKind::Http {
+            url,
+            expected_status: 200,
+        };
+
+        let result = run_check(&check(kind), Duration::from_millis(50)).await;
+        assert!(!result.passed);
+        assert!(result.message.unwrap().contains("timed out"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_check() {
+        let path = std::env::temp_dir().join(format!("duende-check-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+        let kind = CheckKind::Unix { path: path.clone() };
+        assert!(run_check(&check(kind.clone()), TIMEOUT).await.passed);
+
+        drop(listener);
+        std::fs::remove_file(&path).unwrap();
+        assert!(!run_check(&check(kind), TIMEOUT).await.passed);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_exec_check() {
+        assert!(run_check(&check(sh("exit 0")), TIMEOUT).await.passed);
+
+        let result = run_check(&check(sh("echo 'cache cold' >&2; exit 3")), TIMEOUT).await;
+        assert!(!result.passed);
+        let message = result.message.unwrap();
+        assert!(message.contains("exit status: 3"), "{message}");
+        assert!(message.ends_with("cache cold"), "{message}");
+
+        let result = run_check(&check(sh("sleep 5")), Duration::from_millis(50)).await;
+        assert!(result.message.unwrap().contains("timed out"));
+
+        let missing = CheckKind::Exec {
+            command: vec!["/nonexistent/duende-check".to_string()],
+        };
+        assert!(!run_check(&check(missing), TIMEOUT).await.passed);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_checks_by_probe() {
+        let checks = [
+            check(sh("exit 0")),
+            check(sh("exit 1")).with_probe(ProbeKind::Readiness),
+        ];
+
+        let liveness = run_checks(&checks, ProbeKind::Liveness, TIMEOUT, 0)
+            .await
+            .unwrap();
+        assert!(liveness.is_healthy());
+        assert_eq!(liveness.checks.len(), 1);
+
+        let readiness = run_checks(&checks, ProbeKind::Readiness, TIMEOUT, 0)
+            .await
+            .unwrap();
+        assert!(!readiness.is_healthy());
+        assert!(readiness.reason().unwrap().contains("exited with"));
+
+        assert!(
+            run_checks(&checks, ProbeKind::Startup, TIMEOUT, 0)
+                .await
+                .is_none()
+        );
+    }
+}
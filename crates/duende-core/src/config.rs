@@ -5,10 +5,12 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 
 use crate::error::{DaemonError, Result};
+use crate::types::ProbeKind;
 
 /// Daemon configuration.
 ///
@@ -54,6 +56,31 @@ pub struct DaemonConfig {
     #[serde(default)]
     pub working_dir: Option<PathBuf>,
 
+    /// Daemons (by name) that must be running before this one starts.
+    ///
+    /// Implies ordering. If a required daemon fails, this daemon fails too.
+    #[serde(default)]
+    pub requires: Vec<String>,
+
+    /// Daemons (by name) to start before this one, if registered.
+    ///
+    /// Implies ordering. Their failure does not affect this daemon.
+    #[serde(default)]
+    pub wants: Vec<String>,
+
+    /// Daemons (by name) this one starts after. Ordering only.
+    #[serde(default)]
+    pub after: Vec<String>,
+
+    /// Listening sockets bound on the daemon's behalf (socket activation).
+    #[serde(default)]
+    pub sockets: Vec<SocketConfig>,
+
+    /// Daemon reports readiness itself with `READY=1` (systemd
+    /// `Type=notify`); until then it stays `Starting`.
+    #[serde(default)]
+    pub notify: bool,
+
     /// Resource limits.
     #[serde(default)]
     pub resources: ResourceConfig,
@@ -66,6 +93,16 @@ pub struct DaemonConfig {
     #[serde(default)]
     pub restart: RestartPolicy,
 
+    /// Exponential backoff between restarts, with retry limit.
+    ///
+    /// Without it, restarts are retried forever after a fixed delay.
+    #[serde(default)]
+    pub backoff: Option<BackoffConfig>,
+
+    /// Start-rate limit; exceeding it quarantines the daemon.
+    #[serde(default)]
+    pub start_limit: Option<StartLimit>,
+
     /// Graceful shutdown timeout.
     #[serde(default = "default_shutdown_timeout")]
     #[serde(with = "humantime_serde")]
@@ -95,9 +132,16 @@ impl DaemonConfig {
             user: None,
             group: None,
             working_dir: None,
+            requires: vec![],
+            wants: vec![],
+            after: vec![],
+            sockets: vec![],
+            notify: false,
             resources: ResourceConfig::default(),
             health_check: HealthCheckConfig::default(),
             restart: RestartPolicy::default(),
+            backoff: None,
+            start_limit: None,
             shutdown_timeout: default_shutdown_timeout(),
             platform: PlatformConfig::default(),
         }
@@ -127,12 +171,56 @@ impl DaemonConfig {
             return Err(DaemonError::config("binary_path cannot be empty"));
         }
 
+        // A daemon cannot depend on itself
+        if self.dependencies().contains(&self.name.as_str()) {
+            return Err(DaemonError::config("daemon cannot depend on itself"));
+        }
+
+        // Socket names must be unique: they are how the daemon finds them
+        for (i, socket) in self.sockets.iter().enumerate() {
+            socket.validate()?;
+            if self.sockets[..i].iter().any(|s| s.name == socket.name) {
+                return Err(DaemonError::config(format!(
+                    "duplicate socket name: {}",
+                    socket.name
+                )));
+            }
+        }
+
         // Resource limits must be sensible
         self.resources.validate()?;
+        self.health_check.validate()?;
+
+        if let Some(ref backoff) = self.backoff {
+            backoff.validate()?;
+        }
+        if let Some(ref limit) = self.start_limit {
+            limit.validate()?;
+        }
 
         Ok(())
     }
 
+    /// Returns the names of all daemons this one is ordered after.
+    ///
+    /// Union of `requires`, `wants` and `after`, without duplicates.
+    #[must_use]
+    pub fn dependencies(&self) -> Vec<&str> {
+        let mut deps: Vec<&str> = Vec::new();
+        for name in self.requires.iter().chain(&self.wants).chain(&self.after) {
+            if !deps.contains(&name.as_str()) {
+                deps.push(name);
+            }
+        }
+        deps
+    }
+
+    /// Returns the structured difference from this configuration to `new`.
+    #[must_use]
+    pub fn diff(&self, new: &Self) -> ConfigDiff {
+        ConfigDiff::between(self, new)
+    }
+
     /// Loads configuration from a TOML file.
     ///
     /// # Errors
@@ -147,13 +235,225 @@ impl DaemonConfig {
     }
 }
 
+/// A single changed configuration field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigChange {
+    /// Dotted field path, e.g. `env.RUST_LOG` or `resources.memory_bytes`.
+    pub field: String,
+    /// Previous value (`None` if the field was added).
+    pub old: Option<String>,
+    /// New value (`None` if the field was removed).
+    pub new: Option<String>,
+    /// True if the change can be applied to a running daemon in place.
+    pub live: bool,
+}
+
+/// Structured difference between two daemon configurations.
+///
+/// # Toyota Way: Genchi Genbutsu (現地現物)
+/// A reload reports exactly which fields changed and whether each one
+/// can go live, instead of restarting blindly.
+///
+/// Health check interval/timeout/retries, readiness and startup probes,
+/// restart policy, backoff, start limit, shutdown timeout, version and
+/// description apply live. Everything that shapes the process itself
+/// (binary, args, env, credentials, resources, platform) needs a restart,
+/// as does enabling or disabling health checks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConfigDiff {
+    /// Changed fields, in declaration order.
+    pub changes: Vec<ConfigChange>,
+}
+
+impl ConfigDiff {
+    /// Computes the difference from `old` to `new`.
+    #[must_use]
+    pub fn between(old: &DaemonConfig, new: &DaemonConfig) -> Self {
+        let mut diff = Self::default();
+
+        diff.field("name", &old.name, &new.name, false);
+        diff.field("version", &old.version, &new.version, true);
+        diff.field("description", &old.description, &new.description, true);
+        diff.field("binary_path", &old.binary_path, &new.binary_path, false);
+        diff.field("args", &old.args, &new.args, false);
+
+        let mut keys: Vec<_> = old.env.keys().chain(new.env.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let (before, after) = (old.env.get(key), new.env.get(key));
+            if before != after {
+                diff.changes.push(ConfigChange {
+                    field: format!("env.{key}"),
+                    old: before.cloned(),
+                    new: after.cloned(),
+                    live: false,
+                });
+            }
+        }
+
+        diff.field("user", &old.user, &new.user, false);
+        diff.field("group", &old.group, &new.group, false);
+        diff.field("working_dir", &old.working_dir, &new.working_dir, false);
+        diff.field("requires", &old.requires, &new.requires, false);
+        diff.field("wants", &old.wants, &new.wants, false);
+        diff.field("after", &old.after, &new.after, false);
+        diff.field("sockets", &old.sockets, &new.sockets, false);
+        diff.field("notify", &old.notify, &new.notify, false);
+
+        let (r0, r1) = (&old.resources, &new.resources);
+        diff.field(
+            "resources.memory_bytes",
+            &r0.memory_bytes,
+            &r1.memory_bytes,
+            false,
+        );
+        diff.field(
+            "resources.memory_swap_bytes",
+            &r0.memory_swap_bytes,
+            &r1.memory_swap_bytes,
+            false,
+        );
+        diff.field(
+            "resources.address_space_bytes",
+            &r0.address_space_bytes,
+            &r1.address_space_bytes,
+            false,
+        );
+        diff.field(
+            "resources.cpu_quota_percent",
+            &r0.cpu_quota_percent,
+            &r1.cpu_quota_percent,
+            false,
+        );
+        diff.field(
+            "resources.cpu_shares",
+            &r0.cpu_shares,
+            &r1.cpu_shares,
+            false,
+        );
+        diff.field(
+            "resources.io_read_bps",
+            &r0.io_read_bps,
+            &r1.io_read_bps,
+            false,
+        );
+        diff.field(
+            "resources.io_write_bps",
+            &r0.io_write_bps,
+            &r1.io_write_bps,
+            false,
+        );
+        diff.field("resources.pids_max", &r0.pids_max, &r1.pids_max, false);
+        diff.field(
+            "resources.open_files_max",
+            &r0.open_files_max,
+            &r1.open_files_max,
+            false,
+        );
+        diff.field(
+            "resources.lock_memory",
+            &r0.lock_memory,
+            &r1.lock_memory,
+            false,
+        );
+        diff.field(
+            "resources.lock_memory_required",
+            &r0.lock_memory_required,
+            &r1.lock_memory_required,
+            false,
+        );
+
+        let (h0, h1) = (&old.health_check, &new.health_check);
+        diff.field("health_check.enabled", &h0.enabled, &h1.enabled, false);
+        diff.field("health_check.interval", &h0.interval, &h1.interval, true);
+        diff.field("health_check.timeout", &h0.timeout, &h1.timeout, true);
+        diff.field("health_check.retries", &h0.retries, &h1.retries, true);
+        diff.field("health_check.watchdog", &h0.watchdog, &h1.watchdog, false);
+        diff.field("health_check.readiness", &h0.readiness, &h1.readiness, true);
+        diff.field("health_check.startup", &h0.startup, &h1.startup, true);
+        diff.field("health_check.checks", &h0.checks, &h1.checks, true);
+
+        // The start limit and shutdown timeout are read at every start and
+        // stop; restart and backoff at every restart decision of a policy
+        // built from them (`DaemonManager::register_configured`)
+        diff.field("restart", &old.restart, &new.restart, true);
+        diff.field("backoff", &old.backoff, &new.backoff, true);
+        diff.field("start_limit", &old.start_limit, &new.start_limit, true);
+        diff.field(
+            "shutdown_timeout",
+            &old.shutdown_timeout,
+            &new.shutdown_timeout,
+            true,
+        );
+        diff.field("platform", &old.platform, &new.platform, false);
+
+        diff
+    }
+
+    /// Records a change if the debug representations differ.
+    fn field<T: std::fmt::Debug>(&mut self, field: &str, old: &T, new: &T, live: bool) {
+        let (old, new) = (format!("{old:?}"), format!("{new:?}"));
+        if old != new {
+            self.changes.push(ConfigChange {
+                field: field.to_string(),
+                old: Some(old),
+                new: Some(new),
+                live,
+            });
+        }
+    }
+
+    /// Returns true if nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns true if any change needs a restart to take effect.
+    #[must_use]
+    pub fn requires_restart(&self) -> bool {
+        self.changes.iter().any(|c| !c.live)
+    }
+
+    /// Returns true if any change can be applied in place.
+    #[must_use]
+    pub fn has_live_changes(&self) -> bool {
+        self.changes.iter().any(|c| c.live)
+    }
+
+    /// Returns the changes that can be applied in place.
+    pub fn live_changes(&self) -> impl Iterator<Item = &ConfigChange> {
+        self.changes.iter().filter(|c| c.live)
+    }
+
+    /// Returns the changes that need a restart.
+    pub fn restart_changes(&self) -> impl Iterator<Item = &ConfigChange> {
+        self.changes.iter().filter(|c| !c.live)
+    }
+}
+
 /// Resource limits configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceConfig {
     /// Memory limit in bytes.
+    ///
+    /// Limits memory in use (RSS plus page cache), enforced through a
+    /// cgroup by the systemd and container adapters. The native adapter
+    /// has no cgroup and does not enforce it; see
+    /// [`address_space_bytes`](Self::address_space_bytes).
     #[serde(default = "default_memory_limit")]
     pub memory_bytes: u64,
 
+    /// Virtual address-space limit in bytes (`RLIMIT_AS`); 0 = unlimited.
+    ///
+    /// Enforced by the native adapter, and only when set. Address space is
+    /// not memory in use: malloc arenas, thread stacks, async runtimes, Go
+    /// and JVM heaps and mmap'd files reserve far more than they touch, so
+    /// a limit near the expected RSS makes allocations fail early.
+    #[serde(default)]
+    pub address_space_bytes: u64,
+
     /// Memory + swap limit in bytes.
     #[serde(default = "default_memory_swap_limit")]
     pub memory_swap_bytes: u64,
@@ -238,6 +538,7 @@ impl Default for ResourceConfig {
         Self {
             memory_bytes: default_memory_limit(),
             memory_swap_bytes: default_memory_swap_limit(),
+            address_space_bytes: 0, // Unlimited
             cpu_quota_percent: default_cpu_quota(),
             cpu_shares: default_cpu_shares(),
             io_read_bps: 0,  // Unlimited
@@ -270,25 +571,68 @@ impl ResourceConfig {
 }
 
 /// Health check configuration.
+///
+/// Configures the three probes of [`ProbeKind`](crate::types::ProbeKind).
+/// The top-level `interval`, `timeout` and `retries` are the liveness
+/// probe's; `readiness` and `startup` have their own.
+///
+/// ```toml
+/// [health_check]
+/// interval = "30s"
+/// retries = 3
+///
+/// [health_check.readiness]
+/// interval = "5s"
+///
+/// [health_check.startup]
+/// grace_period = "2m"
+/// interval = "1s"
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthCheckConfig {
     /// Whether health checks are enabled.
     #[serde(default = "default_true")]
     pub enabled: bool,
 
-    /// Health check interval.
+    /// Liveness check interval. Zero disables periodic checks.
     #[serde(default = "default_health_interval")]
     #[serde(with = "humantime_serde")]
     pub interval: Duration,
 
-    /// Health check timeout.
+    /// Liveness check timeout.
     #[serde(default = "default_health_timeout")]
     #[serde(with = "humantime_serde")]
     pub timeout: Duration,
 
-    /// Number of retries before marking unhealthy.
+    /// Consecutive liveness failures tolerated before the daemon is
+    /// restarted.
     #[serde(default = "default_health_retries")]
     pub retries: u32,
+
+    /// Watchdog deadline: the daemon must call `DaemonContext::heartbeat`
+    /// (or send `WATCHDOG=1`) at least this often, or it is aborted as hung
+    /// and restarted. An in-process `run()` that blocks its thread is
+    /// abandoned instead, without a restart. Zero disables the watchdog.
+    /// Emitted as `WatchdogSec`.
+    #[serde(default)]
+    #[serde(with = "humantime_serde")]
+    pub watchdog: Duration,
+
+    /// Readiness probe.
+    #[serde(default)]
+    pub readiness: ProbeConfig,
+
+    /// Startup probe.
+    #[serde(default)]
+    pub startup: StartupProbeConfig,
+
+    /// Checks the framework runs itself, for daemons spawned as separate
+    /// binaries: on each probe's interval by the supervisor, or on demand
+    /// by [`DaemonManager::run_checks`].
+    ///
+    /// [`DaemonManager::run_checks`]: crate::manager::DaemonManager::run_checks
+    #[serde(default)]
+    pub checks: Vec<ExternalCheck>,
 }
 
 fn default_true() -> bool {
@@ -314,6 +658,257 @@ impl Default for HealthCheckConfig {
             interval: default_health_interval(),
             timeout: default_health_timeout(),
             retries: default_health_retries(),
+            watchdog: Duration::ZERO,
+            readiness: ProbeConfig::default(),
+            startup: StartupProbeConfig::default(),
+            checks: vec![],
+        }
+    }
+}
+
+impl HealthCheckConfig {
+    /// Returns the liveness probe configuration.
+    #[must_use]
+    pub const fn liveness(&self) -> ProbeConfig {
+        ProbeConfig {
+            interval: self.interval,
+            timeout: self.timeout,
+            retries: self.retries,
+        }
+    }
+
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    /// Returns an error if the startup probe has a grace period but no
+    /// interval, or an external check is invalid.
+    pub fn validate(&self) -> Result<()> {
+        self.startup.validate()?;
+        for check in &self.checks {
+            check.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// Periodic probe configuration (liveness or readiness).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProbeConfig {
+    /// Probe interval. Zero disables periodic probing.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// Probe timeout; a probe that overruns it failed.
+    #[serde(with = "humantime_serde")]
+    pub timeout: Duration,
+    /// Consecutive failures tolerated before the probe counts as failed.
+    pub retries: u32,
+}
+
+impl Default for ProbeConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+            timeout: default_health_timeout(),
+            retries: default_health_retries(),
+        }
+    }
+}
+
+/// Startup probe configuration.
+///
+/// While the startup probe has not passed, liveness and readiness are not
+/// probed and the daemon is not ready. A daemon that does not pass within
+/// the grace period is restarted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartupProbeConfig {
+    /// Time the daemon has to pass the startup probe. Zero disables the
+    /// startup probe.
+    #[serde(with = "humantime_serde")]
+    pub grace_period: Duration,
+    /// Probe interval while starting.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// Probe timeout.
+    #[serde(with = "humantime_serde")]
+    pub timeout: Duration,
+}
+
+impl Default for StartupProbeConfig {
+    fn default() -> Self {
+        Self {
+            grace_period: Duration::ZERO,
+            interval: Duration::from_secs(1),
+            timeout: default_health_timeout(),
+        }
+    }
+}
+
+impl StartupProbeConfig {
+    /// Creates a startup probe with the given grace period.
+    #[must_use]
+    pub fn new(grace_period: Duration) -> Self {
+        Self {
+            grace_period,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the probe interval.
+    #[must_use]
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns true if the startup probe is enabled.
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        !self.grace_period.is_zero()
+    }
+
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    /// Returns an error if a grace period is set without an interval.
+    pub fn validate(&self) -> Result<()> {
+        if self.is_enabled() && self.interval.is_zero() {
+            return Err(DaemonError::config(
+                "health_check.startup.interval must be greater than 0",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A health check the manager runs itself, outside the daemon.
+///
+/// Each attempt is bounded by the probe's `timeout` and a failing check is
+/// retried up to the probe's `retries` times.
+///
+/// ```toml
+/// [[health_check.checks]]
+/// type = "http"
+/// url = "http://127.0.0.1:8080/healthz"
+///
+/// [[health_check.checks]]
+/// type = "tcp"
+/// address = "127.0.0.1:5432"
+/// probe = "readiness"
+///
+/// [[health_check.checks]]
+/// type = "exec"
+/// command = ["/usr/bin/my-daemon-ctl", "ping"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalCheck {
+    /// Name reported in the resulting `HealthCheck`; derived from the
+    /// target if unset.
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Probe the check belongs to.
+    #[serde(default)]
+    pub probe: ProbeKind,
+
+    /// What to check.
+    #[serde(flatten)]
+    pub kind: CheckKind,
+}
+
+impl ExternalCheck {
+    /// Creates a liveness check.
+    #[must_use]
+    pub const fn new(kind: CheckKind) -> Self {
+        Self {
+            name: None,
+            probe: ProbeKind::Liveness,
+            kind,
+        }
+    }
+
+    /// Sets the reported name.
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the probe the check belongs to.
+    #[must_use]
+    pub const fn with_probe(mut self, probe: ProbeKind) -> Self {
+        self.probe = probe;
+        self
+    }
+
+    /// Returns the reported name.
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.kind.to_string())
+    }
+
+    /// Validates the check.
+    ///
+    /// # Errors
+    /// Returns an error if the command, address or URL is unusable.
+    pub fn validate(&self) -> Result<()> {
+        match &self.kind {
+            CheckKind::Exec { command } if command.is_empty() => {
+                Err(DaemonError::config("exec check command cannot be empty"))
+            }
+            CheckKind::Tcp { address } if address.is_empty() => {
+                Err(DaemonError::config("tcp check address cannot be empty"))
+            }
+            CheckKind::Http { url, .. } => crate::checks::HttpTarget::parse(url).map(|_| ()),
+            CheckKind::Unix { path } if path.as_os_str().is_empty() => {
+                Err(DaemonError::config("unix check path cannot be empty"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Kind of external health check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CheckKind {
+    /// Runs a command; passes if it exits with status 0.
+    Exec {
+        /// Program and arguments.
+        command: Vec<String>,
+    },
+    /// Passes if a TCP connection can be opened.
+    Tcp {
+        /// `host:port` to connect to.
+        address: String,
+    },
+    /// Sends an HTTP GET; passes if the response has the expected status.
+    Http {
+        /// `http://` URL to request.
+        url: String,
+        /// Expected response status.
+        #[serde(default = "default_expected_status")]
+        expected_status: u16,
+    },
+    /// Passes if the Unix socket accepts a connection.
+    Unix {
+        /// Socket path.
+        path: PathBuf,
+    },
+}
+
+fn default_expected_status() -> u16 {
+    200
+}
+
+impl std::fmt::Display for CheckKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exec { command } => write!(f, "exec:{}", command.join(" ")),
+            Self::Tcp { address } => write!(f, "tcp://{address}"),
+            Self::Http { url, .. } => write!(f, "{url}"),
+            Self::Unix { path } => write!(f, "unix://{}", path.display()),
         }
     }
 }
@@ -333,6 +928,293 @@ pub enum RestartPolicy {
     UnlessStopped,
 }
 
+/// Backoff configuration for restart delays.
+///
+/// ```toml
+/// [backoff]
+/// initial_delay = "1s"
+/// max_delay = "5m"
+/// multiplier = 2.0
+/// jitter = 0.1
+/// max_retries = 10
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackoffConfig {
+    /// Initial delay before first restart.
+    #[serde(with = "humantime_serde")]
+    pub initial_delay: Duration,
+    /// Maximum delay between restarts.
+    #[serde(with = "humantime_serde")]
+    pub max_delay: Duration,
+    /// Multiplier for exponential backoff.
+    pub multiplier: f64,
+    /// Random spread applied to each delay, as a fraction (0.0-1.0).
+    ///
+    /// 0.1 spreads a 10s delay over 9-11s, so daemons that failed together
+    /// do not all restart at the same instant.
+    pub jitter: f64,
+    /// Maximum number of retries.
+    pub max_retries: u32,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(300), // 5 minutes
+            multiplier: 2.0,
+            jitter: 0.0,
+            max_retries: 10,
+        }
+    }
+}
+
+impl BackoffConfig {
+    /// Creates a new backoff config with builder pattern.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the initial delay.
+    #[must_use]
+    pub const fn with_initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Sets the max delay.
+    #[must_use]
+    pub const fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Sets the multiplier.
+    #[must_use]
+    pub const fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Sets the jitter fraction.
+    #[must_use]
+    pub const fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the max retries.
+    #[must_use]
+    pub const fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Validates the backoff configuration.
+    ///
+    /// # Errors
+    /// Returns an error if the configuration is invalid.
+    pub fn validate(&self) -> Result<()> {
+        if self.multiplier < 1.0 {
+            return Err(DaemonError::config(
+                "backoff multiplier must be at least 1.0",
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err(DaemonError::config(
+                "backoff jitter must be between 0.0 and 1.0",
+            ));
+        }
+        if self.initial_delay > self.max_delay {
+            return Err(DaemonError::config(
+                "backoff initial_delay must not exceed max_delay",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Calculates delay for given restart count (exponential backoff).
+    ///
+    /// With jitter, the delay is randomized by up to `jitter` either way,
+    /// then clamped to `max_delay`.
+    #[must_use]
+    pub fn delay_for(&self, restart_count: u32) -> Duration {
+        let base_secs = self.initial_delay.as_secs_f64();
+        #[allow(clippy::cast_possible_wrap)] // restart_count won't exceed i32::MAX
+        let exp_secs = base_secs * self.multiplier.powi(restart_count as i32);
+        let jittered_secs = if self.jitter > 0.0 {
+            exp_secs
+                * self
+                    .jitter
+                    .mul_add(2.0f64.mul_add(jitter_sample(), -1.0), 1.0)
+        } else {
+            exp_secs
+        };
+        let clamped_secs = jittered_secs.clamp(0.0, self.max_delay.as_secs_f64());
+        Duration::from_secs_f64(clamped_secs)
+    }
+}
+
+/// Returns a random sample in `[0, 1)`.
+///
+/// Jitter needs spread, not cryptographic quality: std's per-instance
+/// randomized hasher keys are enough.
+fn jitter_sample() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+
+    let bits = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    #[allow(clippy::cast_precision_loss)] // 53 bits fit an f64 mantissa
+    let sample = (bits >> 11) as f64 / (1u64 << 53) as f64;
+    sample
+}
+
+/// Start-rate limit, as systemd's `StartLimitBurst`/`StartLimitIntervalSec`.
+///
+/// # Toyota Way: Jidoka (自働化)
+/// A daemon that keeps crashing on start is stopped and quarantined
+/// (`Failed(StartLimitHit)`) instead of looping; an operator must reset it.
+///
+/// ```toml
+/// [start_limit]
+/// burst = 5
+/// interval = "10s"
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartLimit {
+    /// Maximum starts within `interval`.
+    #[serde(default = "default_start_limit_burst")]
+    pub burst: u32,
+
+    /// Sliding window the starts are counted in.
+    #[serde(default = "default_start_limit_interval")]
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+}
+
+fn default_start_limit_burst() -> u32 {
+    5
+}
+
+fn default_start_limit_interval() -> Duration {
+    Duration::from_secs(10)
+}
+
+impl Default for StartLimit {
+    fn default() -> Self {
+        Self {
+            burst: default_start_limit_burst(),
+            interval: default_start_limit_interval(),
+        }
+    }
+}
+
+impl StartLimit {
+    /// Creates a limit of `burst` starts per `interval`.
+    #[must_use]
+    pub const fn new(burst: u32, interval: Duration) -> Self {
+        Self { burst, interval }
+    }
+
+    /// Validates the start limit.
+    ///
+    /// # Errors
+    /// Returns an error if the limit is invalid.
+    pub fn validate(&self) -> Result<()> {
+        if self.burst == 0 {
+            return Err(DaemonError::config(
+                "start_limit burst must be greater than 0",
+            ));
+        }
+        if self.interval.is_zero() {
+            return Err(DaemonError::config("start_limit interval must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// A listening socket bound before the daemon starts (socket activation).
+///
+/// The daemon receives it as an inherited file descriptor under the
+/// `LISTEN_FDS` convention, whether systemd or the native spawner bound it,
+/// and looks it up by name with `DaemonContext::listeners()`. The socket
+/// outlives daemon restarts, so no connection is refused in between.
+///
+/// ```toml
+/// [[sockets]]
+/// name = "http"
+/// tcp = "0.0.0.0:8080"
+///
+/// [[sockets]]
+/// name = "control"
+/// unix = "/run/my-daemon.sock"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SocketConfig {
+    /// Name passed in `LISTEN_FDNAMES`.
+    pub name: String,
+
+    /// Address to listen on.
+    #[serde(flatten)]
+    pub listen: ListenAddress,
+}
+
+impl SocketConfig {
+    /// Creates a socket configuration.
+    #[must_use]
+    pub fn new(name: impl Into<String>, listen: ListenAddress) -> Self {
+        Self {
+            name: name.into(),
+            listen,
+        }
+    }
+
+    /// Validates the socket configuration.
+    ///
+    /// # Errors
+    /// Returns an error if the name cannot be passed in `LISTEN_FDNAMES`.
+    pub fn validate(&self) -> Result<()> {
+        // sd_listen_fds_with_names(3): up to 255 printable chars, no ':'
+        if self.name.is_empty() || self.name.len() > 255 {
+            return Err(DaemonError::config(
+                "socket name must be 1 to 255 characters",
+            ));
+        }
+        if self.name.chars().any(|c| c == ':' || !c.is_ascii_graphic()) {
+            return Err(DaemonError::config(format!(
+                "socket name must be printable ASCII without ':': {}",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Address of a listening socket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListenAddress {
+    /// TCP stream socket.
+    Tcp(SocketAddr),
+    /// UDP datagram socket.
+    Udp(SocketAddr),
+    /// Unix stream socket at a filesystem path.
+    Unix(PathBuf),
+}
+
+impl std::fmt::Display for ListenAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "tcp://{addr}"),
+            Self::Udp(addr) => write!(f, "udp://{addr}"),
+            Self::Unix(path) => write!(f, "unix://{}", path.display()),
+        }
+    }
+}
+
 /// Platform-specific configuration.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PlatformConfig {
@@ -358,7 +1240,7 @@ pub struct PlatformConfig {
 }
 
 /// Serde helper for humantime durations.
-mod humantime_serde {
+pub(crate) mod humantime_serde {
     use serde::{Deserialize, Deserializer, Serializer};
     use std::time::Duration;
 
@@ -498,6 +1380,108 @@ mod tests {
         assert_eq!(config.interval, Duration::from_secs(30));
         assert_eq!(config.timeout, Duration::from_secs(10));
         assert_eq!(config.retries, 3);
+        assert_eq!(config.liveness().interval, config.interval);
+        assert_eq!(config.readiness.interval, Duration::from_secs(10));
+        assert!(!config.startup.is_enabled());
+    }
+
+    #[test]
+    fn test_health_check_probes_from_toml() {
+        let config: DaemonConfig = toml::from_str(
+            r#"
+            name = "model-server"
+            version = "1.0.0"
+            binary_path = "/usr/bin/model-server"
+
+            [health_check]
+            interval = "15s"
+
+            [health_check.readiness]
+            interval = "2s"
+            retries = 0
+
+            [health_check.startup]
+            grace_period = "2m"
+            "#,
+        )
+        .unwrap();
+
+        let health = &config.health_check;
+        assert_eq!(health.liveness().interval, Duration::from_secs(15));
+        assert_eq!(health.readiness.interval, Duration::from_secs(2));
+        assert_eq!(health.readiness.retries, 0);
+        assert_eq!(health.readiness.timeout, Duration::from_secs(10));
+        assert_eq!(health.startup.grace_period, Duration::from_secs(120));
+        assert_eq!(health.startup.interval, Duration::from_secs(1));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_external_checks_from_toml() {
+        let config: DaemonConfig = toml::from_str(
+            r#"
+            name = "model-server"
+            version = "1.0.0"
+            binary_path = "/usr/bin/model-server"
+
+            [[health_check.checks]]
+            type = "http"
+            url = "http://127.0.0.1:8080/healthz"
+
+            [[health_check.checks]]
+            type = "tcp"
+            address = "127.0.0.1:5432"
+            probe = "readiness"
+
+            [[health_check.checks]]
+            type = "exec"
+            name = "ping"
+            command = ["/usr/bin/model-server-ctl", "ping"]
+
+            [[health_check.checks]]
+            type = "unix"
+            path = "/run/model-server.sock"
+            "#,
+        )
+        .unwrap();
+
+        let checks = &config.health_check.checks;
+        assert_eq!(checks.len(), 4);
+        assert_eq!(
+            checks[0].kind,
+            CheckKind::Http {
+                url: "http://127.0.0.1:8080/healthz".to_string(),
+                expected_status: 200,
+            }
+        );
+        assert_eq!(checks[0].probe, ProbeKind::Liveness);
+        assert_eq!(checks[1].probe, ProbeKind::Readiness);
+        assert_eq!(checks[1].name(), "tcp://127.0.0.1:5432");
+        assert_eq!(checks[2].name(), "ping");
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_external_check_validation() {
+        let mut config = DaemonConfig::new("test", "/bin/test");
+        config.health_check.checks = vec![ExternalCheck::new(CheckKind::Http {
+            url: "https://localhost/".to_string(),
+            expected_status: 200,
+        })];
+        assert!(config.validate().is_err());
+
+        config.health_check.checks = vec![ExternalCheck::new(CheckKind::Exec { command: vec![] })];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_startup_probe_requires_interval() {
+        let mut config = DaemonConfig::new("test", "/bin/test");
+        config.health_check.startup = StartupProbeConfig::new(Duration::from_secs(60));
+        assert!(config.validate().is_ok());
+
+        config.health_check.startup = config.health_check.startup.with_interval(Duration::ZERO);
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -524,6 +1508,238 @@ mod tests {
         assert_eq!(config.name, deserialized.name);
     }
 
+    #[test]
+    fn test_config_dependencies() {
+        let mut config = DaemonConfig::new("model-server", "/bin/test");
+        assert!(config.dependencies().is_empty());
+
+        config.requires.push("zram".to_string());
+        config.wants.push("metrics".to_string());
+        config.after.push("zram".to_string());
+        assert_eq!(config.dependencies(), ["zram", "metrics"]);
+        assert!(config.validate().is_ok());
+
+        config.after.push("model-server".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_dependencies_from_toml() {
+        let config: DaemonConfig = toml::from_str(
+            r#"
+            name = "model-server"
+            version = "1.0.0"
+            binary_path = "/usr/bin/model-server"
+            requires = ["zram"]
+            after = ["network"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.requires, ["zram"]);
+        assert!(config.wants.is_empty());
+        assert_eq!(config.after, ["network"]);
+    }
+
+    #[test]
+    fn test_config_restart_backoff_from_toml() {
+        let config: DaemonConfig = toml::from_str(
+            r#"
+            name = "model-server"
+            version = "1.0.0"
+            binary_path = "/usr/bin/model-server"
+            restart = "always"
+
+            [backoff]
+            initial_delay = "500ms"
+            max_delay = "1m"
+            jitter = 0.2
+            max_retries = 3
+
+            [start_limit]
+            burst = 4
+            interval = "30s"
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let backoff = config.backoff.as_ref().unwrap();
+        assert_eq!(backoff.initial_delay, Duration::from_millis(500));
+        assert_eq!(backoff.max_delay, Duration::from_secs(60));
+        assert_eq!(backoff.multiplier, 2.0); // default
+        assert_eq!(backoff.jitter, 0.2);
+        assert_eq!(backoff.max_retries, 3);
+        assert_eq!(
+            config.start_limit,
+            Some(StartLimit::new(4, Duration::from_secs(30)))
+        );
+
+        // Round-trips through TOML
+        let toml = toml::to_string(&config).unwrap();
+        let parsed: DaemonConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.backoff, config.backoff);
+        assert_eq!(parsed.start_limit, config.start_limit);
+    }
+
+    #[test]
+    fn test_config_sockets_from_toml() {
+        let config: DaemonConfig = toml::from_str(
+            r#"
+            name = "api"
+            version = "1.0.0"
+            binary_path = "/usr/bin/api"
+
+            [[sockets]]
+            name = "http"
+            tcp = "0.0.0.0:8080"
+
+            [[sockets]]
+            name = "stats"
+            udp = "[::1]:8125"
+
+            [[sockets]]
+            name = "control"
+            unix = "/run/api.sock"
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(
+            config.sockets,
+            [
+                SocketConfig::new("http", ListenAddress::Tcp("0.0.0.0:8080".parse().unwrap())),
+                SocketConfig::new("stats", ListenAddress::Udp("[::1]:8125".parse().unwrap())),
+                SocketConfig::new("control", ListenAddress::Unix("/run/api.sock".into())),
+            ]
+        );
+        assert_eq!(config.sockets[2].listen.to_string(), "unix:///run/api.sock");
+
+        // Round-trips through TOML and JSON (state file)
+        let parsed: DaemonConfig = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(parsed.sockets, config.sockets);
+        let parsed: DaemonConfig =
+            serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(parsed.sockets, config.sockets);
+
+        // Sockets are bound at start: changing them needs a restart
+        let diff = DaemonConfig::new("api", "/usr/bin/api").diff(&config);
+        assert!(diff.restart_changes().any(|c| c.field == "sockets"));
+    }
+
+    #[test]
+    fn test_config_validate_sockets() {
+        let tcp = ListenAddress::Tcp("127.0.0.1:8080".parse().unwrap());
+        let mut config = DaemonConfig::new("test", "/bin/test");
+
+        config.sockets = vec![SocketConfig::new("a:b", tcp.clone())];
+        assert!(config.validate().is_err());
+
+        config.sockets = vec![SocketConfig::new("", tcp.clone())];
+        assert!(config.validate().is_err());
+
+        config.sockets = vec![
+            SocketConfig::new("http", tcp.clone()),
+            SocketConfig::new("http", tcp.clone()),
+        ];
+        assert!(config.validate().is_err());
+
+        config.sockets.pop();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_validate_backoff_and_start_limit() {
+        let mut config = DaemonConfig::new("test", "/bin/test");
+        config.backoff = Some(BackoffConfig::new().with_jitter(1.5));
+        assert!(config.validate().is_err());
+
+        config.backoff = Some(BackoffConfig::new().with_multiplier(0.5));
+        assert!(config.validate().is_err());
+
+        config.backoff = Some(BackoffConfig::new());
+        config.start_limit = Some(StartLimit::new(0, Duration::from_secs(10)));
+        assert!(config.validate().is_err());
+
+        config.start_limit = Some(StartLimit::default());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_backoff_jitter_stays_in_range() {
+        let backoff = BackoffConfig::new()
+            .with_initial_delay(Duration::from_secs(10))
+            .with_max_delay(Duration::from_secs(60))
+            .with_jitter(0.1);
+
+        for _ in 0..100 {
+            let delay = backoff.delay_for(0);
+            assert!(delay >= Duration::from_secs(9), "{delay:?}");
+            assert!(delay <= Duration::from_secs(11), "{delay:?}");
+        }
+
+        // Clamped after jitter
+        assert!(backoff.delay_for(10) <= Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_config_diff_empty() {
+        let config = DaemonConfig::new("test", "/bin/test");
+        let diff = config.diff(&config.clone());
+        assert!(diff.is_empty());
+        assert!(!diff.requires_restart());
+    }
+
+    #[test]
+    fn test_config_diff_classifies_changes() {
+        let old = DaemonConfig::new("test", "/bin/test");
+        let mut new = old.clone();
+        new.health_check.interval = Duration::from_secs(5);
+        new.shutdown_timeout = Duration::from_secs(10);
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.changes.len(), 2);
+        assert!(diff.has_live_changes());
+        assert!(!diff.requires_restart());
+
+        new.args.push("--verbose".to_string());
+        new.env.insert("RUST_LOG".to_string(), "debug".to_string());
+        new.resources.memory_bytes *= 2;
+
+        let diff = old.diff(&new);
+        let restart: Vec<_> = diff.restart_changes().map(|c| c.field.as_str()).collect();
+        assert_eq!(restart, ["args", "env.RUST_LOG", "resources.memory_bytes"]);
+        assert_eq!(diff.live_changes().count(), 2);
+        assert!(diff.requires_restart());
+    }
+
+    #[test]
+    fn test_config_diff_env_added_and_removed() {
+        let mut old = DaemonConfig::new("test", "/bin/test");
+        old.env.insert("OLD".to_string(), "1".to_string());
+        let mut new = DaemonConfig::new("test", "/bin/test");
+        new.env.insert("NEW".to_string(), "2".to_string());
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.changes,
+            vec![
+                ConfigChange {
+                    field: "env.NEW".to_string(),
+                    old: None,
+                    new: Some("2".to_string()),
+                    live: false,
+                },
+                ConfigChange {
+                    field: "env.OLD".to_string(),
+                    old: Some("1".to_string()),
+                    new: None,
+                    live: false,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_resource_config_serialize_roundtrip() {
         let config = ResourceConfig::default();
@@ -4,15 +4,24 @@
 //! Every daemon follows the same lifecycle contract, enabling
 //! predictable behavior across platforms.
 
-use std::time::Duration;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
 
+use crate::activation::Listeners;
 use crate::config::DaemonConfig;
 use crate::error::{DaemonError, Result};
 use crate::metrics::DaemonMetrics;
-use crate::types::{DaemonId, ExitReason, HealthStatus, Signal};
+#[cfg(unix)]
+use crate::notify::Notifier;
+use crate::notify::{NotifyState, watchdog_from_env};
+use crate::shutdown::{ShutdownToken, SubtaskReport};
+use crate::trace::TraceContext;
+use crate::types::{DaemonId, ExitReason, HealthStatus, ProbeKind, Signal};
 
 /// Core daemon abstraction for cross-platform lifecycle management.
 ///
@@ -21,8 +30,9 @@ use crate::types::{DaemonId, ExitReason, HealthStatus, Signal};
 /// 1. **init**: Validate configuration, allocate resources, open connections.
 ///    Should be fast (< 100ms for most platforms).
 ///
-/// 2. **run**: Main execution loop. Check `ctx.should_shutdown()` regularly.
-///    Handle signals via `ctx.recv_signal()`.
+/// 2. **run**: Main execution loop. Wait on `ctx.cancelled()` (or poll
+///    `ctx.should_shutdown()`). Handle signals via `ctx.recv_signal()`.
+///    Spawn background work with `ctx.spawn()` so it is joined at shutdown.
 ///
 /// 3. **shutdown**: Clean up resources, close connections, flush buffers.
 ///    Must complete within the configured timeout.
@@ -30,6 +40,12 @@ use crate::types::{DaemonId, ExitReason, HealthStatus, Signal};
 /// 4. **health_check**: Return current health status. Called periodically
 ///    by the platform adapter.
 ///
+/// 5. **reload_handler**: Return a [`ReloadHandler`] that applies a
+///    reloaded configuration in place (optional). `DaemonManager::reload`,
+///    e.g. on SIGHUP, reaches it while `run()` executes; `run()` holds
+///    `&mut self`, so the handler is a shared object of its own, as with
+///    `health_probe`. There is no `reload` method on the daemon itself.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -52,15 +68,15 @@ use crate::types::{DaemonId, ExitReason, HealthStatus, Signal};
 ///     }
 ///
 ///     async fn run(&mut self, ctx: &mut DaemonContext) -> Result<ExitReason, DaemonError> {
+///         let shutdown = ctx.shutdown_token();
 ///         loop {
-///             if ctx.should_shutdown() {
-///                 return Ok(ExitReason::Graceful);
+///             tokio::select! {
+///                 () = shutdown.cancelled() => return Ok(ExitReason::Graceful),
+///                 () = tokio::time::sleep(Duration::from_millis(100)) => {
+///                     // Do work...
+///                     self.metrics.record_request();
+///                 }
 ///             }
-///
-///             // Do work...
-///             self.metrics.record_request();
-///
-///             tokio::time::sleep(Duration::from_millis(100)).await;
 ///         }
 ///     }
 ///
@@ -105,7 +121,8 @@ pub trait Daemon: Send + Sync + 'static {
     /// This method contains the daemon's main logic. It should:
     /// - Process work items
     /// - Handle signals via `ctx.recv_signal()`
-    /// - Check `ctx.should_shutdown()` regularly
+    /// - Stop when `ctx.cancelled()` completes (or `ctx.should_shutdown()`)
+    /// - Spawn background work via `ctx.spawn()`
     /// - Update metrics
     ///
     /// # Heijunka
@@ -135,20 +152,124 @@ pub trait Daemon: Send + Sync + 'static {
     /// Returns an error if shutdown fails (timeout, resource leak, etc.)
     async fn shutdown(&mut self, timeout: Duration) -> Result<()>;
 
-    /// Performs a health check.
+    /// Performs a health check: the liveness probe.
     ///
     /// This method is called periodically by the platform adapter.
-    /// It should return quickly (< 1s) and not block.
+    /// It should return quickly (< 1s) and not block. Failing it
+    /// restarts the daemon, so report only what a restart fixes.
     ///
     /// # Genchi Genbutsu
     /// Direct observation of daemon health.
     async fn health_check(&self) -> HealthStatus;
 
+    /// Checks whether the daemon can serve right now: the readiness probe.
+    ///
+    /// Failing it marks the daemon not ready, without restarting it (e.g.
+    /// while warming a cache or shedding load). The default is ready
+    /// whenever alive.
+    async fn readiness_check(&self) -> HealthStatus {
+        self.health_check().await
+    }
+
+    /// Checks whether the daemon has finished starting: the startup probe.
+    ///
+    /// Only probed when the configuration sets a startup grace period.
+    /// The default reports started as soon as `init()` returned.
+    async fn startup_check(&self) -> HealthStatus {
+        HealthStatus::healthy(0)
+    }
+
     /// Returns the daemon's metrics.
     ///
     /// # Kaizen
     /// Continuous improvement via metrics collection.
     fn metrics(&self) -> &DaemonMetrics;
+
+    /// Returns a probe that can check health while `run()` is executing.
+    ///
+    /// `run()` holds `&mut self`, so a supervisor cannot call `health_check()`
+    /// while the daemon runs. Daemons that want periodic checks during `run()`
+    /// return a shareable probe over their health state (typically backed by
+    /// an `Arc`). The default returns `None`, in which case liveness and
+    /// readiness are only checked between `init()` and `run()`, and there
+    /// is no startup probe.
+    fn health_probe(&self) -> Option<Arc<dyn HealthProbe>> {
+        None
+    }
+
+    /// Returns a handler that applies reloaded configuration while `run()`
+    /// is executing.
+    ///
+    /// This is the only reload hook. Same rationale as
+    /// [`health_probe`](Self::health_probe): the supervisor registers the
+    /// handler with the manager for each run, so that
+    /// [`DaemonManager::reload`](crate::manager::DaemonManager::reload)
+    /// reaches the running daemon with the full new configuration when it
+    /// contains changes that can go live (see
+    /// [`ConfigDiff`](crate::config::ConfigDiff)). Changes that need a
+    /// restart are picked up by the next `init()`. The default returns
+    /// `None`: live changes are then only stored.
+    fn reload_handler(&self) -> Option<Arc<dyn ReloadHandler>> {
+        None
+    }
+}
+
+/// Health check that can run concurrently with [`Daemon::run`].
+///
+/// # Genchi Genbutsu
+/// Observe the running daemon directly, without stopping it.
+#[async_trait]
+pub trait HealthProbe: Send + Sync {
+    /// Performs a health check: the liveness probe.
+    ///
+    /// Should return quickly (< 1s) and not block.
+    async fn health_check(&self) -> HealthStatus;
+
+    /// Readiness probe; see [`Daemon::readiness_check`].
+    async fn readiness_check(&self) -> HealthStatus {
+        self.health_check().await
+    }
+
+    /// Startup probe; see [`Daemon::startup_check`].
+    async fn startup_check(&self) -> HealthStatus {
+        HealthStatus::healthy(0)
+    }
+
+    /// Runs the probe of the given kind.
+    async fn check(&self, kind: ProbeKind) -> HealthStatus {
+        match kind {
+            ProbeKind::Liveness => self.health_check().await,
+            ProbeKind::Readiness => self.readiness_check().await,
+            ProbeKind::Startup => self.startup_check().await,
+        }
+    }
+}
+
+/// Returns true for signals that request shutdown (TERM, INT, QUIT).
+pub(crate) const fn is_termination(signal: Signal) -> bool {
+    matches!(signal, Signal::Term | Signal::Int | Signal::Quit)
+}
+
+/// Reload handler for a running daemon.
+///
+/// See [`Daemon::reload_handler`].
+#[async_trait]
+pub trait ReloadHandler: Send + Sync {
+    /// Applies a reloaded configuration in place.
+    ///
+    /// # Poka-Yoke
+    /// Reject a configuration that cannot be applied; the manager keeps
+    /// the previous one.
+    ///
+    /// # Errors
+    /// Returns an error if the configuration cannot be applied.
+    async fn reload(&self, config: &DaemonConfig) -> Result<()>;
+}
+
+impl std::fmt::Debug for dyn ReloadHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ReloadHandler")
+    }
 }
 
 /// Runtime context for a daemon.
@@ -159,8 +280,24 @@ pub struct DaemonContext {
     /// Signal receiver.
     signal_rx: mpsc::Receiver<Signal>,
 
-    /// Shutdown flag.
-    shutdown: bool,
+    /// Shutdown token, shared with the context handle.
+    shutdown: ShutdownToken,
+
+    /// Subtasks spawned via [`spawn`](Self::spawn), by name.
+    subtasks: Vec<(String, JoinHandle<()>)>,
+
+    /// Last heartbeat, shared with the context handle.
+    heartbeat: Arc<watch::Sender<Instant>>,
+
+    /// Pre-bound listening sockets.
+    listeners: Listeners,
+
+    /// Notify socket client, if the supervisor listens for notifications.
+    #[cfg(unix)]
+    notifier: Option<Notifier>,
+
+    /// Trace context of this run.
+    trace: TraceContext,
 
     /// Configuration.
     config: DaemonConfig,
@@ -168,30 +305,120 @@ pub struct DaemonContext {
 
 impl DaemonContext {
     /// Creates a new daemon context.
+    ///
+    /// Listeners inherited through socket activation (`LISTEN_FDS`) are
+    /// available from [`listeners`](Self::listeners), named after the
+    /// configured socket bound to the same address. The trace context
+    /// continues the one inherited through `TRACEPARENT`, if any.
     #[must_use]
     pub fn new(config: DaemonConfig) -> (Self, DaemonContextHandle) {
         let (signal_tx, signal_rx) = mpsc::channel(16);
+        let shutdown = ShutdownToken::new();
+        let heartbeat = Arc::new(watch::channel(Instant::now()).0);
+
+        let mut listeners = Listeners::inherited().try_clone().unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "failed to duplicate inherited listeners");
+            Listeners::new()
+        });
+        listeners.resolve_names(&config.sockets);
 
         let ctx = Self {
             signal_rx,
-            shutdown: false,
+            shutdown: shutdown.clone(),
+            subtasks: Vec::new(),
+            heartbeat: Arc::clone(&heartbeat),
+            listeners,
+            #[cfg(unix)]
+            notifier: Notifier::from_env(),
+            trace: TraceContext::inherited(),
             config,
         };
 
-        let handle = DaemonContextHandle { signal_tx };
+        let handle = DaemonContextHandle {
+            signal_tx,
+            shutdown,
+            killed: ShutdownToken::new(),
+            heartbeat,
+        };
 
         (ctx, handle)
     }
 
     /// Returns true if the daemon should shut down.
     #[must_use]
-    pub const fn should_shutdown(&self) -> bool {
-        self.shutdown
+    pub fn should_shutdown(&self) -> bool {
+        self.shutdown.is_cancelled()
     }
 
     /// Marks the daemon for shutdown.
     pub fn request_shutdown(&mut self) {
-        self.shutdown = true;
+        self.shutdown.cancel();
+    }
+
+    /// Returns a cloneable token that is cancelled on shutdown.
+    #[must_use]
+    pub fn shutdown_token(&self) -> ShutdownToken {
+        self.shutdown.clone()
+    }
+
+    /// Returns a future that completes once shutdown is requested.
+    ///
+    /// The future does not borrow the context, so it can be raced against
+    /// [`recv_signal`](Self::recv_signal) in `tokio::select!`.
+    pub fn cancelled(&self) -> impl Future<Output = ()> + Send + 'static {
+        let token = self.shutdown.clone();
+        async move { token.cancelled().await }
+    }
+
+    /// Spawns a tracked subtask.
+    ///
+    /// Subtasks should stop when the [`shutdown_token`](Self::shutdown_token)
+    /// is cancelled. They are joined by [`join_subtasks`](Self::join_subtasks);
+    /// any still running at the deadline are aborted and reported as leaked.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn spawn<F>(&mut self, name: impl Into<String>, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        // Muda: drop bookkeeping for subtasks that already finished
+        self.subtasks.retain(|(_, task)| !task.is_finished());
+        self.subtasks.push((name.into(), tokio::spawn(future)));
+    }
+
+    /// Returns the number of subtasks still running.
+    #[must_use]
+    pub fn active_subtasks(&self) -> usize {
+        self.subtasks
+            .iter()
+            .filter(|(_, task)| !task.is_finished())
+            .count()
+    }
+
+    /// Cancels the shutdown token and joins all subtasks within `timeout`.
+    ///
+    /// Subtasks still running when `timeout` expires are aborted and listed
+    /// in [`SubtaskReport::leaked`].
+    pub async fn join_subtasks(&mut self, timeout: Duration) -> SubtaskReport {
+        self.shutdown.cancel();
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut report = SubtaskReport::default();
+
+        for (name, mut task) in self.subtasks.drain(..) {
+            match tokio::time::timeout_at(deadline, &mut task).await {
+                Ok(Ok(())) => report.joined += 1,
+                Ok(Err(e)) if e.is_panic() => report.panicked.push(name),
+                // Aborted elsewhere: finished, nothing leaked
+                Ok(Err(_)) => report.joined += 1,
+                Err(_) => {
+                    task.abort();
+                    report.leaked.push(name);
+                }
+            }
+        }
+
+        report
     }
 
     /// Receives a signal, if available.
@@ -201,8 +428,8 @@ impl DaemonContext {
         match self.signal_rx.try_recv() {
             Ok(signal) => {
                 // Auto-set shutdown flag for termination signals
-                if matches!(signal, Signal::Term | Signal::Int | Signal::Quit) {
-                    self.shutdown = true;
+                if is_termination(signal) {
+                    self.shutdown.cancel();
                 }
                 Some(signal)
             }
@@ -217,8 +444,8 @@ impl DaemonContext {
         let signal = self.signal_rx.recv().await?;
 
         // Auto-set shutdown flag for termination signals
-        if matches!(signal, Signal::Term | Signal::Int | Signal::Quit) {
-            self.shutdown = true;
+        if is_termination(signal) {
+            self.shutdown.cancel();
         }
 
         Some(signal)
@@ -229,24 +456,233 @@ impl DaemonContext {
     pub const fn config(&self) -> &DaemonConfig {
         &self.config
     }
+
+    /// Returns the listening sockets bound for this daemon.
+    ///
+    /// Empty unless the daemon declares `sockets` or was socket-activated.
+    #[must_use]
+    pub const fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    /// Returns the listening sockets, e.g. to [`take`](Listeners::take) one.
+    pub const fn listeners_mut(&mut self) -> &mut Listeners {
+        &mut self.listeners
+    }
+
+    /// Replaces the listening sockets (in-process supervision).
+    pub(crate) fn set_listeners(&mut self, listeners: Listeners) {
+        self.listeners = listeners;
+    }
+
+    /// Returns the trace context of the current run.
+    ///
+    /// Pass [`child`](TraceContext::child)ren of it to outgoing requests
+    /// and spawned processes ([`TraceContext::env_vars`]) to keep them in
+    /// the daemon's trace.
+    #[must_use]
+    pub const fn trace_context(&self) -> &TraceContext {
+        &self.trace
+    }
+
+    /// Replaces the trace context (in-process supervision).
+    pub(crate) fn set_trace_context(&mut self, trace: TraceContext) {
+        self.trace = trace;
+    }
+
+    /// Tells the watchdog the daemon is making progress.
+    ///
+    /// With `health_check.watchdog` set, call this from the main loop at
+    /// least every half interval: a daemon that misses the deadline is
+    /// considered hung, aborted and restarted. Also pings the notify
+    /// socket (`WATCHDOG=1`), so the same call satisfies systemd's
+    /// `WatchdogSec`.
+    ///
+    /// # Toyota Way: Jidoka (自働化)
+    /// A hang stops the line: a daemon stuck inside `run` may still answer
+    /// health checks, but it cannot keep beating.
+    pub fn heartbeat(&self) {
+        self.heartbeat.send_replace(Instant::now());
+        if let Err(e) = self.notify_watchdog() {
+            tracing::debug!(error = %e, "watchdog ping not delivered");
+        }
+    }
+
+    /// Reports that startup finished and the daemon is serving.
+    ///
+    /// Only needed with `notify = true`: the daemon then stays `Starting`
+    /// until it calls this.
+    ///
+    /// # Errors
+    /// Returns an error if the notification cannot be sent.
+    pub fn notify_ready(&self) -> Result<()> {
+        self.notify(&[NotifyState::Ready])
+    }
+
+    /// Reports a human-readable status line (`systemctl status`).
+    ///
+    /// # Errors
+    /// Returns an error if the notification cannot be sent.
+    pub fn notify_status(&self, status: &str) -> Result<()> {
+        self.notify(&[NotifyState::Status(status.to_string())])
+    }
+
+    /// Pings the watchdog.
+    ///
+    /// Call at least every half [`watchdog_interval`](Self::watchdog_interval).
+    ///
+    /// # Errors
+    /// Returns an error if the notification cannot be sent.
+    pub fn notify_watchdog(&self) -> Result<()> {
+        self.notify(&[NotifyState::Watchdog])
+    }
+
+    /// Sends state assignments over the notify socket.
+    ///
+    /// A no-op when nothing listens (no `NOTIFY_SOCKET`), as `sd_notify(3)`.
+    ///
+    /// # Errors
+    /// Returns an error if the notification cannot be sent.
+    pub fn notify(&self, states: &[NotifyState]) -> Result<()> {
+        #[cfg(unix)]
+        if let Some(ref notifier) = self.notifier {
+            notifier.notify(states)?;
+        }
+        #[cfg(not(unix))]
+        let _ = states;
+        Ok(())
+    }
+
+    /// Returns true if notifications reach a listener.
+    #[must_use]
+    pub const fn can_notify(&self) -> bool {
+        #[cfg(unix)]
+        return self.notifier.is_some();
+        #[cfg(not(unix))]
+        false
+    }
+
+    /// Returns the watchdog interval the daemon must ping within.
+    ///
+    /// From `health_check.watchdog`, or `WATCHDOG_USEC` when set by systemd.
+    #[must_use]
+    pub fn watchdog_interval(&self) -> Option<Duration> {
+        let configured = self.config.health_check.watchdog;
+        if configured.is_zero() {
+            watchdog_from_env()
+        } else {
+            Some(configured)
+        }
+    }
+
+    /// Points notifications at the supervisor's listener.
+    #[cfg(unix)]
+    pub(crate) fn set_notifier(&mut self, notifier: Notifier) {
+        self.notifier = Some(notifier);
+    }
+}
+
+impl Drop for DaemonContext {
+    fn drop(&mut self) {
+        // Structured concurrency: subtasks never outlive their context
+        for (_, task) in &self.subtasks {
+            task.abort();
+        }
+    }
 }
 
 /// Handle for sending signals to a daemon context.
 #[derive(Clone, Debug)]
 pub struct DaemonContextHandle {
     signal_tx: mpsc::Sender<Signal>,
+    shutdown: ShutdownToken,
+    /// Cancelled once KILL has been sent.
+    killed: ShutdownToken,
+    /// Last heartbeat of the context.
+    heartbeat: Arc<watch::Sender<Instant>>,
 }
 
 impl DaemonContextHandle {
     /// Sends a signal to the daemon.
     ///
+    /// TERM, INT and QUIT also cancel the shutdown token, so `run` loops
+    /// waiting on `ctx.cancelled()` wake without receiving the signal.
+    ///
+    /// KILL cannot be handled by the daemon: it cancels the shutdown token
+    /// and marks the handle [`killed`](Self::killed) even if the context is
+    /// already closed, so a supervisor can abandon `run`.
+    ///
     /// # Errors
     /// Returns an error if the signal cannot be sent (daemon exited).
     pub async fn send_signal(&self, signal: Signal) -> Result<()> {
+        if signal == Signal::Kill {
+            self.killed.cancel();
+            self.shutdown.cancel();
+        }
+
         self.signal_tx
             .send(signal)
             .await
-            .map_err(|_| DaemonError::Signal("daemon context closed".to_string()))
+            .map_err(|_| DaemonError::Signal("daemon context closed".to_string()))?;
+
+        if is_termination(signal) {
+            self.shutdown.cancel();
+        }
+        Ok(())
+    }
+
+    /// Returns the shutdown token of the context.
+    #[must_use]
+    pub fn shutdown_token(&self) -> ShutdownToken {
+        self.shutdown.clone()
+    }
+
+    /// Returns true once KILL has been sent.
+    #[must_use]
+    pub fn is_killed(&self) -> bool {
+        self.killed.is_cancelled()
+    }
+
+    /// Completes once KILL has been sent.
+    pub async fn killed(&self) {
+        self.killed.cancelled().await;
+    }
+
+    /// Returns when the daemon last called [`DaemonContext::heartbeat`].
+    ///
+    /// The context's creation counts as the first heartbeat.
+    #[must_use]
+    pub fn last_heartbeat(&self) -> Instant {
+        *self.heartbeat.borrow()
+    }
+
+    /// Records a heartbeat on the daemon's behalf.
+    ///
+    /// Used for `WATCHDOG=1` pings that arrive over the notify socket, so
+    /// [`DaemonContext::notify_watchdog`] and a spawned binary pinging
+    /// `sd_notify` satisfy the watchdog as [`DaemonContext::heartbeat`] does.
+    pub fn heartbeat(&self) {
+        self.heartbeat.send_replace(Instant::now());
+    }
+
+    /// Completes once no heartbeat has arrived for `deadline`.
+    ///
+    /// Never completes for a zero `deadline` (watchdog disabled).
+    pub async fn heartbeat_missed(&self, deadline: Duration) {
+        if deadline.is_zero() {
+            return std::future::pending().await;
+        }
+
+        let mut heartbeats = self.heartbeat.subscribe();
+        loop {
+            let expires = *heartbeats.borrow_and_update() + deadline;
+            match tokio::time::timeout_at(expires.into(), heartbeats.changed()).await {
+                Ok(Ok(())) => {}
+                // The sender lives in `self`, so the channel cannot close
+                Ok(Err(_)) => std::future::pending().await,
+                Err(_) => return,
+            }
+        }
     }
 
     /// Requests graceful shutdown.
@@ -375,6 +811,111 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_handle_term_cancels_token() {
+        let config = DaemonConfig::new("test", "/bin/test");
+        let (ctx, handle) = DaemonContext::new(config);
+        let cancelled = ctx.cancelled();
+
+        handle.send_signal(Signal::Hup).await.ok();
+        assert!(!handle.shutdown_token().is_cancelled());
+
+        // No recv: a select!-based run loop never polls the signal channel
+        handle.shutdown().await.ok();
+        tokio::time::timeout(std::time::Duration::from_secs(1), cancelled)
+            .await
+            .expect("cancelled() should complete after TERM");
+        assert!(ctx.should_shutdown());
+    }
+
+    #[tokio::test]
+    async fn test_handle_kill_marks_killed() {
+        let config = DaemonConfig::new("test", "/bin/test");
+        let (ctx, handle) = DaemonContext::new(config);
+
+        handle.shutdown().await.ok();
+        assert!(!handle.is_killed());
+
+        // Takes effect even once the context is gone
+        drop(ctx);
+        assert!(handle.send_signal(Signal::Kill).await.is_err());
+        assert!(handle.is_killed());
+        tokio::time::timeout(std::time::Duration::from_secs(1), handle.killed())
+            .await
+            .expect("killed() should complete after KILL");
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_postpones_watchdog() {
+        use std::time::Duration;
+
+        let config = DaemonConfig::new("test", "/bin/test");
+        let (ctx, handle) = DaemonContext::new(config);
+        let deadline = Duration::from_millis(100);
+
+        // Beating every 20ms keeps the watchdog quiet
+        let beating = async {
+            for _ in 0..10 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                ctx.heartbeat();
+            }
+        };
+        tokio::select! {
+            () = handle.heartbeat_missed(deadline) => panic!("watchdog fired despite heartbeats"),
+            () = beating => {}
+        }
+        assert!(handle.last_heartbeat().elapsed() < deadline);
+
+        // Silence trips it
+        tokio::time::timeout(Duration::from_secs(1), handle.heartbeat_missed(deadline))
+            .await
+            .expect("watchdog should fire without heartbeats");
+
+        // Zero disables the watchdog
+        assert!(
+            tokio::time::timeout(deadline * 2, handle.heartbeat_missed(Duration::ZERO))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_join_subtasks_cooperative() {
+        let config = DaemonConfig::new("test", "/bin/test");
+        let (mut ctx, _handle) = DaemonContext::new(config);
+
+        for i in 0..3 {
+            let token = ctx.shutdown_token();
+            ctx.spawn(
+                format!("worker-{i}"),
+                async move { token.cancelled().await },
+            );
+        }
+        assert_eq!(ctx.active_subtasks(), 3);
+
+        let report = ctx.join_subtasks(std::time::Duration::from_secs(1)).await;
+        assert_eq!(report.joined, 3);
+        assert!(report.is_clean());
+        assert!(ctx.should_shutdown());
+        assert_eq!(ctx.active_subtasks(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_join_subtasks_reports_leaks_and_panics() {
+        let config = DaemonConfig::new("test", "/bin/test");
+        let (mut ctx, _handle) = DaemonContext::new(config);
+
+        ctx.spawn("stubborn", std::future::pending::<()>());
+        ctx.spawn("crasher", async { panic!("subtask failure") });
+
+        let report = ctx
+            .join_subtasks(std::time::Duration::from_millis(50))
+            .await;
+        assert_eq!(report.joined, 0);
+        assert_eq!(report.leaked, vec!["stubborn".to_string()]);
+        assert_eq!(report.panicked, vec!["crasher".to_string()]);
+    }
+
     #[tokio::test]
     async fn test_recv_signal_async_term_triggers_shutdown() {
         let config = DaemonConfig::new("test", "/bin/test");
@@ -0,0 +1,211 @@
+//! Dependency graph between daemons - start ordering and cycle detection.
+//!
+//! # Toyota Way: Heijunka (平準化)
+//! Daemons start in a level, predictable order: every daemon after the
+//! daemons it depends on, shutdown in reverse.
+//!
+//! Dependencies are by daemon name. Names that match no registered daemon
+//! are ignored for ordering; they may be registered later.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::error::{DaemonError, Result};
+
+/// A daemon in the dependency graph.
+#[derive(Debug, Clone)]
+pub struct DependencyNode<K> {
+    /// Caller-side key (e.g. `DaemonId`).
+    pub key: K,
+    /// Daemon name.
+    pub name: String,
+    /// Names this daemon is ordered after.
+    pub deps: Vec<String>,
+}
+
+/// Adjacency: for each node, the indices of the nodes it depends on.
+fn edges<K>(nodes: &[DependencyNode<K>]) -> Vec<Vec<usize>> {
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, node) in nodes.iter().enumerate() {
+        by_name.entry(node.name.as_str()).or_default().push(i);
+    }
+
+    nodes
+        .iter()
+        .map(|node| {
+            node.deps
+                .iter()
+                .filter_map(|dep| by_name.get(dep.as_str()))
+                .flatten()
+                .copied()
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns a dependency cycle as a list of names, first name repeated last.
+pub fn find_cycle<K>(nodes: &[DependencyNode<K>]) -> Option<Vec<String>> {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Mark {
+        New,
+        Active,
+        Done,
+    }
+
+    let edges = edges(nodes);
+    let mut marks = vec![Mark::New; nodes.len()];
+
+    for root in 0..nodes.len() {
+        if marks[root] != Mark::New {
+            continue;
+        }
+
+        // Iterative DFS: (node, next edge to visit)
+        let mut stack = vec![(root, 0)];
+        marks[root] = Mark::Active;
+
+        while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+            if let Some(&dep) = edges[node].get(*next) {
+                *next += 1;
+                match marks[dep] {
+                    Mark::New => {
+                        marks[dep] = Mark::Active;
+                        stack.push((dep, 0));
+                    }
+                    Mark::Active => {
+                        let start = stack.iter().position(|&(n, _)| n == dep).unwrap_or(0);
+                        let mut cycle: Vec<String> = stack[start..]
+                            .iter()
+                            .map(|&(n, _)| nodes[n].name.clone())
+                            .collect();
+                        cycle.push(nodes[dep].name.clone());
+                        return Some(cycle);
+                    }
+                    Mark::Done => {}
+                }
+            } else {
+                marks[node] = Mark::Done;
+                stack.pop();
+            }
+        }
+    }
+
+    None
+}
+
+/// Checks that the graph has no dependency cycle.
+///
+/// # Errors
+/// Returns `DaemonError::Dependency` naming the cycle.
+pub fn ensure_acyclic<K>(nodes: &[DependencyNode<K>]) -> Result<()> {
+    find_cycle(nodes).map_or(Ok(()), |cycle| {
+        Err(DaemonError::dependency(format!(
+            "dependency cycle: {}",
+            cycle.join(" -> ")
+        )))
+    })
+}
+
+/// Orders nodes so each comes after everything it depends on.
+///
+/// Independent nodes keep their input order.
+///
+/// # Errors
+/// Returns `DaemonError::Dependency` if the graph has a cycle.
+pub fn start_order<K: Copy>(nodes: &[DependencyNode<K>]) -> Result<Vec<K>> {
+    let edges = edges(nodes);
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    let mut pending: Vec<usize> = edges.iter().map(Vec::len).collect();
+    for (node, deps) in edges.iter().enumerate() {
+        for &dep in deps {
+            dependents[dep].push(node);
+        }
+    }
+
+    let mut ready: VecDeque<usize> = (0..nodes.len()).filter(|&i| pending[i] == 0).collect();
+    let mut order = Vec::with_capacity(nodes.len());
+
+    while let Some(node) = ready.pop_front() {
+        order.push(nodes[node].key);
+        for &dependent in &dependents[node] {
+            pending[dependent] -= 1;
+            if pending[dependent] == 0 {
+                ready.push_back(dependent);
+            }
+        }
+    }
+
+    if order.len() < nodes.len() {
+        ensure_acyclic(nodes)?;
+    }
+
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(key: u32, name: &str, deps: &[&str]) -> DependencyNode<u32> {
+        DependencyNode {
+            key,
+            name: name.to_string(),
+            deps: deps.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn test_start_order_respects_dependencies() {
+        let nodes = [
+            node(1, "model-server", &["zram", "network"]),
+            node(2, "metrics", &[]),
+            node(3, "zram", &[]),
+            node(4, "network", &["zram"]),
+        ];
+
+        let order = start_order(&nodes).unwrap();
+        let pos = |k| order.iter().position(|&o| o == k).unwrap();
+
+        assert_eq!(order.len(), 4);
+        assert!(pos(3) < pos(4));
+        assert!(pos(4) < pos(1));
+        // Independent nodes keep input order
+        assert!(pos(2) < pos(3));
+    }
+
+    #[test]
+    fn test_unknown_dependencies_are_ignored() {
+        let nodes = [node(1, "a", &["not-registered"])];
+        assert_eq!(start_order(&nodes).unwrap(), [1]);
+        assert!(find_cycle(&nodes).is_none());
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let nodes = [
+            node(1, "a", &["b"]),
+            node(2, "b", &["c"]),
+            node(3, "c", &["a"]),
+            node(4, "d", &[]),
+        ];
+
+        let cycle = find_cycle(&nodes).unwrap();
+        assert_eq!(cycle.first(), cycle.last());
+        assert_eq!(cycle.len(), 4);
+
+        let err = start_order(&nodes).unwrap_err();
+        assert!(matches!(err, DaemonError::Dependency(_)));
+        assert!(err.to_string().contains("a -> b -> c -> a"));
+    }
+
+    #[test]
+    fn test_acyclic_diamond() {
+        let nodes = [
+            node(1, "top", &["left", "right"]),
+            node(2, "left", &["base"]),
+            node(3, "right", &["base"]),
+            node(4, "base", &[]),
+        ];
+        assert!(find_cycle(&nodes).is_none());
+        assert_eq!(start_order(&nodes).unwrap()[0], 4);
+    }
+}
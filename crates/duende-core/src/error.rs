@@ -4,6 +4,8 @@
 
 use std::time::Duration;
 
+use crate::types::DaemonStatus;
+
 /// Result type alias for daemon operations.
 pub type Result<T> = std::result::Result<T, DaemonError>;
 
@@ -33,6 +35,10 @@ pub enum DaemonError {
     #[error("shutdown timed out after {0:?}")]
     ShutdownTimeout(Duration),
 
+    /// Watchdog deadline missed: no heartbeat within the interval.
+    #[error("watchdog deadline missed: no heartbeat for {0:?}")]
+    WatchdogTimeout(Duration),
+
     /// Health check failed.
     #[error("health check failed: {0}")]
     HealthCheck(String),
@@ -64,10 +70,35 @@ pub enum DaemonError {
     #[error("invalid state: {0}")]
     State(String),
 
+    /// Dependency error (cycle, missing or failed dependency).
+    #[error("dependency error: {0}")]
+    Dependency(String),
+
+    /// Daemon quarantined after hitting its start limit.
+    #[error("daemon quarantined: {0}")]
+    Quarantined(String),
+
+    /// Illegal lifecycle state transition.
+    #[error("invalid transition: {from:?} -> {to:?}")]
+    InvalidTransition {
+        /// Current state.
+        from: DaemonStatus,
+        /// Rejected target state.
+        to: DaemonStatus,
+    },
+
     /// I/O error.
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Invalid custom metric registration.
+    #[error("metric error: {0}")]
+    Metric(String),
+
+    /// Malformed W3C trace context.
+    #[error("trace context error: {0}")]
+    TraceContext(String),
+
     /// Serialization error.
     #[error("serialization error: {0}")]
     Serialization(String),
@@ -108,12 +139,36 @@ impl DaemonError {
         Self::HealthCheck(msg.into())
     }
 
+    /// Creates a dependency error.
+    #[must_use]
+    pub fn dependency(msg: impl Into<String>) -> Self {
+        Self::Dependency(msg.into())
+    }
+
+    /// Creates a quarantine error.
+    #[must_use]
+    pub fn quarantined(msg: impl Into<String>) -> Self {
+        Self::Quarantined(msg.into())
+    }
+
     /// Creates a policy violation error.
     #[must_use]
     pub fn policy_violation(msg: impl Into<String>) -> Self {
         Self::PolicyViolation(msg.into())
     }
 
+    /// Creates a metric registration error.
+    #[must_use]
+    pub fn metric(msg: impl Into<String>) -> Self {
+        Self::Metric(msg.into())
+    }
+
+    /// Creates a trace context error.
+    #[must_use]
+    pub fn trace_context(msg: impl Into<String>) -> Self {
+        Self::TraceContext(msg.into())
+    }
+
     /// Returns true if this error is recoverable (daemon can continue).
     #[must_use]
     pub const fn is_recoverable(&self) -> bool {
@@ -134,12 +189,32 @@ impl DaemonError {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_invalid_transition_display() {
+        let err = DaemonError::InvalidTransition {
+            from: DaemonStatus::Stopped,
+            to: DaemonStatus::Paused,
+        };
+        assert_eq!(err.to_string(), "invalid transition: Stopped -> Paused");
+        assert!(!err.is_recoverable());
+    }
+
     #[test]
     fn test_error_display() {
         let err = DaemonError::config("invalid port");
         assert_eq!(err.to_string(), "configuration error: invalid port");
     }
 
+    #[test]
+    fn test_watchdog_timeout_display() {
+        let err = DaemonError::WatchdogTimeout(Duration::from_secs(5));
+        assert_eq!(
+            err.to_string(),
+            "watchdog deadline missed: no heartbeat for 5s"
+        );
+        assert!(!err.is_recoverable());
+    }
+
     #[test]
     fn test_error_recoverable() {
         assert!(DaemonError::health_check("timeout").is_recoverable());
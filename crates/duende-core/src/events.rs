@@ -0,0 +1,181 @@
+//! Lifecycle events - what happened to each daemon, as a stream.
+//!
+//! [`DaemonManager::subscribe`] returns a receiver of [`LifecycleEvent`]s:
+//! registration, start, readiness, exit, scheduled restarts, health
+//! changes and removal. Events are timestamped and serializable, so they
+//! can feed dashboards, audit logs and alerting without polling. Events
+//! of a run carry the trace and span IDs of its [`TraceContext`].
+//!
+//! # Toyota Way: Andon (行灯)
+//! Problems are signalled the moment they happen, to everyone watching,
+//! instead of waiting for someone to walk by and look.
+//!
+//! [`DaemonManager::subscribe`]: crate::manager::DaemonManager::subscribe
+//! [`TraceContext`]: crate::trace::TraceContext
+
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::trace::{SpanId, TraceContext, TraceId};
+use crate::types::{DaemonId, DaemonStatus, ExitReason, HealthStatus};
+
+/// Default number of events buffered per subscriber.
+///
+/// A subscriber that falls further behind skips the oldest events and
+/// receives `RecvError::Lagged` once.
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// A lifecycle event of one daemon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleEvent {
+    /// Daemon ID.
+    pub id: DaemonId,
+    /// Daemon name.
+    pub name: String,
+    /// Wall-clock time of the event.
+    pub at: SystemTime,
+    /// Trace of the run the event belongs to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<TraceId>,
+    /// Span of the run the event belongs to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span_id: Option<SpanId>,
+    /// What happened.
+    #[serde(flatten)]
+    pub kind: LifecycleEventKind,
+}
+
+impl LifecycleEvent {
+    /// Creates an event that happened now.
+    #[must_use]
+    pub fn new(id: DaemonId, name: impl Into<String>, kind: LifecycleEventKind) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            at: SystemTime::now(),
+            trace_id: None,
+            span_id: None,
+            kind,
+        }
+    }
+
+    /// Attaches the trace and span IDs of `trace`.
+    #[must_use]
+    pub const fn with_trace(mut self, trace: &TraceContext) -> Self {
+        self.trace_id = Some(trace.trace_id);
+        self.span_id = Some(trace.span_id);
+        self
+    }
+}
+
+/// What happened to a daemon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LifecycleEventKind {
+    /// Added to the manager.
+    Registered,
+    /// Starting: initialization began.
+    Starting,
+    /// Running: initialized (and ready, if it reports readiness).
+    Running,
+    /// A run finished.
+    Exited {
+        /// Why it finished.
+        reason: ExitReason,
+        /// Status recorded for the exit.
+        status: DaemonStatus,
+    },
+    /// A restart will follow after `delay`.
+    RestartScheduled {
+        /// Restart number, starting at 1.
+        attempt: u32,
+        /// Backoff before the restart.
+        #[serde(with = "crate::config::humantime_serde")]
+        delay: Duration,
+    },
+    /// Liveness flipped between healthy and unhealthy, or was first known.
+    HealthChanged {
+        /// The check result that caused the change.
+        health: HealthStatus,
+    },
+    /// Removed from the manager.
+    Unregistered,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FailureReason;
+
+    #[test]
+    fn test_event_serializes_flat() {
+        let id = DaemonId::new();
+        let event = LifecycleEvent::new(
+            id,
+            "api",
+            LifecycleEventKind::RestartScheduled {
+                attempt: 2,
+                delay: Duration::from_millis(1500),
+            },
+        );
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "restart_scheduled");
+        assert_eq!(json["name"], "api");
+        assert_eq!(json["attempt"], 2);
+        assert_eq!(json["delay"], "1s 500ms");
+
+        let back: LifecycleEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(back.id, id);
+        assert_eq!(back.at, event.at);
+        assert!(matches!(
+            back.kind,
+            LifecycleEventKind::RestartScheduled { attempt: 2, delay } if delay == Duration::from_millis(1500)
+        ));
+    }
+
+    #[test]
+    fn test_exited_event_roundtrip() {
+        let event = LifecycleEvent::new(
+            DaemonId::new(),
+            "api",
+            LifecycleEventKind::Exited {
+                reason: ExitReason::Error("boom".to_string()),
+                status: DaemonStatus::Failed(FailureReason::Internal),
+            },
+        );
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains(r#""event":"exited""#));
+        let back: LifecycleEvent = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            back.kind,
+            LifecycleEventKind::Exited {
+                reason: ExitReason::Error(_),
+                status: DaemonStatus::Failed(FailureReason::Internal),
+            }
+        ));
+    }
+
+    #[test]
+    fn test_event_carries_trace() {
+        let untraced = LifecycleEvent::new(DaemonId::new(), "api", LifecycleEventKind::Starting);
+        assert!(
+            serde_json::to_value(&untraced)
+                .unwrap()
+                .get("trace_id")
+                .is_none()
+        );
+
+        let trace = TraceContext::new_root();
+        let event = untraced.with_trace(&trace);
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["trace_id"], trace.trace_id.to_hex());
+        assert_eq!(json["span_id"], trace.span_id.to_hex());
+
+        let back: LifecycleEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(back.trace_id, Some(trace.trace_id));
+        assert_eq!(back.span_id, Some(trace.span_id));
+    }
+}
@@ -0,0 +1,338 @@
+//! Latency histogram - bounded-memory, log-linear buckets.
+//!
+//! Durations are recorded in microseconds. Values below 32µs get exact
+//! buckets; above, every power of two is split into 16 linear
+//! sub-buckets, so any recorded value is known to within 6.25%. Values
+//! up to 2^40µs (about 12.7 days) are distinguished; larger ones land in
+//! the last bucket. The whole histogram is a fixed array of atomics:
+//! recording never allocates or locks.
+//!
+//! # Toyota Way: Genchi Genbutsu (現地現物)
+//! An average hides the slow requests users actually feel; the tail is
+//! where problems show first.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::registry::{Bucket, DEFAULT_BUCKETS, HistogramSnapshot};
+
+/// log2 of the sub-buckets per power of two.
+const SUB_BUCKET_BITS: u32 = 4;
+/// Sub-buckets per power of two.
+const SUB_BUCKETS: usize = 1 << SUB_BUCKET_BITS;
+/// Largest distinguished value is just below `2^MAX_BITS` µs.
+const MAX_BITS: u32 = 40;
+/// Number of buckets.
+const BUCKET_COUNT: usize = (MAX_BITS - SUB_BUCKET_BITS) as usize * SUB_BUCKETS + SUB_BUCKETS;
+
+/// Returns the bucket of a value in microseconds.
+fn bucket_index(us: u64) -> usize {
+    if us < (SUB_BUCKETS as u64) * 2 {
+        return us as usize;
+    }
+    let exp = u64::BITS - 1 - us.leading_zeros();
+    let shift = exp - SUB_BUCKET_BITS;
+    let mantissa = (us >> shift) as usize;
+    (shift as usize * SUB_BUCKETS + mantissa).min(BUCKET_COUNT - 1)
+}
+
+/// Returns the highest value, in microseconds, that maps to `index`.
+fn bucket_upper(index: usize) -> u64 {
+    if index < SUB_BUCKETS * 2 {
+        return index as u64;
+    }
+    let shift = (index / SUB_BUCKETS - 1) as u32;
+    let mantissa = (index % SUB_BUCKETS + SUB_BUCKETS) as u64;
+    ((mantissa + 1) << shift) - 1
+}
+
+// =============================================================================
+// LatencyHistogram
+// =============================================================================
+
+/// Lock-free latency histogram.
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    buckets: Box<[AtomicU64]>,
+    count: AtomicU64,
+    sum_us: AtomicU64,
+    max_us: AtomicU64,
+}
+
+impl LatencyHistogram {
+    /// Creates an empty histogram.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            buckets: (0..BUCKET_COUNT).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_us: AtomicU64::new(0),
+            max_us: AtomicU64::new(0),
+        }
+    }
+
+    /// Records one duration.
+    pub fn record(&self, duration: Duration) {
+        let us = duration.as_micros() as u64;
+        if let Some(bucket) = self.buckets.get(bucket_index(us)) {
+            bucket.fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(us, Ordering::Relaxed);
+        self.max_us.fetch_max(us, Ordering::Relaxed);
+    }
+
+    /// Returns the number of recorded durations.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Returns a point-in-time copy.
+    #[must_use]
+    pub fn snapshot(&self) -> LatencySnapshot {
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .filter_map(|(index, count)| {
+                let count = count.load(Ordering::Relaxed);
+                (count > 0).then_some((index as u16, count))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        LatencySnapshot {
+            // Counted from the buckets so percentiles stay consistent
+            count: buckets.values().sum(),
+            sum_us: self.sum_us.load(Ordering::Relaxed),
+            max_us: self.max_us.load(Ordering::Relaxed),
+            buckets,
+        }
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// LatencySnapshot
+// =============================================================================
+
+/// Latency histogram at a point in time.
+///
+/// Snapshots of several instances [`merge`](Self::merge) into one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencySnapshot {
+    /// Non-empty buckets: index to count.
+    pub buckets: BTreeMap<u16, u64>,
+    /// Number of recorded durations.
+    pub count: u64,
+    /// Sum of recorded durations, in microseconds.
+    pub sum_us: u64,
+    /// Largest recorded duration, in microseconds.
+    pub max_us: u64,
+}
+
+impl LatencySnapshot {
+    /// Adds the recordings of `other`.
+    pub fn merge(&mut self, other: &Self) {
+        for (&index, &count) in &other.buckets {
+            *self.buckets.entry(index).or_default() += count;
+        }
+        self.count += other.count;
+        self.sum_us += other.sum_us;
+        self.max_us = self.max_us.max(other.max_us);
+    }
+
+    /// Returns the duration at quantile `q` (0.0 to 1.0).
+    ///
+    /// Accurate to within 6.25%, and never above the recorded maximum.
+    /// Zero if nothing was recorded.
+    #[must_use]
+    pub fn quantile(&self, q: f64) -> Duration {
+        let rank = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (&index, &count) in &self.buckets {
+            seen += count;
+            if seen >= rank {
+                return self.bucket_value(index);
+            }
+        }
+        Duration::ZERO
+    }
+
+    /// Returns the value bucket `index` stands for: its highest value,
+    /// capped at the recorded maximum.
+    #[must_use]
+    pub fn bucket_value(&self, index: u16) -> Duration {
+        Duration::from_micros(bucket_upper(index.into()).min(self.max_us))
+    }
+
+    /// Returns the median.
+    #[must_use]
+    pub fn p50(&self) -> Duration {
+        self.quantile(0.5)
+    }
+
+    /// Returns the 90th percentile.
+    #[must_use]
+    pub fn p90(&self) -> Duration {
+        self.quantile(0.9)
+    }
+
+    /// Returns the 99th percentile.
+    #[must_use]
+    pub fn p99(&self) -> Duration {
+        self.quantile(0.99)
+    }
+
+    /// Returns the 99.9th percentile.
+    #[must_use]
+    pub fn p999(&self) -> Duration {
+        self.quantile(0.999)
+    }
+
+    /// Returns the histogram in Prometheus form: cumulative buckets with
+    /// upper bounds in seconds ([`DEFAULT_BUCKETS`] if `bounds` is empty).
+    ///
+    /// A log-linear bucket counts toward the first bound at or above its
+    /// highest value, so counts are within the histogram's precision.
+    #[must_use]
+    pub fn prometheus(&self, bounds: &[f64]) -> HistogramSnapshot {
+        let bounds = if bounds.is_empty() {
+            DEFAULT_BUCKETS
+        } else {
+            bounds
+        };
+
+        let mut entries = self.buckets.iter().peekable();
+        let mut cumulative = 0;
+        let buckets = bounds
+            .iter()
+            .map(|&le| {
+                let le_us = le * 1_000_000.0;
+                while let Some((_, &count)) =
+                    entries.next_if(|(index, _)| bucket_upper((**index).into()) as f64 <= le_us)
+                {
+                    cumulative += count;
+                }
+                Bucket {
+                    le,
+                    count: cumulative,
+                }
+            })
+            .collect();
+
+        HistogramSnapshot {
+            buckets,
+            sum: self.sum_us as f64 / 1_000_000.0,
+            count: self.count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_index_roundtrip() {
+        // Exact below 32µs
+        for us in 0..32 {
+            assert_eq!(bucket_upper(bucket_index(us)), us);
+        }
+        // Every value falls in a bucket that bounds it within 6.25%
+        for us in [32, 33, 100, 1_000, 12_345, 999_999, 60_000_000] {
+            let upper = bucket_upper(bucket_index(us));
+            assert!(upper >= us);
+            assert!((upper - us) as f64 <= us as f64 / 16.0, "{us} -> {upper}");
+        }
+        // Buckets are contiguous and increasing
+        for index in 1..BUCKET_COUNT {
+            assert_eq!(bucket_index(bucket_upper(index - 1) + 1), index);
+        }
+        assert_eq!(bucket_index(u64::MAX), BUCKET_COUNT - 1);
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let histogram = LatencyHistogram::new();
+        for ms in 1..=1000 {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 1000);
+        let close = |actual: Duration, expected_ms: u64| {
+            let expected = Duration::from_millis(expected_ms).as_secs_f64();
+            (actual.as_secs_f64() - expected).abs() <= expected / 16.0
+        };
+        assert!(close(snapshot.p50(), 500));
+        assert!(close(snapshot.p90(), 900));
+        assert!(close(snapshot.p99(), 990));
+        assert!(close(snapshot.p999(), 999));
+        assert_eq!(snapshot.quantile(1.0), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_empty_snapshot() {
+        let snapshot = LatencyHistogram::new().snapshot();
+        assert_eq!(snapshot.p99(), Duration::ZERO);
+        assert!(snapshot.buckets.is_empty());
+    }
+
+    #[test]
+    fn test_merge() {
+        let fast = LatencyHistogram::new();
+        let slow = LatencyHistogram::new();
+        for _ in 0..90 {
+            fast.record(Duration::from_micros(100));
+        }
+        for _ in 0..10 {
+            slow.record(Duration::from_millis(50));
+        }
+
+        let mut merged = fast.snapshot();
+        merged.merge(&slow.snapshot());
+        assert_eq!(merged.count, 100);
+        assert_eq!(merged.max_us, 50_000);
+        assert_eq!(merged.sum_us, 90 * 100 + 10 * 50_000);
+        assert!(merged.p50() <= Duration::from_micros(106));
+        assert!(merged.p99() >= Duration::from_millis(47));
+    }
+
+    #[test]
+    fn test_prometheus_buckets() {
+        let histogram = LatencyHistogram::new();
+        histogram.record(Duration::from_millis(3));
+        histogram.record(Duration::from_millis(20));
+        histogram.record(Duration::from_secs(20));
+
+        let prom = histogram.snapshot().prometheus(&[0.005, 0.025, 1.0]);
+        let counts: Vec<u64> = prom.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, [1, 2, 2]);
+        assert_eq!(prom.count, 3);
+        assert!((prom.sum - 20.023).abs() < 1e-9);
+        assert_eq!(
+            histogram.snapshot().prometheus(&[]).buckets.len(),
+            DEFAULT_BUCKETS.len()
+        );
+    }
+
+    #[test]
+    fn test_snapshot_serde_roundtrip() {
+        let histogram = LatencyHistogram::new();
+        histogram.record(Duration::from_micros(250));
+        let snapshot = histogram.snapshot();
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let back: LatencySnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, snapshot);
+    }
+}
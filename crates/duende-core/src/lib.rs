@@ -11,6 +11,7 @@
 //! - [`DaemonConfig`] for daemon configuration
 //! - [`DaemonMetrics`] for RED method metrics (Rate, Errors, Duration)
 //! - [`DaemonContext`] for runtime context and signal handling
+//! - [`SignalBridge`] for forwarding OS signals into a [`DaemonContext`]
 //!
 //! ## Iron Lotus Framework
 //!
@@ -43,18 +44,34 @@
 // Allow significant_drop_tightening - overly aggressive for async code with locks
 #![allow(clippy::significant_drop_tightening)]
 
+pub mod activation;
 pub mod adapter;
 pub mod adapters;
+pub mod checks;
 pub mod config;
 pub mod daemon;
+mod dependencies;
 pub mod error;
+pub mod events;
+pub mod histogram;
 pub mod manager;
 pub mod metrics;
+pub mod notify;
 pub mod platform;
+pub mod prometheus;
+pub mod rates;
+pub mod registry;
+pub mod shutdown;
+pub mod signals;
+pub mod spans;
+pub mod state;
+pub mod supervisor;
 #[cfg(test)]
 pub mod tests;
+pub mod trace;
 pub mod types;
 
+pub use activation::{Listener, Listeners};
 pub use adapter::{
     DaemonHandle, HandleData, PlatformAdapter, PlatformError, PlatformResult, TracerHandle,
     TracerType,
@@ -63,10 +80,36 @@ pub use adapters::{
     ContainerAdapter, ContainerRuntime, LaunchdAdapter, NativeAdapter, PepitaAdapter,
     SystemdAdapter, WosAdapter, select_adapter, select_adapter_auto,
 };
-pub use config::{DaemonConfig, ResourceConfig};
-pub use daemon::{Daemon, DaemonContext, DaemonContextHandle};
+pub use config::{
+    CheckKind, ConfigChange, ConfigDiff, DaemonConfig, ExternalCheck, ListenAddress,
+    ResourceConfig, SocketConfig, StartLimit,
+};
+pub use daemon::{Daemon, DaemonContext, DaemonContextHandle, HealthProbe, ReloadHandler};
 pub use error::{DaemonError, Result};
-pub use manager::{BackoffConfig, DaemonManager, ManagedDaemon, RestartPolicy};
-pub use metrics::DaemonMetrics;
+pub use events::{LifecycleEvent, LifecycleEventKind};
+pub use histogram::{LatencyHistogram, LatencySnapshot};
+pub use manager::{
+    BackoffConfig, DaemonManager, DaemonMetricsSnapshot, ManagedDaemon, ReattachReport,
+    RestartPolicy, ShutdownOutcome, ShutdownReport,
+};
+pub use metrics::{DaemonMetrics, MetricsSnapshot};
+pub use notify::NotifyState;
+#[cfg(unix)]
+pub use notify::{Notifier, NotifyListener};
 pub use platform::{Platform, detect_platform};
-pub use types::{DaemonId, DaemonStatus, ExitReason, FailureReason, HealthStatus, Signal};
+pub use prometheus::{MetricsServer, PrometheusEncoder};
+pub use rates::{ErrorWindow, Meter, Rates, WindowStats};
+pub use registry::{
+    Counter, CustomMetric, Gauge, Histogram, HistogramSnapshot, MetricKind, MetricValue,
+    MetricsRegistry,
+};
+pub use shutdown::{ShutdownToken, SubtaskReport};
+pub use signals::{SignalBridge, SignalBridgeGuard};
+pub use spans::Phase;
+pub use state::{ManagerState, PersistedDaemon};
+pub use supervisor::Supervisor;
+pub use trace::{SpanId, TraceContext, TraceFlags, TraceId, TraceState};
+pub use types::{
+    DaemonId, DaemonStatus, ExitReason, FailureReason, HealthStatus, ProbeKind, Signal,
+    StatusTransition,
+};
@@ -6,16 +6,36 @@
 //! # Toyota Way: Jidoka (自働化)
 //! Automatic restart with exponential backoff on failure.
 
-use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::{Duration, Instant};
-
-use tokio::sync::{Mutex, RwLock};
-
-use crate::config::DaemonConfig;
-use crate::daemon::{Daemon, DaemonContextHandle};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, Notify, RwLock, Semaphore, broadcast, watch};
+use tokio::task::JoinSet;
+use tracing::{Instrument, Span};
+
+use crate::adapter::{DaemonHandle, PlatformAdapter, PlatformError};
+use crate::checks;
+pub use crate::config::BackoffConfig;
+use crate::config::{self, ConfigDiff, DaemonConfig};
+use crate::daemon::{Daemon, DaemonContextHandle, ReloadHandler};
+use crate::dependencies::{self, DependencyNode};
 use crate::error::{DaemonError, Result};
-use crate::types::{DaemonId, DaemonStatus, ExitReason, HealthStatus, Signal};
+use crate::events::{DEFAULT_EVENT_CAPACITY, LifecycleEvent, LifecycleEventKind};
+use crate::metrics::{DaemonMetrics, MetricsSnapshot};
+use crate::notify::NotifyState;
+use crate::platform::{Platform, detect_platform};
+use crate::prometheus::{MetricsServer, PrometheusEncoder};
+use crate::registry::{CustomMetric, MetricValue};
+use crate::spans::{self, Phase};
+use crate::state::{ManagerState, PersistedDaemon};
+use crate::trace::TraceContext;
+use crate::types::{
+    DaemonId, DaemonStatus, ExitReason, FailureReason, HealthStatus, ProbeKind, Signal,
+    StatusTransition,
+};
 
 // =============================================================================
 // RestartPolicy
@@ -25,7 +45,7 @@ use crate::types::{DaemonId, DaemonStatus, ExitReason, HealthStatus, Signal};
 ///
 /// # Toyota Way: Jidoka
 /// Stop-on-error with automatic recovery when safe.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub enum RestartPolicy {
     /// Never restart (run once).
     Never,
@@ -38,6 +58,8 @@ pub enum RestartPolicy {
     MaxRetries(u32),
     /// Custom policy with backoff.
     WithBackoff(BackoffConfig),
+    /// Restart on any exit with backoff, up to `max_retries`.
+    AlwaysWithBackoff(BackoffConfig),
 }
 
 impl RestartPolicy {
@@ -59,6 +81,7 @@ impl RestartPolicy {
                         ExitReason::Error(_) | ExitReason::ResourceExhausted(_)
                     )
             }
+            Self::AlwaysWithBackoff(config) => restart_count < config.max_retries,
         }
     }
 
@@ -66,79 +89,32 @@ impl RestartPolicy {
     #[must_use]
     pub fn restart_delay(&self, restart_count: u32) -> Duration {
         match self {
-            Self::WithBackoff(config) => config.delay_for(restart_count),
+            Self::WithBackoff(config) | Self::AlwaysWithBackoff(config) => {
+                config.delay_for(restart_count)
+            }
             _ => Duration::from_secs(1),
         }
     }
-}
-
-/// Backoff configuration for restart delays.
-#[derive(Debug, Clone)]
-pub struct BackoffConfig {
-    /// Initial delay before first restart.
-    pub initial_delay: Duration,
-    /// Maximum delay between restarts.
-    pub max_delay: Duration,
-    /// Multiplier for exponential backoff.
-    pub multiplier: f64,
-    /// Maximum number of retries.
-    pub max_retries: u32,
-}
-
-impl Default for BackoffConfig {
-    fn default() -> Self {
-        Self {
-            initial_delay: Duration::from_secs(1),
-            max_delay: Duration::from_secs(300), // 5 minutes
-            multiplier: 2.0,
-            max_retries: 10,
-        }
-    }
-}
-
-impl BackoffConfig {
-    /// Creates a new backoff config with builder pattern.
-    #[must_use]
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    /// Sets the initial delay.
-    #[must_use]
-    pub const fn with_initial_delay(mut self, delay: Duration) -> Self {
-        self.initial_delay = delay;
-        self
-    }
-
-    /// Sets the max delay.
-    #[must_use]
-    pub const fn with_max_delay(mut self, delay: Duration) -> Self {
-        self.max_delay = delay;
-        self
-    }
-
-    /// Sets the multiplier.
-    #[must_use]
-    pub const fn with_multiplier(mut self, multiplier: f64) -> Self {
-        self.multiplier = multiplier;
-        self
-    }
-
-    /// Sets the max retries.
-    #[must_use]
-    pub const fn with_max_retries(mut self, max_retries: u32) -> Self {
-        self.max_retries = max_retries;
-        self
-    }
 
-    /// Calculates delay for given restart count (exponential backoff).
+    /// Builds the policy described by a daemon configuration.
+    ///
+    /// `restart` decides when to restart and the optional `backoff` how
+    /// fast and how often. `unless-stopped` restarts like `always`: an
+    /// explicit stop never restarts in-process.
     #[must_use]
-    pub fn delay_for(&self, restart_count: u32) -> Duration {
-        let base_secs = self.initial_delay.as_secs_f64();
-        #[allow(clippy::cast_possible_wrap)] // restart_count won't exceed i32::MAX
-        let exp_secs = base_secs * self.multiplier.powi(restart_count as i32);
-        let clamped_secs = exp_secs.min(self.max_delay.as_secs_f64());
-        Duration::from_secs_f64(clamped_secs)
+    pub fn from_config(config: &DaemonConfig) -> Self {
+        match (config.restart, config.backoff.clone()) {
+            (config::RestartPolicy::Never, _) => Self::Never,
+            (config::RestartPolicy::OnFailure, None) => Self::OnFailure,
+            (config::RestartPolicy::OnFailure, Some(backoff)) => Self::WithBackoff(backoff),
+            (config::RestartPolicy::Always | config::RestartPolicy::UnlessStopped, None) => {
+                Self::Always
+            }
+            (
+                config::RestartPolicy::Always | config::RestartPolicy::UnlessStopped,
+                Some(backoff),
+            ) => Self::AlwaysWithBackoff(backoff),
+        }
     }
 }
 
@@ -146,8 +122,12 @@ impl BackoffConfig {
 // ManagedDaemon
 // =============================================================================
 
+/// Default number of status transitions kept per daemon.
+pub const DEFAULT_TRANSITION_LOG_CAPACITY: usize = 64;
+
 /// State for a managed daemon.
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)] // Independent flags, not one state
 pub struct ManagedDaemon {
     /// Daemon ID.
     pub id: DaemonId,
@@ -159,14 +139,44 @@ pub struct ManagedDaemon {
     pub config: DaemonConfig,
     /// Restart policy.
     pub restart_policy: RestartPolicy,
+    /// True if `restart_policy` follows `config.restart` and
+    /// `config.backoff`, and is rebuilt when they are reloaded.
+    pub restart_policy_from_config: bool,
     /// Number of restarts.
     pub restart_count: u32,
-    /// Last health check result.
+    /// Last health check (liveness probe) result.
     pub last_health: Option<HealthStatus>,
+    /// Last readiness probe result.
+    pub last_readiness: Option<HealthStatus>,
+    /// True while the last recorded readiness probe result passed.
+    /// Cleared on every start.
+    pub ready: bool,
     /// Last started timestamp.
     pub last_started: Option<Instant>,
     /// Context handle for signaling.
     pub context_handle: Option<DaemonContextHandle>,
+    /// Platform handle, if spawned through a [`PlatformAdapter`].
+    pub platform_handle: Option<DaemonHandle>,
+    /// Reload handler of the running daemon, if any.
+    pub reload_handler: Option<Arc<dyn ReloadHandler>>,
+    /// True if a reloaded configuration has changes awaiting a restart.
+    pub restart_required: bool,
+    /// Most recent status transitions, oldest first.
+    pub transitions: VecDeque<StatusTransition>,
+    /// Maximum number of transitions kept in `transitions`.
+    pub transition_log_capacity: usize,
+    /// Start times within the current start-limit interval.
+    pub recent_starts: VecDeque<Instant>,
+    /// True once the start limit was hit; cleared by an operator reset.
+    pub quarantined: bool,
+    /// Last `STATUS=` line the daemon sent over its notify socket.
+    pub status_text: Option<String>,
+    /// Last `WATCHDOG=1` ping.
+    pub last_watchdog: Option<Instant>,
+    /// Metrics of the daemon, once started.
+    pub metrics: Option<DaemonMetrics>,
+    /// Trace context of the current or last run.
+    pub trace_context: Option<TraceContext>,
 }
 
 impl ManagedDaemon {
@@ -179,10 +189,24 @@ impl ManagedDaemon {
             status: DaemonStatus::Created,
             config,
             restart_policy: RestartPolicy::default(),
+            restart_policy_from_config: false,
             restart_count: 0,
             last_health: None,
+            last_readiness: None,
+            ready: false,
             last_started: None,
             context_handle: None,
+            platform_handle: None,
+            reload_handler: None,
+            restart_required: false,
+            transitions: VecDeque::new(),
+            transition_log_capacity: DEFAULT_TRANSITION_LOG_CAPACITY,
+            recent_starts: VecDeque::new(),
+            quarantined: false,
+            status_text: None,
+            last_watchdog: None,
+            metrics: None,
+            trace_context: None,
         }
     }
 
@@ -190,14 +214,325 @@ impl ManagedDaemon {
     #[must_use]
     pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
         self.restart_policy = policy;
+        self.restart_policy_from_config = false;
+        self
+    }
+
+    /// Uses the restart policy the configuration describes, rebuilt
+    /// whenever the configuration is reloaded.
+    #[must_use]
+    pub fn with_config_restart_policy(mut self) -> Self {
+        self.restart_policy = RestartPolicy::from_config(&self.config);
+        self.restart_policy_from_config = true;
+        self
+    }
+
+    /// Sets the maximum number of transitions kept in the log.
+    #[must_use]
+    pub const fn with_transition_log_capacity(mut self, capacity: usize) -> Self {
+        self.transition_log_capacity = capacity;
         self
     }
+
+    /// Moves to `status`, recording the transition.
+    ///
+    /// Re-entering the current status is a no-op and is not recorded.
+    ///
+    /// # Poka-Yoke
+    /// Illegal moves are rejected, so the status always reflects a path
+    /// through the state machine.
+    ///
+    /// A move to `Starting` counts against the configured start limit.
+    /// The start that exceeds it is recorded, then the daemon moves to
+    /// `Failed(StartLimitHit)` and is quarantined: further starts are
+    /// refused until [`reset_failed`](Self::reset_failed).
+    ///
+    /// # Errors
+    /// Returns `DaemonError::InvalidTransition` if the state machine does
+    /// not allow the move, or `DaemonError::Quarantined` if the start limit
+    /// refused a start.
+    pub fn transition(&mut self, status: DaemonStatus) -> Result<()> {
+        if self.status == status {
+            return Ok(());
+        }
+        if status == DaemonStatus::Starting && self.status.can_transition_to(&status) {
+            self.check_start_limit()?;
+            // A new run reports afresh, and is not ready until probed
+            self.status_text = None;
+            self.last_watchdog = None;
+            self.last_readiness = None;
+            self.ready = false;
+        }
+        self.record(status)
+    }
+
+    /// Records a readiness probe result.
+    ///
+    /// A passing probe makes the daemon ready, a failing one not ready.
+    /// The prober applies `health_check.readiness.retries` before it
+    /// records a failure.
+    pub fn record_readiness(&mut self, health: HealthStatus) {
+        self.ready = health.is_healthy();
+        self.last_readiness = Some(health);
+    }
+
+    /// Returns true if the daemon is running and ready to serve.
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.ready && self.status == DaemonStatus::Running
+    }
+
+    /// Clears the quarantine and the start history.
+    ///
+    /// The status is left as is; a `Failed` daemon may be started again.
+    pub fn reset_failed(&mut self) {
+        self.quarantined = false;
+        self.recent_starts.clear();
+    }
+
+    /// Counts a start, quarantining the daemon if it exceeds the limit.
+    fn check_start_limit(&mut self) -> Result<()> {
+        if self.quarantined {
+            return Err(DaemonError::quarantined(format!(
+                "{} hit its start limit; reset it before starting",
+                self.name
+            )));
+        }
+
+        let Some(limit) = self.config.start_limit else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        while self
+            .recent_starts
+            .front()
+            .is_some_and(|start| now.duration_since(*start) >= limit.interval)
+        {
+            self.recent_starts.pop_front();
+        }
+
+        if self.recent_starts.len() >= limit.burst as usize {
+            self.record(DaemonStatus::Starting)?;
+            self.record(DaemonStatus::Failed(FailureReason::StartLimitHit))?;
+            self.quarantined = true;
+            tracing::error!(
+                id = %self.id,
+                burst = limit.burst,
+                interval = ?limit.interval,
+                "start limit hit, daemon quarantined"
+            );
+            return Err(DaemonError::quarantined(format!(
+                "{} started {} times within {:?}",
+                self.name, limit.burst, limit.interval
+            )));
+        }
+
+        self.recent_starts.push_back(now);
+        Ok(())
+    }
+
+    /// Moves to `status` if the state machine allows it and logs the move.
+    fn record(&mut self, status: DaemonStatus) -> Result<()> {
+        if !self.status.can_transition_to(&status) {
+            return Err(DaemonError::InvalidTransition {
+                from: self.status,
+                to: status,
+            });
+        }
+
+        // Muda: bounded log, oldest entries dropped first
+        if self.transition_log_capacity > 0 {
+            while self.transitions.len() >= self.transition_log_capacity {
+                self.transitions.pop_front();
+            }
+            self.transitions.push_back(StatusTransition {
+                from: self.status,
+                to: status,
+                at: SystemTime::now(),
+            });
+        }
+
+        self.status = status;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// ShutdownReport
+// =============================================================================
+
+/// How long [`DaemonManager::shutdown_all`] waits after KILL by default.
+pub const DEFAULT_KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How a daemon ended during [`DaemonManager::shutdown_all`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownOutcome {
+    /// Stopped within its `shutdown_timeout` after TERM.
+    Graceful,
+    /// Stopped only after escalation to KILL.
+    Killed,
+    /// Did not reach a terminal status even after KILL.
+    Stuck,
+    /// Could not be signalled: no in-process run and no adapter for its
+    /// platform handle. Left running.
+    Unsignalable,
+}
+
+/// Per-daemon result of [`DaemonManager::shutdown_all`].
+///
+/// Daemons that were not running (never started, already stopped or
+/// failed) are not listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Daemons that stopped gracefully.
+    pub graceful: Vec<DaemonId>,
+    /// Daemons that had to be killed.
+    pub killed: Vec<DaemonId>,
+    /// Daemons still not stopped.
+    pub stuck: Vec<DaemonId>,
+    /// Daemons that could not be signalled.
+    pub unsignalable: Vec<DaemonId>,
+}
+
+impl ShutdownReport {
+    /// Returns true if every daemon stopped gracefully.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.killed.is_empty() && self.stuck.is_empty() && self.unsignalable.is_empty()
+    }
+
+    /// Returns the outcome recorded for a daemon, if it was shut down.
+    #[must_use]
+    pub fn outcome(&self, id: DaemonId) -> Option<ShutdownOutcome> {
+        if self.graceful.contains(&id) {
+            Some(ShutdownOutcome::Graceful)
+        } else if self.killed.contains(&id) {
+            Some(ShutdownOutcome::Killed)
+        } else if self.stuck.contains(&id) {
+            Some(ShutdownOutcome::Stuck)
+        } else if self.unsignalable.contains(&id) {
+            Some(ShutdownOutcome::Unsignalable)
+        } else {
+            None
+        }
+    }
+
+    /// Records a daemon's outcome.
+    fn record(&mut self, id: DaemonId, outcome: ShutdownOutcome) {
+        match outcome {
+            ShutdownOutcome::Graceful => self.graceful.push(id),
+            ShutdownOutcome::Killed => self.killed.push(id),
+            ShutdownOutcome::Stuck => self.stuck.push(id),
+            ShutdownOutcome::Unsignalable => self.unsignalable.push(id),
+        }
+    }
+}
+
+// =============================================================================
+// ReattachReport
+// =============================================================================
+
+/// Result of [`DaemonManager::reattach`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReattachReport {
+    /// Daemons found still running and reattached.
+    pub reattached: Vec<DaemonId>,
+    /// Daemons that were running but are gone; marked `Failed(Lost)`.
+    pub lost: Vec<DaemonId>,
+    /// Daemons that were not running; restored with their saved status.
+    pub inactive: Vec<DaemonId>,
+}
+
+// =============================================================================
+// DaemonMetricsSnapshot
+// =============================================================================
+
+/// Metrics of one managed daemon, as exported.
+#[derive(Debug, Clone)]
+pub struct DaemonMetricsSnapshot {
+    /// Daemon ID.
+    pub id: DaemonId,
+    /// Daemon name.
+    pub name: String,
+    /// Platform the daemon runs on.
+    pub platform: Platform,
+    /// True if the daemon is running.
+    pub running: bool,
+    /// True if the daemon is running and ready to serve.
+    pub ready: bool,
+    /// Number of restarts.
+    pub restart_count: u32,
+    /// The daemon's own metrics, once started.
+    pub metrics: Option<MetricsSnapshot>,
+}
+
+impl DaemonMetricsSnapshot {
+    /// Returns the labels identifying the daemon: `daemon` (name),
+    /// `daemon_id` and `platform`.
+    #[must_use]
+    pub fn labels(&self) -> [(&'static str, String); 3] {
+        [
+            ("daemon", self.name.clone()),
+            ("daemon_id", self.id.to_string()),
+            ("platform", self.platform.name().to_string()),
+        ]
+    }
+
+    /// Returns `duende_daemon_running`, `duende_daemon_ready`,
+    /// `duende_daemon_restarts_total` and the daemon's own metrics, all
+    /// labeled with [`labels`](Self::labels).
+    #[must_use]
+    pub fn metrics(&self) -> Vec<CustomMetric> {
+        let flag = |value: bool| MetricValue::Gauge {
+            value: f64::from(u8::from(value)),
+        };
+        let mut metrics = vec![
+            CustomMetric::new(
+                "duende_daemon_running",
+                "1 if the daemon is running.",
+                flag(self.running),
+            ),
+            CustomMetric::new(
+                "duende_daemon_ready",
+                "1 if the daemon is running and ready to serve.",
+                flag(self.ready),
+            ),
+            CustomMetric::new(
+                "duende_daemon_restarts_total",
+                "Restarts of the daemon.",
+                MetricValue::Counter {
+                    value: u64::from(self.restart_count),
+                },
+            ),
+        ];
+        if let Some(snapshot) = &self.metrics {
+            metrics.extend(snapshot.metrics());
+        }
+
+        let labels = self.labels();
+        metrics
+            .into_iter()
+            .map(|metric| {
+                labels.iter().fold(metric, |metric, (key, value)| {
+                    metric.with_label(*key, value)
+                })
+            })
+            .collect()
+    }
 }
 
 // =============================================================================
 // DaemonManager
 // =============================================================================
 
+/// Registered daemons by ID.
+type Registry = RwLock<HashMap<DaemonId, Arc<Mutex<ManagedDaemon>>>>;
+
+/// How long the state writer waits after a change, to save a burst of
+/// changes at once.
+const STATE_WRITE_DELAY: Duration = Duration::from_millis(100);
+
 /// Daemon manager for orchestrating daemon lifecycle.
 ///
 /// # Toyota Way: Heijunka
@@ -207,11 +542,31 @@ impl ManagedDaemon {
 /// Automatic failover and restart on errors.
 pub struct DaemonManager {
     /// Registered daemons.
-    daemons: RwLock<HashMap<DaemonId, Arc<Mutex<ManagedDaemon>>>>,
+    daemons: Arc<Registry>,
     /// Health check interval.
     health_check_interval: Duration,
     /// Shutdown timeout.
     shutdown_timeout: Duration,
+    /// Transitions kept per daemon.
+    transition_log_capacity: usize,
+    /// How long `shutdown_all` waits for a daemon to stop after KILL.
+    kill_timeout: Duration,
+    /// Maximum daemons `shutdown_all` stops at once (`None` = unbounded).
+    shutdown_concurrency: Option<usize>,
+    /// Woken on every status change.
+    status_changed: Arc<Notify>,
+    /// Lifecycle event stream.
+    events: broadcast::Sender<LifecycleEvent>,
+    /// Platform reported for daemons without a platform handle.
+    platform: Platform,
+    /// Adapters for daemons with a platform handle.
+    adapters: Vec<Arc<dyn PlatformAdapter>>,
+    /// File the registry is persisted to, if any.
+    state_file: Option<PathBuf>,
+    /// Serializes state file writes.
+    state_lock: Arc<Mutex<()>>,
+    /// Wakes the state writer; started on the first change.
+    state_writer: OnceLock<watch::Sender<()>>,
 }
 
 impl DaemonManager {
@@ -219,12 +574,29 @@ impl DaemonManager {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            daemons: RwLock::new(HashMap::new()),
+            daemons: Arc::default(),
             health_check_interval: Duration::from_secs(30),
             shutdown_timeout: Duration::from_secs(30),
+            transition_log_capacity: DEFAULT_TRANSITION_LOG_CAPACITY,
+            kill_timeout: DEFAULT_KILL_TIMEOUT,
+            shutdown_concurrency: None,
+            status_changed: Arc::new(Notify::new()),
+            events: broadcast::Sender::new(DEFAULT_EVENT_CAPACITY),
+            platform: detect_platform(),
+            adapters: Vec::new(),
+            state_file: None,
+            state_lock: Arc::default(),
+            state_writer: OnceLock::new(),
         }
     }
 
+    /// Sets the number of status transitions kept per daemon.
+    #[must_use]
+    pub const fn with_transition_log_capacity(mut self, capacity: usize) -> Self {
+        self.transition_log_capacity = capacity;
+        self
+    }
+
     /// Sets the health check interval.
     #[must_use]
     pub const fn with_health_check_interval(mut self, interval: Duration) -> Self {
@@ -239,21 +611,114 @@ impl DaemonManager {
         self
     }
 
+    /// Sets how long `shutdown_all` waits for a daemon to stop after KILL.
+    #[must_use]
+    pub const fn with_kill_timeout(mut self, timeout: Duration) -> Self {
+        self.kill_timeout = timeout;
+        self
+    }
+
+    /// Limits how many daemons `shutdown_all` stops at once.
+    ///
+    /// A limit of 0 is treated as 1.
+    #[must_use]
+    pub const fn with_shutdown_concurrency(mut self, limit: usize) -> Self {
+        self.shutdown_concurrency = Some(if limit == 0 { 1 } else { limit });
+        self
+    }
+
+    /// Sets how many lifecycle events are buffered per subscriber.
+    ///
+    /// A capacity of 0 is treated as 1.
+    #[must_use]
+    pub fn with_event_capacity(mut self, capacity: usize) -> Self {
+        self.events = broadcast::Sender::new(capacity.max(1));
+        self
+    }
+
+    /// Sets the platform reported for daemons without a platform handle
+    /// (detected by default).
+    #[must_use]
+    pub const fn with_platform(mut self, platform: Platform) -> Self {
+        self.platform = platform;
+        self
+    }
+
+    /// Adds the adapter for daemons on its platform.
+    ///
+    /// Daemons are [`spawn`](Self::spawn)ed through the adapter for the
+    /// manager's platform. Daemons whose platform handle names the
+    /// adapter's platform are signalled, stopped, watched and
+    /// [`reattach`](Self::reattach)ed through it.
+    #[must_use]
+    pub fn with_adapter(mut self, adapter: Arc<dyn PlatformAdapter>) -> Self {
+        self.adapters.push(adapter);
+        self
+    }
+
+    /// Persists the registry to `path` after changes.
+    ///
+    /// A background writer saves changes shortly after they happen, one
+    /// write per burst; health and readiness results alone do not trigger
+    /// a write. [`shutdown_all`](Self::shutdown_all) and
+    /// [`save_state`](Self::save_state) write at once. Use
+    /// [`reattach`](Self::reattach) on startup to restore it.
+    #[must_use]
+    pub fn with_state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(path.into());
+        self
+    }
+
+    /// Returns the state file, if persistence is enabled.
+    #[must_use]
+    pub fn state_file(&self) -> Option<&Path> {
+        self.state_file.as_deref()
+    }
+
     /// Registers a daemon with the manager.
     ///
+    /// `restart_policy` is kept as given; use
+    /// [`register_configured`](Self::register_configured) to follow the
+    /// configuration's `restart` and `backoff` instead.
+    ///
     /// # Errors
-    /// Returns an error if a daemon with the same ID is already registered.
+    /// Returns an error if a daemon with the same ID is already registered,
+    /// or `DaemonError::Dependency` if its `requires`/`wants`/`after`
+    /// would create a dependency cycle.
     pub async fn register(
         &self,
         daemon: Box<dyn Daemon>,
         config: DaemonConfig,
         restart_policy: RestartPolicy,
     ) -> Result<DaemonId> {
-        let id = daemon.id();
-        let name = daemon.name().to_string();
+        let managed = ManagedDaemon::new(daemon.id(), daemon.name().to_string(), config)
+            .with_restart_policy(restart_policy);
+        self.insert(managed).await
+    }
+
+    /// Registers a daemon with the restart policy its configuration
+    /// describes.
+    ///
+    /// The policy is built by [`RestartPolicy::from_config`] and rebuilt
+    /// whenever a reload changes `restart` or `backoff`, so the next
+    /// restart decision follows the reloaded file.
+    ///
+    /// # Errors
+    /// As [`register`](Self::register).
+    pub async fn register_configured(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: DaemonConfig,
+    ) -> Result<DaemonId> {
+        let managed = ManagedDaemon::new(daemon.id(), daemon.name().to_string(), config)
+            .with_config_restart_policy();
+        self.insert(managed).await
+    }
 
-        let managed =
-            ManagedDaemon::new(id, name.clone(), config).with_restart_policy(restart_policy);
+    /// Adds a new registration.
+    async fn insert(&self, managed: ManagedDaemon) -> Result<DaemonId> {
+        let (id, name) = (managed.id, managed.name.clone());
+        let managed = managed.with_transition_log_capacity(self.transition_log_capacity);
 
         let mut daemons = self.daemons.write().await;
 
@@ -264,9 +729,16 @@ impl DaemonManager {
             )));
         }
 
+        // Poka-Yoke: reject cycles before they can deadlock a start
+        let graph = dependency_graph(&daemons, Some((id, &managed.config))).await;
+        dependencies::ensure_acyclic(&graph)?;
+
         daemons.insert(id, Arc::new(Mutex::new(managed)));
+        drop(daemons);
 
         tracing::info!(id = %id, name = %name, "registered daemon");
+        self.emit(id, name, None, LifecycleEventKind::Registered);
+        self.persist();
 
         Ok(id)
     }
@@ -289,10 +761,15 @@ impl DaemonManager {
                 id
             )));
         }
+        let name = guard.name.clone();
+        let trace = guard.trace_context.clone();
         drop(guard);
 
         daemons.remove(&id);
+        drop(daemons);
         tracing::info!(id = %id, "unregistered daemon");
+        self.emit(id, name, trace.as_ref(), LifecycleEventKind::Unregistered);
+        self.persist();
 
         Ok(())
     }
@@ -324,6 +801,9 @@ impl DaemonManager {
 
     /// Sends a signal to a daemon.
     ///
+    /// A daemon without an in-process run is signalled through the adapter
+    /// for its platform handle.
+    ///
     /// # Errors
     /// Returns an error if the daemon is not found or cannot receive signals.
     pub async fn signal(&self, id: DaemonId, signal: Signal) -> Result<()> {
@@ -345,9 +825,17 @@ impl DaemonManager {
         if let Some(ref handle) = guard.context_handle {
             handle.send_signal(signal).await?;
             tracing::debug!(id = %id, signal = ?signal, "sent signal to daemon");
+        } else if let Some(ref handle) = guard.platform_handle
+            && let Some(adapter) = adapter_for(&self.adapters, handle)
+        {
+            adapter
+                .signal(handle, signal)
+                .await
+                .map_err(|e| DaemonError::Signal(e.to_string()))?;
+            tracing::debug!(id = %id, signal = ?signal, handle = %handle, "sent signal through adapter");
         } else {
             return Err(DaemonError::State(format!(
-                "daemon {} has no context handle",
+                "daemon {} has no context handle or platform adapter",
                 id
             )));
         }
@@ -357,8 +845,12 @@ impl DaemonManager {
 
     /// Updates daemon status.
     ///
+    /// The move must be allowed by [`DaemonStatus::can_transition_to`];
+    /// it is recorded in the daemon's transition log.
+    ///
     /// # Errors
-    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    /// Returns `DaemonError::NotFound` if the daemon is not registered, or
+    /// `DaemonError::InvalidTransition` if the move is illegal.
     pub async fn update_status(&self, id: DaemonId, status: DaemonStatus) -> Result<()> {
         let daemons = self.daemons.read().await;
 
@@ -368,22 +860,46 @@ impl DaemonManager {
 
         let mut guard = daemon.lock().await;
         let old_status = guard.status;
-        guard.status = status;
+        // A start refused by the start limit still moves to Failed
+        let result = guard.transition(status);
+        let new_status = guard.status;
+        let name = guard.config.name.clone();
+        let daemon_name = guard.name.clone();
+        let trace = guard.trace_context.clone();
+        drop(guard);
+        drop(daemons);
 
-        tracing::debug!(id = %id, old = ?old_status, new = ?status, "status changed");
+        if new_status == old_status {
+            return result;
+        }
 
-        Ok(())
+        tracing::debug!(id = %id, old = ?old_status, new = ?new_status, "status changed");
+
+        let kind = match new_status {
+            DaemonStatus::Starting => Some(LifecycleEventKind::Starting),
+            DaemonStatus::Running => Some(LifecycleEventKind::Running),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            self.emit(id, daemon_name, trace.as_ref(), kind);
+        }
+        if matches!(new_status, DaemonStatus::Failed(_)) {
+            self.fail_dependents(name).await;
+        }
+        self.status_changed.notify_waiters();
+        self.persist();
+
+        result
     }
 
-    /// Updates daemon context handle.
+    /// Applies notifications the daemon sent over its notify socket.
+    ///
+    /// `READY=1` moves a `Starting` daemon to `Running`; `STATUS=` and
+    /// `WATCHDOG=1` are recorded. Other assignments are ignored.
     ///
     /// # Errors
     /// Returns `DaemonError::NotFound` if the daemon is not registered.
-    pub async fn set_context_handle(
-        &self,
-        id: DaemonId,
-        handle: DaemonContextHandle,
-    ) -> Result<()> {
+    pub async fn notify(&self, id: DaemonId, states: &[NotifyState]) -> Result<()> {
         let daemons = self.daemons.read().await;
 
         let daemon = daemons
@@ -391,34 +907,51 @@ impl DaemonManager {
             .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
 
         let mut guard = daemon.lock().await;
-        guard.context_handle = Some(handle);
-        guard.last_started = Some(Instant::now());
+        let mut ready = false;
+        for state in states {
+            match state {
+                NotifyState::Ready => ready = guard.status == DaemonStatus::Starting,
+                NotifyState::Status(text) => guard.status_text = Some(text.clone()),
+                NotifyState::Watchdog => {
+                    guard.last_watchdog = Some(Instant::now());
+                    // Same deadline as DaemonContext::heartbeat
+                    if let Some(ref handle) = guard.context_handle {
+                        handle.heartbeat();
+                    }
+                }
+                _ => {}
+            }
+        }
+        drop(guard);
+        drop(daemons);
 
+        if ready {
+            tracing::debug!(id = %id, "daemon reported ready");
+            self.update_status(id, DaemonStatus::Running).await?;
+        }
         Ok(())
     }
 
-    /// Increments restart count and returns the new count.
+    /// Returns the last `STATUS=` line the daemon sent.
     ///
     /// # Errors
     /// Returns `DaemonError::NotFound` if the daemon is not registered.
-    pub async fn increment_restart_count(&self, id: DaemonId) -> Result<u32> {
+    pub async fn status_text(&self, id: DaemonId) -> Result<Option<String>> {
         let daemons = self.daemons.read().await;
 
         let daemon = daemons
             .get(&id)
             .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
 
-        let mut guard = daemon.lock().await;
-        guard.restart_count += 1;
-
-        Ok(guard.restart_count)
+        let guard = daemon.lock().await;
+        Ok(guard.status_text.clone())
     }
 
-    /// Gets restart policy for a daemon.
+    /// Returns when the daemon last pinged its watchdog.
     ///
     /// # Errors
     /// Returns `DaemonError::NotFound` if the daemon is not registered.
-    pub async fn get_restart_policy(&self, id: DaemonId) -> Result<RestartPolicy> {
+    pub async fn last_watchdog(&self, id: DaemonId) -> Result<Option<Instant>> {
         let daemons = self.daemons.read().await;
 
         let daemon = daemons
@@ -426,14 +959,28 @@ impl DaemonManager {
             .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
 
         let guard = daemon.lock().await;
-        Ok(guard.restart_policy.clone())
+        Ok(guard.last_watchdog)
     }
 
-    /// Gets restart count for a daemon.
+    /// Completes on the next status change of any daemon.
+    pub async fn status_changed(&self) {
+        self.status_changed.notified().await;
+    }
+
+    /// Subscribes to lifecycle events of all daemons.
+    ///
+    /// Only events published after the call are received.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publishes a lifecycle event observed outside the manager, such as
+    /// an exit or a scheduled restart seen by a supervisor.
     ///
     /// # Errors
     /// Returns `DaemonError::NotFound` if the daemon is not registered.
-    pub async fn get_restart_count(&self, id: DaemonId) -> Result<u32> {
+    pub async fn publish(&self, id: DaemonId, kind: LifecycleEventKind) -> Result<()> {
         let daemons = self.daemons.read().await;
 
         let daemon = daemons
@@ -441,58 +988,1032 @@ impl DaemonManager {
             .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
 
         let guard = daemon.lock().await;
-        Ok(guard.restart_count)
+        let (name, trace) = (guard.name.clone(), guard.trace_context.clone());
+        drop(guard);
+        drop(daemons);
+
+        self.emit(id, name, trace.as_ref(), kind);
+        Ok(())
     }
 
-    /// Updates last health check result.
+    /// Sends an event to the current subscribers, if any, tagged with the
+    /// trace of the daemon's run.
+    fn emit(
+        &self,
+        id: DaemonId,
+        name: String,
+        trace: Option<&TraceContext>,
+        kind: LifecycleEventKind,
+    ) {
+        let event = LifecycleEvent::new(id, name, kind);
+        let event = match trace {
+            Some(trace) => event.with_trace(trace),
+            None => event,
+        };
+        let _ = self.events.send(event);
+    }
+
+    /// Fails every daemon that (transitively) requires `name`.
     ///
-    /// # Errors
-    /// Returns `DaemonError::NotFound` if the daemon is not registered.
-    pub async fn update_health(&self, id: DaemonId, health: HealthStatus) -> Result<()> {
-        let daemons = self.daemons.read().await;
+    /// # Toyota Way: Jidoka
+    /// A daemon whose required dependency failed cannot work correctly, so
+    /// it is stopped (TERM) and marked `Failed(DependencyFailed)`.
+    async fn fail_dependents(&self, name: String) {
+        let mut failed = vec![name];
+
+        while let Some(name) = failed.pop() {
+            let mut handles = Vec::new();
+
+            for (id, daemon) in self.daemons.read().await.iter() {
+                let mut guard = daemon.lock().await;
+                if !guard.config.requires.contains(&name) {
+                    continue;
+                }
 
-        let daemon = daemons
-            .get(&id)
-            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+                let could_signal = guard.status.can_signal();
+                if guard
+                    .transition(DaemonStatus::Failed(FailureReason::DependencyFailed))
+                    .is_err()
+                {
+                    // Already terminal: nothing to stop
+                    continue;
+                }
 
-        let mut guard = daemon.lock().await;
-        guard.last_health = Some(health);
+                tracing::warn!(id = %id, dependency = %name, "required dependency failed");
+                if could_signal && let Some(handle) = guard.context_handle.clone() {
+                    handles.push(handle);
+                }
+                failed.push(guard.config.name.clone());
+            }
 
-        Ok(())
+            // Signal outside the locks; the daemon may already have exited
+            for handle in handles {
+                let _ = handle.shutdown().await;
+            }
+        }
     }
 
-    /// Gets last health check result.
+    /// Returns registered daemons in dependency order.
+    ///
+    /// Every daemon comes after the daemons it `requires`, `wants` or
+    /// starts `after`; independent daemons are ordered by name.
     ///
     /// # Errors
-    /// Returns `DaemonError::NotFound` if the daemon is not registered.
-    pub async fn get_health(&self, id: DaemonId) -> Result<Option<HealthStatus>> {
+    /// Returns `DaemonError::Dependency` if the dependencies form a cycle.
+    pub async fn start_order(&self) -> Result<Vec<DaemonId>> {
         let daemons = self.daemons.read().await;
-
-        let daemon = daemons
-            .get(&id)
-            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
-
-        let guard = daemon.lock().await;
-        Ok(guard.last_health.clone())
+        let graph = dependency_graph(&daemons, None).await;
+        dependencies::start_order(&graph)
     }
 
-    /// Initiates graceful shutdown of all daemons.
+    /// Returns the daemon's `requires` entries that are not satisfied.
+    ///
+    /// A requirement is satisfied when a registered daemon with that name is
+    /// `Running` or `Paused`.
     ///
     /// # Errors
-    /// Returns an error if any daemon fails to shut down within the timeout.
-    pub async fn shutdown_all(&self) -> Result<()> {
-        let ids = self.list().await;
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn unmet_requirements(&self, id: DaemonId) -> Result<Vec<String>> {
+        let daemons = self.daemons.read().await;
+        let requires = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?
+            .lock()
+            .await
+            .config
+            .requires
+            .clone();
+
+        let mut unmet = Vec::new();
+        for name in requires {
+            let mut satisfied = false;
+            for daemon in daemons.values() {
+                let guard = daemon.lock().await;
+                if guard.config.name == name
+                    && matches!(guard.status, DaemonStatus::Running | DaemonStatus::Paused)
+                {
+                    satisfied = true;
+                    break;
+                }
+            }
+            if !satisfied {
+                unmet.push(name);
+            }
+        }
+
+        Ok(unmet)
+    }
+
+    /// Returns the IDs of registered daemons with the given config name.
+    pub async fn find_by_name(&self, name: &str) -> Vec<DaemonId> {
+        let mut ids = Vec::new();
+        for (id, daemon) in self.daemons.read().await.iter() {
+            if daemon.lock().await.config.name == name {
+                ids.push(*id);
+            }
+        }
+        ids
+    }
+
+    /// Returns true if the daemon is quarantined by its start limit.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn is_quarantined(&self, id: DaemonId) -> Result<bool> {
+        let daemons = self.daemons.read().await;
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+        Ok(daemon.lock().await.quarantined)
+    }
+
+    /// Lifts a start-limit quarantine, like `systemctl reset-failed`.
+    ///
+    /// The daemon stays `Failed` but may be started again.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn reset_failed(&self, id: DaemonId) -> Result<()> {
+        let daemons = self.daemons.read().await;
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+        daemon.lock().await.reset_failed();
+        drop(daemons);
+
+        tracing::info!(id = %id, "start limit reset");
+        self.persist();
+        Ok(())
+    }
+
+    /// Returns the daemon's recorded status transitions, oldest first.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn transitions(&self, id: DaemonId) -> Result<Vec<StatusTransition>> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.transitions.iter().copied().collect())
+    }
+
+    /// Updates daemon context handle.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn set_context_handle(
+        &self,
+        id: DaemonId,
+        handle: DaemonContextHandle,
+    ) -> Result<()> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let mut guard = daemon.lock().await;
+        guard.context_handle = Some(handle);
+        guard.last_started = Some(Instant::now());
+        // A fresh run starts from the stored configuration
+        guard.restart_required = false;
+
+        Ok(())
+    }
+
+    /// Records the platform handle of a daemon spawned through an adapter.
+    ///
+    /// Persisted with the registry so the daemon can be reattached.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn set_platform_handle(&self, id: DaemonId, handle: DaemonHandle) -> Result<()> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        daemon.lock().await.platform_handle = Some(handle);
+        drop(daemons);
+        self.persist();
+
+        Ok(())
+    }
+
+    /// Returns the platform handle of a daemon, if any.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn platform_handle(&self, id: DaemonId) -> Result<Option<DaemonHandle>> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.platform_handle.clone())
+    }
+
+    /// Spawns a registered daemon through the adapter for the manager's
+    /// platform.
+    ///
+    /// The daemon moves to `Starting` and its platform handle is recorded,
+    /// so it can be signalled, stopped, watched and reattached; it moves on
+    /// to `Running` once the platform reports it running. A failed spawn
+    /// marks it `Failed`.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered,
+    /// `DaemonError::Config` if no adapter was added for the platform,
+    /// `DaemonError::InvalidTransition` if it cannot start, or
+    /// `DaemonError::Runtime` if the adapter cannot spawn it.
+    pub async fn spawn(&self, daemon: Box<dyn Daemon>) -> Result<DaemonHandle> {
+        let id = daemon.id();
+        let config = self.get_config(id).await?;
+        let adapter = self
+            .adapters
+            .iter()
+            .find(|adapter| adapter.platform() == self.platform)
+            .ok_or_else(|| {
+                DaemonError::config(format!("no adapter for platform {}", self.platform))
+            })?;
+
+        self.update_status(id, DaemonStatus::Starting).await?;
+        let handle = match adapter.spawn_with_config(daemon, &config).await {
+            Ok(handle) => handle,
+            Err(e) => {
+                self.update_status(id, DaemonStatus::Failed(FailureReason::Internal))
+                    .await?;
+                return Err(DaemonError::runtime(format!("spawn failed: {}", e)));
+            }
+        };
+
+        if let Some(daemon) = self.daemons.read().await.get(&id) {
+            // Runs through the adapter now, not in-process
+            daemon.lock().await.context_handle = None;
+        }
+        self.set_platform_handle(id, handle.clone()).await?;
+        self.poll_platform(id).await;
+
+        Ok(handle)
+    }
+
+    /// Records the status platforms report for daemons run by an adapter.
+    ///
+    /// Daemons with a platform handle, a matching adapter and no in-process
+    /// run are queried while starting or running. One that exited moves to
+    /// `Stopped` or `Failed`, and one the platform no longer knows to
+    /// `Failed(Lost)`.
+    pub async fn poll_platforms(&self) {
+        for id in self.list().await {
+            self.poll_platform(id).await;
+        }
+    }
+
+    /// Polls platforms every health check interval until the manager is
+    /// dropped.
+    ///
+    /// Watches daemons spawned by [`spawn`](Self::spawn) or restored by
+    /// [`reattach`](Self::reattach), which no supervisor reports on.
+    pub fn watch_platforms(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let manager = Arc::downgrade(self);
+        // A zero period would panic
+        let period = self.health_check_interval.max(Duration::from_millis(1));
+
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(period);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticks.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                manager.poll_platforms().await;
+            }
+        })
+    }
+
+    /// Records the status the platform reports for one daemon.
+    async fn poll_platform(&self, id: DaemonId) {
+        let Some(daemon) = self.daemons.read().await.get(&id).map(Arc::clone) else {
+            return;
+        };
+        let (status, handle) = {
+            let guard = daemon.lock().await;
+            let started = guard.status == DaemonStatus::Starting || guard.status.can_signal();
+            if guard.context_handle.is_some() || !started {
+                return;
+            }
+            let Some(handle) = guard.platform_handle.clone() else {
+                return;
+            };
+            (guard.status, handle)
+        };
+        let Some(adapter) = adapter_for(&self.adapters, &handle) else {
+            return;
+        };
+
+        let Some(reported) = platform_status(adapter.as_ref(), &handle).await else {
+            return;
+        };
+        if reported == status {
+            return;
+        }
+        // Stopped on its own: as if stopped under the manager
+        if reported == DaemonStatus::Stopped
+            && status != DaemonStatus::Stopping
+            && let Err(e) = self.update_status(id, DaemonStatus::Stopping).await
+        {
+            tracing::debug!(id = %id, error = %e, "platform status not recorded");
+            return;
+        }
+        if let Err(e) = self.update_status(id, reported).await {
+            tracing::debug!(id = %id, error = %e, "platform status not recorded");
+        }
+    }
+
+    /// Sets or clears the reload handler of a running daemon.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn set_reload_handler(
+        &self,
+        id: DaemonId,
+        handler: Option<Arc<dyn ReloadHandler>>,
+    ) -> Result<()> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let mut guard = daemon.lock().await;
+        guard.reload_handler = handler;
+
+        Ok(())
+    }
+
+    /// Reloads a daemon's configuration from its `config_path`.
+    ///
+    /// Re-reads the TOML file via [`DaemonConfig::load`], then applies it
+    /// as [`reload_with`](Self::reload_with) does.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered,
+    /// `DaemonError::Config` if it has no `config_path` or the file is
+    /// invalid, or the daemon's error if it rejects the reload.
+    pub async fn reload(&self, id: DaemonId) -> Result<ConfigDiff> {
+        let path = self.get_config(id).await?.config_path.ok_or_else(|| {
+            DaemonError::config(format!("daemon {} has no config_path to reload", id))
+        })?;
+
+        let mut config = DaemonConfig::load(&path)?;
+        config.config_path.get_or_insert(path);
+
+        self.reload_with(id, config).await
+    }
+
+    /// Applies a new configuration to a daemon.
+    ///
+    /// # Toyota Way: Jidoka
+    /// The new configuration is validated and diffed against the current
+    /// one. Live changes are passed to the running daemon's reload handler;
+    /// if it fails, the previous configuration is re-applied and kept.
+    /// Changes that need a restart are stored and flagged via
+    /// [`is_restart_required`](Self::is_restart_required).
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered,
+    /// `DaemonError::Config` if the configuration is invalid, or the
+    /// daemon's error if it rejects the reload.
+    pub async fn reload_with(&self, id: DaemonId, config: DaemonConfig) -> Result<ConfigDiff> {
+        config.validate()?;
+
+        let daemon = self
+            .daemons
+            .read()
+            .await
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let graph = dependency_graph(&*self.daemons.read().await, Some((id, &config))).await;
+        dependencies::ensure_acyclic(&graph)?;
+
+        let (old, handler) = {
+            let guard = daemon.lock().await;
+            (guard.config.clone(), guard.reload_handler.clone())
+        };
+
+        let diff = old.diff(&config);
+        if diff.is_empty() {
+            tracing::debug!(id = %id, "reload: configuration unchanged");
+            return Ok(diff);
+        }
+
+        // No lock held across the handler: it may call back into the manager
+        if let (true, Some(handler)) = (diff.has_live_changes(), handler) {
+            if let Err(e) = handler.reload(&config).await {
+                tracing::warn!(id = %id, error = %e, "reload rejected, rolling back");
+                if let Err(rollback) = handler.reload(&old).await {
+                    tracing::error!(id = %id, error = %rollback, "reload rollback failed");
+                }
+                return Err(e);
+            }
+        }
+
+        let mut guard = daemon.lock().await;
+        guard.config = config;
+        if guard.restart_policy_from_config {
+            guard.restart_policy = RestartPolicy::from_config(&guard.config);
+        }
+        guard.restart_required |= diff.requires_restart();
+
+        tracing::info!(
+            id = %id,
+            changes = diff.changes.len(),
+            restart_required = guard.restart_required,
+            "configuration reloaded"
+        );
+        drop(guard);
+        self.persist();
+
+        Ok(diff)
+    }
+
+    /// Returns true if a reload left changes that need a restart.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn is_restart_required(&self, id: DaemonId) -> Result<bool> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.restart_required)
+    }
+
+    /// Increments restart count and returns the new count.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn increment_restart_count(&self, id: DaemonId) -> Result<u32> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let count = {
+            let mut guard = daemon.lock().await;
+            guard.restart_count += 1;
+            guard.restart_count
+        };
+        drop(daemons);
+        self.persist();
+
+        Ok(count)
+    }
+
+    /// Gets the configuration of a daemon.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn get_config(&self, id: DaemonId) -> Result<DaemonConfig> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.config.clone())
+    }
+
+    /// Gets restart policy for a daemon.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn get_restart_policy(&self, id: DaemonId) -> Result<RestartPolicy> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.restart_policy.clone())
+    }
+
+    /// Gets restart count for a daemon.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn get_restart_count(&self, id: DaemonId) -> Result<u32> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.restart_count)
+    }
+
+    /// Updates last health check result.
+    ///
+    /// Publishes [`LifecycleEventKind::HealthChanged`] when the daemon
+    /// turns healthy or unhealthy.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn update_health(&self, id: DaemonId, health: HealthStatus) -> Result<()> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let mut guard = daemon.lock().await;
+        let changed = guard
+            .last_health
+            .as_ref()
+            .is_none_or(|last| last.is_healthy() != health.is_healthy());
+        let name = guard.name.clone();
+        let trace = guard.trace_context.clone();
+        guard.last_health = Some(health.clone());
+        drop(guard);
+        drop(daemons);
+
+        if changed {
+            let kind = LifecycleEventKind::HealthChanged { health };
+            self.emit(id, name, trace.as_ref(), kind);
+        }
+
+        Ok(())
+    }
+
+    /// Gets last health check result.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn get_health(&self, id: DaemonId) -> Result<Option<HealthStatus>> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.last_health.clone())
+    }
+
+    /// Records a readiness probe result.
+    ///
+    /// See [`ManagedDaemon::record_readiness`].
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn update_readiness(&self, id: DaemonId, health: HealthStatus) -> Result<()> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        daemon.lock().await.record_readiness(health);
+
+        Ok(())
+    }
+
+    /// Runs the daemon's external checks for a probe once and records the
+    /// result.
+    ///
+    /// The supervisor runs them on the probe's interval; this runs them on
+    /// demand, e.g. for a daemon it does not supervise.
+    ///
+    /// Liveness results are recorded as by [`update_health`](Self::update_health)
+    /// and readiness results as by [`update_readiness`](Self::update_readiness);
+    /// startup results are only returned. Each check is bounded by the
+    /// probe's timeout and retried up to the probe's retries.
+    ///
+    /// Returns `None` if health checks are disabled or no external check
+    /// belongs to the probe.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn run_checks(&self, id: DaemonId, probe: ProbeKind) -> Result<Option<HealthStatus>> {
+        let config = self.get_config(id).await?.health_check;
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let (timeout, retries) = match probe {
+            ProbeKind::Liveness => (config.timeout, config.retries),
+            ProbeKind::Readiness => (config.readiness.timeout, config.readiness.retries),
+            ProbeKind::Startup => (config.startup.timeout, 0),
+        };
+        let span = self.lifecycle_span(id, Phase::HealthCheck).await?;
+        span.record("probe", tracing::field::display(probe));
+        let Some(health) = checks::run_checks(&config.checks, probe, timeout, retries)
+            .instrument(span.clone())
+            .await
+        else {
+            return Ok(None);
+        };
+        spans::record_health(&span, &health);
+
+        match probe {
+            ProbeKind::Liveness => self.update_health(id, health.clone()).await?,
+            ProbeKind::Readiness => self.update_readiness(id, health.clone()).await?,
+            ProbeKind::Startup => {}
+        }
+        Ok(Some(health))
+    }
+
+    /// Gets last readiness probe result.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn get_readiness(&self, id: DaemonId) -> Result<Option<HealthStatus>> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.last_readiness.clone())
+    }
+
+    /// Returns true if the daemon is running and ready to serve.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn is_ready(&self, id: DaemonId) -> Result<bool> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.is_ready())
+    }
+
+    /// Sets the metrics of a daemon, exported by
+    /// [`to_prometheus`](Self::to_prometheus).
+    ///
+    /// The supervisor sets them on every start.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn set_metrics(&self, id: DaemonId, metrics: DaemonMetrics) -> Result<()> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let mut guard = daemon.lock().await;
+        guard.metrics = Some(metrics);
+
+        Ok(())
+    }
+
+    /// Returns the metrics of a daemon, if set.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn metrics(&self, id: DaemonId) -> Result<Option<DaemonMetrics>> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.metrics.clone())
+    }
+
+    /// Sets the trace context of a daemon's run; lifecycle events of the
+    /// daemon carry its IDs from then on.
+    ///
+    /// The supervisor sets a new one on every start.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn set_trace_context(&self, id: DaemonId, trace: TraceContext) -> Result<()> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let mut guard = daemon.lock().await;
+        guard.trace_context = Some(trace);
+
+        Ok(())
+    }
+
+    /// Returns a [lifecycle span](crate::spans) for `phase` of a daemon.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn lifecycle_span(&self, id: DaemonId, phase: Phase) -> Result<Span> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(spans::lifecycle_span(phase, &guard, self.platform))
+    }
+
+    /// Returns the trace context of a daemon's current or last run.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered.
+    pub async fn trace_context(&self, id: DaemonId) -> Result<Option<TraceContext>> {
+        let daemons = self.daemons.read().await;
+
+        let daemon = daemons
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        let guard = daemon.lock().await;
+        Ok(guard.trace_context.clone())
+    }
+
+    /// Returns the metrics of all daemons, ordered by name, for export.
+    pub async fn metrics_snapshots(&self) -> Vec<DaemonMetricsSnapshot> {
+        let mut daemons = Vec::new();
+        for daemon in self.daemons.read().await.values() {
+            let guard = daemon.lock().await;
+            daemons.push(DaemonMetricsSnapshot {
+                id: guard.id,
+                name: guard.name.clone(),
+                platform: guard
+                    .platform_handle
+                    .as_ref()
+                    .map_or(self.platform, DaemonHandle::platform),
+                running: guard.status == DaemonStatus::Running,
+                ready: guard.is_ready(),
+                restart_count: guard.restart_count,
+                metrics: guard.metrics.as_ref().map(DaemonMetrics::snapshot),
+            });
+        }
+        daemons.sort_by(|a, b| (&a.name, a.id.to_string()).cmp(&(&b.name, b.id.to_string())));
+        daemons
+    }
 
-        for id in ids {
-            if let Err(e) = self.signal(id, Signal::Term).await {
-                tracing::warn!(id = %id, error = %e, "failed to signal daemon for shutdown");
+    /// Encodes the metrics of all daemons in the Prometheus text format.
+    ///
+    /// See [`DaemonMetricsSnapshot::metrics`] for the series and labels.
+    pub async fn to_prometheus(&self) -> String {
+        let mut encoder = PrometheusEncoder::new();
+        for daemon in self.metrics_snapshots().await {
+            for metric in daemon.metrics() {
+                encoder.metric(&metric);
             }
         }
+        encoder.finish()
+    }
 
-        let count = self.count().await;
-        tracing::info!(count = count, "shutdown initiated for all daemons");
+    /// Serves [`to_prometheus`](Self::to_prometheus) on `GET /metrics` at
+    /// `addr` until the returned server is stopped or dropped.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Io` if the address cannot be bound.
+    pub async fn serve_metrics(
+        self: &Arc<Self>,
+        addr: impl tokio::net::ToSocketAddrs,
+    ) -> Result<MetricsServer> {
+        MetricsServer::bind(Arc::clone(self), addr).await
+    }
 
-        Ok(())
+    /// Returns a snapshot of the registry, ordered by name.
+    pub async fn snapshot(&self) -> ManagerState {
+        snapshot(&self.daemons).await
+    }
+
+    /// Writes the registry to the state file.
+    ///
+    /// Does nothing if no state file is configured. Registry changes are
+    /// saved in the background; this writes at once and reports failures.
+    ///
+    /// # Errors
+    /// Returns an error if the state file cannot be written.
+    pub async fn save_state(&self) -> Result<()> {
+        let Some(path) = self.state_file.clone() else {
+            return Ok(());
+        };
+        write_state(&self.daemons, &self.state_lock, path).await
+    }
+
+    /// Has the state writer save the registry after a change.
+    fn persist(&self) {
+        let Some(ref path) = self.state_file else {
+            return;
+        };
+        self.state_writer
+            .get_or_init(|| {
+                let (changes, watcher) = watch::channel(());
+                tokio::spawn(state_writer(
+                    Arc::clone(&self.daemons),
+                    Arc::clone(&self.state_lock),
+                    path.clone(),
+                    watcher,
+                ));
+                changes
+            })
+            .send_replace(());
+    }
+
+    /// Restores the registry from the state file and reattaches daemons.
+    ///
+    /// Daemons that were running are checked through the added adapter
+    /// matching their platform handle ([`PlatformAdapter::reattach`]), and
+    /// controlled through it afterwards; [`watch_platforms`] notices when
+    /// they exit. Those still
+    /// running are reattached with the status the platform reports; the
+    /// rest (no handle, no adapter, or not running) are marked
+    /// `Failed(Lost)`. In-process daemons cannot be reattached. Daemons
+    /// that were not running keep their saved status. Daemons already
+    /// registered are left alone.
+    ///
+    /// [`watch_platforms`]: Self::watch_platforms
+    ///
+    /// # Errors
+    /// Returns an error if the state file cannot be read.
+    pub async fn reattach(&self) -> Result<ReattachReport> {
+        let mut report = ReattachReport::default();
+        let Some(ref path) = self.state_file else {
+            return Ok(report);
+        };
+        let Some(state) = ManagerState::load(path)? else {
+            return Ok(report);
+        };
+
+        for saved in state.daemons {
+            let id = saved.id;
+            if self.daemons.read().await.contains_key(&id) {
+                tracing::debug!(id = %id, "already registered, not restored");
+                continue;
+            }
+
+            let was_running = saved.status.can_signal() || saved.status == DaemonStatus::Starting;
+            let status = if was_running {
+                match probe(&self.adapters, saved.handle.as_ref()).await {
+                    Some(status) if status.is_active() => {
+                        report.reattached.push(id);
+                        status
+                    }
+                    _ => {
+                        tracing::warn!(id = %id, name = %saved.name, "daemon lost");
+                        report.lost.push(id);
+                        DaemonStatus::Failed(FailureReason::Lost)
+                    }
+                }
+            } else {
+                report.inactive.push(id);
+                saved.status
+            };
+
+            let managed = ManagedDaemon::new(id, saved.name, saved.config);
+            let mut managed = if saved.restart_policy_from_config {
+                managed.with_config_restart_policy()
+            } else {
+                managed.with_restart_policy(saved.restart_policy)
+            }
+            .with_transition_log_capacity(self.transition_log_capacity);
+            managed.status = status;
+            managed.restart_count = saved.restart_count;
+            managed.last_health = saved.last_health;
+            managed.ready = saved.ready;
+            managed.platform_handle = saved.handle;
+            managed.quarantined = saved.quarantined;
+
+            self.daemons
+                .write()
+                .await
+                .insert(id, Arc::new(Mutex::new(managed)));
+        }
+
+        tracing::info!(
+            reattached = report.reattached.len(),
+            lost = report.lost.len(),
+            inactive = report.inactive.len(),
+            "manager state restored"
+        );
+        self.persist();
+
+        Ok(report)
+    }
+
+    /// Shuts down all running daemons and reports how each one ended.
+    ///
+    /// Each daemon gets TERM and up to its configured `shutdown_timeout` to
+    /// reach `Stopped` or `Failed`; if it does not, it gets KILL and up to
+    /// the manager's kill timeout. Daemons stop concurrently (bounded by
+    /// [`with_shutdown_concurrency`](Self::with_shutdown_concurrency)), but
+    /// never before the running daemons that depend on them.
+    ///
+    /// Daemons that are not running are left alone.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Dependency` if the dependencies form a cycle,
+    /// or `DaemonError::Internal` if a shutdown task panicked. Daemons that
+    /// do not stop are reported as stuck, not as errors.
+    pub async fn shutdown_all(&self) -> Result<ShutdownReport> {
+        let daemons = self.daemons.read().await;
+        let graph = dependency_graph(&daemons, None).await;
+        dependencies::ensure_acyclic(&graph)?;
+
+        // One completion flag per daemon; dependencies wait on dependents'
+        let done: HashMap<DaemonId, watch::Sender<bool>> = graph
+            .iter()
+            .map(|node| (node.key, watch::channel(false).0))
+            .collect();
+        let mut dependents: HashMap<DaemonId, Vec<watch::Receiver<bool>>> = HashMap::new();
+        for node in &graph {
+            for dep in graph.iter().filter(|n| node.deps.contains(&n.name)) {
+                if let Some(flag) = done.get(&node.key) {
+                    dependents
+                        .entry(dep.key)
+                        .or_default()
+                        .push(flag.subscribe());
+                }
+            }
+        }
+
+        let limit = self
+            .shutdown_concurrency
+            .map(|n| Arc::new(Semaphore::new(n)));
+        let mut tasks = JoinSet::new();
+
+        for (id, flag) in done {
+            let Some(daemon) = daemons.get(&id).map(Arc::clone) else {
+                continue;
+            };
+            let waits = dependents.remove(&id).unwrap_or_default();
+            let limit = limit.clone();
+            let notify = Arc::clone(&self.status_changed);
+            let kill_timeout = self.kill_timeout;
+            let platform = self.platform;
+            let adapters = self.adapters.clone();
+
+            tasks.spawn(async move {
+                // Reverse dependency order: dependents stop first
+                for mut wait in waits {
+                    // A dropped flag means that task ended: go ahead
+                    let _ = wait.wait_for(|done| *done).await;
+                }
+
+                let _permit = match limit {
+                    Some(limit) => limit.acquire_owned().await.ok(),
+                    None => None,
+                };
+                let outcome =
+                    stop_daemon(id, &daemon, &notify, kill_timeout, platform, &adapters).await;
+                flag.send_replace(true);
+                (id, outcome)
+            });
+        }
+        drop(daemons);
+
+        let mut report = ShutdownReport::default();
+        while let Some(joined) = tasks.join_next().await {
+            let (id, outcome) = joined
+                .map_err(|e| DaemonError::Internal(format!("shutdown task failed: {}", e)))?;
+            if let Some(outcome) = outcome {
+                report.record(id, outcome);
+            }
+        }
+
+        tracing::info!(
+            graceful = report.graceful.len(),
+            killed = report.killed.len(),
+            stuck = report.stuck.len(),
+            unsignalable = report.unsignalable.len(),
+            "all daemons shut down"
+        );
+        // Saved at once: the process is likely about to exit
+        if let Err(e) = self.save_state().await {
+            tracing::warn!(error = %e, "failed to persist manager state");
+        }
+
+        Ok(report)
     }
 }
 
@@ -502,6 +2023,279 @@ impl Default for DaemonManager {
     }
 }
 
+/// Returns a snapshot of `daemons`, ordered by name.
+async fn snapshot(daemons: &Registry) -> ManagerState {
+    let mut saved = Vec::new();
+    for daemon in daemons.read().await.values() {
+        let guard = daemon.lock().await;
+        saved.push(PersistedDaemon {
+            id: guard.id,
+            name: guard.name.clone(),
+            status: guard.status,
+            config: guard.config.clone(),
+            restart_policy: guard.restart_policy.clone(),
+            restart_policy_from_config: guard.restart_policy_from_config,
+            restart_count: guard.restart_count,
+            last_health: guard.last_health.clone(),
+            ready: guard.is_ready(),
+            handle: guard.platform_handle.clone(),
+            quarantined: guard.quarantined,
+        });
+    }
+    saved.sort_by(|a, b| a.name.cmp(&b.name));
+
+    ManagerState::new(saved)
+}
+
+/// Writes a snapshot of `daemons` to `path`.
+async fn write_state(daemons: &Registry, lock: &Mutex<()>, path: PathBuf) -> Result<()> {
+    // Held across snapshot and write: the last write has the newest state
+    let _lock = lock.lock().await;
+    let state = snapshot(daemons).await;
+    tokio::task::spawn_blocking(move || state.save(path))
+        .await
+        .map_err(|e| DaemonError::Internal(format!("state writer failed: {}", e)))?
+}
+
+/// Saves `daemons` to `path` after each burst of changes, logging failures.
+///
+/// Ends once the manager is dropped, after saving its last changes.
+async fn state_writer(
+    daemons: Arc<Registry>,
+    lock: Arc<Mutex<()>>,
+    path: PathBuf,
+    mut changes: watch::Receiver<()>,
+) {
+    while changes.changed().await.is_ok() {
+        tokio::time::sleep(STATE_WRITE_DELAY).await;
+        changes.borrow_and_update();
+        if let Err(e) = write_state(&daemons, &lock, path.clone()).await {
+            tracing::warn!(error = %e, "failed to persist manager state");
+        }
+    }
+}
+
+/// Asks the adapter for `handle`'s platform whether the daemon still runs.
+///
+/// Returns `None` if there is no handle, no matching adapter, or the
+/// platform cannot tell.
+async fn probe(
+    adapters: &[Arc<dyn PlatformAdapter>],
+    handle: Option<&DaemonHandle>,
+) -> Option<DaemonStatus> {
+    let handle = handle?;
+    let adapter = adapter_for(adapters, handle)?;
+
+    match adapter.reattach(handle).await {
+        Ok(status) => Some(status),
+        Err(e) => {
+            tracing::warn!(handle = %handle, error = %e, "reattach failed");
+            None
+        }
+    }
+}
+
+/// Returns the adapter for `handle`'s platform.
+fn adapter_for<'a>(
+    adapters: &'a [Arc<dyn PlatformAdapter>],
+    handle: &DaemonHandle,
+) -> Option<&'a Arc<dyn PlatformAdapter>> {
+    adapters
+        .iter()
+        .find(|adapter| adapter.platform() == handle.platform())
+}
+
+/// Asks `adapter` for the status of the daemon behind `handle`.
+///
+/// A daemon the platform no longer knows is `Failed(Lost)`; `None` if the
+/// platform cannot tell.
+async fn platform_status(
+    adapter: &dyn PlatformAdapter,
+    handle: &DaemonHandle,
+) -> Option<DaemonStatus> {
+    match adapter.status(handle).await {
+        Ok(status) => Some(status),
+        Err(PlatformError::NotFound(_)) => Some(DaemonStatus::Failed(FailureReason::Lost)),
+        Err(e) => {
+            tracing::debug!(handle = %handle, error = %e, "platform status unknown");
+            None
+        }
+    }
+}
+
+/// Stops one daemon: TERM, wait, KILL, wait.
+///
+/// A daemon without an in-process run is signalled and polled through the
+/// adapter for its platform handle.
+///
+/// Returns `None` if the daemon was not running.
+async fn stop_daemon(
+    id: DaemonId,
+    daemon: &Mutex<ManagedDaemon>,
+    notify: &Notify,
+    kill_timeout: Duration,
+    platform: Platform,
+    adapters: &[Arc<dyn PlatformAdapter>],
+) -> Option<ShutdownOutcome> {
+    let (status, run, platform_handle, timeout, span) = {
+        let guard = daemon.lock().await;
+        (
+            guard.status,
+            guard.context_handle.clone(),
+            guard.platform_handle.clone(),
+            guard.config.shutdown_timeout,
+            spans::lifecycle_span(Phase::Stop, &guard, platform),
+        )
+    };
+
+    if !status.can_signal() {
+        return None;
+    }
+    let adapter = match (&run, &platform_handle) {
+        (None, Some(handle)) => {
+            adapter_for(adapters, handle).map(|adapter| (adapter.as_ref(), handle))
+        }
+        _ => None,
+    };
+
+    let outcome = async {
+        if run.is_none() && adapter.is_none() {
+            tracing::warn!(id = %id, "daemon has no run or platform adapter to signal");
+            return ShutdownOutcome::Unsignalable;
+        }
+        // Already stopping: do not resend TERM, but still enforce the deadline
+        if status != DaemonStatus::Stopping
+            && let Err(e) = deliver(run.as_ref(), adapter, Signal::Term).await
+        {
+            tracing::debug!(id = %id, error = %e, "TERM not delivered");
+        }
+        if wait_for_terminal(daemon, notify, timeout, adapter).await {
+            return ShutdownOutcome::Graceful;
+        }
+
+        tracing::warn!(id = %id, timeout = ?timeout, "daemon did not stop, sending KILL");
+        let _ = deliver(run.as_ref(), adapter, Signal::Kill).await;
+        if wait_for_terminal(daemon, notify, kill_timeout, adapter).await {
+            return ShutdownOutcome::Killed;
+        }
+
+        tracing::error!(id = %id, "daemon stuck after KILL");
+        ShutdownOutcome::Stuck
+    }
+    .instrument(span.clone())
+    .await;
+
+    span.record(
+        "outcome",
+        match outcome {
+            ShutdownOutcome::Graceful => "graceful",
+            ShutdownOutcome::Killed => "killed",
+            ShutdownOutcome::Stuck => "stuck",
+            ShutdownOutcome::Unsignalable => "unsignalable",
+        },
+    );
+    Some(outcome)
+}
+
+/// Sends `signal` to the in-process run, or else through the adapter.
+async fn deliver(
+    run: Option<&DaemonContextHandle>,
+    adapter: Option<(&dyn PlatformAdapter, &DaemonHandle)>,
+    signal: Signal,
+) -> Result<()> {
+    if let Some(run) = run {
+        return run.send_signal(signal).await;
+    }
+    if let Some((adapter, handle)) = adapter {
+        return adapter
+            .signal(handle, signal)
+            .await
+            .map_err(|e| DaemonError::Signal(e.to_string()));
+    }
+    Ok(())
+}
+
+/// Waits up to `timeout` for the daemon to reach `Stopped` or `Failed`.
+///
+/// With an adapter, the platform is polled too, and the status it reports
+/// once the daemon exited is recorded.
+async fn wait_for_terminal(
+    daemon: &Mutex<ManagedDaemon>,
+    notify: &Notify,
+    timeout: Duration,
+    adapter: Option<(&dyn PlatformAdapter, &DaemonHandle)>,
+) -> bool {
+    /// Re-check interval, in case a change lands before we listen.
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Some((adapter, handle)) = adapter
+            && let Some(reported) = platform_status(adapter, handle).await
+            && reported.is_terminal()
+        {
+            let mut guard = daemon.lock().await;
+            if !guard.status.is_terminal() {
+                // Stopped or failed: reached through Stopping
+                let result = match guard.status {
+                    DaemonStatus::Stopping => Ok(()),
+                    _ => guard.transition(DaemonStatus::Stopping),
+                }
+                .and_then(|()| guard.transition(reported));
+                if let Err(e) = result {
+                    tracing::debug!(handle = %handle, error = %e, "platform status not recorded");
+                }
+                notify.notify_waiters();
+            }
+        }
+        if daemon.lock().await.status.is_terminal() {
+            return true;
+        }
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        let _ = tokio::time::timeout((deadline - now).min(POLL_INTERVAL), notify.notified()).await;
+    }
+}
+
+/// Builds the dependency graph of all daemons, ordered by name.
+///
+/// If `replace` is given, that daemon is added (or overridden) with the
+/// given configuration.
+async fn dependency_graph(
+    daemons: &HashMap<DaemonId, Arc<Mutex<ManagedDaemon>>>,
+    replace: Option<(DaemonId, &DaemonConfig)>,
+) -> Vec<DependencyNode<DaemonId>> {
+    let node = |key, config: &DaemonConfig| DependencyNode {
+        key,
+        name: config.name.clone(),
+        deps: config
+            .dependencies()
+            .into_iter()
+            .map(String::from)
+            .collect(),
+    };
+
+    let mut graph = Vec::with_capacity(daemons.len() + 1);
+    for (id, daemon) in daemons {
+        if replace.is_some_and(|(r, _)| r == *id) {
+            continue;
+        }
+        graph.push(node(*id, &daemon.lock().await.config));
+    }
+    if let Some((id, config)) = replace {
+        graph.push(node(id, config));
+    }
+
+    graph.sort_by(|a, b| {
+        a.name
+            .cmp(&b.name)
+            .then_with(|| a.key.as_uuid().cmp(b.key.as_uuid()))
+    });
+    graph
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -511,7 +2305,9 @@ impl Default for DaemonManager {
 mod tests {
     use super::*;
     use crate::DaemonContext;
+    use crate::config::StartLimit;
     use crate::metrics::DaemonMetrics;
+    use crate::types::FailureReason;
     use async_trait::async_trait;
     use std::time::Duration;
 
@@ -792,6 +2588,10 @@ mod tests {
             .await
             .unwrap();
 
+        manager
+            .update_status(id, DaemonStatus::Starting)
+            .await
+            .unwrap();
         manager
             .update_status(id, DaemonStatus::Running)
             .await
@@ -801,6 +2601,96 @@ mod tests {
         assert_eq!(status, DaemonStatus::Running);
     }
 
+    #[tokio::test]
+    async fn test_manager_rejects_illegal_transition() {
+        let manager = DaemonManager::new();
+        let daemon = TestDaemon::new("test");
+        let id = daemon.id;
+        let config = DaemonConfig::new("test", "/bin/test");
+
+        manager
+            .register(Box::new(daemon), config, RestartPolicy::Never)
+            .await
+            .unwrap();
+
+        let result = manager.update_status(id, DaemonStatus::Paused).await;
+        assert!(matches!(
+            result,
+            Err(DaemonError::InvalidTransition {
+                from: DaemonStatus::Created,
+                to: DaemonStatus::Paused,
+            })
+        ));
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Created);
+        assert!(manager.transitions(id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_manager_transition_log() {
+        let manager = DaemonManager::new();
+        let daemon = TestDaemon::new("test");
+        let id = daemon.id;
+        let config = DaemonConfig::new("test", "/bin/test");
+
+        manager
+            .register(Box::new(daemon), config, RestartPolicy::Never)
+            .await
+            .unwrap();
+
+        for status in [
+            DaemonStatus::Starting,
+            DaemonStatus::Running,
+            DaemonStatus::Running, // no-op, not recorded
+            DaemonStatus::Stopping,
+            DaemonStatus::Stopped,
+        ] {
+            manager.update_status(id, status).await.unwrap();
+        }
+
+        let log = manager.transitions(id).await.unwrap();
+        let path: Vec<_> = log.iter().map(|t| (t.from, t.to)).collect();
+        assert_eq!(
+            path,
+            [
+                (DaemonStatus::Created, DaemonStatus::Starting),
+                (DaemonStatus::Starting, DaemonStatus::Running),
+                (DaemonStatus::Running, DaemonStatus::Stopping),
+                (DaemonStatus::Stopping, DaemonStatus::Stopped),
+            ]
+        );
+        assert!(log.windows(2).all(|w| w[0].at <= w[1].at));
+    }
+
+    #[tokio::test]
+    async fn test_manager_transition_log_is_bounded() {
+        let manager = DaemonManager::new().with_transition_log_capacity(3);
+        let daemon = TestDaemon::new("test");
+        let id = daemon.id;
+        let config = DaemonConfig::new("test", "/bin/test");
+
+        manager
+            .register(Box::new(daemon), config, RestartPolicy::Never)
+            .await
+            .unwrap();
+
+        for _ in 0..5 {
+            manager
+                .update_status(id, DaemonStatus::Starting)
+                .await
+                .unwrap();
+            manager
+                .update_status(id, DaemonStatus::Failed(FailureReason::Internal))
+                .await
+                .unwrap();
+        }
+
+        let log = manager.transitions(id).await.unwrap();
+        assert_eq!(log.len(), 3);
+        // Oldest entries were dropped
+        assert_eq!(log[2].to, DaemonStatus::Failed(FailureReason::Internal));
+        assert_eq!(log[0].to, DaemonStatus::Failed(FailureReason::Internal));
+    }
+
     #[tokio::test]
     async fn test_manager_restart_count() {
         let manager = DaemonManager::new();
@@ -818,33 +2708,217 @@ mod tests {
         manager.increment_restart_count(id).await.unwrap();
         assert_eq!(manager.get_restart_count(id).await.unwrap(), 1);
 
-        manager.increment_restart_count(id).await.unwrap();
-        manager.increment_restart_count(id).await.unwrap();
-        assert_eq!(manager.get_restart_count(id).await.unwrap(), 3);
+        manager.increment_restart_count(id).await.unwrap();
+        manager.increment_restart_count(id).await.unwrap();
+        assert_eq!(manager.get_restart_count(id).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_manager_health() {
+        let manager = DaemonManager::new();
+        let daemon = TestDaemon::new("test");
+        let id = daemon.id;
+        let config = DaemonConfig::new("test", "/bin/test");
+
+        manager
+            .register(Box::new(daemon), config, RestartPolicy::Never)
+            .await
+            .unwrap();
+
+        // No health check yet
+        assert!(manager.get_health(id).await.unwrap().is_none());
+
+        // Update health
+        let health = HealthStatus::healthy(5);
+        manager.update_health(id, health).await.unwrap();
+
+        let retrieved = manager.get_health(id).await.unwrap();
+        assert!(retrieved.is_some());
+        assert!(retrieved.unwrap().is_healthy());
+    }
+
+    #[tokio::test]
+    async fn test_to_prometheus_labels_each_daemon() {
+        let manager = DaemonManager::new().with_platform(Platform::Container);
+        let api = TestDaemon::new("api");
+        let id = api.id;
+        manager
+            .register(
+                Box::new(api),
+                DaemonConfig::new("api", "/bin/api"),
+                RestartPolicy::Never,
+            )
+            .await
+            .unwrap();
+        manager
+            .register(
+                Box::new(TestDaemon::new("idle")),
+                DaemonConfig::new("idle", "/bin/idle"),
+                RestartPolicy::Never,
+            )
+            .await
+            .unwrap();
+
+        let metrics = DaemonMetrics::new();
+        metrics.record_request();
+        manager.set_metrics(id, metrics).await.unwrap();
+        manager
+            .update_status(id, DaemonStatus::Starting)
+            .await
+            .unwrap();
+        manager
+            .update_status(id, DaemonStatus::Running)
+            .await
+            .unwrap();
+        manager
+            .update_readiness(id, HealthStatus::healthy(1))
+            .await
+            .unwrap();
+
+        let text = manager.to_prometheus().await;
+        let labels = format!("daemon=\"api\",daemon_id=\"{id}\",platform=\"container\"");
+        assert!(text.contains(&format!("duende_daemon_ready{{{labels}}} 1\n")));
+        assert!(text.contains(&format!("duende_daemon_running{{{labels}}} 1\n")));
+        assert!(text.contains(&format!("duende_requests_total{{{labels}}} 1\n")));
+        // Daemons without metrics still report their state
+        assert!(text.contains("duende_daemon_ready{daemon=\"idle\","));
+        assert_eq!(text.matches("duende_requests_total{").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_manager_publishes_lifecycle_events() {
+        let manager = DaemonManager::new();
+        let mut events = manager.subscribe();
+        let daemon = TestDaemon::new("events");
+        let id = daemon.id;
+
+        manager
+            .register(
+                Box::new(daemon),
+                DaemonConfig::new("events", "/bin/test"),
+                RestartPolicy::Never,
+            )
+            .await
+            .unwrap();
+        manager
+            .update_status(id, DaemonStatus::Starting)
+            .await
+            .unwrap();
+        manager
+            .update_status(id, DaemonStatus::Running)
+            .await
+            .unwrap();
+        manager
+            .update_health(id, HealthStatus::healthy(1))
+            .await
+            .unwrap();
+        // Unchanged health is not an event
+        manager
+            .update_health(id, HealthStatus::healthy(2))
+            .await
+            .unwrap();
+        manager
+            .update_health(id, HealthStatus::unhealthy("down", 3))
+            .await
+            .unwrap();
+        manager
+            .publish(
+                id,
+                LifecycleEventKind::Exited {
+                    reason: ExitReason::Graceful,
+                    status: DaemonStatus::Stopped,
+                },
+            )
+            .await
+            .unwrap();
+        manager
+            .update_status(id, DaemonStatus::Stopping)
+            .await
+            .unwrap();
+        manager
+            .update_status(id, DaemonStatus::Stopped)
+            .await
+            .unwrap();
+        manager.unregister(id).await.unwrap();
+
+        let mut kinds = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            assert_eq!(event.id, id);
+            assert_eq!(event.name, "events");
+            kinds.push(event.kind);
+        }
+        assert!(matches!(
+            kinds.as_slice(),
+            [
+                LifecycleEventKind::Registered,
+                LifecycleEventKind::Starting,
+                LifecycleEventKind::Running,
+                LifecycleEventKind::HealthChanged { health: up },
+                LifecycleEventKind::HealthChanged { health: down },
+                LifecycleEventKind::Exited {
+                    reason: ExitReason::Graceful,
+                    ..
+                },
+                LifecycleEventKind::Unregistered,
+            ] if up.is_healthy() && !down.is_healthy()
+        ));
+
+        assert!(matches!(
+            manager.publish(id, LifecycleEventKind::Registered).await,
+            Err(DaemonError::NotFound(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_manager_health() {
+    async fn test_manager_run_checks() {
+        use crate::config::{CheckKind, ExternalCheck};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let open = listener.local_addr().unwrap().to_string();
+        let closed = {
+            let l = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            l.local_addr().unwrap().to_string()
+        };
+
         let manager = DaemonManager::new();
         let daemon = TestDaemon::new("test");
         let id = daemon.id;
-        let config = DaemonConfig::new("test", "/bin/test");
-
+        let mut config = DaemonConfig::new("test", "/bin/test");
+        config.health_check.retries = 0;
+        config.health_check.readiness.retries = 0;
+        config.health_check.checks = vec![
+            ExternalCheck::new(CheckKind::Tcp { address: open }),
+            ExternalCheck::new(CheckKind::Tcp { address: closed }).with_probe(ProbeKind::Readiness),
+        ];
         manager
             .register(Box::new(daemon), config, RestartPolicy::Never)
             .await
             .unwrap();
+        manager
+            .update_status(id, DaemonStatus::Starting)
+            .await
+            .unwrap();
+        manager
+            .update_status(id, DaemonStatus::Running)
+            .await
+            .unwrap();
 
-        // No health check yet
-        assert!(manager.get_health(id).await.unwrap().is_none());
+        let liveness = manager.run_checks(id, ProbeKind::Liveness).await.unwrap();
+        assert!(liveness.unwrap().is_healthy());
+        assert!(manager.get_health(id).await.unwrap().unwrap().is_healthy());
 
-        // Update health
-        let health = HealthStatus::healthy(5);
-        manager.update_health(id, health).await.unwrap();
+        let readiness = manager.run_checks(id, ProbeKind::Readiness).await.unwrap();
+        assert!(!readiness.unwrap().is_healthy());
+        assert!(!manager.is_ready(id).await.unwrap());
 
-        let retrieved = manager.get_health(id).await.unwrap();
-        assert!(retrieved.is_some());
-        assert!(retrieved.unwrap().is_healthy());
+        assert!(
+            manager
+                .run_checks(id, ProbeKind::Startup)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        drop(listener);
     }
 
     #[tokio::test]
@@ -944,6 +3018,10 @@ mod tests {
             .unwrap();
 
         // Set to Running but no context handle
+        manager
+            .update_status(id, DaemonStatus::Starting)
+            .await
+            .unwrap();
         manager
             .update_status(id, DaemonStatus::Running)
             .await
@@ -1016,9 +3094,155 @@ mod tests {
                 .unwrap();
         }
 
-        // Shutdown all should not error even if daemons can't be signaled
-        let result = manager.shutdown_all().await;
-        assert!(result.is_ok());
+        // Daemons that never started are left alone
+        let report = manager.shutdown_all().await.unwrap();
+        assert_eq!(report, ShutdownReport::default());
+        assert!(report.is_clean());
+    }
+
+    // -------------------------------------------------------------------------
+    // shutdown_all Tests
+    // -------------------------------------------------------------------------
+
+    /// How a simulated daemon reacts to shutdown.
+    #[derive(Clone, Copy)]
+    enum OnStop {
+        /// Stops after the delay on TERM.
+        Term(Duration),
+        /// Only stops on KILL.
+        Kill,
+        /// Never stops.
+        Never,
+    }
+
+    /// Registers a running daemon whose lifecycle is driven by a task.
+    async fn spawn_running(
+        manager: &Arc<DaemonManager>,
+        name: &str,
+        requires: &[&str],
+        on_stop: OnStop,
+        stopped: Arc<std::sync::Mutex<Vec<String>>>,
+    ) -> DaemonId {
+        let id = register_named(manager, name, requires).await;
+        let mut config = manager.get_config(id).await.unwrap();
+        config.shutdown_timeout = Duration::from_millis(100);
+        manager.reload_with(id, config.clone()).await.unwrap();
+
+        let (ctx, handle) = DaemonContext::new(config);
+        manager
+            .set_context_handle(id, handle.clone())
+            .await
+            .unwrap();
+        run(manager, id).await;
+
+        let manager = Arc::clone(manager);
+        let name = name.to_string();
+        tokio::spawn(async move {
+            let _ctx = ctx;
+            match on_stop {
+                OnStop::Term(delay) => {
+                    handle.shutdown_token().cancelled().await;
+                    tokio::time::sleep(delay).await;
+                }
+                OnStop::Kill => handle.killed().await,
+                OnStop::Never => std::future::pending().await,
+            }
+            stopped.lock().unwrap().push(name);
+            manager
+                .update_status(id, DaemonStatus::Stopping)
+                .await
+                .unwrap();
+            manager
+                .update_status(id, DaemonStatus::Stopped)
+                .await
+                .unwrap();
+        });
+
+        id
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_all_reports_outcomes() {
+        let manager = Arc::new(DaemonManager::new().with_kill_timeout(Duration::from_millis(100)));
+        let stopped = Arc::default();
+        let graceful = spawn_running(
+            &manager,
+            "graceful",
+            &[],
+            OnStop::Term(Duration::ZERO),
+            Arc::clone(&stopped),
+        )
+        .await;
+        let killed =
+            spawn_running(&manager, "killed", &[], OnStop::Kill, Arc::clone(&stopped)).await;
+        let stuck =
+            spawn_running(&manager, "stuck", &[], OnStop::Never, Arc::clone(&stopped)).await;
+        let idle = register_named(&manager, "idle", &[]).await;
+        // Running, but neither in-process nor through an adapter
+        let orphan = register_named(&manager, "orphan", &[]).await;
+        run(&manager, orphan).await;
+
+        let report = manager.shutdown_all().await.unwrap();
+
+        assert_eq!(report.graceful, [graceful]);
+        assert_eq!(report.killed, [killed]);
+        assert_eq!(report.stuck, [stuck]);
+        assert_eq!(report.unsignalable, [orphan]);
+        assert_eq!(report.outcome(orphan), Some(ShutdownOutcome::Unsignalable));
+        assert_eq!(report.outcome(idle), None);
+        assert!(!report.is_clean());
+        assert_eq!(manager.status(killed).await.unwrap(), DaemonStatus::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_all_stops_dependents_first() {
+        let manager = Arc::new(DaemonManager::new());
+        let stopped = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let delay = OnStop::Term(Duration::from_millis(20));
+        spawn_running(&manager, "zram", &[], delay, Arc::clone(&stopped)).await;
+        spawn_running(
+            &manager,
+            "model-server",
+            &["zram"],
+            delay,
+            Arc::clone(&stopped),
+        )
+        .await;
+        spawn_running(
+            &manager,
+            "gateway",
+            &["model-server"],
+            delay,
+            Arc::clone(&stopped),
+        )
+        .await;
+
+        let report = manager.shutdown_all().await.unwrap();
+
+        assert!(report.is_clean());
+        assert_eq!(report.graceful.len(), 3);
+        assert_eq!(
+            *stopped.lock().unwrap(),
+            ["gateway", "model-server", "zram"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_all_concurrency_bound() {
+        let stopped = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let delay = OnStop::Term(Duration::from_millis(30));
+
+        let manager = Arc::new(DaemonManager::new().with_shutdown_concurrency(1));
+        for i in 0..3 {
+            let name = format!("d{}", i);
+            spawn_running(&manager, &name, &[], delay, Arc::clone(&stopped)).await;
+        }
+
+        let started = Instant::now();
+        let report = manager.shutdown_all().await.unwrap();
+        assert_eq!(report.graceful.len(), 3);
+        // One at a time: the delays add up
+        assert!(started.elapsed() >= Duration::from_millis(90));
     }
 
     #[test]
@@ -1038,6 +3262,573 @@ mod tests {
         assert!(managed.context_handle.is_none());
     }
 
+    // -------------------------------------------------------------------------
+    // Reload Tests
+    // -------------------------------------------------------------------------
+
+    /// Reload handler recording every applied configuration.
+    #[derive(Default)]
+    struct RecordingHandler {
+        applied: Mutex<Vec<DaemonConfig>>,
+        reject: bool,
+    }
+
+    #[async_trait]
+    impl ReloadHandler for RecordingHandler {
+        async fn reload(&self, config: &DaemonConfig) -> Result<()> {
+            let mut applied = self.applied.lock().await;
+            applied.push(config.clone());
+            if self.reject && applied.len() == 1 {
+                return Err(DaemonError::config("rejected"));
+            }
+            Ok(())
+        }
+    }
+
+    async fn register_with_handler(
+        manager: &DaemonManager,
+        config: DaemonConfig,
+        handler: &Arc<RecordingHandler>,
+    ) -> DaemonId {
+        let id = manager
+            .register(
+                Box::new(TestDaemon::new("test")),
+                config,
+                RestartPolicy::Never,
+            )
+            .await
+            .unwrap();
+        manager
+            .set_reload_handler(id, Some(Arc::clone(handler) as Arc<dyn ReloadHandler>))
+            .await
+            .unwrap();
+        id
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_live_changes() {
+        let manager = DaemonManager::new();
+        let handler = Arc::new(RecordingHandler::default());
+        let config = DaemonConfig::new("test", "/bin/test");
+        let id = register_with_handler(&manager, config.clone(), &handler).await;
+
+        let mut new = config;
+        new.health_check.interval = Duration::from_secs(5);
+        let diff = manager.reload_with(id, new).await.unwrap();
+
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(handler.applied.lock().await.len(), 1);
+        assert!(!manager.is_restart_required(id).await.unwrap());
+        let stored = manager.get_config(id).await.unwrap();
+        assert_eq!(stored.health_check.interval, Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_reload_flags_restart_changes() {
+        let manager = DaemonManager::new();
+        let handler = Arc::new(RecordingHandler::default());
+        let config = DaemonConfig::new("test", "/bin/test");
+        let id = register_with_handler(&manager, config.clone(), &handler).await;
+
+        let mut new = config;
+        new.args.push("--verbose".to_string());
+        let diff = manager.reload_with(id, new).await.unwrap();
+
+        assert!(diff.requires_restart());
+        // Nothing live to apply: the handler is not called
+        assert!(handler.applied.lock().await.is_empty());
+        assert!(manager.is_restart_required(id).await.unwrap());
+        assert_eq!(manager.get_config(id).await.unwrap().args, ["--verbose"]);
+    }
+
+    #[tokio::test]
+    async fn test_reload_rolls_back_on_rejection() {
+        let manager = DaemonManager::new();
+        let handler = Arc::new(RecordingHandler {
+            reject: true,
+            ..RecordingHandler::default()
+        });
+        let config = DaemonConfig::new("test", "/bin/test");
+        let id = register_with_handler(&manager, config.clone(), &handler).await;
+
+        let mut new = config.clone();
+        new.shutdown_timeout = Duration::from_secs(1);
+        assert!(manager.reload_with(id, new).await.is_err());
+
+        // Applied new, then re-applied old
+        let applied = handler.applied.lock().await;
+        assert_eq!(applied.len(), 2);
+        assert_eq!(applied[1].shutdown_timeout, config.shutdown_timeout);
+        drop(applied);
+
+        let stored = manager.get_config(id).await.unwrap();
+        assert_eq!(stored.shutdown_timeout, config.shutdown_timeout);
+    }
+
+    #[tokio::test]
+    async fn test_reload_rejects_invalid_config() {
+        let manager = DaemonManager::new();
+        let handler = Arc::new(RecordingHandler::default());
+        let config = DaemonConfig::new("test", "/bin/test");
+        let id = register_with_handler(&manager, config.clone(), &handler).await;
+
+        let mut new = config;
+        new.resources.memory_bytes = 0;
+        assert!(matches!(
+            manager.reload_with(id, new).await,
+            Err(DaemonError::Config(_))
+        ));
+        assert!(handler.applied.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reload_from_file() {
+        let dir = std::env::temp_dir().join(format!("duende-reload-{}", DaemonId::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("daemon.toml");
+
+        let mut config = DaemonConfig::new("test", "/bin/test");
+        config.config_path = Some(path.clone());
+        std::fs::write(&path, toml::to_string(&config).unwrap()).unwrap();
+
+        let manager = DaemonManager::new();
+        let handler = Arc::new(RecordingHandler::default());
+        let id = register_with_handler(&manager, config.clone(), &handler).await;
+        assert!(manager.reload(id).await.unwrap().is_empty());
+
+        config.health_check.retries = 7;
+        config
+            .env
+            .insert("RUST_LOG".to_string(), "debug".to_string());
+        std::fs::write(&path, toml::to_string(&config).unwrap()).unwrap();
+
+        let diff = manager.reload(id).await.unwrap();
+        let fields: Vec<_> = diff.changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, ["env.RUST_LOG", "health_check.retries"]);
+        assert!(manager.is_restart_required(id).await.unwrap());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_reload_without_config_path() {
+        let manager = DaemonManager::new();
+        let id = manager
+            .register(
+                Box::new(TestDaemon::new("test")),
+                DaemonConfig::new("test", "/bin/test"),
+                RestartPolicy::Never,
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            manager.reload(id).await,
+            Err(DaemonError::Config(_))
+        ));
+    }
+
+    // -------------------------------------------------------------------------
+    // Dependency Tests
+    // -------------------------------------------------------------------------
+
+    async fn register_named(manager: &DaemonManager, name: &str, requires: &[&str]) -> DaemonId {
+        let mut config = DaemonConfig::new(name, "/bin/test");
+        config.requires = requires.iter().map(ToString::to_string).collect();
+        manager
+            .register(
+                Box::new(TestDaemon::new(name)),
+                config,
+                RestartPolicy::Never,
+            )
+            .await
+            .unwrap()
+    }
+
+    async fn run(manager: &DaemonManager, id: DaemonId) {
+        manager
+            .update_status(id, DaemonStatus::Starting)
+            .await
+            .unwrap();
+        manager
+            .update_status(id, DaemonStatus::Running)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_start_order_follows_dependencies() {
+        let manager = DaemonManager::new();
+        let model = register_named(&manager, "model-server", &["zram"]).await;
+        let zram = register_named(&manager, "zram", &[]).await;
+
+        assert_eq!(manager.start_order().await.unwrap(), [zram, model]);
+        assert_eq!(manager.find_by_name("zram").await, [zram]);
+    }
+
+    #[tokio::test]
+    async fn test_register_rejects_dependency_cycle() {
+        let manager = DaemonManager::new();
+        register_named(&manager, "a", &["b"]).await;
+
+        let mut config = DaemonConfig::new("b", "/bin/test");
+        config.after = vec!["a".to_string()];
+        let result = manager
+            .register(Box::new(TestDaemon::new("b")), config, RestartPolicy::Never)
+            .await;
+
+        assert!(matches!(result, Err(DaemonError::Dependency(_))));
+        assert_eq!(manager.list().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reload_rejects_dependency_cycle() {
+        let manager = DaemonManager::new();
+        register_named(&manager, "a", &["b"]).await;
+        let b = register_named(&manager, "b", &[]).await;
+
+        let mut config = manager.get_config(b).await.unwrap();
+        config.wants = vec!["a".to_string()];
+        assert!(matches!(
+            manager.reload_with(b, config).await,
+            Err(DaemonError::Dependency(_))
+        ));
+        assert!(manager.get_config(b).await.unwrap().wants.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unmet_requirements() {
+        let manager = DaemonManager::new();
+        let zram = register_named(&manager, "zram", &[]).await;
+        let model = register_named(&manager, "model-server", &["zram", "network"]).await;
+
+        assert_eq!(
+            manager.unmet_requirements(model).await.unwrap(),
+            ["zram", "network"]
+        );
+
+        run(&manager, zram).await;
+        assert_eq!(
+            manager.unmet_requirements(model).await.unwrap(),
+            ["network"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failure_propagates_to_dependents() {
+        let manager = DaemonManager::new();
+        let zram = register_named(&manager, "zram", &[]).await;
+        let model = register_named(&manager, "model-server", &["zram"]).await;
+        let gateway = register_named(&manager, "gateway", &["model-server"]).await;
+        let metrics = register_named(&manager, "metrics", &[]).await;
+
+        let (_ctx, handle) = DaemonContext::new(DaemonConfig::new("model-server", "/bin/test"));
+        manager
+            .set_context_handle(model, handle.clone())
+            .await
+            .unwrap();
+        run(&manager, zram).await;
+        run(&manager, model).await;
+
+        manager
+            .update_status(zram, DaemonStatus::Failed(FailureReason::Internal))
+            .await
+            .unwrap();
+
+        let dependency_failed = DaemonStatus::Failed(FailureReason::DependencyFailed);
+        assert_eq!(manager.status(model).await.unwrap(), dependency_failed);
+        assert_eq!(manager.status(gateway).await.unwrap(), dependency_failed);
+        assert_eq!(
+            manager.status(metrics).await.unwrap(),
+            DaemonStatus::Created
+        );
+
+        // The running dependent was asked to stop
+        assert!(handle.shutdown_token().is_cancelled());
+    }
+
+    // -------------------------------------------------------------------------
+    // Config-driven restart Tests
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_restart_policy_from_config() {
+        let mut config = DaemonConfig::new("test", "/bin/test");
+        assert!(matches!(
+            RestartPolicy::from_config(&config),
+            RestartPolicy::OnFailure
+        ));
+
+        let backoff = BackoffConfig::new().with_max_retries(3);
+        config.backoff = Some(backoff.clone());
+        assert!(matches!(
+            RestartPolicy::from_config(&config),
+            RestartPolicy::WithBackoff(ref b) if *b == backoff
+        ));
+
+        config.restart = config::RestartPolicy::UnlessStopped;
+        let policy = RestartPolicy::from_config(&config);
+        assert!(matches!(policy, RestartPolicy::AlwaysWithBackoff(_)));
+        assert!(policy.should_restart(&ExitReason::Graceful, 2));
+        assert!(!policy.should_restart(&ExitReason::Graceful, 3));
+
+        config.restart = config::RestartPolicy::Never;
+        assert!(matches!(
+            RestartPolicy::from_config(&config),
+            RestartPolicy::Never
+        ));
+    }
+
+    fn start_and_fail(managed: &mut ManagedDaemon) -> Result<()> {
+        managed.transition(DaemonStatus::Starting)?;
+        managed.transition(DaemonStatus::Failed(FailureReason::Internal))
+    }
+
+    #[test]
+    fn test_start_limit_quarantines() {
+        let mut config = DaemonConfig::new("test", "/bin/test");
+        config.start_limit = Some(StartLimit::new(2, Duration::from_secs(60)));
+        let mut managed = ManagedDaemon::new(DaemonId::new(), "test".to_string(), config);
+
+        start_and_fail(&mut managed).unwrap();
+        start_and_fail(&mut managed).unwrap();
+
+        let err = managed.transition(DaemonStatus::Starting).unwrap_err();
+        assert!(matches!(err, DaemonError::Quarantined(_)));
+        assert!(managed.quarantined);
+        assert_eq!(
+            managed.status,
+            DaemonStatus::Failed(FailureReason::StartLimitHit)
+        );
+
+        // Refused without a state change while quarantined
+        let transitions = managed.transitions.len();
+        assert!(managed.transition(DaemonStatus::Starting).is_err());
+        assert_eq!(managed.transitions.len(), transitions);
+
+        managed.reset_failed();
+        start_and_fail(&mut managed).unwrap();
+    }
+
+    #[test]
+    fn test_start_limit_window_slides() {
+        let mut config = DaemonConfig::new("test", "/bin/test");
+        config.start_limit = Some(StartLimit::new(1, Duration::from_millis(20)));
+        let mut managed = ManagedDaemon::new(DaemonId::new(), "test".to_string(), config);
+
+        start_and_fail(&mut managed).unwrap();
+        std::thread::sleep(Duration::from_millis(30));
+        start_and_fail(&mut managed).unwrap();
+        assert!(!managed.quarantined);
+    }
+
+    #[tokio::test]
+    async fn test_manager_reset_failed() {
+        let manager = DaemonManager::new();
+        let zram = register_named(&manager, "zram", &[]).await;
+        let model = register_named(&manager, "model-server", &["zram"]).await;
+
+        let mut config = manager.get_config(zram).await.unwrap();
+        config.start_limit = Some(StartLimit::new(1, Duration::from_secs(60)));
+        manager.reload_with(zram, config).await.unwrap();
+
+        manager
+            .update_status(zram, DaemonStatus::Starting)
+            .await
+            .unwrap();
+        manager
+            .update_status(zram, DaemonStatus::Failed(FailureReason::Internal))
+            .await
+            .unwrap();
+        let result = manager.update_status(zram, DaemonStatus::Starting).await;
+
+        assert!(matches!(result, Err(DaemonError::Quarantined(_))));
+        assert!(manager.is_quarantined(zram).await.unwrap());
+        // Quarantine is a failure: dependents fail too
+        assert_eq!(
+            manager.status(model).await.unwrap(),
+            DaemonStatus::Failed(FailureReason::DependencyFailed)
+        );
+
+        manager.reset_failed(zram).await.unwrap();
+        assert!(!manager.is_quarantined(zram).await.unwrap());
+        manager
+            .update_status(zram, DaemonStatus::Starting)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.reset_failed(DaemonId::new()).await,
+            Err(DaemonError::NotFound(_))
+        ));
+    }
+
+    // -------------------------------------------------------------------------
+    // Persistence Tests
+    // -------------------------------------------------------------------------
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_manager_state_survives_restart() {
+        use crate::adapter::PlatformAdapter;
+        use crate::adapters::NativeAdapter;
+
+        let path = std::env::temp_dir().join(format!("duende-manager-{}.json", DaemonId::new()));
+        let owner = NativeAdapter::new();
+
+        // First manager: one adapter-spawned, one in-process, one idle daemon
+        let manager = DaemonManager::new().with_state_file(&path);
+        let native = register_named(&manager, "native", &[]).await;
+        let in_process = register_named(&manager, "in-process", &[]).await;
+        let idle = register_named(&manager, "idle", &[]).await;
+
+        let mut daemon = TestDaemon::new("native");
+        daemon.id = native;
+        let handle = owner.spawn(Box::new(daemon)).await.unwrap();
+        manager
+            .set_platform_handle(native, handle.clone())
+            .await
+            .unwrap();
+        run(&manager, native).await;
+        run(&manager, in_process).await;
+        manager.increment_restart_count(native).await.unwrap();
+        manager
+            .update_health(native, HealthStatus::healthy(7))
+            .await
+            .unwrap();
+        manager.save_state().await.unwrap();
+        drop(manager);
+
+        // Second manager, as after a restart
+        let manager = DaemonManager::new()
+            .with_state_file(&path)
+            .with_adapter(Arc::new(NativeAdapter::new()));
+        let report = manager.reattach().await.unwrap();
+
+        assert_eq!(report.reattached, [native]);
+        assert_eq!(report.lost, [in_process]);
+        assert_eq!(report.inactive, [idle]);
+
+        assert_eq!(manager.status(native).await.unwrap(), DaemonStatus::Running);
+        assert_eq!(manager.get_restart_count(native).await.unwrap(), 1);
+        assert_eq!(
+            manager
+                .get_health(native)
+                .await
+                .unwrap()
+                .unwrap()
+                .latency_ms,
+            7
+        );
+        assert_eq!(
+            manager
+                .platform_handle(native)
+                .await
+                .unwrap()
+                .unwrap()
+                .pid(),
+            handle.pid()
+        );
+        assert_eq!(
+            manager.status(in_process).await.unwrap(),
+            DaemonStatus::Failed(FailureReason::Lost)
+        );
+        assert_eq!(manager.status(idle).await.unwrap(), DaemonStatus::Created);
+
+        // The reattached daemon is controlled through the adapter again
+        manager.signal(native, Signal::Kill).await.unwrap();
+        while owner.status(&handle).await.unwrap() == DaemonStatus::Running {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        manager.poll_platforms().await;
+        assert_eq!(manager.status(native).await.unwrap(), DaemonStatus::Stopped);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_manager_spawns_and_stops_through_adapter() {
+        use crate::adapters::NativeAdapter;
+
+        let manager = DaemonManager::new()
+            .with_platform(Platform::Native)
+            .with_adapter(Arc::new(NativeAdapter::new()));
+        let mut config = DaemonConfig::new("sleeper", "/bin/sleep");
+        config.args = vec!["30".to_string()];
+        config.shutdown_timeout = Duration::from_secs(5);
+        let id = manager
+            .register(
+                Box::new(TestDaemon::new("sleeper")),
+                config,
+                RestartPolicy::Never,
+            )
+            .await
+            .unwrap();
+        let mut daemon = TestDaemon::new("sleeper");
+        daemon.id = id;
+
+        let handle = manager.spawn(Box::new(daemon)).await.unwrap();
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Running);
+        let recorded = manager.platform_handle(id).await.unwrap().unwrap();
+        assert_eq!(recorded.pid(), handle.pid());
+
+        let report = manager.shutdown_all().await.unwrap();
+        assert_eq!(report.graceful, [id]);
+        assert!(manager.status(id).await.unwrap().is_terminal());
+    }
+
+    #[tokio::test]
+    async fn test_manager_spawn_without_adapter() {
+        let manager = DaemonManager::new();
+        let id = register_named(&manager, "orphan", &[]).await;
+        let mut daemon = TestDaemon::new("orphan");
+        daemon.id = id;
+
+        assert!(matches!(
+            manager.spawn(Box::new(daemon)).await,
+            Err(DaemonError::Config(_))
+        ));
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Created);
+    }
+
+    #[tokio::test]
+    async fn test_state_writer_skips_health_only_changes() {
+        let path = std::env::temp_dir().join(format!("duende-manager-{}.json", DaemonId::new()));
+        let load = || ManagerState::load(&path).unwrap();
+        let settle = || tokio::time::sleep(STATE_WRITE_DELAY * 3);
+
+        let manager = DaemonManager::new().with_state_file(&path);
+        let id = register_named(&manager, "api", &[]).await;
+        run(&manager, id).await;
+        settle().await;
+        let saved = load().unwrap();
+        assert_eq!(saved.daemons[0].status, DaemonStatus::Running);
+
+        manager
+            .update_health(id, HealthStatus::healthy(7))
+            .await
+            .unwrap();
+        settle().await;
+        assert!(load().unwrap().daemons[0].last_health.is_none());
+
+        // The next change saves it along
+        manager.increment_restart_count(id).await.unwrap();
+        settle().await;
+        let saved = load().unwrap();
+        assert_eq!(saved.daemons[0].restart_count, 1);
+        assert_eq!(saved.daemons[0].last_health.as_ref().unwrap().latency_ms, 7);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_manager_reattach_without_state_file() {
+        let manager = DaemonManager::new();
+        assert!(manager.state_file().is_none());
+        manager.save_state().await.unwrap();
+
+        let report = manager.reattach().await.unwrap();
+        assert_eq!(report, ReattachReport::default());
+    }
+
     #[test]
     fn test_backoff_new_is_default() {
         let config = BackoffConfig::new();
@@ -9,8 +9,14 @@
 
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+
+use crate::error::Result;
+use crate::histogram::{LatencyHistogram, LatencySnapshot};
+use crate::prometheus::PrometheusEncoder;
+use crate::rates::{ERROR_WINDOW_SECS, ErrorWindow, Meter, Rates, WindowStats};
+use crate::registry::{Counter, CustomMetric, Gauge, Histogram, MetricValue, MetricsRegistry};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 /// Daemon metrics collection following RED method.
 ///
@@ -24,14 +30,18 @@ pub struct DaemonMetrics {
 struct MetricsInner {
     // Rate metrics
     requests_total: AtomicU64,
+    requests: Meter,
 
     // Error metrics
     errors_total: AtomicU64,
+    errors: Meter,
+    error_window: ErrorWindow,
 
     // Duration metrics (stored as microseconds for atomic operations)
     duration_sum_us: AtomicU64,
     duration_count: AtomicU64,
     duration_max_us: AtomicU64,
+    latency: LatencyHistogram,
 
     // Resource metrics
     cpu_usage_permille: AtomicU64, // CPU usage * 1000 (for precision)
@@ -43,8 +53,16 @@ struct MetricsInner {
     circuit_breaker_trips: AtomicU64,
     successful_recoveries: AtomicU64,
 
+    // Watchdog
+    watchdog_timeouts: AtomicU64,
+
+    // Labeled custom metrics
+    registry: MetricsRegistry,
+
     // Start time for uptime calculation
     start_time: Instant,
+    // Wall-clock start time, the start of cumulative series
+    created_at: SystemTime,
 }
 
 impl DaemonMetrics {
@@ -54,17 +72,24 @@ impl DaemonMetrics {
         Self {
             inner: Arc::new(MetricsInner {
                 requests_total: AtomicU64::new(0),
+                requests: Meter::new(),
                 errors_total: AtomicU64::new(0),
+                errors: Meter::new(),
+                error_window: ErrorWindow::new(),
                 duration_sum_us: AtomicU64::new(0),
                 duration_count: AtomicU64::new(0),
                 duration_max_us: AtomicU64::new(0),
+                latency: LatencyHistogram::new(),
                 cpu_usage_permille: AtomicU64::new(0),
                 memory_bytes: AtomicU64::new(0),
                 open_fds: AtomicU64::new(0),
                 thread_count: AtomicU64::new(0),
                 circuit_breaker_trips: AtomicU64::new(0),
                 successful_recoveries: AtomicU64::new(0),
+                watchdog_timeouts: AtomicU64::new(0),
+                registry: MetricsRegistry::new(),
                 start_time: Instant::now(),
+                created_at: SystemTime::now(),
             }),
         }
     }
@@ -76,6 +101,8 @@ impl DaemonMetrics {
     /// Increments the request counter.
     pub fn record_request(&self) {
         self.inner.requests_total.fetch_add(1, Ordering::Relaxed);
+        self.inner.requests.mark(1);
+        self.inner.error_window.record_request();
     }
 
     /// Returns total requests processed.
@@ -85,6 +112,8 @@ impl DaemonMetrics {
     }
 
     /// Returns requests per second since start.
+    ///
+    /// See [`request_rates`](Self::request_rates) for recent rates.
     #[must_use]
     pub fn requests_per_second(&self) -> f64 {
         let elapsed = self.inner.start_time.elapsed().as_secs_f64();
@@ -95,6 +124,12 @@ impl DaemonMetrics {
         }
     }
 
+    /// Returns the 1/5/15-minute EWMA request rates, per second.
+    #[must_use]
+    pub fn request_rates(&self) -> Rates {
+        self.inner.requests.rates()
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Error metrics
     // ═══════════════════════════════════════════════════════════════════════════
@@ -102,6 +137,8 @@ impl DaemonMetrics {
     /// Increments the error counter.
     pub fn record_error(&self) {
         self.inner.errors_total.fetch_add(1, Ordering::Relaxed);
+        self.inner.errors.mark(1);
+        self.inner.error_window.record_error();
     }
 
     /// Returns total errors.
@@ -110,7 +147,10 @@ impl DaemonMetrics {
         self.inner.errors_total.load(Ordering::Relaxed)
     }
 
-    /// Returns error rate (errors / requests).
+    /// Returns error rate (errors / requests) since start.
+    ///
+    /// See [`recent_error_rate`](Self::recent_error_rate) for the current
+    /// error rate.
     #[must_use]
     pub fn error_rate(&self) -> f64 {
         let requests = self.requests_total();
@@ -121,15 +161,38 @@ impl DaemonMetrics {
         }
     }
 
+    /// Returns the 1/5/15-minute EWMA error rates, per second.
+    #[must_use]
+    pub fn error_rates(&self) -> Rates {
+        self.inner.errors.rates()
+    }
+
+    /// Returns requests and errors of the last `window` (at most a
+    /// minute).
+    #[must_use]
+    pub fn recent(&self, window: Duration) -> WindowStats {
+        self.inner.error_window.stats(window)
+    }
+
+    /// Returns the error rate (errors / requests) of the last minute.
+    #[must_use]
+    pub fn recent_error_rate(&self) -> f64 {
+        self.recent(Duration::from_secs(ERROR_WINDOW_SECS))
+            .error_rate()
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Duration metrics
     // ═══════════════════════════════════════════════════════════════════════════
 
     /// Records a request duration.
+    ///
+    /// Feeds the average, the maximum and the latency histogram.
     pub fn record_duration(&self, duration: Duration) {
         let us = duration.as_micros() as u64;
         self.inner.duration_sum_us.fetch_add(us, Ordering::Relaxed);
         self.inner.duration_count.fetch_add(1, Ordering::Relaxed);
+        self.inner.latency.record(duration);
 
         // Update max (not perfectly atomic but close enough for metrics)
         let mut current_max = self.inner.duration_max_us.load(Ordering::Relaxed);
@@ -164,6 +227,12 @@ impl DaemonMetrics {
         Duration::from_micros(self.inner.duration_max_us.load(Ordering::Relaxed))
     }
 
+    /// Returns the latency histogram of recorded durations.
+    #[must_use]
+    pub fn latency(&self) -> LatencySnapshot {
+        self.inner.latency.snapshot()
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Resource metrics
     // ═══════════════════════════════════════════════════════════════════════════
@@ -245,6 +314,67 @@ impl DaemonMetrics {
         self.inner.successful_recoveries.load(Ordering::Relaxed)
     }
 
+    // ═══════════════════════════════════════════════════════════════════════════
+    // Watchdog metrics
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    /// Records a missed watchdog deadline.
+    pub fn record_watchdog_timeout(&self) {
+        self.inner.watchdog_timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of missed watchdog deadlines.
+    #[must_use]
+    pub fn watchdog_timeouts(&self) -> u64 {
+        self.inner.watchdog_timeouts.load(Ordering::Relaxed)
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    // Custom metrics
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    /// Registers (or looks up) a labeled counter.
+    ///
+    /// Keep the handle: recording through it is lock-free.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Metric` if the name or labels are invalid, or
+    /// the name is registered as another kind.
+    pub fn counter(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Result<Counter> {
+        self.inner.registry.counter(name, help, labels)
+    }
+
+    /// Registers (or looks up) a labeled gauge.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Metric` if the name or labels are invalid, or
+    /// the name is registered as another kind.
+    pub fn gauge(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Result<Gauge> {
+        self.inner.registry.gauge(name, help, labels)
+    }
+
+    /// Registers (or looks up) a labeled histogram over `buckets`
+    /// (Prometheus defaults if empty).
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Metric` if the name, labels or buckets are
+    /// invalid, or the name is registered differently.
+    pub fn histogram(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        buckets: &[f64],
+    ) -> Result<Histogram> {
+        self.inner.registry.histogram(name, help, labels, buckets)
+    }
+
+    /// Returns the custom metrics registry.
+    #[must_use]
+    pub fn registry(&self) -> &MetricsRegistry {
+        &self.inner.registry
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Uptime
     // ═══════════════════════════════════════════════════════════════════════════
@@ -255,6 +385,12 @@ impl DaemonMetrics {
         self.inner.start_time.elapsed()
     }
 
+    /// Returns when the metrics were created.
+    #[must_use]
+    pub fn created_at(&self) -> SystemTime {
+        self.inner.created_at
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Snapshot
     // ═══════════════════════════════════════════════════════════════════════════
@@ -262,22 +398,42 @@ impl DaemonMetrics {
     /// Creates a snapshot of current metrics.
     #[must_use]
     pub fn snapshot(&self) -> MetricsSnapshot {
+        let latency = self.latency();
         MetricsSnapshot {
             requests_total: self.requests_total(),
             requests_per_second: self.requests_per_second(),
             errors_total: self.errors_total(),
             error_rate: self.error_rate(),
+            request_rates: self.request_rates(),
+            error_rates: self.error_rates(),
+            recent_error_rate: self.recent_error_rate(),
             duration_avg_us: self.duration_avg().as_micros() as u64,
             duration_max_us: self.duration_max().as_micros() as u64,
+            duration_p50_us: latency.p50().as_micros() as u64,
+            duration_p90_us: latency.p90().as_micros() as u64,
+            duration_p99_us: latency.p99().as_micros() as u64,
+            duration_p999_us: latency.p999().as_micros() as u64,
+            latency,
             cpu_usage_percent: self.cpu_usage(),
             memory_bytes: self.memory_bytes(),
             open_fds: self.open_fds(),
             thread_count: self.thread_count(),
             circuit_breaker_trips: self.circuit_breaker_trips(),
             successful_recoveries: self.successful_recoveries(),
+            watchdog_timeouts: self.watchdog_timeouts(),
             uptime_secs: self.uptime().as_secs(),
+            created_at: Some(self.created_at()),
+            custom: self.inner.registry.snapshot(),
         }
     }
+
+    /// Exports the current metrics in the Prometheus text format.
+    ///
+    /// Unlabeled; use [`PrometheusEncoder`] to add daemon labels.
+    #[must_use]
+    pub fn to_prometheus(&self) -> String {
+        self.snapshot().to_prometheus()
+    }
 }
 
 impl Default for DaemonMetrics {
@@ -297,10 +453,35 @@ pub struct MetricsSnapshot {
     pub errors_total: u64,
     /// Error rate (0.0 to 1.0).
     pub error_rate: f64,
+    /// 1/5/15-minute EWMA request rates, per second.
+    #[serde(default)]
+    pub request_rates: Rates,
+    /// 1/5/15-minute EWMA error rates, per second.
+    #[serde(default)]
+    pub error_rates: Rates,
+    /// Error rate (0.0 to 1.0) of the last minute.
+    #[serde(default)]
+    pub recent_error_rate: f64,
     /// Average duration in microseconds.
     pub duration_avg_us: u64,
     /// Maximum duration in microseconds.
     pub duration_max_us: u64,
+    /// Median duration in microseconds.
+    #[serde(default)]
+    pub duration_p50_us: u64,
+    /// 90th percentile duration in microseconds.
+    #[serde(default)]
+    pub duration_p90_us: u64,
+    /// 99th percentile duration in microseconds.
+    #[serde(default)]
+    pub duration_p99_us: u64,
+    /// 99.9th percentile duration in microseconds.
+    #[serde(default)]
+    pub duration_p999_us: u64,
+    /// Latency histogram; merge snapshots of several instances with
+    /// [`LatencySnapshot::merge`].
+    #[serde(default)]
+    pub latency: LatencySnapshot,
     /// CPU usage percentage.
     pub cpu_usage_percent: f64,
     /// Memory usage in bytes.
@@ -313,8 +494,118 @@ pub struct MetricsSnapshot {
     pub circuit_breaker_trips: u64,
     /// Successful recoveries.
     pub successful_recoveries: u64,
+    /// Missed watchdog deadlines.
+    #[serde(default)]
+    pub watchdog_timeouts: u64,
     /// Uptime in seconds.
     pub uptime_secs: u64,
+    /// When the metrics were created, the start of cumulative series.
+    #[serde(default)]
+    pub created_at: Option<SystemTime>,
+    /// Labeled custom metrics, ordered by name then labels.
+    #[serde(default)]
+    pub custom: Vec<CustomMetric>,
+}
+
+impl MetricsSnapshot {
+    /// Returns every metric of the snapshot as a named series, the form
+    /// exporters consume: the built-in metrics (prefixed `duende_`, rates
+    /// labeled by `window`), then the custom ones.
+    #[must_use]
+    pub fn metrics(&self) -> Vec<CustomMetric> {
+        let counter = |name: &str, help: &str, value: u64| {
+            CustomMetric::new(
+                format!("duende_{name}"),
+                help,
+                MetricValue::Counter { value },
+            )
+        };
+        let gauge = |name: &str, help: &str, value: f64| {
+            CustomMetric::new(format!("duende_{name}"), help, MetricValue::Gauge { value })
+        };
+
+        let mut metrics = vec![
+            counter(
+                "requests_total",
+                "Total requests processed.",
+                self.requests_total,
+            ),
+            counter("errors_total", "Total errors.", self.errors_total),
+        ];
+        for (window, requests, errors) in [
+            ("1m", self.request_rates.m1, self.error_rates.m1),
+            ("5m", self.request_rates.m5, self.error_rates.m5),
+            ("15m", self.request_rates.m15, self.error_rates.m15),
+        ] {
+            metrics.push(
+                gauge(
+                    "requests_per_second",
+                    "Moving average of requests per second.",
+                    requests,
+                )
+                .with_label("window", window),
+            );
+            metrics.push(
+                gauge(
+                    "errors_per_second",
+                    "Moving average of errors per second.",
+                    errors,
+                )
+                .with_label("window", window),
+            );
+        }
+        metrics.extend([
+            gauge(
+                "error_ratio",
+                "Errors per request over the last minute.",
+                self.recent_error_rate,
+            ),
+            CustomMetric::new(
+                "duende_request_duration_seconds",
+                "Request duration.",
+                MetricValue::Histogram(self.latency.prometheus(&[])),
+            ),
+            gauge(
+                "request_duration_max_seconds",
+                "Longest request duration.",
+                Duration::from_micros(self.duration_max_us).as_secs_f64(),
+            ),
+            gauge("cpu_usage_percent", "CPU usage.", self.cpu_usage_percent),
+            gauge("memory_bytes", "Memory usage.", self.memory_bytes as f64),
+            gauge("open_fds", "Open file descriptors.", self.open_fds as f64),
+            gauge("threads", "Thread count.", self.thread_count as f64),
+            counter(
+                "circuit_breaker_trips_total",
+                "Circuit breaker trips.",
+                self.circuit_breaker_trips,
+            ),
+            counter(
+                "recoveries_total",
+                "Successful recoveries.",
+                self.successful_recoveries,
+            ),
+            counter(
+                "watchdog_timeouts_total",
+                "Missed watchdog deadlines.",
+                self.watchdog_timeouts,
+            ),
+            gauge(
+                "uptime_seconds",
+                "Time since the metrics were created.",
+                self.uptime_secs as f64,
+            ),
+        ]);
+        metrics.extend(self.custom.iter().cloned());
+        metrics
+    }
+
+    /// Encodes the snapshot in the Prometheus text format, unlabeled.
+    #[must_use]
+    pub fn to_prometheus(&self) -> String {
+        let mut encoder = PrometheusEncoder::new();
+        encoder.snapshot(&[], self);
+        encoder.finish()
+    }
 }
 
 #[cfg(test)]
@@ -348,6 +639,29 @@ mod tests {
         assert!((metrics.error_rate() - 0.2).abs() < 0.001);
     }
 
+    #[test]
+    fn test_recent_error_rate() {
+        let metrics = DaemonMetrics::new();
+        for _ in 0..4 {
+            metrics.record_request();
+        }
+        metrics.record_error();
+
+        assert_eq!(
+            metrics.recent(Duration::from_secs(60)),
+            WindowStats {
+                requests: 4,
+                errors: 1
+            }
+        );
+        assert!((metrics.recent_error_rate() - 0.25).abs() < f64::EPSILON);
+
+        let snapshot = metrics.snapshot();
+        assert!((snapshot.recent_error_rate - 0.25).abs() < f64::EPSILON);
+        // No EWMA tick has happened yet
+        assert_eq!(snapshot.request_rates, Rates::default());
+    }
+
     #[test]
     fn test_duration_tracking() {
         let metrics = DaemonMetrics::new();
@@ -467,6 +781,8 @@ mod tests {
         std::thread::sleep(Duration::from_millis(10));
         let uptime = metrics.uptime();
         assert!(uptime >= Duration::from_millis(10));
+        assert_eq!(metrics.snapshot().created_at, Some(metrics.created_at()));
+        assert!(metrics.created_at().elapsed().unwrap() >= Duration::from_millis(10));
     }
 
     #[test]
@@ -492,6 +808,25 @@ mod tests {
         assert_eq!(metrics.duration_max(), Duration::from_millis(200));
     }
 
+    #[test]
+    fn test_snapshot_percentiles() {
+        let metrics = DaemonMetrics::new();
+        for _ in 0..98 {
+            metrics.record_duration(Duration::from_millis(1));
+        }
+        metrics.record_duration(Duration::from_millis(100));
+        metrics.record_duration(Duration::from_millis(800));
+
+        let snapshot = metrics.snapshot();
+        // The average hides the tail; the percentiles show it, to 6.25%
+        assert!(snapshot.duration_avg_us < 10_000);
+        assert!(snapshot.duration_p50_us <= 1_063);
+        assert!(snapshot.duration_p90_us <= 1_063);
+        assert!(snapshot.duration_p99_us >= 94_000);
+        assert_eq!(snapshot.duration_p999_us, 800_000);
+        assert_eq!(snapshot.latency.count, 100);
+    }
+
     #[test]
     fn test_snapshot_all_fields() {
         let metrics = DaemonMetrics::new();
@@ -530,4 +865,46 @@ mod tests {
         let deserialized: MetricsSnapshot = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.requests_total, 1);
     }
+
+    #[test]
+    fn test_snapshot_includes_custom_metrics() {
+        let metrics = DaemonMetrics::new();
+        let clone = metrics.clone();
+        clone
+            .counter("inference_tokens_total", "Tokens", &[("model", "x")])
+            .unwrap()
+            .inc_by(7);
+        metrics
+            .histogram("batch_size", "Batch size", &[], &[1.0, 10.0])
+            .unwrap()
+            .observe(3.0);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.custom.len(), 2);
+        let tokens = &snapshot.custom[1];
+        assert_eq!(tokens.name, "inference_tokens_total");
+        assert_eq!(tokens.labels["model"], "x");
+        assert_eq!(
+            tokens.value,
+            crate::registry::MetricValue::Counter { value: 7 }
+        );
+
+        // Snapshots from before custom metrics still parse
+        let mut json = serde_json::to_value(&snapshot).unwrap();
+        json.as_object_mut().unwrap().remove("custom");
+        let old: MetricsSnapshot = serde_json::from_value(json).unwrap();
+        assert!(old.custom.is_empty());
+    }
+
+    #[test]
+    fn test_to_prometheus() {
+        let metrics = DaemonMetrics::new();
+        metrics.record_request();
+        metrics.set_memory_bytes(4096);
+
+        let text = metrics.to_prometheus();
+        assert!(text.contains("# TYPE duende_requests_total counter\n"));
+        assert!(text.contains("duende_requests_total 1\n"));
+        assert!(text.contains("duende_memory_bytes 4096\n"));
+    }
 }
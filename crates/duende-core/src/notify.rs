@@ -0,0 +1,345 @@
+//! Readiness notification - the `sd_notify(3)` datagram protocol.
+//!
+//! A daemon reports its own state (`READY=1`, `STATUS=...`, `WATCHDOG=1`)
+//! by sending newline-separated `KEY=VALUE` assignments to the Unix
+//! datagram socket named by `NOTIFY_SOCKET`. Under systemd that socket is
+//! systemd's; under duende's native spawner and [`Supervisor`] it is a
+//! [`NotifyListener`], so the same daemon code works in both places.
+//!
+//! # Toyota Way: Genchi Genbutsu (現地現物)
+//! Only the daemon knows when it is ready to serve. The supervisor waits
+//! to be told instead of assuming that a started process is a ready one.
+//!
+//! [`Supervisor`]: crate::supervisor::Supervisor
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// =============================================================================
+// NotifyState
+// =============================================================================
+
+/// A state assignment sent over the notify socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotifyState {
+    /// `READY=1`: startup finished, the daemon is serving.
+    Ready,
+    /// `RELOADING=1`: the daemon is reloading its configuration.
+    Reloading,
+    /// `STOPPING=1`: the daemon is shutting down.
+    Stopping,
+    /// `STATUS=...`: free-form, human-readable status.
+    Status(String),
+    /// `WATCHDOG=1`: keep-alive ping.
+    Watchdog,
+    /// Any other assignment, passed through untouched.
+    Other(String, String),
+}
+
+impl NotifyState {
+    /// Parses a notification datagram into its assignments.
+    ///
+    /// Lines without `=` are ignored, as by systemd.
+    #[must_use]
+    pub fn parse(message: &str) -> Vec<Self> {
+        message
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| match (key, value) {
+                ("READY", "1") => Self::Ready,
+                ("RELOADING", "1") => Self::Reloading,
+                ("STOPPING", "1") => Self::Stopping,
+                ("WATCHDOG", "1") => Self::Watchdog,
+                ("STATUS", status) => Self::Status(status.to_string()),
+                (key, value) => Self::Other(key.to_string(), value.to_string()),
+            })
+            .collect()
+    }
+
+    /// Encodes assignments as one notification datagram.
+    #[must_use]
+    pub fn encode(states: &[Self]) -> String {
+        use std::fmt::Write;
+
+        let mut message = String::new();
+        for state in states {
+            // Writing to a String cannot fail
+            let _ = writeln!(message, "{state}");
+        }
+        message
+    }
+}
+
+impl fmt::Display for NotifyState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ready => write!(f, "READY=1"),
+            Self::Reloading => write!(f, "RELOADING=1"),
+            Self::Stopping => write!(f, "STOPPING=1"),
+            // One assignment per line: a newline would start another
+            Self::Status(status) => write!(f, "STATUS={}", status.replace('\n', " ")),
+            Self::Watchdog => write!(f, "WATCHDOG=1"),
+            Self::Other(key, value) => write!(f, "{key}={}", value.replace('\n', " ")),
+        }
+    }
+}
+
+// =============================================================================
+// Notifier
+// =============================================================================
+
+/// Sending side of the notify socket, used by the daemon.
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct Notifier {
+    /// Unbound datagram socket.
+    socket: std::os::unix::net::UnixDatagram,
+    /// Address of the notify socket.
+    addr: std::os::unix::net::SocketAddr,
+}
+
+#[cfg(unix)]
+impl Notifier {
+    /// Creates a notifier for the socket at `path`.
+    ///
+    /// A path starting with `@` names a Linux abstract socket.
+    ///
+    /// # Errors
+    /// Returns an error if the socket cannot be created or the address is
+    /// invalid.
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let addr = match path.to_str().and_then(|p| p.strip_prefix('@')) {
+            #[cfg(target_os = "linux")]
+            Some(name) => {
+                use std::os::linux::net::SocketAddrExt;
+                std::os::unix::net::SocketAddr::from_abstract_name(name)?
+            }
+            #[cfg(not(target_os = "linux"))]
+            Some(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "abstract sockets are only supported on Linux",
+                ));
+            }
+            None => std::os::unix::net::SocketAddr::from_pathname(path)?,
+        };
+
+        Ok(Self {
+            socket: std::os::unix::net::UnixDatagram::unbound()?,
+            addr,
+        })
+    }
+
+    /// Creates a notifier from `NOTIFY_SOCKET`, if set.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var_os("NOTIFY_SOCKET")?;
+        match Self::new(&path) {
+            Ok(notifier) => Some(notifier),
+            Err(e) => {
+                tracing::warn!(socket = ?path, error = %e, "ignoring NOTIFY_SOCKET");
+                None
+            }
+        }
+    }
+
+    /// Sends the assignments as one datagram.
+    ///
+    /// # Errors
+    /// Returns an error if the datagram cannot be sent.
+    pub fn notify(&self, states: &[NotifyState]) -> io::Result<()> {
+        let message = NotifyState::encode(states);
+        self.socket.send_to_addr(message.as_bytes(), &self.addr)?;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// NotifyListener
+// =============================================================================
+
+/// Receiving side of the notify socket, run by the supervisor.
+///
+/// The socket file is removed when the listener is dropped.
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct NotifyListener {
+    /// Bound datagram socket.
+    socket: tokio::net::UnixDatagram,
+    /// Socket path.
+    path: PathBuf,
+}
+
+#[cfg(unix)]
+impl NotifyListener {
+    /// Largest datagram accepted, as systemd's notify socket.
+    const MAX_MESSAGE: usize = 4096;
+
+    /// Binds a notify socket at `path`, replacing a stale one.
+    ///
+    /// Must be called from within a tokio runtime.
+    ///
+    /// # Errors
+    /// Returns an error if the socket cannot be bound.
+    pub fn bind(path: impl Into<PathBuf>) -> io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+
+        let path = path.into();
+        if std::fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_socket()) {
+            std::fs::remove_file(&path)?;
+        }
+        let socket = tokio::net::UnixDatagram::bind(&path)?;
+        Ok(Self { socket, path })
+    }
+
+    /// Binds the notify socket for a daemon in the runtime directory.
+    ///
+    /// # Errors
+    /// Returns an error if the socket cannot be bound.
+    pub fn for_daemon(id: crate::types::DaemonId) -> io::Result<Self> {
+        Self::bind(runtime_dir().join(format!("duende-notify-{id}.sock")))
+    }
+
+    /// Returns the socket path, as passed in `NOTIFY_SOCKET`.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Creates a notifier that sends to this listener.
+    ///
+    /// # Errors
+    /// Returns an error if the notifier socket cannot be created.
+    pub fn notifier(&self) -> io::Result<Notifier> {
+        Notifier::new(&self.path)
+    }
+
+    /// Receives the next notification.
+    ///
+    /// Cancel-safe.
+    ///
+    /// # Errors
+    /// Returns an error if receiving fails.
+    pub async fn recv(&self) -> io::Result<Vec<NotifyState>> {
+        let mut buf = vec![0u8; Self::MAX_MESSAGE];
+        let len = self.socket.recv(&mut buf).await?;
+        Ok(NotifyState::parse(&String::from_utf8_lossy(&buf[..len])))
+    }
+}
+
+#[cfg(unix)]
+impl Drop for NotifyListener {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Directory for duende's runtime sockets.
+///
+/// `XDG_RUNTIME_DIR` when set, the system temporary directory otherwise.
+#[must_use]
+pub fn runtime_dir() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR").map_or_else(std::env::temp_dir, PathBuf::from)
+}
+
+/// Returns the watchdog interval requested through `WATCHDOG_USEC`.
+///
+/// `None` if unset, invalid, or meant for another process (`WATCHDOG_PID`).
+#[must_use]
+pub fn watchdog_from_env() -> Option<Duration> {
+    let var = |key| std::env::var(key).ok();
+    parse_watchdog_env(
+        var("WATCHDOG_USEC").as_deref(),
+        var("WATCHDOG_PID").as_deref(),
+        std::process::id(),
+    )
+}
+
+/// Parses `WATCHDOG_USEC`/`WATCHDOG_PID` as `sd_watchdog_enabled(3)`.
+fn parse_watchdog_env(usec: Option<&str>, pid: Option<&str>, own_pid: u32) -> Option<Duration> {
+    if let Some(pid) = pid
+        && pid.parse::<u32>().ok()? != own_pid
+    {
+        return None;
+    }
+    let usec: u64 = usec?.parse().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_encode() {
+        let states =
+            NotifyState::parse("READY=1\nSTATUS=warming cache\nWATCHDOG=1\nMAINPID=42\nbogus");
+        assert_eq!(
+            states,
+            [
+                NotifyState::Ready,
+                NotifyState::Status("warming cache".to_string()),
+                NotifyState::Watchdog,
+                NotifyState::Other("MAINPID".to_string(), "42".to_string()),
+            ]
+        );
+        assert_eq!(
+            NotifyState::encode(&states),
+            "READY=1\nSTATUS=warming cache\nWATCHDOG=1\nMAINPID=42\n"
+        );
+    }
+
+    #[test]
+    fn test_status_stays_on_one_line() {
+        let encoded = NotifyState::encode(&[NotifyState::Status("a\nREADY=1".to_string())]);
+        assert_eq!(
+            NotifyState::parse(&encoded),
+            [NotifyState::Status("a READY=1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_watchdog_env() {
+        let us = |v| parse_watchdog_env(Some(v), None, 42);
+        assert_eq!(us("3000000"), Some(Duration::from_secs(3)));
+        assert_eq!(us("0"), None);
+        assert_eq!(us("soon"), None);
+        assert_eq!(
+            parse_watchdog_env(Some("1000"), Some("42"), 42),
+            Some(Duration::from_millis(1))
+        );
+        assert_eq!(parse_watchdog_env(Some("1000"), Some("7"), 42), None);
+        assert_eq!(parse_watchdog_env(None, None, 42), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_notifier_reaches_listener() {
+        let listener = NotifyListener::for_daemon(crate::types::DaemonId::new()).unwrap();
+        let path = listener.path().to_path_buf();
+        assert!(path.exists());
+
+        let notifier = Notifier::new(&path).unwrap();
+        notifier
+            .notify(&[NotifyState::Ready, NotifyState::Status("up".to_string())])
+            .unwrap();
+        listener
+            .notifier()
+            .unwrap()
+            .notify(&[NotifyState::Watchdog])
+            .unwrap();
+
+        let recv = || tokio::time::timeout(Duration::from_secs(1), listener.recv());
+        assert_eq!(
+            recv().await.unwrap().unwrap(),
+            [NotifyState::Ready, NotifyState::Status("up".to_string())]
+        );
+        assert_eq!(recv().await.unwrap().unwrap(), [NotifyState::Watchdog]);
+
+        drop(listener);
+        assert!(!path.exists());
+    }
+}
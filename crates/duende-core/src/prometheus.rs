@@ -0,0 +1,488 @@
+//! Prometheus exposition - metrics in the text format scrapers expect.
+//!
+//! [`PrometheusEncoder`] renders [`MetricsSnapshot`]s, built-in and custom
+//! metrics alike, with per-daemon labels. Samples of several daemons are
+//! grouped under one `# HELP`/`# TYPE` header per metric, as the format
+//! requires. [`MetricsServer`] serves the encoding of every registered
+//! daemon on `GET /metrics`:
+//!
+//! ```rust,ignore
+//! let manager = Arc::new(DaemonManager::new());
+//! let server = manager.serve_metrics("0.0.0.0:9100").await?;
+//! println!("scrape http://{}/metrics", server.local_addr());
+//! ```
+//!
+//! # Toyota Way: Visual Management (目で見る管理)
+//! Metrics only help if they reach the dashboards people already watch.
+
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::task::JoinHandle;
+
+use crate::error::Result;
+use crate::manager::DaemonManager;
+use crate::metrics::MetricsSnapshot;
+use crate::registry::{CustomMetric, HistogramSnapshot, MetricKind, MetricValue};
+
+/// Content type of the text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Longest request head the metrics server reads.
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// Time a client gets to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// =============================================================================
+// PrometheusEncoder
+// =============================================================================
+
+/// Encodes metrics in the Prometheus text exposition format (0.0.4).
+///
+/// Series come from [`MetricsSnapshot::metrics`]. Labels passed with a
+/// snapshot (typically `daemon`, `daemon_id` and `platform`) are added to
+/// every sample and take precedence over custom labels of the same name.
+#[derive(Debug, Default)]
+pub struct PrometheusEncoder {
+    families: Vec<Family>,
+}
+
+/// One metric name: its header and all its samples.
+#[derive(Debug)]
+struct Family {
+    name: String,
+    help: String,
+    kind: MetricKind,
+    samples: String,
+}
+
+impl PrometheusEncoder {
+    /// Creates an empty encoder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the built-in and custom metrics of one snapshot.
+    pub fn snapshot(&mut self, labels: &[(&str, &str)], snapshot: &MetricsSnapshot) {
+        for metric in snapshot.metrics() {
+            let metric = labels.iter().fold(metric, |metric, &(key, value)| {
+                metric.with_label(key, value)
+            });
+            self.metric(&metric);
+        }
+    }
+
+    /// Adds one series.
+    ///
+    /// An `le` label of a histogram is dropped: the format reserves it.
+    pub fn metric(&mut self, metric: &CustomMetric) {
+        let mut labels: Vec<(&str, &str)> = metric
+            .labels
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        match &metric.value {
+            MetricValue::Counter { value } => {
+                self.counter(&metric.name, &metric.help, &labels, *value);
+            }
+            MetricValue::Gauge { value } => {
+                self.gauge(&metric.name, &metric.help, &labels, *value);
+            }
+            MetricValue::Histogram(histogram) => {
+                labels.retain(|(key, _)| *key != "le");
+                self.histogram(&metric.name, &metric.help, &labels, histogram);
+            }
+        }
+    }
+
+    /// Adds a counter sample.
+    pub fn counter(&mut self, name: &str, help: &str, labels: &[(&str, &str)], value: u64) {
+        if let Some(samples) = self.samples(name, help, MetricKind::Counter) {
+            sample(samples, name, "", labels, None, value);
+        }
+    }
+
+    /// Adds a gauge sample.
+    pub fn gauge(&mut self, name: &str, help: &str, labels: &[(&str, &str)], value: f64) {
+        if let Some(samples) = self.samples(name, help, MetricKind::Gauge) {
+            sample(samples, name, "", labels, None, Float(value));
+        }
+    }
+
+    /// Adds the buckets, sum and count of a histogram.
+    pub fn histogram(
+        &mut self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        histogram: &HistogramSnapshot,
+    ) {
+        let Some(samples) = self.samples(name, help, MetricKind::Histogram) else {
+            return;
+        };
+        for bucket in &histogram.buckets {
+            let le = Float(bucket.le).to_string();
+            sample(samples, name, "_bucket", labels, Some(&le), bucket.count);
+        }
+        sample(
+            samples,
+            name,
+            "_bucket",
+            labels,
+            Some("+Inf"),
+            histogram.count,
+        );
+        sample(samples, name, "_sum", labels, None, Float(histogram.sum));
+        sample(samples, name, "_count", labels, None, histogram.count);
+    }
+
+    /// Returns the exposition text.
+    #[must_use]
+    pub fn finish(self) -> String {
+        let mut out = String::new();
+        for family in self.families {
+            let _ = writeln!(out, "# HELP {} {}", family.name, escape_help(&family.help));
+            let _ = writeln!(out, "# TYPE {} {}", family.name, family.kind);
+            out.push_str(&family.samples);
+        }
+        out
+    }
+
+    /// Returns the sample buffer of `name`, adding the family on first
+    /// use. `None` if the name is already used by another kind: its
+    /// samples are dropped rather than producing an invalid exposition.
+    fn samples(&mut self, name: &str, help: &str, kind: MetricKind) -> Option<&mut String> {
+        let index = self.families.iter().position(|f| f.name == name);
+        let index = index.unwrap_or_else(|| {
+            self.families.push(Family {
+                name: name.to_string(),
+                help: help.to_string(),
+                kind,
+                samples: String::new(),
+            });
+            self.families.len() - 1
+        });
+        self.families
+            .get_mut(index)
+            .filter(|family| family.kind == kind)
+            .map(|family| &mut family.samples)
+    }
+}
+
+/// Writes one sample line.
+fn sample(
+    out: &mut String,
+    name: &str,
+    suffix: &str,
+    labels: &[(&str, &str)],
+    le: Option<&str>,
+    value: impl std::fmt::Display,
+) {
+    out.push_str(name);
+    out.push_str(suffix);
+    let mut labels = labels
+        .iter()
+        .copied()
+        .chain(le.map(|le| ("le", le)))
+        .peekable();
+    if labels.peek().is_some() {
+        out.push('{');
+        for (i, (key, value)) in labels.enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{key}=\"{}\"", escape_label(value));
+        }
+        out.push('}');
+    }
+    let _ = writeln!(out, " {value}");
+}
+
+/// Escapes a label value: backslash, double quote and newline.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+/// Escapes help text: backslash and newline.
+fn escape_help(help: &str) -> String {
+    help.replace('\\', r"\\").replace('\n', r"\n")
+}
+
+/// A sample value as the format spells it (`+Inf`, `-Inf`, `NaN`).
+struct Float(f64);
+
+impl std::fmt::Display for Float {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            v if v.is_nan() => f.write_str("NaN"),
+            v if v == f64::INFINITY => f.write_str("+Inf"),
+            v if v == f64::NEG_INFINITY => f.write_str("-Inf"),
+            v => write!(f, "{v}"),
+        }
+    }
+}
+
+// =============================================================================
+// MetricsServer
+// =============================================================================
+
+/// HTTP listener serving the metrics of all daemons of a manager.
+///
+/// Answers `GET /metrics` (and `HEAD`) with
+/// [`DaemonManager::to_prometheus`]; anything else gets a 404 or 405.
+/// One connection, one request. Stops when dropped.
+#[derive(Debug)]
+pub struct MetricsServer {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl MetricsServer {
+    /// Binds `addr` and starts serving.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Io` if the address cannot be bound.
+    pub async fn bind(manager: Arc<DaemonManager>, addr: impl ToSocketAddrs) -> Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        tracing::info!(addr = %local_addr, "serving metrics");
+
+        let task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let manager = Arc::clone(&manager);
+                        tokio::spawn(async move {
+                            if let Err(error) = respond(stream, &manager).await {
+                                tracing::debug!(peer = %peer, error = %error, "metrics request failed");
+                            }
+                        });
+                    }
+                    Err(error) => {
+                        // Out of descriptors, most likely: back off
+                        tracing::warn!(error = %error, "metrics listener accept failed");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(Self { local_addr, task })
+    }
+
+    /// Returns the bound address (useful when binding port 0).
+    #[must_use]
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops accepting connections.
+    pub fn stop(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Reads one request and answers it.
+async fn respond(mut stream: TcpStream, manager: &DaemonManager) -> std::io::Result<()> {
+    let head = tokio::time::timeout(REQUEST_TIMEOUT, read_head(&mut stream))
+        .await
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "request timed out"))??;
+
+    let line = head.split(|&b| b == b'\n').next().unwrap_or_default();
+    let line = String::from_utf8_lossy(line);
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts
+        .next()
+        .unwrap_or_default()
+        .split('?')
+        .next()
+        .unwrap_or_default();
+
+    let (status, body) = match (method, path) {
+        ("GET" | "HEAD", "/metrics") => ("200 OK", manager.to_prometheus().await),
+        (_, "/metrics") => ("405 Method Not Allowed", String::new()),
+        _ => ("404 Not Found", String::new()),
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    if method != "HEAD" {
+        stream.write_all(body.as_bytes()).await?;
+    }
+    stream.shutdown().await
+}
+
+/// Reads up to the blank line ending the request head.
+async fn read_head(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    let mut chunk = [0u8; 512];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() <= MAX_REQUEST_HEAD {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&chunk[..n]);
+    }
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::DaemonMetrics;
+
+    fn lines(text: &str, prefix: &str) -> Vec<String> {
+        text.lines()
+            .filter(|line| line.starts_with(prefix))
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshot_encoding() {
+        let metrics = DaemonMetrics::new();
+        metrics.record_request();
+        metrics.record_request();
+        metrics.record_error();
+        metrics.record_duration(Duration::from_millis(3));
+
+        let mut encoder = PrometheusEncoder::new();
+        encoder.snapshot(&[("daemon", "api")], &metrics.snapshot());
+        let text = encoder.finish();
+
+        assert!(text.contains("# TYPE duende_requests_total counter\n"));
+        assert!(text.contains("duende_requests_total{daemon=\"api\"} 2\n"));
+        assert!(text.contains("duende_errors_total{daemon=\"api\"} 1\n"));
+        assert!(text.contains("duende_error_ratio{daemon=\"api\"} 0.5\n"));
+        assert!(text.contains("duende_requests_per_second{daemon=\"api\",window=\"5m\"} "));
+        assert!(text.contains("# TYPE duende_request_duration_seconds histogram\n"));
+        assert!(
+            text.contains(
+                "duende_request_duration_seconds_bucket{daemon=\"api\",le=\"0.005\"} 1\n"
+            )
+        );
+        assert!(
+            text.contains("duende_request_duration_seconds_bucket{daemon=\"api\",le=\"+Inf\"} 1\n")
+        );
+        assert!(text.contains("duende_request_duration_seconds_count{daemon=\"api\"} 1\n"));
+    }
+
+    #[test]
+    fn test_families_group_daemons() {
+        let mut encoder = PrometheusEncoder::new();
+        encoder.snapshot(&[("daemon", "a")], &DaemonMetrics::new().snapshot());
+        encoder.snapshot(&[("daemon", "b")], &DaemonMetrics::new().snapshot());
+        let text = encoder.finish();
+
+        assert_eq!(lines(&text, "# TYPE duende_requests_total ").len(), 1);
+        let samples = lines(&text, "duende_requests_total{");
+        assert_eq!(
+            samples,
+            [
+                "duende_requests_total{daemon=\"a\"} 0",
+                "duende_requests_total{daemon=\"b\"} 0"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_metrics() {
+        let metrics = DaemonMetrics::new();
+        metrics
+            .counter(
+                "jobs_total",
+                "Jobs done",
+                &[("queue", "fast"), ("daemon", "x")],
+            )
+            .unwrap()
+            .inc_by(3);
+        metrics
+            .histogram("batch_size", "Batch size", &[], &[1.0, 8.0])
+            .unwrap()
+            .observe(4.0);
+
+        let mut encoder = PrometheusEncoder::new();
+        encoder.snapshot(&[("daemon", "api")], &metrics.snapshot());
+        let text = encoder.finish();
+
+        assert!(text.contains("# HELP jobs_total Jobs done\n"));
+        // Daemon labels win over custom labels of the same name
+        assert!(text.contains("jobs_total{daemon=\"api\",queue=\"fast\"} 3\n"));
+        assert!(text.contains("batch_size_bucket{daemon=\"api\",le=\"1\"} 0\n"));
+        assert!(text.contains("batch_size_bucket{daemon=\"api\",le=\"8\"} 1\n"));
+        assert!(text.contains("batch_size_sum{daemon=\"api\"} 4\n"));
+    }
+
+    #[test]
+    fn test_kind_conflict_drops_samples() {
+        let mut encoder = PrometheusEncoder::new();
+        encoder.counter("jobs", "Jobs", &[], 1);
+        encoder.gauge("jobs", "Jobs", &[], 2.0);
+        assert_eq!(
+            encoder.finish(),
+            "# HELP jobs Jobs\n# TYPE jobs counter\njobs 1\n"
+        );
+    }
+
+    #[test]
+    fn test_escaping_and_special_values() {
+        let mut encoder = PrometheusEncoder::new();
+        encoder.gauge("g", "line\\one\ntwo", &[("path", "C:\\\"x\"\n")], f64::NAN);
+        encoder.gauge("h", "h", &[], f64::INFINITY);
+        let text = encoder.finish();
+
+        assert!(text.contains("# HELP g line\\\\one\\ntwo\n"));
+        assert!(text.contains("g{path=\"C:\\\\\\\"x\\\"\\n\"} NaN\n"));
+        assert!(text.contains("h +Inf\n"));
+    }
+
+    async fn get(addr: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_metrics_server() {
+        let manager = Arc::new(DaemonManager::new());
+        let server = MetricsServer::bind(Arc::clone(&manager), "127.0.0.1:0")
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+
+        let response = get(addr, "GET /metrics HTTP/1.1\r\nHost: x\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(CONTENT_TYPE));
+
+        let response = get(addr, "HEAD /metrics?x=1 HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\n"));
+
+        let response = get(addr, "POST /metrics HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 405"));
+        let response = get(addr, "GET / HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404"));
+
+        server.stop();
+    }
+}
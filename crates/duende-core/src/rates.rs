@@ -0,0 +1,425 @@
+//! Windowed rates - exponentially weighted 1/5/15-minute rates and a
+//! sliding-window error rate.
+//!
+//! Lifetime averages flatten with uptime: after a week, an error burst
+//! barely moves them. [`Meter`] keeps exponentially weighted moving
+//! averages, as the Unix load average, ticked every 5 seconds.
+//! [`ErrorWindow`] counts requests and errors per second over the last
+//! minute, so its error rate reflects only recent traffic.
+//!
+//! Both are lock-free: recording is a few relaxed atomic adds, and ticks
+//! happen lazily on whichever thread first notices one is due.
+//!
+//! # Toyota Way: Jidoka (自働化)
+//! A circuit breaker or Jidoka gate can only stop the line in time if
+//! it sees the current error rate, not the average since startup.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Interval between EWMA ticks.
+const TICK: Duration = Duration::from_secs(5);
+
+/// Length of the sliding error window, in one-second slots.
+pub const ERROR_WINDOW_SECS: u64 = 60;
+
+// =============================================================================
+// Ewma
+// =============================================================================
+
+/// Exponentially weighted moving average of an event rate.
+#[derive(Debug)]
+struct Ewma {
+    /// Weight of the newest tick.
+    alpha: f64,
+    /// Events since the last tick.
+    uncounted: AtomicU64,
+    /// Rate in events per second, as `f64` bits.
+    rate: AtomicU64,
+    /// False until the first tick.
+    initialized: AtomicBool,
+}
+
+impl Ewma {
+    /// Creates an average over `minutes`.
+    fn new(minutes: f64) -> Self {
+        Self {
+            alpha: 1.0 - (-TICK.as_secs_f64() / 60.0 / minutes).exp(),
+            uncounted: AtomicU64::new(0),
+            rate: AtomicU64::new(0.0_f64.to_bits()),
+            initialized: AtomicBool::new(false),
+        }
+    }
+
+    fn update(&self, n: u64) {
+        self.uncounted.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Applies `ticks` ticks; events since the last tick count toward the
+    /// first, the rest decay the rate. Called by one thread at a time.
+    fn tick(&self, ticks: u64) {
+        if ticks == 0 {
+            return;
+        }
+        let instant = self.uncounted.swap(0, Ordering::Relaxed) as f64 / TICK.as_secs_f64();
+        let mut rate = if self.initialized.swap(true, Ordering::Relaxed) {
+            let rate = f64::from_bits(self.rate.load(Ordering::Relaxed));
+            self.alpha.mul_add(instant - rate, rate)
+        } else {
+            instant
+        };
+        let idle = i32::try_from(ticks - 1).unwrap_or(i32::MAX);
+        rate *= (1.0 - self.alpha).powi(idle);
+        self.rate.store(rate.to_bits(), Ordering::Relaxed);
+    }
+
+    fn rate(&self) -> f64 {
+        f64::from_bits(self.rate.load(Ordering::Relaxed))
+    }
+}
+
+// =============================================================================
+// Meter
+// =============================================================================
+
+/// Event rates over the last 1, 5 and 15 minutes, in events per second.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rates {
+    /// One-minute rate.
+    pub m1: f64,
+    /// Five-minute rate.
+    pub m5: f64,
+    /// Fifteen-minute rate.
+    pub m15: f64,
+}
+
+/// Counts events and keeps their 1/5/15-minute EWMA rates.
+#[derive(Debug)]
+pub struct Meter {
+    m1: Ewma,
+    m5: Ewma,
+    m15: Ewma,
+    /// Reference point for tick times.
+    start: Instant,
+    /// Time of the last tick since `start`, in nanoseconds.
+    last_tick: AtomicU64,
+}
+
+impl Meter {
+    /// Creates a meter with no events.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now())
+    }
+
+    fn starting_at(start: Instant) -> Self {
+        Self {
+            m1: Ewma::new(1.0),
+            m5: Ewma::new(5.0),
+            m15: Ewma::new(15.0),
+            start,
+            last_tick: AtomicU64::new(0),
+        }
+    }
+
+    /// Records `n` events.
+    pub fn mark(&self, n: u64) {
+        self.mark_at(n, Instant::now());
+    }
+
+    /// Returns the current rates.
+    #[must_use]
+    pub fn rates(&self) -> Rates {
+        self.rates_at(Instant::now())
+    }
+
+    fn mark_at(&self, n: u64, now: Instant) {
+        self.tick_at(now);
+        self.m1.update(n);
+        self.m5.update(n);
+        self.m15.update(n);
+    }
+
+    fn rates_at(&self, now: Instant) -> Rates {
+        self.tick_at(now);
+        Rates {
+            m1: self.m1.rate(),
+            m5: self.m5.rate(),
+            m15: self.m15.rate(),
+        }
+    }
+
+    /// Applies the ticks due by `now`; only the thread that advances
+    /// `last_tick` applies them.
+    fn tick_at(&self, now: Instant) {
+        let tick = TICK.as_nanos() as u64;
+        let age = now.saturating_duration_since(self.start).as_nanos() as u64;
+        let last = self.last_tick.load(Ordering::Relaxed);
+        let ticks = age.saturating_sub(last) / tick;
+        if ticks == 0 {
+            return;
+        }
+        let next = last + ticks * tick;
+        if self
+            .last_tick
+            .compare_exchange(last, next, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok()
+        {
+            self.m1.tick(ticks);
+            self.m5.tick(ticks);
+            self.m15.tick(ticks);
+        }
+    }
+}
+
+impl Default for Meter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// ErrorWindow
+// =============================================================================
+
+/// Requests and errors seen in a recent window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowStats {
+    /// Requests in the window.
+    pub requests: u64,
+    /// Errors in the window.
+    pub errors: u64,
+}
+
+impl WindowStats {
+    /// Returns errors / requests (0.0 without requests).
+    #[must_use]
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.requests as f64
+        }
+    }
+}
+
+/// Counters of one second.
+#[derive(Debug, Default)]
+struct Slot {
+    /// Second (since start) the counters belong to.
+    second: AtomicU64,
+    requests: AtomicU64,
+    errors: AtomicU64,
+}
+
+/// Sliding window of requests and errors over the last minute.
+///
+/// A slot is recycled by the first recording of a new second; a
+/// recording racing with that reset may be lost, which is within the
+/// precision metrics need.
+#[derive(Debug)]
+pub struct ErrorWindow {
+    start: Instant,
+    slots: Box<[Slot]>,
+}
+
+impl ErrorWindow {
+    /// Creates an empty window.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now())
+    }
+
+    fn starting_at(start: Instant) -> Self {
+        Self {
+            start,
+            slots: (0..ERROR_WINDOW_SECS).map(|_| Slot::default()).collect(),
+        }
+    }
+
+    /// Records a request.
+    pub fn record_request(&self) {
+        self.slot_at(Instant::now())
+            .requests
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records an error.
+    pub fn record_error(&self) {
+        self.slot_at(Instant::now())
+            .errors
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the counts of the last `window`, at most
+    /// [`ERROR_WINDOW_SECS`], rounded up to whole seconds.
+    #[must_use]
+    pub fn stats(&self, window: Duration) -> WindowStats {
+        self.stats_at(window, Instant::now())
+    }
+
+    fn second(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.start).as_secs()
+    }
+
+    fn slot_at(&self, now: Instant) -> &Slot {
+        let second = self.second(now);
+        let slot = &self.slots[(second % ERROR_WINDOW_SECS) as usize];
+        let seen = slot.second.load(Ordering::Acquire);
+        if seen != second
+            && slot
+                .second
+                .compare_exchange(seen, second, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        {
+            slot.requests.store(0, Ordering::Relaxed);
+            slot.errors.store(0, Ordering::Relaxed);
+        }
+        slot
+    }
+
+    fn stats_at(&self, window: Duration, now: Instant) -> WindowStats {
+        let secs = window
+            .as_secs()
+            .saturating_add(u64::from(window.subsec_nanos() > 0))
+            .clamp(1, ERROR_WINDOW_SECS);
+        let current = self.second(now);
+
+        self.slots
+            .iter()
+            .filter(|slot| {
+                let second = slot.second.load(Ordering::Acquire);
+                second <= current && current - second < secs
+            })
+            .fold(WindowStats::default(), |stats, slot| WindowStats {
+                requests: stats.requests + slot.requests.load(Ordering::Relaxed),
+                errors: stats.errors + slot.errors.load(Ordering::Relaxed),
+            })
+    }
+}
+
+impl Default for ErrorWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_converges_to_steady_rate() {
+        let start = Instant::now();
+        let meter = Meter::starting_at(start);
+
+        // 10 events/s for 15 minutes
+        for second in 0..900 {
+            meter.mark_at(10, start + Duration::from_secs(second));
+        }
+        let rates = meter.rates_at(start + Duration::from_secs(900));
+        assert!((rates.m1 - 10.0).abs() < 0.1, "{rates:?}");
+        assert!((rates.m5 - 10.0).abs() < 0.1, "{rates:?}");
+        assert!((rates.m15 - 10.0).abs() < 0.1, "{rates:?}");
+    }
+
+    #[test]
+    fn test_meter_decays_when_idle() {
+        let start = Instant::now();
+        let meter = Meter::starting_at(start);
+        for second in 0..300 {
+            meter.mark_at(10, start + Duration::from_secs(second));
+        }
+
+        // Five idle minutes: the 1-minute rate forgets, the 15-minute rate remembers
+        let rates = meter.rates_at(start + Duration::from_secs(600));
+        assert!(rates.m1 < 0.1, "{rates:?}");
+        assert!(rates.m15 > rates.m5 && rates.m5 > rates.m1, "{rates:?}");
+    }
+
+    #[test]
+    fn test_meter_reacts_to_burst_after_long_uptime() {
+        let start = Instant::now();
+        let meter = Meter::starting_at(start);
+        let week = Duration::from_secs(7 * 24 * 3600);
+
+        for second in 0..60 {
+            meter.mark_at(100, start + week + Duration::from_secs(second));
+        }
+        let rates = meter.rates_at(start + week + Duration::from_secs(60));
+        assert!(rates.m1 > 50.0, "{rates:?}");
+    }
+
+    #[test]
+    fn test_meter_before_first_tick() {
+        let meter = Meter::new();
+        meter.mark(5);
+        assert_eq!(meter.rates(), Rates::default());
+    }
+
+    #[test]
+    fn test_error_window_counts_recent_seconds() {
+        let start = Instant::now();
+        let window = ErrorWindow::starting_at(start);
+        let at = |s| start + Duration::from_secs(s);
+
+        for second in 0..10 {
+            window
+                .slot_at(at(second))
+                .requests
+                .fetch_add(10, Ordering::Relaxed);
+        }
+        window.slot_at(at(9)).errors.fetch_add(5, Ordering::Relaxed);
+
+        let last = window.stats_at(Duration::from_secs(1), at(9));
+        assert_eq!(
+            last,
+            WindowStats {
+                requests: 10,
+                errors: 5
+            }
+        );
+        assert!((last.error_rate() - 0.5).abs() < f64::EPSILON);
+
+        let minute = window.stats_at(Duration::from_secs(60), at(9));
+        assert_eq!(minute.requests, 100);
+        assert!((minute.error_rate() - 0.05).abs() < f64::EPSILON);
+
+        // Slots older than the window drop out and are recycled
+        assert_eq!(window.stats_at(Duration::from_secs(60), at(69)).requests, 0);
+        window
+            .slot_at(at(60))
+            .requests
+            .fetch_add(1, Ordering::Relaxed);
+        assert_eq!(
+            window.stats_at(Duration::from_secs(60), at(60)).requests,
+            91
+        );
+    }
+
+    #[test]
+    fn test_window_stats_without_requests() {
+        let window = ErrorWindow::new();
+        let stats = window.stats(Duration::from_secs(30));
+        assert_eq!(stats, WindowStats::default());
+        assert_eq!(stats.error_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_error_window_records() {
+        let window = ErrorWindow::new();
+        window.record_request();
+        window.record_request();
+        window.record_error();
+        let stats = window.stats(Duration::from_secs(60));
+        assert_eq!(
+            stats,
+            WindowStats {
+                requests: 2,
+                errors: 1
+            }
+        );
+    }
+}
@@ -0,0 +1,653 @@
+//! Labeled custom metrics - counters, gauges and histograms a daemon
+//! registers for itself.
+//!
+//! Registration takes a lock once; the returned handle records with
+//! atomics only, so the hot path never blocks:
+//!
+//! ```rust
+//! use duende_core::DaemonMetrics;
+//!
+//! # fn main() -> duende_core::Result<()> {
+//! let metrics = DaemonMetrics::new();
+//! let tokens = metrics.counter(
+//!     "inference_tokens_total",
+//!     "Tokens generated",
+//!     &[("model", "llama")],
+//! )?;
+//! tokens.inc_by(42);
+//!
+//! assert_eq!(metrics.snapshot().custom.len(), 1);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Toyota Way: Visual Management (目で見る管理)
+//! The RED metrics show that a daemon works; custom metrics show what it
+//! is working on.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DaemonError, Result};
+
+/// Default histogram buckets, as Prometheus client libraries.
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+// =============================================================================
+// Handles
+// =============================================================================
+
+/// Monotonic counter.
+///
+/// Clones share the same series.
+#[derive(Debug, Clone, Default)]
+pub struct Counter(Arc<AtomicU64>);
+
+impl Counter {
+    /// Increments by one.
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    /// Increments by `n`.
+    pub fn inc_by(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Returns the current value.
+    #[must_use]
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Value that can go up and down.
+///
+/// Clones share the same series.
+#[derive(Debug, Clone, Default)]
+pub struct Gauge(Arc<AtomicU64>);
+
+impl Gauge {
+    /// Sets the value.
+    pub fn set(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Adds `delta`, which may be negative.
+    pub fn add(&self, delta: f64) {
+        add_f64(&self.0, delta);
+    }
+
+    /// Increments by one.
+    pub fn inc(&self) {
+        self.add(1.0);
+    }
+
+    /// Decrements by one.
+    pub fn dec(&self) {
+        self.add(-1.0);
+    }
+
+    /// Returns the current value.
+    #[must_use]
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+/// Distribution of observed values over fixed buckets.
+///
+/// Clones share the same series.
+#[derive(Debug, Clone)]
+pub struct Histogram(Arc<HistogramInner>);
+
+#[derive(Debug)]
+struct HistogramInner {
+    /// Upper bounds, strictly increasing; `+Inf` is implicit.
+    bounds: Box<[f64]>,
+    /// Per-bucket counts (not cumulative), one more than `bounds`.
+    buckets: Box<[AtomicU64]>,
+    /// Sum of observed values, as `f64` bits.
+    sum: AtomicU64,
+    /// Number of observations.
+    count: AtomicU64,
+}
+
+impl Histogram {
+    fn new(bounds: &[f64]) -> Self {
+        Self(Arc::new(HistogramInner {
+            bounds: bounds.into(),
+            buckets: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum: AtomicU64::new(0.0_f64.to_bits()),
+            count: AtomicU64::new(0),
+        }))
+    }
+
+    /// Records one observation.
+    pub fn observe(&self, value: f64) {
+        // First bucket whose upper bound is >= value (`le` semantics)
+        let bucket = self.0.bounds.partition_point(|&bound| bound < value);
+        if let Some(count) = self.0.buckets.get(bucket) {
+            count.fetch_add(1, Ordering::Relaxed);
+        }
+        add_f64(&self.0.sum, value);
+        self.0.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of observations.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.0.count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the sum of observed values.
+    #[must_use]
+    pub fn sum(&self) -> f64 {
+        f64::from_bits(self.0.sum.load(Ordering::Relaxed))
+    }
+
+    /// Returns a point-in-time copy.
+    #[must_use]
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let mut cumulative = 0;
+        let buckets = self
+            .0
+            .bounds
+            .iter()
+            .zip(self.0.buckets.iter())
+            .map(|(&le, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                Bucket {
+                    le,
+                    count: cumulative,
+                }
+            })
+            .collect();
+
+        HistogramSnapshot {
+            buckets,
+            sum: self.sum(),
+            count: self.count(),
+        }
+    }
+}
+
+/// Adds to an `f64` stored as bits.
+fn add_f64(cell: &AtomicU64, delta: f64) {
+    let mut current = cell.load(Ordering::Relaxed);
+    loop {
+        let next = (f64::from_bits(current) + delta).to_bits();
+        match cell.compare_exchange_weak(current, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => break,
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+// =============================================================================
+// Snapshot types
+// =============================================================================
+
+/// Kind of a custom metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricKind {
+    /// Monotonic counter.
+    Counter,
+    /// Value that can go up and down.
+    Gauge,
+    /// Distribution over buckets.
+    Histogram,
+}
+
+impl fmt::Display for MetricKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Counter => write!(f, "counter"),
+            Self::Gauge => write!(f, "gauge"),
+            Self::Histogram => write!(f, "histogram"),
+        }
+    }
+}
+
+/// A histogram bucket: observations less than or equal to `le`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bucket {
+    /// Upper bound.
+    pub le: f64,
+    /// Cumulative count.
+    pub count: u64,
+}
+
+/// Histogram at a point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistogramSnapshot {
+    /// Cumulative buckets; the implicit `+Inf` bucket is `count`.
+    pub buckets: Vec<Bucket>,
+    /// Sum of observed values.
+    pub sum: f64,
+    /// Number of observations.
+    pub count: u64,
+}
+
+/// Value of a custom metric series.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MetricValue {
+    /// Counter value.
+    Counter {
+        /// Current value.
+        value: u64,
+    },
+    /// Gauge value.
+    Gauge {
+        /// Current value.
+        value: f64,
+    },
+    /// Histogram value.
+    Histogram(HistogramSnapshot),
+}
+
+impl MetricValue {
+    /// Returns the kind of metric this value belongs to.
+    #[must_use]
+    pub const fn kind(&self) -> MetricKind {
+        match self {
+            Self::Counter { .. } => MetricKind::Counter,
+            Self::Gauge { .. } => MetricKind::Gauge,
+            Self::Histogram(_) => MetricKind::Histogram,
+        }
+    }
+}
+
+/// One labeled series of a custom metric, as snapshotted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomMetric {
+    /// Metric name.
+    pub name: String,
+    /// Help text.
+    pub help: String,
+    /// Labels, sorted by name.
+    pub labels: BTreeMap<String, String>,
+    /// Value.
+    #[serde(flatten)]
+    pub value: MetricValue,
+}
+
+impl CustomMetric {
+    /// Creates an unlabeled series.
+    #[must_use]
+    pub fn new(name: impl Into<String>, help: impl Into<String>, value: MetricValue) -> Self {
+        Self {
+            name: name.into(),
+            help: help.into(),
+            labels: BTreeMap::new(),
+            value,
+        }
+    }
+
+    /// Adds a label, replacing one of the same name.
+    #[must_use]
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+}
+
+// =============================================================================
+// MetricsRegistry
+// =============================================================================
+
+/// Registered series of one metric name.
+#[derive(Debug)]
+struct Family {
+    help: String,
+    kind: MetricKind,
+    /// Histogram bucket bounds; empty for other kinds.
+    bounds: Vec<f64>,
+    series: BTreeMap<BTreeMap<String, String>, Series>,
+}
+
+#[derive(Debug)]
+enum Series {
+    Counter(Counter),
+    Gauge(Gauge),
+    Histogram(Histogram),
+}
+
+impl Series {
+    fn value(&self) -> MetricValue {
+        match self {
+            Self::Counter(c) => MetricValue::Counter { value: c.get() },
+            Self::Gauge(g) => MetricValue::Gauge { value: g.get() },
+            Self::Histogram(h) => MetricValue::Histogram(h.snapshot()),
+        }
+    }
+}
+
+/// Registry of labeled custom metrics.
+///
+/// Registering an existing name and label set returns the existing
+/// series, so independent parts of a daemon can share a metric.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    families: RwLock<BTreeMap<String, Family>>,
+}
+
+impl MetricsRegistry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers (or looks up) a counter series.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Metric` if the name or labels are invalid, or
+    /// the name is registered as another kind.
+    pub fn counter(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Result<Counter> {
+        self.register(
+            name,
+            help,
+            labels,
+            MetricKind::Counter,
+            &[],
+            |series| match series {
+                Series::Counter(counter) => Some(counter.clone()),
+                _ => None,
+            },
+        )
+    }
+
+    /// Registers (or looks up) a gauge series.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Metric` if the name or labels are invalid, or
+    /// the name is registered as another kind.
+    pub fn gauge(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Result<Gauge> {
+        self.register(
+            name,
+            help,
+            labels,
+            MetricKind::Gauge,
+            &[],
+            |series| match series {
+                Series::Gauge(gauge) => Some(gauge.clone()),
+                _ => None,
+            },
+        )
+    }
+
+    /// Registers (or looks up) a histogram series with the given bucket
+    /// upper bounds ([`DEFAULT_BUCKETS`] if empty).
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Metric` if the name, labels or buckets are
+    /// invalid, or the name is registered as another kind or with other
+    /// buckets.
+    pub fn histogram(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        buckets: &[f64],
+    ) -> Result<Histogram> {
+        let buckets = if buckets.is_empty() {
+            DEFAULT_BUCKETS
+        } else {
+            buckets
+        };
+        if buckets.iter().any(|b| !b.is_finite())
+            || buckets.windows(2).any(|pair| pair[0] >= pair[1])
+        {
+            return Err(DaemonError::metric(format!(
+                "histogram {name}: buckets must be finite and strictly increasing"
+            )));
+        }
+
+        self.register(
+            name,
+            help,
+            labels,
+            MetricKind::Histogram,
+            buckets,
+            |series| match series {
+                Series::Histogram(histogram) => Some(histogram.clone()),
+                _ => None,
+            },
+        )
+    }
+
+    /// Returns the number of registered series.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.families.read().values().map(|f| f.series.len()).sum()
+    }
+
+    /// Returns true if no series is registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns every series, ordered by name then labels.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<CustomMetric> {
+        let families = self.families.read();
+        families
+            .iter()
+            .flat_map(|(name, family)| {
+                family.series.iter().map(|(labels, series)| CustomMetric {
+                    name: name.clone(),
+                    help: family.help.clone(),
+                    labels: labels.clone(),
+                    value: series.value(),
+                })
+            })
+            .collect()
+    }
+
+    /// Finds or creates a series; `select` extracts the typed handle.
+    fn register<T>(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        kind: MetricKind,
+        bounds: &[f64],
+        select: impl Fn(&Series) -> Option<T>,
+    ) -> Result<T> {
+        validate_name(name)?;
+        let labels = label_set(name, labels)?;
+
+        let mut families = self.families.write();
+        let family = families.entry(name.to_string()).or_insert_with(|| Family {
+            help: help.to_string(),
+            kind,
+            bounds: bounds.to_vec(),
+            series: BTreeMap::new(),
+        });
+        if family.kind != kind {
+            return Err(DaemonError::metric(format!(
+                "metric {name} is registered as a {}, not a {kind}",
+                family.kind
+            )));
+        }
+        if family.bounds != bounds {
+            return Err(DaemonError::metric(format!(
+                "histogram {name} is registered with other buckets"
+            )));
+        }
+
+        let series = family.series.entry(labels).or_insert_with(|| match kind {
+            MetricKind::Counter => Series::Counter(Counter::default()),
+            MetricKind::Gauge => Series::Gauge(Gauge::default()),
+            MetricKind::Histogram => Series::Histogram(Histogram::new(bounds)),
+        });
+        select(series).ok_or_else(|| DaemonError::Internal(format!("metric {name} kind mismatch")))
+    }
+}
+
+/// Checks a metric name against `[a-zA-Z_:][a-zA-Z0-9_:]*`.
+fn validate_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':');
+    if valid {
+        Ok(())
+    } else {
+        Err(DaemonError::metric(format!("invalid metric name {name:?}")))
+    }
+}
+
+/// Builds a label set, checking names against `[a-zA-Z_][a-zA-Z0-9_]*`
+/// (without the reserved `__` prefix) and rejecting duplicates.
+fn label_set(metric: &str, labels: &[(&str, &str)]) -> Result<BTreeMap<String, String>> {
+    let mut set = BTreeMap::new();
+    for &(key, value) in labels {
+        let mut chars = key.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !key.starts_with("__");
+        if !valid {
+            return Err(DaemonError::metric(format!(
+                "metric {metric}: invalid label name {key:?}"
+            )));
+        }
+        if set.insert(key.to_string(), value.to_string()).is_some() {
+            return Err(DaemonError::metric(format!(
+                "metric {metric}: duplicate label {key:?}"
+            )));
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_series_are_shared_by_labels() {
+        let registry = MetricsRegistry::new();
+        let a = registry
+            .counter("tokens_total", "Tokens", &[("model", "a")])
+            .unwrap();
+        let a_again = registry
+            .counter("tokens_total", "Tokens", &[("model", "a")])
+            .unwrap();
+        let b = registry
+            .counter("tokens_total", "Tokens", &[("model", "b")])
+            .unwrap();
+
+        a.inc();
+        a_again.inc_by(2);
+        b.inc();
+        assert_eq!(a.get(), 3);
+        assert_eq!(b.get(), 1);
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn test_gauge_add_and_set() {
+        let registry = MetricsRegistry::new();
+        let gauge = registry.gauge("queue_depth", "Queue depth", &[]).unwrap();
+
+        gauge.inc();
+        gauge.add(2.5);
+        gauge.dec();
+        assert!((gauge.get() - 2.5).abs() < f64::EPSILON);
+        gauge.set(-4.0);
+        assert!((gauge.get() + 4.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let registry = MetricsRegistry::new();
+        let histogram = registry
+            .histogram("batch_size", "Batch size", &[], &[1.0, 8.0, 32.0])
+            .unwrap();
+
+        for value in [0.5, 1.0, 4.0, 100.0] {
+            histogram.observe(value);
+        }
+
+        let snapshot = histogram.snapshot();
+        let counts: Vec<u64> = snapshot.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, [2, 3, 3]);
+        assert_eq!(snapshot.count, 4);
+        assert!((snapshot.sum - 105.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_histogram_default_buckets() {
+        let registry = MetricsRegistry::new();
+        let histogram = registry.histogram("latency", "Latency", &[], &[]).unwrap();
+        assert_eq!(histogram.snapshot().buckets.len(), DEFAULT_BUCKETS.len());
+    }
+
+    #[test]
+    fn test_registration_errors() {
+        let registry = MetricsRegistry::new();
+        registry.counter("requests", "Requests", &[]).unwrap();
+        registry
+            .histogram("sizes", "Sizes", &[], &[1.0, 2.0])
+            .unwrap();
+
+        let metric_err = |result: Result<()>| matches!(result, Err(DaemonError::Metric(_)));
+        assert!(metric_err(registry.gauge("requests", "", &[]).map(drop)));
+        assert!(metric_err(
+            registry.histogram("sizes", "", &[], &[1.0, 3.0]).map(drop)
+        ));
+        assert!(metric_err(registry.counter("9lives", "", &[]).map(drop)));
+        assert!(metric_err(registry.counter("a-b", "", &[]).map(drop)));
+        assert!(metric_err(
+            registry.counter("x", "", &[("__name", "v")]).map(drop)
+        ));
+        assert!(metric_err(
+            registry
+                .counter("x", "", &[("k", "1"), ("k", "2")])
+                .map(drop)
+        ));
+        assert!(metric_err(
+            registry.histogram("h", "", &[], &[2.0, 1.0]).map(drop)
+        ));
+        assert!(metric_err(
+            registry.histogram("h", "", &[], &[f64::NAN]).map(drop)
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_is_ordered_and_serializable() {
+        let registry = MetricsRegistry::new();
+        registry
+            .counter("b_total", "B", &[("z", "1"), ("a", "2")])
+            .unwrap()
+            .inc();
+        registry.gauge("a_value", "A", &[]).unwrap().set(1.5);
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot[0].name, "a_value");
+        assert_eq!(snapshot[1].name, "b_total");
+        let keys: Vec<&str> = snapshot[1].labels.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["a", "z"]);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(json.contains(r#""type":"counter","value":1"#));
+        let back: Vec<CustomMetric> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, snapshot);
+        assert_eq!(back[0].value.kind(), MetricKind::Gauge);
+    }
+}
@@ -0,0 +1,165 @@
+//! Shutdown coordination - cancellation token and subtask accounting.
+//!
+//! # Toyota Way: Jidoka (自働化)
+//! Shutdown is a signal every part of the daemon can wait on, not a flag
+//! each loop has to poll. Work stops as soon as the line is stopped.
+//!
+//! # Toyota Way: Muda (無駄)
+//! Subtasks that outlive their daemon are waste. They are joined within
+//! the shutdown timeout and any leak is reported.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+// =============================================================================
+// ShutdownToken
+// =============================================================================
+
+/// Cloneable, awaitable shutdown token.
+///
+/// Every clone observes the same state: once any clone is cancelled,
+/// [`is_cancelled`](Self::is_cancelled) returns true and all pending
+/// [`cancelled`](Self::cancelled) futures complete.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let token = ctx.shutdown_token();
+/// loop {
+///     tokio::select! {
+///         () = token.cancelled() => return Ok(ExitReason::Graceful),
+///         job = queue.recv() => process(job).await,
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShutdownToken {
+    /// Cancellation state (true = cancelled).
+    state: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownToken {
+    /// Creates a token that is not cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        let (state, _) = watch::channel(false);
+        Self {
+            state: Arc::new(state),
+        }
+    }
+
+    /// Cancels the token, waking every waiter. Idempotent.
+    pub fn cancel(&self) {
+        self.state.send_replace(true);
+    }
+
+    /// Returns true if the token has been cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        *self.state.borrow()
+    }
+
+    /// Completes once the token is cancelled.
+    ///
+    /// Completes immediately if it already is. Cancel-safe.
+    pub async fn cancelled(&self) {
+        let mut rx = self.state.subscribe();
+        // The sender lives in `self`, so the channel cannot close while waiting
+        let _ = rx.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Default for ShutdownToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// SubtaskReport
+// =============================================================================
+
+/// Outcome of joining a daemon's subtasks at shutdown.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubtaskReport {
+    /// Subtasks that finished before the deadline.
+    pub joined: usize,
+    /// Names of subtasks that panicked.
+    pub panicked: Vec<String>,
+    /// Names of subtasks still running at the deadline (aborted).
+    pub leaked: Vec<String>,
+}
+
+impl SubtaskReport {
+    /// Returns true if every subtask finished cleanly before the deadline.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.panicked.is_empty() && self.leaked.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_token_starts_uncancelled() {
+        let token = ShutdownToken::new();
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn test_token_clones_share_state() {
+        let token = ShutdownToken::new();
+        let clone = token.clone();
+
+        clone.cancel();
+        assert!(token.is_cancelled());
+
+        // Idempotent
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_wakes_waiters() {
+        let token = ShutdownToken::new();
+        let waiter = {
+            let token = token.clone();
+            tokio::spawn(async move { token.cancelled().await })
+        };
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        token.cancel();
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter not woken")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_completes_when_already_cancelled() {
+        let token = ShutdownToken::new();
+        token.cancel();
+
+        tokio::time::timeout(Duration::from_millis(100), token.cancelled())
+            .await
+            .expect("already-cancelled token should complete");
+    }
+
+    #[test]
+    fn test_subtask_report_is_clean() {
+        let mut report = SubtaskReport {
+            joined: 3,
+            ..SubtaskReport::default()
+        };
+        assert!(report.is_clean());
+
+        report.leaked.push("reader".to_string());
+        assert!(!report.is_clean());
+    }
+}
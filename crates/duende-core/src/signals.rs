@@ -0,0 +1,301 @@
+//! OS signal bridge - forwards process signals into a [`DaemonContext`].
+//!
+//! # Toyota Way: Standardized Work (標準作業)
+//! A daemon sees the same signals whether it runs under systemd, Docker
+//! or a bare shell. No per-daemon signal plumbing is required.
+//!
+//! # Toyota Way: Jidoka (自働化)
+//! TERM, INT and QUIT still set the shutdown flag: the bridge only delivers
+//! signals, the [`DaemonContext`] applies the shutdown rule on receipt.
+//!
+//! [`DaemonContext`]: crate::daemon::DaemonContext
+
+use tokio::task::JoinHandle;
+
+use crate::daemon::DaemonContextHandle;
+use crate::error::{DaemonError, Result};
+use crate::types::Signal;
+
+// =============================================================================
+// SignalBridge
+// =============================================================================
+
+/// Bridges OS signals into a daemon context.
+///
+/// Installs a handler for every catchable [`Signal`] variant and forwards
+/// each delivery through a [`DaemonContextHandle`]. Masked signals are
+/// still caught, so they never trigger the default OS action, but they are
+/// discarded instead of being forwarded.
+///
+/// KILL and STOP cannot be caught and are never forwarded.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use duende_core::{DaemonContext, Signal, SignalBridge};
+///
+/// let (mut ctx, handle) = DaemonContext::new(config);
+/// let _guard = SignalBridge::new().with_masked(Signal::Hup).install(&handle)?;
+///
+/// daemon.run(&mut ctx).await?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignalBridge {
+    /// Signals caught but not forwarded.
+    masked: Vec<Signal>,
+}
+
+impl SignalBridge {
+    /// Creates a bridge that forwards every catchable signal.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Masks a signal so it is not forwarded to the daemon.
+    #[must_use]
+    pub fn with_masked(mut self, signal: Signal) -> Self {
+        if !self.masked.contains(&signal) {
+            self.masked.push(signal);
+        }
+        self
+    }
+
+    /// Masks every signal in `signals`.
+    #[must_use]
+    pub fn with_mask(self, signals: impl IntoIterator<Item = Signal>) -> Self {
+        signals.into_iter().fold(self, Self::with_masked)
+    }
+
+    /// Returns true if the signal is masked.
+    #[must_use]
+    pub fn is_masked(&self, signal: Signal) -> bool {
+        self.masked.contains(&signal)
+    }
+
+    /// Returns the signals that will be forwarded to the daemon.
+    #[must_use]
+    pub fn forwarded_signals(&self) -> Vec<Signal> {
+        Signal::ALL
+            .into_iter()
+            .filter(|s| s.is_catchable() && !self.is_masked(*s))
+            .collect()
+    }
+
+    /// Installs the signal handlers and starts forwarding.
+    ///
+    /// Must be called from within a tokio runtime. Forwarding stops when the
+    /// returned guard is dropped or the daemon context is closed. Handlers
+    /// are process-wide: once installed, the default OS action for a
+    /// catchable signal is not restored for the lifetime of the process.
+    /// Off Unix, only Ctrl+C is caught; it is forwarded as [`Signal::Int`].
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Signal` if a handler cannot be installed.
+    pub fn install(&self, handle: &DaemonContextHandle) -> Result<SignalBridgeGuard> {
+        let handle = handle.clone();
+        self.install_with(move |sig| {
+            let handle = handle.clone();
+            async move { handle.send_signal(sig).await.is_ok() }
+        })
+    }
+
+    /// Installs the signal handlers and passes every unmasked signal to
+    /// `forward`, until it returns false or the guard is dropped.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Signal` if a handler cannot be installed.
+    #[cfg(unix)]
+    pub(crate) fn install_with<F, Fut>(&self, forward: F) -> Result<SignalBridgeGuard>
+    where
+        F: Fn(Signal) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = bool> + Send,
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        let mut tasks: Vec<JoinHandle<()>> = Vec::new();
+
+        for sig in Signal::ALL.into_iter().filter(Signal::is_catchable) {
+            let mut stream = match signal(SignalKind::from_raw(os_signal_number(sig))) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    // Do not leave a half-installed bridge behind
+                    for task in &tasks {
+                        task.abort();
+                    }
+                    return Err(DaemonError::Signal(format!(
+                        "failed to install {:?} handler: {}",
+                        sig, e
+                    )));
+                }
+            };
+
+            let masked = self.is_masked(sig);
+            let forward = forward.clone();
+
+            tasks.push(tokio::spawn(async move {
+                while stream.recv().await.is_some() {
+                    if masked {
+                        tracing::debug!(signal = ?sig, "masked signal discarded");
+                        continue;
+                    }
+                    tracing::debug!(signal = ?sig, "forwarding signal");
+                    if !forward(sig).await {
+                        break;
+                    }
+                }
+            }));
+        }
+
+        Ok(SignalBridgeGuard { tasks })
+    }
+
+    /// Installs the signal handlers and passes every unmasked signal to
+    /// `forward`, until it returns false or the guard is dropped.
+    ///
+    /// # Errors
+    /// Never fails on this platform.
+    #[cfg(not(unix))]
+    pub(crate) fn install_with<F, Fut>(&self, forward: F) -> Result<SignalBridgeGuard>
+    where
+        F: Fn(Signal) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = bool> + Send,
+    {
+        let masked = self.is_masked(Signal::Int);
+
+        let task = tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if masked {
+                    continue;
+                }
+                if !forward(Signal::Int).await {
+                    break;
+                }
+            }
+        });
+
+        Ok(SignalBridgeGuard { tasks: vec![task] })
+    }
+}
+
+/// Maps a [`Signal`] to the platform's signal number.
+///
+/// `Signal::as_i32` uses Linux numbering; USR1/USR2/STOP/CONT differ on
+/// other Unixes, so the raw value comes from the platform headers.
+#[cfg(unix)]
+const fn os_signal_number(signal: Signal) -> i32 {
+    use nix::sys::signal::Signal as Os;
+
+    let os = match signal {
+        Signal::Hup => Os::SIGHUP,
+        Signal::Int => Os::SIGINT,
+        Signal::Quit => Os::SIGQUIT,
+        Signal::Term => Os::SIGTERM,
+        Signal::Kill => Os::SIGKILL,
+        Signal::Usr1 => Os::SIGUSR1,
+        Signal::Usr2 => Os::SIGUSR2,
+        Signal::Stop => Os::SIGSTOP,
+        Signal::Cont => Os::SIGCONT,
+    };
+    os as i32
+}
+
+// =============================================================================
+// SignalBridgeGuard
+// =============================================================================
+
+/// Keeps a [`SignalBridge`] forwarding; stops forwarding when dropped.
+#[derive(Debug)]
+pub struct SignalBridgeGuard {
+    /// One forwarding task per signal.
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl SignalBridgeGuard {
+    /// Returns true while at least one signal is being forwarded.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.tasks.iter().any(|task| !task.is_finished())
+    }
+}
+
+impl Drop for SignalBridgeGuard {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DaemonConfig;
+    use crate::daemon::DaemonContext;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bridge_forwards_all_catchable_by_default() {
+        let bridge = SignalBridge::new();
+        let forwarded = bridge.forwarded_signals();
+
+        assert_eq!(forwarded.len(), 7);
+        assert!(!forwarded.contains(&Signal::Kill));
+        assert!(!forwarded.contains(&Signal::Stop));
+    }
+
+    #[test]
+    fn test_bridge_masking() {
+        let bridge = SignalBridge::new()
+            .with_masked(Signal::Hup)
+            .with_mask([Signal::Usr1, Signal::Hup]);
+
+        assert!(bridge.is_masked(Signal::Hup));
+        assert!(bridge.is_masked(Signal::Usr1));
+        assert!(!bridge.is_masked(Signal::Term));
+        assert_eq!(bridge.forwarded_signals().len(), 5);
+        assert_eq!(
+            bridge,
+            SignalBridge::new().with_mask([Signal::Hup, Signal::Usr1])
+        );
+    }
+
+    /// Receives signals until `expected` arrives; other tests may raise too.
+    async fn recv_until(ctx: &mut DaemonContext, expected: Signal) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while ctx.recv_signal().await != Some(expected) {}
+        })
+        .await
+        .expect("signal not forwarded");
+    }
+
+    // All OS-level assertions live in one test: signals are process-wide.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bridge_forwards_os_signals() {
+        use nix::sys::signal::{Signal as Os, raise};
+
+        let (mut ctx, handle) = DaemonContext::new(DaemonConfig::new("test", "/bin/test"));
+        let guard = SignalBridge::new()
+            .with_masked(Signal::Usr2)
+            .install(&handle)
+            .expect("install bridge");
+        assert!(guard.is_active());
+
+        raise(Os::SIGUSR1).expect("raise USR1");
+        recv_until(&mut ctx, Signal::Usr1).await;
+        assert!(!ctx.should_shutdown());
+
+        raise(Os::SIGUSR2).expect("raise USR2");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        while let Some(signal) = ctx.try_recv_signal() {
+            assert_ne!(signal, Signal::Usr2, "masked signal was forwarded");
+        }
+
+        raise(Os::SIGTERM).expect("raise TERM");
+        recv_until(&mut ctx, Signal::Term).await;
+        assert!(ctx.should_shutdown());
+
+        drop(guard);
+    }
+}
@@ -0,0 +1,179 @@
+//! Lifecycle spans - structured `tracing` spans around each phase.
+//!
+//! The [`Supervisor`] opens a span for every `init`, `run` and `shutdown`
+//! of a daemon, every health probe and every restart backoff; the
+//! [`DaemonManager`] for every external check and every stop during
+//! [`shutdown_all`]. All have target [`TARGET`] and carry the daemon's ID,
+//! name, platform and restart count, and record their `outcome` when the
+//! phase ends. Spans of a traced run also name its trace (`trace_id`) and
+//! span (`parent_span_id`), so an exporter can place them below the run.
+//!
+//! # Toyota Way: Mieruka (見える化)
+//! Make the work visible: every step the framework takes on a daemon's
+//! behalf shows up where the daemon's own spans do.
+//!
+//! [`Supervisor`]: crate::supervisor::Supervisor
+//! [`DaemonManager`]: crate::manager::DaemonManager
+//! [`shutdown_all`]: crate::manager::DaemonManager::shutdown_all
+
+use std::fmt;
+
+use tracing::Span;
+use tracing::field::Empty;
+
+use crate::adapter::DaemonHandle;
+use crate::error::Result;
+use crate::manager::ManagedDaemon;
+use crate::platform::Platform;
+use crate::types::{ExitReason, HealthStatus};
+
+/// Target of all lifecycle spans, e.g. for `EnvFilter` directives.
+pub const TARGET: &str = "duende::lifecycle";
+
+/// A lifecycle phase traced as a span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// [`Daemon::init`](crate::daemon::Daemon::init).
+    Init,
+    /// [`Daemon::run`](crate::daemon::Daemon::run), until it returns.
+    Run,
+    /// [`Daemon::shutdown`](crate::daemon::Daemon::shutdown).
+    Shutdown,
+    /// One health, readiness or startup probe.
+    HealthCheck,
+    /// Backoff before a restart.
+    Restart,
+    /// Stop of a daemon by the manager: TERM, then KILL if needed.
+    Stop,
+}
+
+impl Phase {
+    /// Returns the span name.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Init => "init",
+            Self::Run => "run",
+            Self::Shutdown => "shutdown",
+            Self::HealthCheck => "health_check",
+            Self::Restart => "restart",
+            Self::Stop => "stop",
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Returns the span for `phase` of `daemon`, to enter or instrument a
+/// future with.
+///
+/// `platform` is reported for daemons without a platform handle.
+#[must_use]
+pub fn lifecycle_span(phase: Phase, daemon: &ManagedDaemon, platform: Platform) -> Span {
+    // Span names must be literals
+    macro_rules! span {
+        ($name:literal) => {
+            tracing::info_span!(
+                target: TARGET,
+                $name,
+                daemon.id = %daemon.id,
+                daemon.name = %daemon.name,
+                platform = %daemon
+                    .platform_handle
+                    .as_ref()
+                    .map_or(platform, DaemonHandle::platform),
+                restart_count = daemon.restart_count,
+                trace_id = Empty,
+                parent_span_id = Empty,
+                outcome = Empty,
+                error = Empty,
+                exit_reason = Empty,
+                probe = Empty,
+                latency_ms = Empty,
+                attempt = Empty,
+                delay_ms = Empty,
+            )
+        };
+    }
+
+    let span = match phase {
+        Phase::Init => span!("init"),
+        Phase::Run => span!("run"),
+        Phase::Shutdown => span!("shutdown"),
+        Phase::HealthCheck => span!("health_check"),
+        Phase::Restart => span!("restart"),
+        Phase::Stop => span!("stop"),
+    };
+    if let Some(ref trace) = daemon.trace_context {
+        span.record("trace_id", tracing::field::display(trace.trace_id));
+        span.record("parent_span_id", tracing::field::display(trace.span_id));
+    }
+    span
+}
+
+/// Records the outcome of a phase: `ok`, or `error` with the error.
+pub(crate) fn record_result<T>(span: &Span, result: &Result<T>) {
+    match result {
+        Ok(_) => span.record("outcome", "ok"),
+        Err(e) => span
+            .record("outcome", "error")
+            .record("error", tracing::field::display(e)),
+    };
+}
+
+/// Records how a run ended; failed runs are errors.
+pub(crate) fn record_exit(span: &Span, result: &Result<ExitReason>) {
+    record_result(span, result);
+    if let Ok(reason) = result {
+        span.record("exit_reason", tracing::field::debug(reason));
+        if let ExitReason::Error(message) = reason {
+            span.record("outcome", "error")
+                .record("error", message.as_str());
+        }
+    }
+}
+
+/// Records a probe result: `healthy`, or `unhealthy` with the reason.
+pub(crate) fn record_health(span: &Span, health: &HealthStatus) {
+    span.record("latency_ms", health.latency_ms);
+    if health.is_healthy() {
+        span.record("outcome", "healthy");
+    } else {
+        span.record("outcome", "unhealthy")
+            .record("error", health.reason().unwrap_or("unhealthy"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DaemonConfig;
+    use crate::trace::TraceContext;
+    use crate::types::DaemonId;
+
+    #[test]
+    fn test_phase_names() {
+        assert_eq!(Phase::Init.to_string(), "init");
+        assert_eq!(Phase::HealthCheck.name(), "health_check");
+    }
+
+    #[test]
+    fn test_lifecycle_span_without_subscriber() {
+        let mut daemon = ManagedDaemon::new(
+            DaemonId::new(),
+            "api".to_string(),
+            DaemonConfig::new("api", "/bin/true"),
+        );
+        daemon.trace_context = Some(TraceContext::new_root());
+
+        // Disabled without a subscriber; recording is a no-op
+        let span = lifecycle_span(Phase::Run, &daemon, Platform::Native);
+        record_exit(&span, &Ok(ExitReason::Graceful));
+        record_health(&span, &HealthStatus::unhealthy("down", 3));
+        assert!(span.is_disabled());
+    }
+}
@@ -0,0 +1,212 @@
+//! Persistent manager state - survive a restart of the managing process.
+//!
+//! # Toyota Way: Genchi Genbutsu (現地現物)
+//! After a restart the manager does not guess: it reads back what it was
+//! managing and asks each platform whether the daemon is actually running.
+//!
+//! # Poka-Yoke
+//! The state file is replaced atomically (write, fsync, rename), so a crash
+//! mid-write leaves the previous state intact instead of a torn file.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::DaemonHandle;
+use crate::config::DaemonConfig;
+use crate::error::{DaemonError, Result};
+use crate::manager::RestartPolicy;
+use crate::types::{DaemonId, DaemonStatus, HealthStatus};
+
+/// Version of the state file format.
+pub const STATE_VERSION: u32 = 1;
+
+// =============================================================================
+// ManagerState
+// =============================================================================
+
+/// Snapshot of a manager's registry, as written to the state file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagerState {
+    /// State file format version.
+    pub version: u32,
+    /// When the snapshot was taken.
+    pub saved_at: SystemTime,
+    /// Registered daemons.
+    pub daemons: Vec<PersistedDaemon>,
+}
+
+/// A registered daemon, as persisted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedDaemon {
+    /// Daemon ID.
+    pub id: DaemonId,
+    /// Daemon name.
+    pub name: String,
+    /// Status when the snapshot was taken.
+    pub status: DaemonStatus,
+    /// Configuration.
+    pub config: DaemonConfig,
+    /// Restart policy.
+    pub restart_policy: RestartPolicy,
+    /// True if the restart policy follows the configuration.
+    #[serde(default)]
+    pub restart_policy_from_config: bool,
+    /// Number of restarts.
+    pub restart_count: u32,
+    /// Last health check result.
+    pub last_health: Option<HealthStatus>,
+    /// True if running and ready to serve.
+    #[serde(default)]
+    pub ready: bool,
+    /// Platform handle, if the daemon was spawned through an adapter.
+    pub handle: Option<DaemonHandle>,
+    /// True if quarantined by its start limit.
+    pub quarantined: bool,
+}
+
+impl ManagerState {
+    /// Creates a snapshot of the given daemons, taken now.
+    #[must_use]
+    pub fn new(daemons: Vec<PersistedDaemon>) -> Self {
+        Self {
+            version: STATE_VERSION,
+            saved_at: SystemTime::now(),
+            daemons,
+        }
+    }
+
+    /// Loads a state file.
+    ///
+    /// Returns `None` if the file does not exist yet.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed, or was
+    /// written by an incompatible version.
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        let content = match std::fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let state: Self = serde_json::from_slice(&content).map_err(|e| {
+            DaemonError::Serialization(format!("invalid state file {}: {e}", path.display()))
+        })?;
+        if state.version != STATE_VERSION {
+            return Err(DaemonError::Serialization(format!(
+                "state file {} has version {}, expected {}",
+                path.display(),
+                state.version,
+                STATE_VERSION
+            )));
+        }
+
+        Ok(Some(state))
+    }
+
+    /// Writes the state file atomically.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let content = serde_json::to_vec_pretty(self)
+            .map_err(|e| DaemonError::Serialization(e.to_string()))?;
+        write_atomic(path.as_ref(), &content)?;
+        Ok(())
+    }
+}
+
+/// Replaces `path` with `content` so readers see either the old or the
+/// new file, never a partial one.
+fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut tmp = PathBuf::from(path).into_os_string();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    {
+        let mut file = File::create(&tmp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+    }
+    std::fs::rename(&tmp, path)?;
+
+    // Persist the rename itself
+    #[cfg(unix)]
+    {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        File::open(dir)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn persisted(name: &str) -> PersistedDaemon {
+        let id = DaemonId::new();
+        PersistedDaemon {
+            id,
+            name: name.to_string(),
+            status: DaemonStatus::Running,
+            config: DaemonConfig::new(name, "/bin/test"),
+            restart_policy: RestartPolicy::OnFailure,
+            restart_policy_from_config: false,
+            restart_count: 2,
+            last_health: Some(HealthStatus::healthy(3)),
+            ready: true,
+            handle: Some(DaemonHandle::native(id, 4242)),
+            quarantined: false,
+        }
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("duende-state-{}", DaemonId::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("manager.json");
+
+        let state = ManagerState::new(vec![persisted("a"), persisted("b")]);
+        state.save(&path).unwrap();
+        // Overwrite in place
+        state.save(&path).unwrap();
+        assert!(!dir.join("manager.json.tmp").exists());
+
+        let loaded = ManagerState::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.daemons.len(), 2);
+        assert_eq!(loaded.daemons[0].id, state.daemons[0].id);
+        assert_eq!(loaded.daemons[0].restart_count, 2);
+        assert_eq!(loaded.daemons[1].handle.as_ref().unwrap().pid(), Some(4242));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_state_load_missing_file() {
+        let path = std::env::temp_dir().join(format!("duende-missing-{}.json", DaemonId::new()));
+        assert!(ManagerState::load(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_state_load_rejects_other_version() {
+        let path = std::env::temp_dir().join(format!("duende-version-{}.json", DaemonId::new()));
+        let mut state = ManagerState::new(vec![]);
+        state.version = STATE_VERSION + 1;
+        state.save(&path).unwrap();
+
+        assert!(matches!(
+            ManagerState::load(&path),
+            Err(DaemonError::Serialization(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
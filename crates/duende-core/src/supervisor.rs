@@ -0,0 +1,2463 @@
+//! In-process supervisor - drives the daemon lifecycle end to end.
+//!
+//! # Toyota Way: Jidoka (自働化)
+//! Stop on error, then restart according to the restart policy and backoff.
+//!
+//! # Toyota Way: Genchi Genbutsu (現地現物)
+//! Health is observed directly: whether the daemon has started, is alive,
+//! and is ready to serve are separate probes with separate consequences.
+//!
+//! The [`DaemonManager`] only stores lifecycle metadata. The [`Supervisor`]
+//! owns the daemon objects: it runs `init`, `run` and `shutdown` on tokio
+//! tasks and records status, restart count and health in the manager.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{Mutex, oneshot, watch};
+use tokio::task::JoinHandle;
+use tracing::{Instrument, Span};
+
+use crate::activation::Listeners;
+use crate::checks;
+use crate::config::{DaemonConfig, HealthCheckConfig, ProbeConfig, StartupProbeConfig};
+use crate::daemon::{Daemon, DaemonContext, DaemonContextHandle, HealthProbe, is_termination};
+use crate::error::{DaemonError, Result};
+use crate::events::LifecycleEventKind;
+use crate::manager::DaemonManager;
+#[cfg(unix)]
+use crate::notify::NotifyListener;
+use crate::shutdown::SubtaskReport;
+use crate::signals::{SignalBridge, SignalBridgeGuard};
+use crate::spans::{self, Phase};
+use crate::trace::TraceContext;
+use crate::types::{
+    DaemonId, DaemonStatus, ExitReason, FailureReason, HealthStatus, ProbeKind, Signal,
+};
+
+// =============================================================================
+// Supervisor
+// =============================================================================
+
+/// How often `start_all` re-checks a dependency it is waiting on.
+const DEPENDENCY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long an abandoned `run()` has to give the daemon back, which it does
+/// at its next await.
+const RUN_CANCEL_GRACE: Duration = Duration::from_secs(1);
+
+/// Names of leaked subtasks, per daemon.
+type LeakedTasks = Mutex<HashMap<DaemonId, Vec<String>>>;
+
+/// A daemon lifecycle task owned by the supervisor.
+struct SupervisedTask {
+    /// Stop request flag (true = stop, do not restart).
+    stop_tx: watch::Sender<bool>,
+    /// Lifecycle task.
+    join: JoinHandle<Result<ExitReason>>,
+}
+
+/// A [`SignalBridge`] installed once and shared by every run.
+struct SignalRelay {
+    bridge: SignalBridge,
+    /// Installed on the first start; dropping it stops forwarding.
+    guard: Mutex<Option<SignalBridgeGuard>>,
+    /// Where signals go, per supervised daemon.
+    targets: Arc<std::sync::Mutex<HashMap<DaemonId, SignalTarget>>>,
+}
+
+/// Signal target of one supervised daemon.
+struct SignalTarget {
+    /// Stops the daemon between runs.
+    stop_tx: watch::Sender<bool>,
+    /// Context of the current run, if one is running.
+    run: Option<DaemonContextHandle>,
+}
+
+impl SignalRelay {
+    fn new(bridge: SignalBridge) -> Self {
+        Self {
+            bridge,
+            guard: Mutex::new(None),
+            targets: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Installs the bridge unless it already is.
+    async fn install(&self) -> Result<()> {
+        let mut guard = self.guard.lock().await;
+        if guard.is_none() {
+            let targets = Arc::clone(&self.targets);
+            *guard = Some(self.bridge.install_with(move |signal| {
+                let runs = route(&targets, signal);
+                async move {
+                    for run in runs {
+                        // The run may have ended since; nothing to deliver
+                        let _ = run.send_signal(signal).await;
+                    }
+                    true
+                }
+            })?);
+        }
+        Ok(())
+    }
+
+    /// Starts relaying signals for a daemon.
+    fn attach(&self, id: DaemonId, stop_tx: watch::Sender<bool>) {
+        if let Ok(mut targets) = self.targets.lock() {
+            targets.insert(id, SignalTarget { stop_tx, run: None });
+        }
+    }
+
+    /// Sets or clears the current run of a daemon.
+    fn set_run(&self, id: DaemonId, run: Option<DaemonContextHandle>) {
+        if let Ok(mut targets) = self.targets.lock()
+            && let Some(target) = targets.get_mut(&id)
+        {
+            target.run = run;
+        }
+    }
+
+    /// Stops relaying signals for a daemon.
+    fn detach(&self, id: DaemonId) {
+        if let Ok(mut targets) = self.targets.lock() {
+            targets.remove(&id);
+        }
+    }
+}
+
+/// Returns the runs to deliver `signal` to; daemons between runs are
+/// stopped by a termination signal instead.
+fn route(
+    targets: &std::sync::Mutex<HashMap<DaemonId, SignalTarget>>,
+    signal: Signal,
+) -> Vec<DaemonContextHandle> {
+    let Ok(targets) = targets.lock() else {
+        return Vec::new();
+    };
+    let mut runs = Vec::new();
+    for (id, target) in targets.iter() {
+        match target.run {
+            Some(ref run) => runs.push(run.clone()),
+            None if is_termination(signal) => {
+                tracing::info!(id = %id, signal = ?signal, "stopping daemon between runs");
+                let _ = target.stop_tx.send(true);
+            }
+            None => tracing::debug!(id = %id, signal = ?signal, "no run; signal discarded"),
+        }
+    }
+    runs
+}
+
+/// In-process daemon supervisor.
+///
+/// Takes daemons registered with a [`DaemonManager`] and drives their
+/// lifecycle on tokio tasks:
+///
+/// 1. `init` with the registered configuration
+/// 2. `run` with a fresh [`DaemonContext`], probed via
+///    [`Daemon::health_probe`] and the configured `health_check.checks`:
+///    the startup probe within its grace period,
+///    then liveness and readiness on their own intervals. Readiness is
+///    only recorded; a failed startup or liveness probe stops the daemon
+///    (`Failed(HealthCheckFailed)`) so it is restarted. With a `watchdog`
+///    deadline, a `run` that stops calling [`DaemonContext::heartbeat`] is
+///    aborted as hung (`Failed(HealthCheckTimeout)`). `run` has a task of
+///    its own, so this holds even if it blocks its thread (given a
+///    multi-threaded runtime); such a run cannot be cancelled, keeps the
+///    daemon object and is not restarted
+/// 3. `shutdown` within the configured `shutdown_timeout`, as reloaded
+///    during the run, while subtasks spawned via [`DaemonContext::spawn`]
+///    are cancelled and joined within the same deadline; leaks are
+///    reported by [`leaked_tasks`](Self::leaked_tasks)
+/// 4. restart according to the registered
+///    [`RestartPolicy`](crate::manager::RestartPolicy), re-read
+///    after every run so a reload of a policy built by
+///    [`register_configured`](DaemonManager::register_configured) applies,
+///    until the configured start limit quarantines the daemon
+///
+/// A daemon only starts while its `requires` dependencies are running;
+/// [`start_all`](Self::start_all) starts a batch in dependency order.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use std::sync::Arc;
+/// use duende_core::{DaemonManager, RestartPolicy, Supervisor};
+///
+/// let manager = Arc::new(DaemonManager::new());
+/// manager.register(Box::new(MyDaemon::new(id)), config, RestartPolicy::OnFailure).await?;
+///
+/// let supervisor = Supervisor::new(Arc::clone(&manager));
+/// supervisor.start(Box::new(MyDaemon::new(id))).await?;
+/// // ...
+/// supervisor.stop(id).await?;
+/// let exit = supervisor.wait(id).await?;
+/// ```
+pub struct Supervisor {
+    /// Manager holding daemon metadata.
+    manager: Arc<DaemonManager>,
+    /// Supervised daemons.
+    tasks: Mutex<HashMap<DaemonId, SupervisedTask>>,
+    /// OS signal bridge shared by all runs, if any.
+    signals: Option<Arc<SignalRelay>>,
+    /// Subtasks that leaked past the shutdown deadline, per daemon.
+    leaked: Arc<LeakedTasks>,
+}
+
+impl Supervisor {
+    /// Creates a supervisor backed by the given manager.
+    #[must_use]
+    pub fn new(manager: Arc<DaemonManager>) -> Self {
+        Self {
+            manager,
+            tasks: Mutex::new(HashMap::new()),
+            signals: None,
+            leaked: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Forwards OS signals to supervised daemons through `bridge`.
+    ///
+    /// The bridge is installed on the first [`start`](Self::start) and
+    /// kept until the supervisor is dropped. A signal delivered to the
+    /// supervisor process reaches the current run of each daemon. Between
+    /// runs, e.g. during a restart backoff, TERM, INT and QUIT stop the
+    /// daemon as [`stop`](Self::stop) does; other signals are discarded.
+    #[must_use]
+    pub fn with_signal_bridge(mut self, bridge: SignalBridge) -> Self {
+        self.signals = Some(Arc::new(SignalRelay::new(bridge)));
+        self
+    }
+
+    /// Returns the manager backing this supervisor.
+    #[must_use]
+    pub const fn manager(&self) -> &Arc<DaemonManager> {
+        &self.manager
+    }
+
+    /// Starts supervising a registered daemon.
+    ///
+    /// The daemon must have been registered with the manager; its
+    /// configuration and restart policy are taken from the registration,
+    /// anew for every run.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not registered,
+    /// `DaemonError::State` if it is already being supervised, or
+    /// `DaemonError::Signal` if the signal bridge cannot be installed.
+    pub async fn start(&self, daemon: Box<dyn Daemon>) -> Result<DaemonId> {
+        let id = daemon.id();
+        // Fail fast on an unregistered daemon
+        self.manager.get_restart_policy(id).await?;
+
+        let mut tasks = self.tasks.lock().await;
+        if tasks.get(&id).is_some_and(|task| !task.join.is_finished()) {
+            return Err(DaemonError::State(format!(
+                "daemon {} is already supervised",
+                id
+            )));
+        }
+
+        let (stop_tx, stop_rx) = watch::channel(false);
+        if let Some(ref signals) = self.signals {
+            signals.install().await?;
+            signals.attach(id, stop_tx.clone());
+        }
+        let manager = Arc::clone(&self.manager);
+        let signals = self.signals.clone();
+        let leaked = Arc::clone(&self.leaked);
+        let join = tokio::spawn(async move {
+            let result = supervise(manager, daemon, signals.as_deref(), leaked, stop_rx).await;
+            if let Some(signals) = signals {
+                signals.detach(id);
+            }
+            result
+        });
+
+        tasks.insert(id, SupervisedTask { stop_tx, join });
+        tracing::info!(id = %id, "supervising daemon");
+
+        Ok(id)
+    }
+
+    /// Starts supervising several registered daemons in dependency order.
+    ///
+    /// Each daemon is started once every daemon it `requires`, `wants` or
+    /// starts `after` that is part of this batch has left `Starting`. A
+    /// daemon whose required dependency did not reach `Running` is marked
+    /// `Failed(DependencyFailed)` and not started.
+    ///
+    /// Returns the IDs of the daemons that were started, in start order.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::Dependency` if the dependencies form a cycle,
+    /// `DaemonError::NotFound` if a daemon is not registered, or the first
+    /// error from [`start`](Self::start). Daemons started before the error
+    /// stay supervised.
+    pub async fn start_all(&self, daemons: Vec<Box<dyn Daemon>>) -> Result<Vec<DaemonId>> {
+        let mut pending: HashMap<DaemonId, Box<dyn Daemon>> =
+            daemons.into_iter().map(|d| (d.id(), d)).collect();
+        let batch: Vec<DaemonId> = pending.keys().copied().collect();
+        let order: Vec<DaemonId> = self
+            .manager
+            .start_order()
+            .await?
+            .into_iter()
+            .filter(|id| pending.contains_key(id))
+            .collect();
+
+        if let Some(id) = batch.iter().find(|id| !order.contains(id)) {
+            return Err(DaemonError::NotFound(id.to_string()));
+        }
+
+        let mut started = Vec::with_capacity(order.len());
+        for id in order {
+            let Some(daemon) = pending.remove(&id) else {
+                continue;
+            };
+
+            let config = self.manager.get_config(id).await?;
+            self.wait_for_dependencies(&config, &batch).await;
+
+            let unmet = self.manager.unmet_requirements(id).await?;
+            if !unmet.is_empty() {
+                tracing::warn!(id = %id, unmet = ?unmet, "not starting: required dependencies not running");
+                // May already be failed by propagation
+                set_status(
+                    &self.manager,
+                    id,
+                    DaemonStatus::Failed(FailureReason::DependencyFailed),
+                )
+                .await?;
+                continue;
+            }
+
+            started.push(self.start(daemon).await?);
+        }
+
+        Ok(started)
+    }
+
+    /// Waits until every dependency of `config` in `batch` has settled.
+    ///
+    /// Settled means `Running`, `Paused`, or no longer supervised.
+    async fn wait_for_dependencies(&self, config: &DaemonConfig, batch: &[DaemonId]) {
+        for name in config.dependencies() {
+            for dep in self.manager.find_by_name(name).await {
+                if !batch.contains(&dep) {
+                    continue;
+                }
+
+                loop {
+                    let status = self.manager.status(dep).await;
+                    let settled = matches!(
+                        status,
+                        Ok(DaemonStatus::Running | DaemonStatus::Paused) | Err(_)
+                    ) || !self.is_supervised(dep).await;
+                    if settled {
+                        break;
+                    }
+
+                    // Poll as a fallback: a change may land before we listen
+                    let _ = tokio::time::timeout(
+                        DEPENDENCY_POLL_INTERVAL,
+                        self.manager.status_changed(),
+                    )
+                    .await;
+                }
+            }
+        }
+    }
+
+    /// Requests a graceful stop of a supervised daemon.
+    ///
+    /// Sends TERM to the running daemon and cancels any pending restart.
+    /// Use [`wait`](Self::wait) to wait for the lifecycle to finish.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not supervised.
+    pub async fn stop(&self, id: DaemonId) -> Result<()> {
+        let tasks = self.tasks.lock().await;
+        let task = tasks
+            .get(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        // Receiver is gone only if the lifecycle already finished
+        let _ = task.stop_tx.send(true);
+        tracing::debug!(id = %id, "stop requested");
+
+        Ok(())
+    }
+
+    /// Waits for a supervised daemon's lifecycle to finish.
+    ///
+    /// Returns the final exit reason once the daemon has stopped and will
+    /// not be restarted.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::NotFound` if the daemon is not supervised, or
+    /// the error of the final run if it failed.
+    pub async fn wait(&self, id: DaemonId) -> Result<ExitReason> {
+        let task = self
+            .tasks
+            .lock()
+            .await
+            .remove(&id)
+            .ok_or_else(|| DaemonError::NotFound(id.to_string()))?;
+
+        task.join.await.map_err(|e| {
+            DaemonError::Internal(format!("supervisor task for {} failed: {}", id, e))
+        })?
+    }
+
+    /// Stops all supervised daemons and waits for them to finish.
+    ///
+    /// # Errors
+    /// Returns the first lifecycle error encountered; all daemons are
+    /// still waited for.
+    pub async fn shutdown(&self) -> Result<()> {
+        let tasks: Vec<_> = self.tasks.lock().await.drain().collect();
+
+        for (_, task) in &tasks {
+            let _ = task.stop_tx.send(true);
+        }
+
+        let mut first_error = None;
+        for (id, task) in tasks {
+            let result = task.join.await.map_err(|e| {
+                DaemonError::Internal(format!("supervisor task for {} failed: {}", id, e))
+            });
+            if let Err(e) = result.and_then(|r| r) {
+                tracing::warn!(id = %id, error = %e, "daemon finished with error");
+                first_error.get_or_insert(e);
+            }
+        }
+
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Returns the names of subtasks that leaked past a shutdown deadline.
+    ///
+    /// Leaked subtasks were aborted; the list accumulates across restarts.
+    pub async fn leaked_tasks(&self, id: DaemonId) -> Vec<String> {
+        self.leaked
+            .lock()
+            .await
+            .get(&id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the daemon's lifecycle is still being driven.
+    pub async fn is_supervised(&self, id: DaemonId) -> bool {
+        self.tasks
+            .lock()
+            .await
+            .get(&id)
+            .is_some_and(|task| !task.join.is_finished())
+    }
+}
+
+// =============================================================================
+// Lifecycle
+// =============================================================================
+
+/// Drives one daemon through init/run/shutdown until it should not restart.
+async fn supervise(
+    manager: Arc<DaemonManager>,
+    daemon: Box<dyn Daemon>,
+    signals: Option<&SignalRelay>,
+    leaked: Arc<LeakedTasks>,
+    mut stop_rx: watch::Receiver<bool>,
+) -> Result<ExitReason> {
+    let id = daemon.id();
+    // Empty once a run that blocks its thread holds on to the daemon
+    let mut daemon = Some(daemon);
+    // Held across restarts so clients queue instead of being refused
+    let mut listeners = Listeners::new();
+
+    loop {
+        // Re-read every cycle: a reload may have changed it since the last run
+        let config = manager.get_config(id).await?;
+        let outcome = run_once(
+            &manager,
+            &mut daemon,
+            &config,
+            &mut listeners,
+            signals,
+            &leaked,
+            &mut stop_rx,
+        )
+        .await;
+        set_status(&manager, id, final_status(&outcome)).await?;
+
+        let exit_reason = match outcome {
+            Ok(ref reason) => reason.clone(),
+            Err(ref e) => ExitReason::Error(e.to_string()),
+        };
+        let exited = LifecycleEventKind::Exited {
+            reason: exit_reason.clone(),
+            status: manager.status(id).await?,
+        };
+        manager.publish(id, exited).await?;
+        let restart_count = manager.get_restart_count(id).await?;
+        // Also re-read: it follows a reloaded `restart` and `backoff`
+        let policy = manager.get_restart_policy(id).await?;
+
+        if daemon.is_none() {
+            tracing::error!(id = %id, "hung run() still holds the daemon; not restarting");
+            return outcome;
+        }
+        // Quarantined by the start limit: only an operator reset restarts it
+        let quarantined = matches!(outcome, Err(DaemonError::Quarantined(_)));
+        if *stop_rx.borrow() || quarantined || !policy.should_restart(&exit_reason, restart_count) {
+            tracing::info!(id = %id, exit = ?exit_reason, "daemon finished");
+            return outcome;
+        }
+
+        let delay = policy.restart_delay(restart_count);
+        tracing::warn!(
+            id = %id,
+            exit = ?exit_reason,
+            restart_count = restart_count,
+            delay_ms = delay.as_millis() as u64,
+            "restarting daemon after backoff"
+        );
+        let scheduled = LifecycleEventKind::RestartScheduled {
+            attempt: restart_count.saturating_add(1),
+            delay,
+        };
+        manager.publish(id, scheduled).await?;
+
+        let span = manager.lifecycle_span(id, Phase::Restart).await?;
+        span.record("attempt", restart_count.saturating_add(1))
+            .record("delay_ms", delay.as_millis() as u64);
+        let stopped = async {
+            tokio::select! {
+                () = tokio::time::sleep(delay) => false,
+                () = stop_requested(&mut stop_rx) => true,
+            }
+        }
+        .instrument(span.clone())
+        .await;
+        if stopped {
+            span.record("outcome", "cancelled");
+            return outcome;
+        }
+        span.record("outcome", "ok");
+
+        manager.increment_restart_count(id).await?;
+    }
+}
+
+/// Runs a single init → run → shutdown cycle.
+///
+/// `slot` is left empty if `run()` blocked its thread past the watchdog
+/// or a KILL and never gave the daemon back.
+async fn run_once(
+    manager: &Arc<DaemonManager>,
+    slot: &mut Option<Box<dyn Daemon>>,
+    config: &DaemonConfig,
+    listeners: &mut Listeners,
+    signals: Option<&SignalRelay>,
+    leaked: &LeakedTasks,
+    stop_rx: &mut watch::Receiver<bool>,
+) -> Result<ExitReason> {
+    let Some(daemon) = slot.as_deref_mut() else {
+        return Err(DaemonError::Internal("daemon held by a hung run".into()));
+    };
+    let id = daemon.id();
+
+    // Each run is a span, below the caller's TRACEPARENT if there is one
+    let trace = TraceContext::inherited();
+    manager.set_trace_context(id, trace.clone()).await?;
+
+    set_status(manager, id, DaemonStatus::Starting).await?;
+    listeners.ensure_bound(&config.sockets)?;
+
+    // Poka-Yoke: never start on top of a missing required dependency
+    let unmet = manager.unmet_requirements(id).await?;
+    if !unmet.is_empty() {
+        return Err(DaemonError::dependency(format!(
+            "required dependencies not running: {}",
+            unmet.join(", ")
+        )));
+    }
+
+    let span = manager.lifecycle_span(id, Phase::Init).await?;
+    let init = daemon.init(config).instrument(span.clone()).await;
+    spans::record_result(&span, &init);
+    init?;
+
+    let (mut ctx, handle) = DaemonContext::new(config.clone());
+    if !config.sockets.is_empty() {
+        ctx.set_listeners(listeners.try_clone()?);
+    }
+    ctx.set_trace_context(trace);
+    let notify_task = spawn_notify_listener(Arc::clone(manager), id, &mut ctx, config.notify)?;
+    manager.set_context_handle(id, handle.clone()).await?;
+    let metrics = daemon.metrics().clone();
+    manager.set_metrics(id, metrics.clone()).await?;
+    manager
+        .set_reload_handler(id, daemon.reload_handler())
+        .await?;
+
+    // Forward OS signals to this run while it can receive them
+    if let Some(signals) = signals {
+        signals.set_run(id, Some(handle.clone()));
+    }
+    let reload_task = signals
+        .filter(|s| !s.bridge.is_masked(Signal::Hup))
+        .and_then(|_| spawn_hup_reload(Arc::clone(manager), id));
+
+    // Initial checks while the daemon is not yet borrowed by run()
+    let probe = daemon.health_probe();
+    let health_config = &config.health_check;
+    let health = traced_check(manager, id, ProbeKind::Liveness, async {
+        let health = daemon.health_check().await;
+        with_checks(
+            manager,
+            id,
+            ProbeKind::Liveness,
+            health_config.timeout,
+            Some(health),
+        )
+        .await
+    })
+    .await;
+    manager.update_health(id, health).await?;
+    // With a startup probe, the daemon is not ready until it passes
+    if !startup_probed(health_config, probe.is_some()) {
+        let readiness = traced_check(manager, id, ProbeKind::Readiness, async {
+            let health = daemon.readiness_check().await;
+            let timeout = health_config.readiness.timeout;
+            with_checks(manager, id, ProbeKind::Readiness, timeout, Some(health)).await
+        })
+        .await;
+        manager.update_readiness(id, readiness).await?;
+    }
+    // A required dependency may have failed during init()
+    let runnable = if config.notify {
+        // Stays Starting until the daemon sends READY=1
+        !manager.status(id).await?.is_terminal()
+    } else {
+        set_status(manager, id, DaemonStatus::Running).await?
+    };
+
+    let mut probe_task = spawn_probes(Arc::clone(manager), id, probe, health_config);
+
+    let (result, ctx) = if runnable {
+        let span = manager.lifecycle_span(id, Phase::Run).await?;
+        let Some(daemon) = slot.take() else {
+            return Err(DaemonError::Internal("daemon held by a hung run".into()));
+        };
+        // On its own task: a run() that blocks its thread cannot keep the
+        // watchdog, probes or stop requests below from firing
+        let mut run = RunTask::spawn(daemon, ctx, span.clone());
+
+        // KILL cannot be handled: run() is abandoned. So is a hung run()
+        // that missed its watchdog deadline.
+        let watchdog = config.health_check.watchdog;
+        let result = tokio::select! {
+            result = run.result() => result,
+            () = handle.killed() => {
+                run.abandon().await;
+                Ok(ExitReason::Signal(Signal::Kill))
+            }
+            () = handle.heartbeat_missed(watchdog) => {
+                run.abandon().await;
+                Err(DaemonError::WatchdogTimeout(watchdog))
+            }
+            error = probe_failed(probe_task.as_mut()) => {
+                tracing::error!(id = %id, error = %error, "health probe failed; stopping daemon");
+                // Alive but failing: give run() the chance to stop cleanly
+                let _ = handle.shutdown().await;
+                let timeout = shutdown_timeout(manager, id, config).await;
+                tokio::select! {
+                    result = tokio::time::timeout(timeout, run.result()) => {
+                        if result.is_err() {
+                            run.abandon().await;
+                        }
+                    }
+                    () = handle.killed() => run.abandon().await,
+                }
+                Err(error)
+            }
+            () = stop_requested(stop_rx) => {
+                // Daemon may already have exited; run() then completes anyway
+                let _ = handle.shutdown().await;
+                tokio::select! {
+                    result = run.result() => result,
+                    () = handle.killed() => {
+                        run.abandon().await;
+                        Ok(ExitReason::Signal(Signal::Kill))
+                    }
+                }
+            }
+        };
+        spans::record_exit(&span, &result);
+
+        let ctx = run.returned.map(|(daemon, ctx)| {
+            *slot = Some(daemon);
+            ctx
+        });
+        if ctx.is_none() {
+            tracing::error!(id = %id, "run() blocks its thread; daemon abandoned");
+        }
+        (result, ctx)
+    } else {
+        let result = Err(DaemonError::dependency(
+            "required dependency failed during init",
+        ));
+        (result, Some(ctx))
+    };
+
+    if let Some(task) = probe_task {
+        task.abort();
+    }
+    for task in [reload_task, notify_task].into_iter().flatten() {
+        task.abort();
+    }
+    if let Some(signals) = signals {
+        signals.set_run(id, None);
+    }
+    manager.set_reload_handler(id, None).await?;
+
+    set_status(manager, id, DaemonStatus::Stopping).await?;
+
+    let hung = matches!(result, Err(DaemonError::WatchdogTimeout(_)));
+    if hung {
+        tracing::error!(id = %id, "watchdog deadline missed; aborting daemon");
+        metrics.record_watchdog_timeout();
+    }
+
+    // Subtasks are cancelled and joined while the daemon shuts down
+    let timeout = shutdown_timeout(manager, id, config).await;
+    let (shutdown, subtasks) = match (ctx, slot.as_deref_mut()) {
+        (Some(mut ctx), Some(daemon)) if !handle.is_killed() && !hung => {
+            let span = manager.lifecycle_span(id, Phase::Shutdown).await?;
+            tokio::join!(
+                async {
+                    let result = tokio::select! {
+                        result = tokio::time::timeout(timeout, daemon.shutdown(timeout)) => {
+                            result.unwrap_or(Err(DaemonError::ShutdownTimeout(timeout)))
+                        }
+                        () = handle.killed() => Ok(()),
+                    };
+                    spans::record_result(&span, &result);
+                    result
+                }
+                .instrument(span.clone()),
+                ctx.join_subtasks(timeout),
+            )
+        }
+        // No shutdown hook after KILL or a hang; dropping the context
+        // aborts subtasks
+        _ => (Ok(()), SubtaskReport::default()),
+    };
+    report_subtasks(id, subtasks, leaked).await;
+
+    // A run failure is the root cause; report it over a shutdown failure
+    let exit_reason = result?;
+    shutdown?;
+
+    Ok(exit_reason)
+}
+
+/// What a run task gives back: the daemon and context, and run()'s result
+/// unless it was cancelled.
+type RunOutput = (Box<dyn Daemon>, DaemonContext, Option<Result<ExitReason>>);
+
+/// A `run()` on its own task, so one that blocks its thread cannot stall
+/// the supervisor.
+struct RunTask {
+    task: JoinHandle<RunOutput>,
+    /// Cancels run() at its next await.
+    cancel: Option<oneshot::Sender<()>>,
+    /// Daemon and context, once the task gave them back.
+    returned: Option<(Box<dyn Daemon>, DaemonContext)>,
+}
+
+impl RunTask {
+    fn spawn(mut daemon: Box<dyn Daemon>, mut ctx: DaemonContext, span: Span) -> Self {
+        let (cancel, cancelled) = oneshot::channel::<()>();
+        let task = tokio::spawn(
+            async move {
+                let result = tokio::select! {
+                    result = daemon.run(&mut ctx) => Some(result),
+                    _ = cancelled => None,
+                };
+                (daemon, ctx, result)
+            }
+            .instrument(span),
+        );
+        Self {
+            task,
+            cancel: Some(cancel),
+            returned: None,
+        }
+    }
+
+    /// Waits for run() to return. Cancel-safe; not to be awaited again
+    /// once it completed.
+    async fn result(&mut self) -> Result<ExitReason> {
+        match (&mut self.task).await {
+            Ok((daemon, ctx, result)) => {
+                self.returned = Some((daemon, ctx));
+                result.unwrap_or_else(|| Err(DaemonError::Internal("run() cancelled".into())))
+            }
+            Err(e) => Err(DaemonError::Internal(format!("run() failed: {}", e))),
+        }
+    }
+
+    /// Cancels run() and waits up to [`RUN_CANCEL_GRACE`] for the daemon.
+    ///
+    /// A run() that blocks its thread never gets there; it is left behind
+    /// with the daemon.
+    async fn abandon(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            let _ = cancel.send(());
+        }
+        if self.returned.is_none()
+            && let Ok(Ok((daemon, ctx, _))) =
+                tokio::time::timeout(RUN_CANCEL_GRACE, &mut self.task).await
+        {
+            self.returned = Some((daemon, ctx));
+        }
+    }
+}
+
+/// Returns the stored `shutdown_timeout`, which a reload may have changed
+/// since `config` was read.
+async fn shutdown_timeout(
+    manager: &DaemonManager,
+    id: DaemonId,
+    config: &DaemonConfig,
+) -> Duration {
+    manager
+        .get_config(id)
+        .await
+        .map_or(config.shutdown_timeout, |current| current.shutdown_timeout)
+}
+
+/// Logs a subtask report and records leaked subtasks.
+async fn report_subtasks(id: DaemonId, report: SubtaskReport, leaked: &LeakedTasks) {
+    if !report.panicked.is_empty() {
+        tracing::error!(id = %id, panicked = ?report.panicked, "subtasks panicked");
+    }
+    if report.leaked.is_empty() {
+        return;
+    }
+
+    tracing::warn!(
+        id = %id,
+        leaked = ?report.leaked,
+        "subtasks leaked past shutdown deadline"
+    );
+    leaked
+        .lock()
+        .await
+        .entry(id)
+        .or_default()
+        .extend(report.leaked);
+}
+
+/// Probes a running daemon and records the results in the manager.
+///
+/// Each probe runs the daemon's in-process [`HealthProbe`], if any, and the
+/// configured external checks of its kind; a probe with neither is not
+/// run. The startup probe runs first, if configured; liveness and
+/// readiness are only probed once it passed. A readiness failure is only recorded once
+/// readiness failed more than its retries allow. The task completes with
+/// the error once the startup probe misses its grace period or liveness
+/// fails more than its retries allow, and never while the daemon is live.
+fn spawn_probes(
+    manager: Arc<DaemonManager>,
+    id: DaemonId,
+    probe: Option<Arc<dyn HealthProbe>>,
+    config: &HealthCheckConfig,
+) -> Option<JoinHandle<DaemonError>> {
+    let startup = startup_probed(config, probe.is_some()).then_some(config.startup);
+    let (mut liveness, mut readiness) = periodic_probes(config, probe.is_some());
+    if !config.enabled
+        || (startup.is_none() && liveness.interval.is_zero() && readiness.interval.is_zero())
+    {
+        return None;
+    }
+
+    Some(tokio::spawn(async move {
+        let probe = probe.as_deref();
+        if let Some(startup) = startup {
+            if let Err(e) = await_startup(&manager, id, probe, &startup).await {
+                return e;
+            }
+            tracing::debug!(id = %id, "startup probe passed");
+            // Not ready until now: probe readiness right away
+            let health = check(&manager, id, probe, ProbeKind::Readiness, readiness.timeout).await;
+            if manager.update_readiness(id, health).await.is_err() {
+                return std::future::pending().await;
+            }
+        }
+
+        // The initial checks were already recorded before run()
+        let now = tokio::time::Instant::now();
+        let mut liveness_at = now + liveness.interval;
+        let mut readiness_at = now + readiness.interval;
+        let mut failures: u32 = 0;
+        let mut not_ready: u32 = 0;
+
+        loop {
+            tokio::select! {
+                () = tokio::time::sleep_until(liveness_at), if !liveness.interval.is_zero() => {
+                    let health = check(&manager, id, probe, ProbeKind::Liveness, liveness.timeout).await;
+                    let reason = health.reason().unwrap_or("unhealthy").to_string();
+                    if health.is_healthy() {
+                        failures = 0;
+                    } else {
+                        failures = failures.saturating_add(1);
+                        tracing::warn!(id = %id, health = ?health, "daemon unhealthy");
+                    }
+
+                    if manager.update_health(id, health).await.is_err() {
+                        // Daemon was unregistered
+                        break;
+                    }
+                    if failures > liveness.retries {
+                        return DaemonError::health_check(format!(
+                            "liveness probe failed {failures} times in a row: {reason}"
+                        ));
+                    }
+                    liveness_at = tokio::time::Instant::now() + liveness.interval;
+                }
+                () = tokio::time::sleep_until(readiness_at), if !readiness.interval.is_zero() => {
+                    let health = check(&manager, id, probe, ProbeKind::Readiness, readiness.timeout).await;
+                    if health.is_healthy() {
+                        not_ready = 0;
+                    } else {
+                        not_ready = not_ready.saturating_add(1);
+                        tracing::debug!(id = %id, health = ?health, "daemon not ready");
+                    }
+
+                    // Failures within the retries leave readiness as it was
+                    let record = health.is_healthy() || not_ready > readiness.retries;
+                    if record && manager.update_readiness(id, health).await.is_err() {
+                        break;
+                    }
+                    readiness_at = tokio::time::Instant::now() + readiness.interval;
+                }
+                else => break,
+            }
+
+            // Pick up probe changes applied by a live reload
+            if let Ok(config) = manager.get_config(id).await {
+                (liveness, readiness) = periodic_probes(&config.health_check, probe.is_some());
+            }
+        }
+
+        std::future::pending().await
+    }))
+}
+
+/// Returns true if the daemon has a startup probe to pass.
+fn startup_probed(config: &HealthCheckConfig, in_process: bool) -> bool {
+    config.enabled
+        && config.startup.is_enabled()
+        && (in_process || has_checks(config, ProbeKind::Startup))
+}
+
+/// Returns the liveness and readiness probes; a probe with nothing to
+/// check gets a zero interval.
+fn periodic_probes(config: &HealthCheckConfig, in_process: bool) -> (ProbeConfig, ProbeConfig) {
+    let mut liveness = config.liveness();
+    let mut readiness = config.readiness;
+    for (probe, kind) in [
+        (&mut liveness, ProbeKind::Liveness),
+        (&mut readiness, ProbeKind::Readiness),
+    ] {
+        if !in_process && !has_checks(config, kind) {
+            probe.interval = Duration::ZERO;
+        }
+    }
+    (liveness, readiness)
+}
+
+/// Returns true if an external check belongs to `kind`.
+fn has_checks(config: &HealthCheckConfig, kind: ProbeKind) -> bool {
+    config.checks.iter().any(|check| check.probe == kind)
+}
+
+/// Waits for the startup probe to pass within its grace period.
+async fn await_startup(
+    manager: &DaemonManager,
+    id: DaemonId,
+    probe: Option<&dyn HealthProbe>,
+    config: &StartupProbeConfig,
+) -> Result<()> {
+    let deadline = Instant::now() + config.grace_period;
+    loop {
+        let health = check(manager, id, probe, ProbeKind::Startup, config.timeout).await;
+        if health.is_healthy() {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(DaemonError::health_check(format!(
+                "startup probe did not pass within {:?}: {}",
+                config.grace_period,
+                health.reason().unwrap_or("not started")
+            )));
+        }
+        tokio::time::sleep(config.interval.min(deadline - now)).await;
+    }
+}
+
+/// Runs one probe; a probe that overruns its timeout failed.
+async fn check(
+    manager: &DaemonManager,
+    id: DaemonId,
+    probe: Option<&dyn HealthProbe>,
+    kind: ProbeKind,
+    timeout: Duration,
+) -> HealthStatus {
+    let started = Instant::now();
+    let check = async {
+        let health = match probe {
+            Some(probe) => Some(
+                match tokio::time::timeout(timeout, probe.check(kind)).await {
+                    Ok(health) => health,
+                    Err(_) => HealthStatus::unhealthy(
+                        format!("{kind} probe timed out after {timeout:?}"),
+                        started.elapsed().as_millis() as u64,
+                    ),
+                },
+            ),
+            None => None,
+        };
+        with_checks(manager, id, kind, timeout, health).await
+    };
+    traced_check(manager, id, kind, check).await
+}
+
+/// Runs the configured external checks of `kind` and merges them with
+/// the in-process result, if any: healthy only if both are.
+///
+/// Each check gets one attempt within `timeout`; the caller applies the
+/// probe's retries over consecutive probes.
+async fn with_checks(
+    manager: &DaemonManager,
+    id: DaemonId,
+    kind: ProbeKind,
+    timeout: Duration,
+    health: Option<HealthStatus>,
+) -> HealthStatus {
+    // Re-read: a live reload may have changed the checks
+    let external = match manager.get_config(id).await {
+        Ok(config) if config.health_check.enabled => {
+            checks::run_checks(&config.health_check.checks, kind, timeout, 0).await
+        }
+        _ => None,
+    };
+    match (health, external) {
+        (Some(mut health), Some(external)) => {
+            health.healthy &= external.healthy;
+            health.checks.extend(external.checks);
+            health.latency_ms = health.latency_ms.saturating_add(external.latency_ms);
+            health.last_check_epoch_ms = external.last_check_epoch_ms;
+            health
+        }
+        (Some(health), None) | (None, Some(health)) => health,
+        // Checks removed by a reload: nothing failed
+        (None, None) => HealthStatus::healthy(0),
+    }
+}
+
+/// Runs a health check inside a lifecycle span of the daemon.
+async fn traced_check(
+    manager: &DaemonManager,
+    id: DaemonId,
+    kind: ProbeKind,
+    check: impl Future<Output = HealthStatus>,
+) -> HealthStatus {
+    // An unregistered daemon is noticed by the caller
+    let span = manager
+        .lifecycle_span(id, Phase::HealthCheck)
+        .await
+        .unwrap_or_else(|_| Span::none());
+    span.record("probe", tracing::field::display(kind));
+    let health = check.instrument(span.clone()).await;
+    spans::record_health(&span, &health);
+    health
+}
+
+/// Completes with the error once the probe task reports a failure.
+///
+/// Never completes without a probe task.
+async fn probe_failed(task: Option<&mut JoinHandle<DaemonError>>) -> DaemonError {
+    match task {
+        Some(task) => match task.await {
+            Ok(error) => error,
+            // Aborted: nothing more to report
+            Err(_) => std::future::pending().await,
+        },
+        None => std::future::pending().await,
+    }
+}
+
+/// Reloads the daemon's configuration whenever the process receives HUP.
+#[cfg(unix)]
+fn spawn_hup_reload(manager: Arc<DaemonManager>, id: DaemonId) -> Option<JoinHandle<()>> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(stream) => stream,
+        Err(e) => {
+            tracing::warn!(id = %id, error = %e, "cannot listen for HUP; reload disabled");
+            return None;
+        }
+    };
+
+    Some(tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match manager.reload(id).await {
+                Ok(diff) => tracing::info!(
+                    id = %id,
+                    changes = diff.changes.len(),
+                    restart_required = diff.requires_restart(),
+                    "reloaded on HUP"
+                ),
+                Err(e) => tracing::warn!(id = %id, error = %e, "reload on HUP failed"),
+            }
+        }
+    }))
+}
+
+/// HUP is not available off Unix.
+#[cfg(not(unix))]
+const fn spawn_hup_reload(_manager: Arc<DaemonManager>, _id: DaemonId) -> Option<JoinHandle<()>> {
+    None
+}
+
+/// Listens on a notify socket for the daemon and applies what it sends.
+///
+/// The context's notifier points at it, so `ctx.notify_ready()` works as
+/// under systemd. Without `notify`, a socket that cannot be bound only
+/// disables notifications.
+#[cfg(unix)]
+fn spawn_notify_listener(
+    manager: Arc<DaemonManager>,
+    id: DaemonId,
+    ctx: &mut DaemonContext,
+    required: bool,
+) -> Result<Option<JoinHandle<()>>> {
+    let bound =
+        NotifyListener::for_daemon(id).and_then(|listener| Ok((listener.notifier()?, listener)));
+    let (notifier, listener) = match bound {
+        Ok(bound) => bound,
+        Err(e) if required => return Err(e.into()),
+        Err(e) => {
+            tracing::warn!(id = %id, error = %e, "no notify socket; notifications disabled");
+            return Ok(None);
+        }
+    };
+    ctx.set_notifier(notifier);
+
+    Ok(Some(tokio::spawn(async move {
+        while let Ok(states) = listener.recv().await {
+            if let Err(e) = manager.notify(id, &states).await {
+                tracing::warn!(id = %id, error = %e, "failed to apply notification");
+            }
+        }
+    })))
+}
+
+/// Notify sockets are not available off Unix.
+#[cfg(not(unix))]
+fn spawn_notify_listener(
+    _manager: Arc<DaemonManager>,
+    _id: DaemonId,
+    _ctx: &mut DaemonContext,
+    required: bool,
+) -> Result<Option<JoinHandle<()>>> {
+    if required {
+        return Err(DaemonError::config(
+            "notify is not supported on this platform",
+        ));
+    }
+    Ok(None)
+}
+
+/// Completes once a stop has been requested.
+///
+/// If the supervisor is dropped without requesting a stop, the daemon keeps
+/// running and this never completes.
+async fn stop_requested(stop_rx: &mut watch::Receiver<bool>) {
+    if stop_rx.wait_for(|stop| *stop).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Records a status change driven by the lifecycle.
+///
+/// Returns `Ok(false)` if the manager already failed the daemon (e.g. a
+/// required dependency failed): that status is kept, not overwritten.
+async fn set_status(manager: &DaemonManager, id: DaemonId, status: DaemonStatus) -> Result<bool> {
+    match manager.update_status(id, status).await {
+        Ok(()) => Ok(true),
+        Err(DaemonError::InvalidTransition {
+            from: DaemonStatus::Failed(_),
+            ..
+        }) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Maps a lifecycle outcome to the status recorded in the manager.
+fn final_status(outcome: &Result<ExitReason>) -> DaemonStatus {
+    match outcome {
+        Ok(ExitReason::Graceful | ExitReason::Signal(_)) => DaemonStatus::Stopped,
+        Ok(ExitReason::ResourceExhausted(_)) => {
+            DaemonStatus::Failed(FailureReason::ResourceExhausted)
+        }
+        Ok(ExitReason::PolicyViolation(_)) => DaemonStatus::Failed(FailureReason::PolicyViolation),
+        Err(DaemonError::Dependency(_)) => DaemonStatus::Failed(FailureReason::DependencyFailed),
+        Err(DaemonError::Quarantined(_)) => DaemonStatus::Failed(FailureReason::StartLimitHit),
+        Err(DaemonError::WatchdogTimeout(_)) => {
+            DaemonStatus::Failed(FailureReason::HealthCheckTimeout)
+        }
+        Err(DaemonError::HealthCheck(_)) => DaemonStatus::Failed(FailureReason::HealthCheckFailed),
+        Ok(ExitReason::Error(_)) | Err(_) => DaemonStatus::Failed(FailureReason::Internal),
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::Listener;
+    use crate::daemon::ReloadHandler;
+    use crate::manager::{BackoffConfig, RestartPolicy};
+    use crate::metrics::DaemonMetrics;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    use std::time::Duration;
+
+    /// Shared state observed by tests and the daemon's health probe.
+    #[derive(Default)]
+    struct Shared {
+        inits: AtomicU32,
+        runs: AtomicU32,
+        shutdowns: AtomicU32,
+        unhealthy: AtomicBool,
+        not_ready: AtomicBool,
+        starting: AtomicBool,
+        leak_subtask: AtomicBool,
+        ignore_term: AtomicBool,
+        /// Blocks the thread inside run() until cleared.
+        block_thread: AtomicBool,
+        /// Pings the watchdog over the notify socket instead of beating.
+        ping_watchdog: AtomicBool,
+        /// Has no in-process health probe.
+        no_probe: AtomicBool,
+        reloads: AtomicU32,
+        /// Address of the `http` listener seen by each run.
+        listeners: std::sync::Mutex<Vec<std::net::SocketAddr>>,
+        /// Lets a `notify` daemon report ready.
+        ready: tokio::sync::Notify,
+        metrics: DaemonMetrics,
+    }
+
+    #[async_trait]
+    impl HealthProbe for Shared {
+        async fn health_check(&self) -> HealthStatus {
+            if self.unhealthy.load(Ordering::SeqCst) {
+                HealthStatus::unhealthy("probe failed", 0)
+            } else {
+                HealthStatus::healthy(0)
+            }
+        }
+
+        async fn readiness_check(&self) -> HealthStatus {
+            if self.not_ready.load(Ordering::SeqCst) {
+                HealthStatus::unhealthy("warming cache", 0)
+            } else {
+                HealthStatus::healthy(0)
+            }
+        }
+
+        async fn startup_check(&self) -> HealthStatus {
+            if self.starting.load(Ordering::SeqCst) {
+                HealthStatus::unhealthy("loading model", 0)
+            } else {
+                HealthStatus::healthy(0)
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ReloadHandler for Shared {
+        async fn reload(&self, _config: &DaemonConfig) -> Result<()> {
+            self.reloads.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    /// Daemon whose first `fail_runs` runs return an error.
+    struct TestDaemon {
+        id: DaemonId,
+        fail_runs: u32,
+        shared: Arc<Shared>,
+        metrics: DaemonMetrics,
+    }
+
+    impl TestDaemon {
+        fn new(id: DaemonId, fail_runs: u32, shared: Arc<Shared>) -> Self {
+            Self {
+                id,
+                fail_runs,
+                metrics: shared.metrics.clone(),
+                shared,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Daemon for TestDaemon {
+        fn id(&self) -> DaemonId {
+            self.id
+        }
+
+        fn name(&self) -> &str {
+            "supervised"
+        }
+
+        async fn init(&mut self, _config: &DaemonConfig) -> Result<()> {
+            self.shared.inits.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn run(&mut self, ctx: &mut DaemonContext) -> Result<ExitReason> {
+            let run = self.shared.runs.fetch_add(1, Ordering::SeqCst);
+            if let Some(Listener::Tcp(l)) = ctx.listeners().get("http") {
+                self.shared
+                    .listeners
+                    .lock()
+                    .unwrap()
+                    .push(l.local_addr().unwrap());
+            }
+            if run < self.fail_runs {
+                return Err(DaemonError::runtime(format!("run {} failed", run)));
+            }
+
+            if ctx.config().notify {
+                ctx.notify_status("warming up").unwrap();
+                self.shared.ready.notified().await;
+                ctx.notify_status("serving").unwrap();
+                ctx.notify_ready().unwrap();
+            }
+
+            let token = ctx.shutdown_token();
+            ctx.spawn("worker", async move { token.cancelled().await });
+            if self.shared.leak_subtask.load(Ordering::SeqCst) {
+                ctx.spawn("stubborn", std::future::pending());
+            }
+
+            while self.shared.block_thread.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            if self.shared.ignore_term.load(Ordering::SeqCst) {
+                std::future::pending::<()>().await;
+            }
+            if self.shared.ping_watchdog.load(Ordering::SeqCst) {
+                while !ctx.should_shutdown() {
+                    ctx.notify_watchdog().unwrap();
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+            }
+
+            while !ctx.should_shutdown() {
+                if ctx.recv_signal().await.is_none() {
+                    break;
+                }
+            }
+            Ok(ExitReason::Graceful)
+        }
+
+        async fn shutdown(&mut self, _timeout: Duration) -> Result<()> {
+            self.shared.shutdowns.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn health_check(&self) -> HealthStatus {
+            self.shared.health_check().await
+        }
+
+        async fn readiness_check(&self) -> HealthStatus {
+            self.shared.readiness_check().await
+        }
+
+        fn metrics(&self) -> &DaemonMetrics {
+            &self.metrics
+        }
+
+        fn health_probe(&self) -> Option<Arc<dyn HealthProbe>> {
+            if self.shared.no_probe.load(Ordering::SeqCst) {
+                return None;
+            }
+            Some(Arc::clone(&self.shared) as Arc<dyn HealthProbe>)
+        }
+
+        fn reload_handler(&self) -> Option<Arc<dyn ReloadHandler>> {
+            Some(Arc::clone(&self.shared) as Arc<dyn ReloadHandler>)
+        }
+    }
+
+    async fn setup(
+        policy: RestartPolicy,
+        health_interval: Duration,
+    ) -> (Arc<DaemonManager>, Supervisor, DaemonId, Arc<Shared>) {
+        let mut config = DaemonConfig::new("supervised", "/bin/true");
+        config.health_check.interval = health_interval;
+        config.shutdown_timeout = Duration::from_secs(1);
+        setup_with_config(policy, config).await
+    }
+
+    async fn setup_with_config(
+        policy: RestartPolicy,
+        config: DaemonConfig,
+    ) -> (Arc<DaemonManager>, Supervisor, DaemonId, Arc<Shared>) {
+        let manager = Arc::new(DaemonManager::new());
+        let shared = Arc::new(Shared::default());
+        let id = DaemonId::new();
+
+        manager
+            .register(
+                Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))),
+                config,
+                policy,
+            )
+            .await
+            .unwrap();
+
+        let supervisor = Supervisor::new(Arc::clone(&manager));
+        (manager, supervisor, id, shared)
+    }
+
+    async fn wait_for_status(manager: &DaemonManager, id: DaemonId, status: DaemonStatus) {
+        for _ in 0..200 {
+            if manager.status(id).await.unwrap() == status {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("daemon never reached {:?}", status);
+    }
+
+    async fn wait_for_ready(manager: &DaemonManager, id: DaemonId, ready: bool) {
+        for _ in 0..200 {
+            if manager.is_ready(id).await.unwrap() == ready {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("daemon readiness never became {}", ready);
+    }
+
+    /// Probe configuration for tests: fast intervals, no retries.
+    fn probed_config() -> DaemonConfig {
+        let mut config = DaemonConfig::new("supervised", "/bin/true");
+        config.health_check.interval = Duration::from_millis(10);
+        config.health_check.retries = 0;
+        config.health_check.readiness.interval = Duration::from_millis(10);
+        config.health_check.readiness.retries = 0;
+        config.shutdown_timeout = Duration::from_secs(1);
+        config
+    }
+
+    fn fast_backoff(max_retries: u32) -> RestartPolicy {
+        RestartPolicy::WithBackoff(
+            BackoffConfig::new()
+                .with_initial_delay(Duration::from_millis(1))
+                .with_max_delay(Duration::from_millis(5))
+                .with_max_retries(max_retries),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_runs_full_lifecycle() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        assert!(supervisor.is_supervised(id).await);
+
+        supervisor.stop(id).await.unwrap();
+        let exit = supervisor.wait(id).await.unwrap();
+
+        assert!(matches!(exit, ExitReason::Graceful));
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Stopped);
+        assert_eq!(shared.inits.load(Ordering::SeqCst), 1);
+        assert_eq!(shared.shutdowns.load(Ordering::SeqCst), 1);
+        assert!(manager.get_health(id).await.unwrap().unwrap().is_healthy());
+
+        let path: Vec<_> = manager
+            .transitions(id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|t| t.to)
+            .collect();
+        assert_eq!(
+            path,
+            [
+                DaemonStatus::Starting,
+                DaemonStatus::Running,
+                DaemonStatus::Stopping,
+                DaemonStatus::Stopped,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_with_signal_bridge() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+        let supervisor = supervisor.with_signal_bridge(SignalBridge::new());
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+
+        supervisor.stop(id).await.unwrap();
+        let exit = supervisor.wait(id).await.unwrap();
+
+        assert!(matches!(exit, ExitReason::Graceful));
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_signal_relay_stops_daemon_between_runs() {
+        let relay = SignalRelay::new(SignalBridge::new());
+        let (running, idle) = (DaemonId::new(), DaemonId::new());
+        let (running_tx, running_rx) = watch::channel(false);
+        let (idle_tx, idle_rx) = watch::channel(false);
+        relay.attach(running, running_tx);
+        relay.attach(idle, idle_tx);
+        let (mut ctx, handle) = DaemonContext::new(DaemonConfig::new("test", "/bin/test"));
+        relay.set_run(running, Some(handle));
+
+        // Other signals reach only the current run
+        let runs = relay_signal(&relay, Signal::Usr1).await;
+        assert_eq!(runs, 1);
+        assert_eq!(ctx.try_recv_signal(), Some(Signal::Usr1));
+        assert!(!*idle_rx.borrow());
+
+        // TERM reaches the run, and stops the daemon in backoff
+        relay_signal(&relay, Signal::Term).await;
+        assert_eq!(ctx.try_recv_signal(), Some(Signal::Term));
+        assert!(!*running_rx.borrow());
+        assert!(*idle_rx.borrow());
+
+        relay.detach(idle);
+        relay.set_run(running, None);
+        assert_eq!(relay_signal(&relay, Signal::Usr1).await, 0);
+    }
+
+    /// Relays a signal as the installed bridge would; returns the number
+    /// of runs it was delivered to.
+    async fn relay_signal(relay: &SignalRelay, signal: Signal) -> usize {
+        let runs = route(&relay.targets, signal);
+        for run in &runs {
+            run.send_signal(signal).await.unwrap();
+        }
+        runs.len()
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_reports_leaked_subtasks() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+        shared.leak_subtask.store(true, Ordering::SeqCst);
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        assert!(supervisor.leaked_tasks(id).await.is_empty());
+
+        supervisor.stop(id).await.unwrap();
+        let exit = supervisor.wait(id).await.unwrap();
+
+        // The cooperative worker is joined; only the stubborn one leaks
+        assert!(matches!(exit, ExitReason::Graceful));
+        assert_eq!(
+            supervisor.leaked_tasks(id).await,
+            vec!["stubborn".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_applies_reloaded_shutdown_timeout() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+        shared.leak_subtask.store(true, Ordering::SeqCst);
+        let mut config = manager.get_config(id).await.unwrap();
+        config.shutdown_timeout = Duration::from_secs(60);
+        manager.reload_with(id, config.clone()).await.unwrap();
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+
+        config.shutdown_timeout = Duration::from_millis(50);
+        let diff = manager.reload_with(id, config).await.unwrap();
+        assert!(!diff.requires_restart());
+
+        // The stubborn subtask is given up on after the reloaded deadline
+        supervisor.stop(id).await.unwrap();
+        let exit = tokio::time::timeout(Duration::from_secs(5), supervisor.wait(id))
+            .await
+            .unwrap();
+        assert!(exit.is_ok());
+        assert_eq!(
+            supervisor.leaked_tasks(id).await,
+            vec!["stubborn".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_routes_reload_to_running_daemon() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+
+        let mut config = manager.get_config(id).await.unwrap();
+        config.health_check.retries += 1;
+        manager.reload_with(id, config.clone()).await.unwrap();
+        assert_eq!(shared.reloads.load(Ordering::SeqCst), 1);
+
+        supervisor.stop(id).await.unwrap();
+        supervisor.wait(id).await.unwrap();
+
+        // Stopped daemons have no handler; the config is still stored
+        config.health_check.retries += 1;
+        manager.reload_with(id, config).await.unwrap();
+        assert_eq!(shared.reloads.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_restarts_on_run_error() {
+        let (manager, supervisor, id, shared) =
+            setup(fast_backoff(5), Duration::from_secs(30)).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 2, Arc::clone(&shared))))
+            .await
+            .unwrap();
+
+        // Two failed runs, then a run that stays up
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        for _ in 0..200 {
+            if shared.runs.load(Ordering::SeqCst) == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+
+        assert_eq!(manager.get_restart_count(id).await.unwrap(), 2);
+        assert_eq!(shared.inits.load(Ordering::SeqCst), 3);
+
+        supervisor.stop(id).await.unwrap();
+        assert!(supervisor.wait(id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_publishes_exit_and_restart_events() {
+        let (manager, supervisor, id, shared) =
+            setup(fast_backoff(1), Duration::from_secs(30)).await;
+        let mut events = manager.subscribe();
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 1, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        supervisor.stop(id).await.unwrap();
+        assert!(supervisor.wait(id).await.is_ok());
+
+        let mut kinds = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            kinds.push(event.kind);
+        }
+        let failed = kinds.iter().position(|kind| {
+            matches!(
+                kind,
+                LifecycleEventKind::Exited {
+                    reason: ExitReason::Error(_),
+                    status: DaemonStatus::Failed(_),
+                }
+            )
+        });
+        let scheduled = kinds.iter().position(|kind| {
+            matches!(
+                kind,
+                LifecycleEventKind::RestartScheduled { attempt: 1, .. }
+            )
+        });
+        assert!(failed.unwrap() < scheduled.unwrap());
+        assert!(matches!(
+            kinds.last(),
+            Some(LifecycleEventKind::Exited {
+                reason: ExitReason::Graceful,
+                status: DaemonStatus::Stopped,
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_events_carry_run_trace() {
+        let (manager, supervisor, id, shared) =
+            setup(fast_backoff(1), Duration::from_secs(30)).await;
+        let mut events = manager.subscribe();
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 1, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        let current = manager.trace_context(id).await.unwrap().unwrap();
+        supervisor.stop(id).await.unwrap();
+        assert!(supervisor.wait(id).await.is_ok());
+
+        let mut runs = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if matches!(event.kind, LifecycleEventKind::Registered) {
+                continue;
+            }
+            let span = event.span_id.unwrap();
+            assert!(event.trace_id.is_some());
+            if matches!(event.kind, LifecycleEventKind::Starting) {
+                runs.push(span);
+            } else {
+                assert_eq!(Some(&span), runs.last());
+            }
+        }
+        // One span per run; the last is the running one
+        assert_eq!(runs.len(), 2);
+        assert_ne!(runs[0], runs[1]);
+        assert_eq!(runs[1], current.span_id);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_notify_daemon_reports_ready() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+        let mut config = manager.get_config(id).await.unwrap();
+        config.notify = true;
+        manager.reload_with(id, config).await.unwrap();
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+
+        // Running only once the daemon says so
+        for _ in 0..200 {
+            if manager.status_text(id).await.unwrap().is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(
+            manager.status_text(id).await.unwrap().as_deref(),
+            Some("warming up")
+        );
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Starting);
+
+        shared.ready.notify_one();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        assert_eq!(
+            manager.status_text(id).await.unwrap().as_deref(),
+            Some("serving")
+        );
+
+        supervisor.stop(id).await.unwrap();
+        assert!(supervisor.wait(id).await.is_ok());
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_aborts_hung_daemon_on_watchdog() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+        let mut config = manager.get_config(id).await.unwrap();
+        config.health_check.watchdog = Duration::from_millis(100);
+        manager.reload_with(id, config).await.unwrap();
+        // Hangs inside run() without a heartbeat
+        shared.ignore_term.store(true, Ordering::SeqCst);
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        let exit = tokio::time::timeout(Duration::from_secs(5), supervisor.wait(id))
+            .await
+            .expect("hung daemon was not aborted");
+
+        assert!(matches!(exit, Err(DaemonError::WatchdogTimeout(_))));
+        assert_eq!(
+            manager.status(id).await.unwrap(),
+            DaemonStatus::Failed(FailureReason::HealthCheckTimeout)
+        );
+        assert_eq!(shared.metrics.watchdog_timeouts(), 1);
+        // A hung daemon gets no shutdown hook
+        assert_eq!(shared.shutdowns.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_supervisor_abandons_run_blocking_its_thread() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Always, Duration::from_secs(30)).await;
+        let mut config = manager.get_config(id).await.unwrap();
+        config.health_check.watchdog = Duration::from_millis(100);
+        manager.reload_with(id, config).await.unwrap();
+        // Deadlocked inside run(): never yields, never beats
+        shared.block_thread.store(true, Ordering::SeqCst);
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        let exit = tokio::time::timeout(Duration::from_secs(5), supervisor.wait(id))
+            .await
+            .expect("blocked daemon was not abandoned");
+
+        assert!(matches!(exit, Err(DaemonError::WatchdogTimeout(_))));
+        assert_eq!(
+            manager.status(id).await.unwrap(),
+            DaemonStatus::Failed(FailureReason::HealthCheckTimeout)
+        );
+        assert_eq!(shared.metrics.watchdog_timeouts(), 1);
+        // The blocked run still holds the daemon: no restart, no shutdown hook
+        assert_eq!(shared.runs.load(Ordering::SeqCst), 1);
+        assert_eq!(shared.shutdowns.load(Ordering::SeqCst), 0);
+        shared.block_thread.store(false, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_notify_watchdog_counts_as_heartbeat() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+        let mut config = manager.get_config(id).await.unwrap();
+        config.health_check.watchdog = Duration::from_millis(100);
+        manager.reload_with(id, config).await.unwrap();
+        shared.ping_watchdog.store(true, Ordering::SeqCst);
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Running);
+        assert!(manager.last_watchdog(id).await.unwrap().is_some());
+
+        supervisor.stop(id).await.unwrap();
+        let exit = supervisor.wait(id).await.unwrap();
+        assert!(matches!(exit, ExitReason::Graceful));
+        assert_eq!(shared.metrics.watchdog_timeouts(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_keeps_listeners_across_restarts() {
+        use crate::config::{ListenAddress, SocketConfig};
+
+        let manager = Arc::new(DaemonManager::new());
+        let shared = Arc::new(Shared::default());
+        let id = DaemonId::new();
+
+        let mut config = DaemonConfig::new("supervised", "/bin/true");
+        config.sockets = vec![SocketConfig::new(
+            "http",
+            ListenAddress::Tcp("127.0.0.1:0".parse().unwrap()),
+        )];
+        manager
+            .register(
+                Box::new(TestDaemon::new(id, 1, Arc::clone(&shared))),
+                config,
+                fast_backoff(5),
+            )
+            .await
+            .unwrap();
+
+        let supervisor = Supervisor::new(Arc::clone(&manager));
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 1, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        for _ in 0..200 {
+            if shared.runs.load(Ordering::SeqCst) == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        // Same socket, same port, before and after the restart
+        let seen = shared.listeners.lock().unwrap().clone();
+        assert_eq!(seen.len(), 2);
+        assert_ne!(seen[0].port(), 0);
+        assert_eq!(seen[0], seen[1]);
+
+        supervisor.stop(id).await.unwrap();
+        assert!(supervisor.wait(id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_gives_up_after_max_retries() {
+        let (manager, supervisor, id, shared) =
+            setup(fast_backoff(2), Duration::from_secs(30)).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, u32::MAX, Arc::clone(&shared))))
+            .await
+            .unwrap();
+
+        let result = supervisor.wait(id).await;
+        assert!(result.is_err());
+        assert_eq!(shared.runs.load(Ordering::SeqCst), 3);
+        assert_eq!(manager.get_restart_count(id).await.unwrap(), 2);
+        assert_eq!(
+            manager.status(id).await.unwrap(),
+            DaemonStatus::Failed(FailureReason::Internal)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_start_limit_quarantines() {
+        let (manager, supervisor, id, shared) =
+            setup(fast_backoff(10), Duration::from_secs(30)).await;
+        let mut config = manager.get_config(id).await.unwrap();
+        config.start_limit = Some(crate::config::StartLimit::new(2, Duration::from_secs(60)));
+        manager.reload_with(id, config).await.unwrap();
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 100, Arc::clone(&shared))))
+            .await
+            .unwrap();
+
+        let result = supervisor.wait(id).await;
+        assert!(matches!(result, Err(DaemonError::Quarantined(_))));
+        assert_eq!(shared.runs.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            manager.status(id).await.unwrap(),
+            DaemonStatus::Failed(FailureReason::StartLimitHit)
+        );
+
+        // An operator reset allows starting again
+        manager.reset_failed(id).await.unwrap();
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        supervisor.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_never_policy_does_not_restart() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 1, Arc::clone(&shared))))
+            .await
+            .unwrap();
+
+        assert!(supervisor.wait(id).await.is_err());
+        assert_eq!(shared.runs.load(Ordering::SeqCst), 1);
+        assert_eq!(manager.get_restart_count(id).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_follows_reloaded_restart_policy() {
+        let manager = Arc::new(DaemonManager::new());
+        let shared = Arc::new(Shared::default());
+        let id = DaemonId::new();
+        let mut config = probed_config();
+        config.restart = crate::config::RestartPolicy::Never;
+        manager
+            .register_configured(
+                Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))),
+                config.clone(),
+            )
+            .await
+            .unwrap();
+        let supervisor = Supervisor::new(Arc::clone(&manager));
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+
+        // Reloaded while running; applies to the next restart decision
+        config.restart = crate::config::RestartPolicy::OnFailure;
+        config.backoff = Some(
+            BackoffConfig::new()
+                .with_initial_delay(Duration::from_millis(1))
+                .with_max_retries(5),
+        );
+        manager.reload_with(id, config).await.unwrap();
+        assert!(matches!(
+            manager.get_restart_policy(id).await.unwrap(),
+            RestartPolicy::WithBackoff(_)
+        ));
+
+        shared.unhealthy.store(true, Ordering::SeqCst);
+        for _ in 0..200 {
+            if manager.get_restart_count(id).await.unwrap() > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        shared.unhealthy.store(false, Ordering::SeqCst);
+        assert!(manager.get_restart_count(id).await.unwrap() > 0);
+
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        supervisor.shutdown().await.unwrap();
+    }
+
+    async fn register_named(
+        manager: &DaemonManager,
+        name: &str,
+        requires: &[&str],
+        fail_runs: u32,
+    ) -> (DaemonId, Box<dyn Daemon>) {
+        let id = DaemonId::new();
+        let mut config = DaemonConfig::new(name, "/bin/true");
+        config.requires = requires.iter().map(ToString::to_string).collect();
+        config.shutdown_timeout = Duration::from_secs(1);
+
+        let shared = Arc::new(Shared::default());
+        manager
+            .register(
+                Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))),
+                config,
+                RestartPolicy::Never,
+            )
+            .await
+            .unwrap();
+        (id, Box::new(TestDaemon::new(id, fail_runs, shared)))
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_start_all_in_dependency_order() {
+        let manager = Arc::new(DaemonManager::new());
+        let (model, model_daemon) = register_named(&manager, "model-server", &["zram"], 0).await;
+        let (zram, zram_daemon) = register_named(&manager, "zram", &[], 0).await;
+        let supervisor = Supervisor::new(Arc::clone(&manager));
+
+        let started = supervisor
+            .start_all(vec![model_daemon, zram_daemon])
+            .await
+            .unwrap();
+        assert_eq!(started, [zram, model]);
+
+        wait_for_status(&manager, model, DaemonStatus::Running).await;
+        assert_eq!(manager.status(zram).await.unwrap(), DaemonStatus::Running);
+
+        supervisor.shutdown().await.unwrap();
+        assert_eq!(manager.status(model).await.unwrap(), DaemonStatus::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_dependents_fail_with_dependency() {
+        let manager = Arc::new(DaemonManager::new());
+        let (zram, zram_daemon) = register_named(&manager, "zram", &[], 1).await;
+        let (model, model_daemon) = register_named(&manager, "model-server", &["zram"], 0).await;
+        let supervisor = Supervisor::new(Arc::clone(&manager));
+
+        supervisor
+            .start_all(vec![zram_daemon, model_daemon])
+            .await
+            .unwrap();
+
+        wait_for_status(
+            &manager,
+            zram,
+            DaemonStatus::Failed(FailureReason::Internal),
+        )
+        .await;
+        wait_for_status(
+            &manager,
+            model,
+            DaemonStatus::Failed(FailureReason::DependencyFailed),
+        )
+        .await;
+
+        let _ = supervisor.shutdown().await;
+        // The dependency failure is kept, not overwritten on exit
+        assert_eq!(
+            manager.status(model).await.unwrap(),
+            DaemonStatus::Failed(FailureReason::DependencyFailed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_refuses_start_without_required_dependency() {
+        let manager = Arc::new(DaemonManager::new());
+        let (model, model_daemon) = register_named(&manager, "model-server", &["zram"], 0).await;
+        let supervisor = Supervisor::new(Arc::clone(&manager));
+
+        supervisor.start(model_daemon).await.unwrap();
+
+        let result = supervisor.wait(model).await;
+        assert!(matches!(result, Err(DaemonError::Dependency(_))));
+        assert_eq!(
+            manager.status(model).await.unwrap(),
+            DaemonStatus::Failed(FailureReason::DependencyFailed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_start_all_rejects_unregistered_daemon() {
+        let manager = Arc::new(DaemonManager::new());
+        let supervisor = Supervisor::new(Arc::clone(&manager));
+        let daemon = TestDaemon::new(DaemonId::new(), 0, Arc::new(Shared::default()));
+
+        let result = supervisor.start_all(vec![Box::new(daemon)]).await;
+        assert!(matches!(result, Err(DaemonError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_manager_shutdown_all_kills_stubborn_daemon() {
+        let (manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+        shared.ignore_term.store(true, Ordering::SeqCst);
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+
+        let report = manager.shutdown_all().await.unwrap();
+        assert_eq!(report.killed, [id]);
+
+        // KILL skips the shutdown hook
+        let exit = supervisor.wait(id).await.unwrap();
+        assert!(matches!(exit, ExitReason::Signal(Signal::Kill)));
+        assert_eq!(shared.shutdowns.load(Ordering::SeqCst), 0);
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_records_health_on_interval() {
+        let mut config = DaemonConfig::new("supervised", "/bin/true");
+        config.health_check.interval = Duration::from_millis(10);
+        // Record failures without acting on them
+        config.health_check.retries = u32::MAX;
+        let (manager, supervisor, id, shared) =
+            setup_with_config(RestartPolicy::Never, config).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+
+        shared.unhealthy.store(true, Ordering::SeqCst);
+        let mut unhealthy = false;
+        for _ in 0..200 {
+            if manager
+                .get_health(id)
+                .await
+                .unwrap()
+                .is_some_and(|h| !h.is_healthy())
+            {
+                unhealthy = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(unhealthy, "probe result never recorded");
+
+        supervisor.shutdown().await.unwrap();
+        assert!(!supervisor.is_supervised(id).await);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_restarts_on_liveness_failure() {
+        let (manager, supervisor, id, shared) =
+            setup_with_config(fast_backoff(1), probed_config()).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        assert!(manager.is_ready(id).await.unwrap());
+
+        shared.unhealthy.store(true, Ordering::SeqCst);
+        let result = tokio::time::timeout(Duration::from_secs(5), supervisor.wait(id))
+            .await
+            .unwrap();
+
+        assert!(matches!(result, Err(DaemonError::HealthCheck(_))));
+        assert_eq!(manager.get_restart_count(id).await.unwrap(), 1);
+        assert_eq!(
+            manager.status(id).await.unwrap(),
+            DaemonStatus::Failed(FailureReason::HealthCheckFailed)
+        );
+        // Stopped gracefully each time, not abandoned
+        assert_eq!(shared.shutdowns.load(Ordering::SeqCst), 2);
+        assert!(!manager.is_ready(id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_readiness_failure_does_not_restart() {
+        let (manager, supervisor, id, shared) =
+            setup_with_config(fast_backoff(1), probed_config()).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        wait_for_ready(&manager, id, true).await;
+
+        shared.not_ready.store(true, Ordering::SeqCst);
+        wait_for_ready(&manager, id, false).await;
+        let readiness = manager.get_readiness(id).await.unwrap().unwrap();
+        assert_eq!(readiness.reason(), Some("warming cache"));
+
+        // Still alive and never restarted
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Running);
+        assert_eq!(manager.get_restart_count(id).await.unwrap(), 0);
+        assert_eq!(shared.runs.load(Ordering::SeqCst), 1);
+
+        shared.not_ready.store(false, Ordering::SeqCst);
+        wait_for_ready(&manager, id, true).await;
+
+        supervisor.shutdown().await.unwrap();
+        assert!(!manager.is_ready(id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_readiness_tolerates_retries() {
+        let mut config = probed_config();
+        config.health_check.readiness.interval = Duration::from_millis(20);
+        config.health_check.readiness.retries = 3;
+        let (manager, supervisor, id, shared) =
+            setup_with_config(RestartPolicy::Never, config).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_ready(&manager, id, true).await;
+
+        // At most two failed probes fit in 30ms: still within the retries
+        shared.not_ready.store(true, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(manager.is_ready(id).await.unwrap());
+        assert!(
+            manager
+                .get_readiness(id)
+                .await
+                .unwrap()
+                .unwrap()
+                .is_healthy()
+        );
+
+        wait_for_ready(&manager, id, false).await;
+        supervisor.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_runs_external_checks_without_probe() {
+        use crate::config::{CheckKind, ExternalCheck};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut config = probed_config();
+        config.health_check.checks = vec![
+            ExternalCheck::new(CheckKind::Tcp {
+                address: listener.local_addr().unwrap().to_string(),
+            })
+            .with_probe(ProbeKind::Readiness),
+        ];
+        let (manager, supervisor, id, shared) =
+            setup_with_config(RestartPolicy::Never, config).await;
+        shared.no_probe.store(true, Ordering::SeqCst);
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+        wait_for_ready(&manager, id, true).await;
+
+        // Readiness follows the checked port
+        drop(listener);
+        wait_for_ready(&manager, id, false).await;
+        let readiness = manager.get_readiness(id).await.unwrap().unwrap();
+        assert!(readiness.reason().unwrap().contains("connect"));
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Running);
+
+        supervisor.stop(id).await.unwrap();
+        supervisor.wait(id).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_supervisor_restarts_on_external_liveness_failure() {
+        use crate::config::{CheckKind, ExternalCheck};
+
+        let mut config = probed_config();
+        config.health_check.checks = vec![ExternalCheck::new(CheckKind::Exec {
+            command: vec!["false".to_string()],
+        })];
+        let (manager, supervisor, id, shared) = setup_with_config(fast_backoff(1), config).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), supervisor.wait(id))
+            .await
+            .unwrap();
+
+        // The in-process probe passes; the external check does not
+        assert!(matches!(result, Err(DaemonError::HealthCheck(_))));
+        assert_eq!(manager.get_restart_count(id).await.unwrap(), 1);
+        let health = manager.get_health(id).await.unwrap().unwrap();
+        assert!(!health.is_healthy());
+        assert_eq!(health.checks.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_startup_probe_gates_liveness_and_readiness() {
+        let mut config = probed_config();
+        config.health_check.startup = StartupProbeConfig::new(Duration::from_secs(5))
+            .with_interval(Duration::from_millis(10));
+        let (manager, supervisor, id, shared) =
+            setup_with_config(RestartPolicy::Never, config).await;
+
+        // Liveness would fail immediately, were it probed while starting
+        shared.starting.store(true, Ordering::SeqCst);
+        shared.unhealthy.store(true, Ordering::SeqCst);
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        wait_for_status(&manager, id, DaemonStatus::Running).await;
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(manager.status(id).await.unwrap(), DaemonStatus::Running);
+        assert!(!manager.is_ready(id).await.unwrap());
+
+        shared.unhealthy.store(false, Ordering::SeqCst);
+        shared.starting.store(false, Ordering::SeqCst);
+        wait_for_ready(&manager, id, true).await;
+
+        supervisor.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_fails_when_startup_grace_period_expires() {
+        let mut config = probed_config();
+        config.health_check.startup = StartupProbeConfig::new(Duration::from_millis(50))
+            .with_interval(Duration::from_millis(10));
+        let (manager, supervisor, id, shared) =
+            setup_with_config(RestartPolicy::Never, config).await;
+
+        shared.starting.store(true, Ordering::SeqCst);
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+
+        let result = tokio::time::timeout(Duration::from_secs(5), supervisor.wait(id))
+            .await
+            .unwrap();
+        let Err(DaemonError::HealthCheck(message)) = result else {
+            panic!("expected startup probe failure, got {result:?}");
+        };
+        assert!(message.contains("loading model"));
+        assert_eq!(
+            manager.status(id).await.unwrap(),
+            DaemonStatus::Failed(FailureReason::HealthCheckFailed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_rejects_unregistered_daemon() {
+        let manager = Arc::new(DaemonManager::new());
+        let supervisor = Supervisor::new(manager);
+        let daemon = TestDaemon::new(DaemonId::new(), 0, Arc::new(Shared::default()));
+
+        let result = supervisor.start(Box::new(daemon)).await;
+        assert!(matches!(result, Err(DaemonError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_rejects_double_start() {
+        let (_manager, supervisor, id, shared) =
+            setup(RestartPolicy::Never, Duration::from_secs(30)).await;
+
+        supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await
+            .unwrap();
+        let result = supervisor
+            .start(Box::new(TestDaemon::new(id, 0, Arc::clone(&shared))))
+            .await;
+        assert!(matches!(result, Err(DaemonError::State(_))));
+
+        supervisor.shutdown().await.unwrap();
+    }
+
+    #[test]
+    fn test_final_status_mapping() {
+        assert_eq!(
+            final_status(&Ok(ExitReason::Graceful)),
+            DaemonStatus::Stopped
+        );
+        assert_eq!(
+            final_status(&Ok(ExitReason::ResourceExhausted("oom".into()))),
+            DaemonStatus::Failed(FailureReason::ResourceExhausted)
+        );
+        assert_eq!(
+            final_status(&Err(DaemonError::runtime("boom"))),
+            DaemonStatus::Failed(FailureReason::Internal)
+        );
+        assert_eq!(
+            final_status(&Err(DaemonError::quarantined("too many starts"))),
+            DaemonStatus::Failed(FailureReason::StartLimitHit)
+        );
+        assert_eq!(
+            final_status(&Err(DaemonError::health_check("liveness probe failed"))),
+            DaemonStatus::Failed(FailureReason::HealthCheckFailed)
+        );
+    }
+}
@@ -394,20 +394,24 @@ async fn f019_cannot_unregister_active() {
         .expect("register should succeed");
 
     // Set status to Running
-    manager
-        .update_status(id, DaemonStatus::Running)
-        .await
-        .expect("update should succeed");
+    for status in [DaemonStatus::Starting, DaemonStatus::Running] {
+        manager
+            .update_status(id, status)
+            .await
+            .expect("update should succeed");
+    }
 
     // Try to unregister - should fail
     let result = manager.unregister(id).await;
     assert!(result.is_err());
 
     // Set to Stopped and unregister should work
-    manager
-        .update_status(id, DaemonStatus::Stopped)
-        .await
-        .expect("update should succeed");
+    for status in [DaemonStatus::Stopping, DaemonStatus::Stopped] {
+        manager
+            .update_status(id, status)
+            .await
+            .expect("update should succeed");
+    }
 
     let result = manager.unregister(id).await;
     assert!(result.is_ok());
@@ -431,11 +435,13 @@ async fn f020_shutdown_all_signals_all() {
 
     assert_eq!(manager.count().await, 5);
 
-    // Shutdown all - this sends signals to all daemons
-    // (In practice would wait for shutdown, but stub adapters
-    // don't actually run processes so signals may fail)
-    manager.shutdown_all().await.ok();
+    // Shutdown all - signals every running daemon and waits for it
+    let report = manager
+        .shutdown_all()
+        .await
+        .expect("shutdown_all should succeed");
 
-    // All daemons should have been signaled (or at least attempted)
-    // The shutdown_all just sends signals, doesn't wait for completion
+    // None of them was started, so none needed stopping
+    assert!(report.is_clean());
+    assert!(report.graceful.is_empty());
 }
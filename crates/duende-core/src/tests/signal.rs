@@ -154,11 +154,18 @@ async fn f029_signal_to_stopped_daemon_fails() {
         .await
         .expect("register should succeed");
 
-    // Set daemon to Stopped state
-    manager
-        .update_status(id, DaemonStatus::Stopped)
-        .await
-        .expect("update should succeed");
+    // Drive daemon to Stopped state through the state machine
+    for status in [
+        DaemonStatus::Starting,
+        DaemonStatus::Running,
+        DaemonStatus::Stopping,
+        DaemonStatus::Stopped,
+    ] {
+        manager
+            .update_status(id, status)
+            .await
+            .expect("update should succeed");
+    }
 
     // Try to signal - should fail because Stopped.can_signal() == false
     let result = manager.signal(id, Signal::Term).await;
@@ -366,10 +373,12 @@ async fn f039_manager_signal_forwards_correctly() {
         .expect("register should succeed");
 
     // Set to running so we can signal
-    manager
-        .update_status(id, DaemonStatus::Running)
-        .await
-        .expect("update should succeed");
+    for status in [DaemonStatus::Starting, DaemonStatus::Running] {
+        manager
+            .update_status(id, status)
+            .await
+            .expect("update should succeed");
+    }
 
     // Create context and set handle
     let (mut ctx, context_handle) = DaemonContext::new(config);
@@ -0,0 +1,628 @@
+//! Trace context - W3C `traceparent` and `tracestate` propagation.
+//!
+//! A [`TraceContext`] names the trace a daemon run belongs to and the span
+//! that represents it. The supervisor gives every run one, available from
+//! [`DaemonContext::trace_context`]; lifecycle events carry its IDs, and
+//! processes spawned by the native adapter receive it through the
+//! `TRACEPARENT` and `TRACESTATE` environment variables. A daemon started
+//! with `TRACEPARENT` set joins the caller's trace instead of starting one.
+//!
+//! Parsing follows [W3C Trace Context](https://www.w3.org/TR/trace-context/)
+//! strictly: uppercase hex, all-zero IDs, version `ff` and malformed
+//! `tracestate` members are rejected.
+//!
+//! # Toyota Way: Genchi Genbutsu (現地現物)
+//! A failure is followed back to where it started, across process
+//! boundaries, instead of being guessed at from one daemon's logs.
+//!
+//! [`DaemonContext::trace_context`]: crate::daemon::DaemonContext::trace_context
+
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+use crate::error::{DaemonError, Result};
+
+/// Environment variable carrying the `traceparent` of the parent span.
+pub const TRACEPARENT_ENV: &str = "TRACEPARENT";
+
+/// Environment variable carrying the `tracestate` of the parent span.
+pub const TRACESTATE_ENV: &str = "TRACESTATE";
+
+/// Maximum number of `tracestate` list members.
+const MAX_TRACESTATE_MEMBERS: usize = 32;
+
+/// Length of a version `00` `traceparent`.
+const TRACEPARENT_LEN: usize = 55;
+
+/// Decodes lowercase hex into `N` bytes.
+fn decode_hex<const N: usize>(hex: &str, what: &str) -> Result<[u8; N]> {
+    let invalid = || DaemonError::trace_context(format!("invalid {what}: {hex:?}"));
+    let digits = hex.as_bytes();
+    if digits.len() != N * 2 {
+        return Err(invalid());
+    }
+
+    let nibble = |digit: u8| match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        _ => None,
+    };
+    let mut bytes = [0; N];
+    for (byte, pair) in bytes.iter_mut().zip(digits.chunks_exact(2)) {
+        *byte = nibble(pair[0])
+            .zip(nibble(pair[1]))
+            .map(|(high, low)| high << 4 | low)
+            .ok_or_else(invalid)?;
+    }
+    Ok(bytes)
+}
+
+/// Encodes bytes as lowercase hex.
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
+
+macro_rules! hex_id {
+    ($(#[$doc:meta])* $name:ident, $len:literal, $what:literal) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name([u8; $len]);
+
+        impl $name {
+            /// Creates an ID from raw bytes.
+            ///
+            /// # Errors
+            /// Returns `DaemonError::TraceContext` if all bytes are zero.
+            pub fn from_bytes(bytes: [u8; $len]) -> Result<Self> {
+                if bytes == [0; $len] {
+                    return Err(DaemonError::trace_context(concat!("all-zero ", $what)));
+                }
+                Ok(Self(bytes))
+            }
+
+            /// Parses lowercase hex.
+            ///
+            /// # Errors
+            /// Returns `DaemonError::TraceContext` unless `hex` is exactly
+            #[doc = concat!(stringify!($len), " bytes of lowercase hex and not all zero.")]
+            pub fn from_hex(hex: &str) -> Result<Self> {
+                Self::from_bytes(decode_hex(hex, $what)?)
+            }
+
+            /// Returns the raw bytes.
+            #[must_use]
+            pub const fn to_bytes(self) -> [u8; $len] {
+                self.0
+            }
+
+            /// Returns the ID as lowercase hex.
+            #[must_use]
+            pub fn to_hex(self) -> String {
+                encode_hex(&self.0)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.to_hex())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = DaemonError;
+
+            fn from_str(hex: &str) -> Result<Self> {
+                Self::from_hex(hex)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.to_hex())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                let hex = String::deserialize(deserializer)?;
+                Self::from_hex(&hex).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+hex_id!(
+    /// 16-byte trace ID, shared by every span of a trace.
+    TraceId,
+    16,
+    "trace ID"
+);
+
+hex_id!(
+    /// 8-byte span ID.
+    SpanId,
+    8,
+    "span ID"
+);
+
+impl TraceId {
+    /// Returns a random trace ID.
+    #[must_use]
+    pub fn random() -> Self {
+        let bytes = Uuid::new_v4().into_bytes();
+        Self(if bytes == [0; 16] { [1; 16] } else { bytes })
+    }
+}
+
+impl SpanId {
+    /// Returns a random span ID.
+    #[must_use]
+    pub fn random() -> Self {
+        let (high, low) = Uuid::new_v4().as_u64_pair();
+        Self((high ^ low).max(1).to_be_bytes())
+    }
+}
+
+// =============================================================================
+// TraceFlags
+// =============================================================================
+
+/// The `trace-flags` field of a `traceparent`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TraceFlags(pub u8);
+
+impl TraceFlags {
+    /// The caller may have recorded the trace.
+    pub const SAMPLED: Self = Self(0x01);
+
+    /// Returns true if the sampled flag is set.
+    #[must_use]
+    pub const fn is_sampled(self) -> bool {
+        self.0 & Self::SAMPLED.0 != 0
+    }
+}
+
+// =============================================================================
+// TraceState
+// =============================================================================
+
+/// Vendor-specific trace data: the `tracestate` list, most recent first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceState(Vec<(String, String)>);
+
+impl TraceState {
+    /// Parses a `tracestate` header.
+    ///
+    /// Empty list members are skipped.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::TraceContext` if a member is malformed, a key
+    /// repeats or there are more than 32 members. Receivers should then
+    /// drop the whole `tracestate`, not the trace.
+    pub fn parse(header: &str) -> Result<Self> {
+        let mut state = Self::default();
+        for member in header.split(',').map(|m| m.trim_matches([' ', '\t'])) {
+            if member.is_empty() {
+                continue;
+            }
+            let (key, value) = member.split_once('=').ok_or_else(|| {
+                DaemonError::trace_context(format!("invalid tracestate member: {member:?}"))
+            })?;
+            validate_member(key, value)?;
+            if state.get(key).is_some() {
+                return Err(DaemonError::trace_context(format!(
+                    "duplicate tracestate key: {key:?}"
+                )));
+            }
+            state.0.push((key.to_string(), value.to_string()));
+        }
+
+        if state.0.len() > MAX_TRACESTATE_MEMBERS {
+            return Err(DaemonError::trace_context(format!(
+                "tracestate has {} members, at most {MAX_TRACESTATE_MEMBERS} allowed",
+                state.0.len()
+            )));
+        }
+        Ok(state)
+    }
+
+    /// Returns the value of `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Sets `key` and moves it to the front, as a vendor does for its own
+    /// entry. The oldest member is dropped past 32.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::TraceContext` if the key or value is invalid.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> Result<()> {
+        let (key, value) = (key.into(), value.into());
+        validate_member(&key, &value)?;
+        self.remove(&key);
+        self.0.insert(0, (key, value));
+        self.0.truncate(MAX_TRACESTATE_MEMBERS);
+        Ok(())
+    }
+
+    /// Removes `key`, returning its value.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let index = self.0.iter().position(|(k, _)| k == key)?;
+        Some(self.0.remove(index).1)
+    }
+
+    /// Returns true if there are no members.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the `tracestate` header.
+    #[must_use]
+    pub fn header(&self) -> String {
+        self.0
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl fmt::Display for TraceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.header())
+    }
+}
+
+/// Checks a `tracestate` key and value against the W3C grammar.
+fn validate_member(key: &str, value: &str) -> Result<()> {
+    let key_char = |c: char| {
+        c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '*' | '/')
+    };
+    // simple-key, or tenant-id@system-id
+    let valid_key = match key.split_once('@') {
+        None => {
+            key.len() <= 256
+                && key.starts_with(|c: char| c.is_ascii_lowercase())
+                && key.chars().all(key_char)
+        }
+        Some((tenant, system)) => {
+            (1..=241).contains(&tenant.len())
+                && tenant.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+                && tenant.chars().all(key_char)
+                && (1..=14).contains(&system.len())
+                && system.starts_with(|c: char| c.is_ascii_lowercase())
+                && system.chars().all(key_char)
+        }
+    };
+    if !valid_key {
+        return Err(DaemonError::trace_context(format!(
+            "invalid tracestate key: {key:?}"
+        )));
+    }
+
+    let value_char = |c: char| matches!(c, ' '..='~') && c != ',' && c != '=';
+    let valid_value =
+        (1..=256).contains(&value.len()) && value.chars().all(value_char) && !value.ends_with(' ');
+    if !valid_value {
+        return Err(DaemonError::trace_context(format!(
+            "invalid tracestate value for {key:?}: {value:?}"
+        )));
+    }
+    Ok(())
+}
+
+// =============================================================================
+// TraceContext
+// =============================================================================
+
+/// The position of a span in a distributed trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// Trace the span belongs to.
+    pub trace_id: TraceId,
+    /// The span itself; the parent of spans created from this context.
+    pub span_id: SpanId,
+    /// Trace flags.
+    pub trace_flags: TraceFlags,
+    /// Vendor-specific trace data.
+    pub trace_state: TraceState,
+}
+
+impl TraceContext {
+    /// Starts a new, sampled trace.
+    #[must_use]
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: TraceId::random(),
+            span_id: SpanId::random(),
+            trace_flags: TraceFlags::SAMPLED,
+            trace_state: TraceState::default(),
+        }
+    }
+
+    /// Returns a context for a new span below this one: same trace, flags
+    /// and state, new span ID.
+    #[must_use]
+    pub fn child(&self) -> Self {
+        Self {
+            span_id: SpanId::random(),
+            ..self.clone()
+        }
+    }
+
+    /// Parses a `traceparent` header.
+    ///
+    /// Version `00` must match exactly. Higher versions are parsed by
+    /// their version `00` prefix, as the specification asks.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::TraceContext` if the header is malformed.
+    pub fn from_traceparent(header: &str) -> Result<Self> {
+        let invalid = || DaemonError::trace_context(format!("invalid traceparent: {header:?}"));
+
+        let version = header.get(..2).ok_or_else(invalid)?;
+        let version = decode_hex::<1>(version, "traceparent version")?[0];
+        let fields = match version {
+            0xff => return Err(invalid()),
+            0x00 if header.len() != TRACEPARENT_LEN => return Err(invalid()),
+            // Future versions may append fields after a dash
+            _ if header.len() > TRACEPARENT_LEN && header.as_bytes()[TRACEPARENT_LEN] != b'-' => {
+                return Err(invalid());
+            }
+            _ => header.get(..TRACEPARENT_LEN).ok_or_else(invalid)?,
+        };
+
+        let mut parts = fields.split('-');
+        let (Some(_), Some(trace_id), Some(span_id), Some(flags), None) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return Err(invalid());
+        };
+
+        Ok(Self {
+            trace_id: TraceId::from_hex(trace_id)?,
+            span_id: SpanId::from_hex(span_id)?,
+            trace_flags: TraceFlags(decode_hex::<1>(flags, "trace flags")?[0]),
+            trace_state: TraceState::default(),
+        })
+    }
+
+    /// Parses a `traceparent` header and its `tracestate`.
+    ///
+    /// An invalid `tracestate` is dropped: the trace itself stays valid.
+    ///
+    /// # Errors
+    /// Returns `DaemonError::TraceContext` if `traceparent` is malformed.
+    pub fn from_headers(traceparent: &str, tracestate: Option<&str>) -> Result<Self> {
+        let mut context = Self::from_traceparent(traceparent)?;
+        if let Some(header) = tracestate {
+            context.trace_state = TraceState::parse(header).unwrap_or_else(|e| {
+                tracing::debug!(error = %e, "ignoring invalid tracestate");
+                TraceState::default()
+            });
+        }
+        Ok(context)
+    }
+
+    /// Returns the context this process was started with, from the
+    /// `TRACEPARENT` and `TRACESTATE` environment variables.
+    ///
+    /// `None` if `TRACEPARENT` is unset or invalid.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let traceparent = std::env::var(TRACEPARENT_ENV).ok()?;
+        let tracestate = std::env::var(TRACESTATE_ENV).ok();
+        Self::from_headers(&traceparent, tracestate.as_deref())
+            .map_err(|e| tracing::warn!(error = %e, "ignoring invalid {TRACEPARENT_ENV}"))
+            .ok()
+    }
+
+    /// Returns a context for a new span of this process: a child of
+    /// [`from_env`](Self::from_env), or a new root.
+    #[must_use]
+    pub fn inherited() -> Self {
+        Self::from_env().map_or_else(Self::new_root, |parent| parent.child())
+    }
+
+    /// Returns a context for a process spawned with the environment `env`.
+    ///
+    /// A valid `TRACEPARENT` in `env` is kept as is; otherwise the process
+    /// gets an [`inherited`](Self::inherited) context.
+    #[must_use]
+    pub fn for_spawn(env: &HashMap<String, String>) -> Self {
+        env.get(TRACEPARENT_ENV)
+            .and_then(|traceparent| {
+                let tracestate = env.get(TRACESTATE_ENV).map(String::as_str);
+                Self::from_headers(traceparent, tracestate).ok()
+            })
+            .unwrap_or_else(Self::inherited)
+    }
+
+    /// Returns true if the sampled flag is set.
+    #[must_use]
+    pub const fn is_sampled(&self) -> bool {
+        self.trace_flags.is_sampled()
+    }
+
+    /// Returns the `traceparent` header (version `00`).
+    #[must_use]
+    pub fn to_traceparent(&self) -> String {
+        format!(
+            "00-{}-{}-{:02x}",
+            self.trace_id, self.span_id, self.trace_flags.0
+        )
+    }
+
+    /// Returns the `tracestate` header, `None` if there is no state.
+    #[must_use]
+    pub fn to_tracestate(&self) -> Option<String> {
+        (!self.trace_state.is_empty()).then(|| self.trace_state.header())
+    }
+
+    /// Returns the environment variables that pass this context to a
+    /// child process.
+    #[must_use]
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let mut vars = vec![(TRACEPARENT_ENV, self.to_traceparent())];
+        vars.extend(self.to_tracestate().map(|state| (TRACESTATE_ENV, state)));
+        vars
+    }
+}
+
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_traceparent())
+    }
+}
+
+impl FromStr for TraceContext {
+    type Err = DaemonError;
+
+    fn from_str(header: &str) -> Result<Self> {
+        Self::from_traceparent(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_traceparent_roundtrip() {
+        let context = TraceContext::from_traceparent(EXAMPLE).unwrap();
+        assert_eq!(
+            context.trace_id.to_hex(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        assert_eq!(context.span_id.to_hex(), "00f067aa0ba902b7");
+        assert!(context.is_sampled());
+        assert_eq!(context.to_traceparent(), EXAMPLE);
+        assert_eq!(EXAMPLE.parse::<TraceContext>().unwrap(), context);
+    }
+
+    #[test]
+    fn test_traceparent_rejects_malformed() {
+        for header in [
+            "",
+            // Uppercase hex
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00F067AA0BA902B7-01",
+            // All-zero IDs
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            // Forbidden version
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            // Version 00 with trailing data
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-",
+            // Wrong field lengths and separators
+            "00-4bf92f3577b34da6a3ce929d0e0e473-600f067aa0ba902b7-01",
+            "00_4bf92f3577b34da6a3ce929d0e0e4736_00f067aa0ba902b7_01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-0g",
+        ] {
+            assert!(
+                TraceContext::from_traceparent(header).is_err(),
+                "accepted {header:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_traceparent_future_version() {
+        let future = "cc-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra";
+        let context = TraceContext::from_traceparent(future).unwrap();
+        assert_eq!(context.to_traceparent(), EXAMPLE);
+
+        let glued = "cc-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01extra";
+        assert!(TraceContext::from_traceparent(glued).is_err());
+    }
+
+    #[test]
+    fn test_child_keeps_trace() {
+        let root = TraceContext::new_root();
+        let child = root.child();
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_ne!(child.span_id, root.span_id);
+        assert_eq!(
+            TraceContext::from_traceparent(&root.to_traceparent()).unwrap(),
+            root
+        );
+        assert_ne!(TraceContext::new_root().trace_id, root.trace_id);
+    }
+
+    #[test]
+    fn test_tracestate() {
+        let mut state = TraceState::parse("rojo=00f067aa0ba902b7, ,congo=t61rcWkgMzE").unwrap();
+        assert_eq!(state.get("congo"), Some("t61rcWkgMzE"));
+        assert_eq!(state.header(), "rojo=00f067aa0ba902b7,congo=t61rcWkgMzE");
+
+        state.insert("congo", "x").unwrap();
+        assert_eq!(state.header(), "congo=x,rojo=00f067aa0ba902b7");
+        state.insert("tenant@vendor", "1").unwrap();
+        assert_eq!(state.remove("rojo").as_deref(), Some("00f067aa0ba902b7"));
+        assert_eq!(state.header(), "tenant@vendor=1,congo=x");
+
+        for header in ["Rojo=1", "rojo", "rojo=a,rojo=b", "rojo=a=b", "rojo="] {
+            assert!(TraceState::parse(header).is_err(), "accepted {header:?}");
+        }
+        let many: Vec<String> = (0..33).map(|i| format!("k{i}=v")).collect();
+        assert!(TraceState::parse(&many.join(",")).is_err());
+    }
+
+    #[test]
+    fn test_invalid_tracestate_keeps_trace() {
+        let context = TraceContext::from_headers(EXAMPLE, Some("not valid")).unwrap();
+        assert!(context.trace_state.is_empty());
+        assert_eq!(context.to_tracestate(), None);
+
+        let context = TraceContext::from_headers(EXAMPLE, Some("rojo=1")).unwrap();
+        assert_eq!(
+            context.env_vars(),
+            [
+                (TRACEPARENT_ENV, EXAMPLE.to_string()),
+                (TRACESTATE_ENV, "rojo=1".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_for_spawn_keeps_configured_parent() {
+        let mut env = HashMap::new();
+        env.insert(TRACEPARENT_ENV.to_string(), EXAMPLE.to_string());
+        assert_eq!(TraceContext::for_spawn(&env).to_traceparent(), EXAMPLE);
+
+        env.insert(TRACEPARENT_ENV.to_string(), "garbage".to_string());
+        assert_ne!(TraceContext::for_spawn(&env).to_traceparent(), "garbage");
+    }
+
+    #[test]
+    fn test_ids_serialize_as_hex() {
+        let context = TraceContext::from_traceparent(EXAMPLE).unwrap();
+        let json = serde_json::to_string(&context.span_id).unwrap();
+        assert_eq!(json, "\"00f067aa0ba902b7\"");
+        let back: SpanId = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, context.span_id);
+        assert!(serde_json::from_str::<SpanId>("\"0000000000000000\"").is_err());
+    }
+}
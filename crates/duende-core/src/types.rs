@@ -52,6 +52,10 @@ impl std::fmt::Display for DaemonId {
 ///                  ↓                   ↓
 ///               Failed ←───────────────┘
 /// ```
+///
+/// Every non-terminal state may fail, `Starting`/`Running`/`Paused` may
+/// stop, and `Stopped`/`Failed` may restart (→ `Starting`). See
+/// [`can_transition_to`](Self::can_transition_to) for the full table.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DaemonStatus {
     /// Daemon has been created but not started.
@@ -88,6 +92,47 @@ impl DaemonStatus {
     pub const fn can_signal(&self) -> bool {
         matches!(self, Self::Running | Self::Paused | Self::Stopping)
     }
+
+    /// Returns true if the state machine allows moving to `next`.
+    ///
+    /// | From       | To                                  |
+    /// |------------|-------------------------------------|
+    /// | `Created`  | `Starting`, `Failed`                |
+    /// | `Starting` | `Running`, `Stopping`, `Failed`     |
+    /// | `Running`  | `Paused`, `Stopping`, `Failed`      |
+    /// | `Paused`   | `Running`, `Stopping`, `Failed`     |
+    /// | `Stopping` | `Stopped`, `Failed`                 |
+    /// | `Stopped`  | `Starting`                          |
+    /// | `Failed`   | `Starting`                          |
+    #[must_use]
+    pub const fn can_transition_to(&self, next: &Self) -> bool {
+        matches!(
+            (self, next),
+            (Self::Created, Self::Starting | Self::Failed(_))
+                | (Self::Starting | Self::Paused, Self::Running)
+                | (Self::Running, Self::Paused)
+                | (
+                    Self::Starting | Self::Running | Self::Paused,
+                    Self::Stopping | Self::Failed(_)
+                )
+                | (Self::Stopping, Self::Stopped | Self::Failed(_))
+                | (Self::Stopped | Self::Failed(_), Self::Starting)
+        )
+    }
+}
+
+/// A recorded lifecycle state change.
+///
+/// # Toyota Way: Genchi Genbutsu (現地現物)
+/// The transition log is the incident timeline: what happened, and when.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    /// State before the transition.
+    pub from: DaemonStatus,
+    /// State after the transition.
+    pub to: DaemonStatus,
+    /// Wall-clock time of the transition.
+    pub at: std::time::SystemTime,
 }
 
 /// Reason for daemon failure.
@@ -103,6 +148,14 @@ pub enum FailureReason {
     PolicyViolation,
     /// Health check timeout.
     HealthCheckTimeout,
+    /// Liveness or startup probe failed.
+    HealthCheckFailed,
+    /// A required dependency failed.
+    DependencyFailed,
+    /// Started too often within the start-limit interval (quarantined).
+    StartLimitHit,
+    /// No longer running when the manager reattached after a restart.
+    Lost,
     /// Internal error.
     Internal,
 }
@@ -146,6 +199,27 @@ pub enum Signal {
 }
 
 impl Signal {
+    /// All signal variants.
+    pub const ALL: [Self; 9] = [
+        Self::Hup,
+        Self::Int,
+        Self::Quit,
+        Self::Term,
+        Self::Kill,
+        Self::Usr1,
+        Self::Usr2,
+        Self::Stop,
+        Self::Cont,
+    ];
+
+    /// Returns true if a process can install a handler for this signal.
+    ///
+    /// KILL and STOP are enforced by the kernel and can never be caught.
+    #[must_use]
+    pub const fn is_catchable(&self) -> bool {
+        !matches!(self, Self::Kill | Self::Stop)
+    }
+
     /// Returns the Unix signal number.
     #[must_use]
     pub const fn as_i32(&self) -> i32 {
@@ -208,6 +282,20 @@ impl HealthStatus {
         }
     }
 
+    /// Creates a status from individual checks: healthy if all passed.
+    #[must_use]
+    pub fn from_checks(checks: Vec<HealthCheck>, latency_ms: u64) -> Self {
+        Self {
+            healthy: checks.iter().all(|check| check.passed),
+            checks,
+            latency_ms,
+            last_check_epoch_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        }
+    }
+
     /// Creates an unhealthy status.
     #[must_use]
     pub fn unhealthy(reason: impl Into<String>, latency_ms: u64) -> Self {
@@ -231,6 +319,46 @@ impl HealthStatus {
     pub fn is_healthy(&self) -> bool {
         self.healthy
     }
+
+    /// Returns the message of the first failed check, if any.
+    #[must_use]
+    pub fn reason(&self) -> Option<&str> {
+        self.checks
+            .iter()
+            .find(|check| !check.passed)
+            .and_then(|check| check.message.as_deref())
+    }
+}
+
+/// Kind of health probe.
+///
+/// Each answers a different question, and failing it has a different
+/// consequence:
+///
+/// - `Liveness`: is the daemon working at all? Failing restarts it.
+/// - `Readiness`: can it serve right now? Failing only marks it not ready.
+/// - `Startup`: has it finished starting? Liveness and readiness are not
+///   probed until it passes; not passing within the grace period restarts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeKind {
+    /// Is the daemon alive?
+    #[default]
+    Liveness,
+    /// Is the daemon ready to serve?
+    Readiness,
+    /// Has the daemon finished starting?
+    Startup,
+}
+
+impl std::fmt::Display for ProbeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Liveness => write!(f, "liveness"),
+            Self::Readiness => write!(f, "readiness"),
+            Self::Startup => write!(f, "startup"),
+        }
+    }
 }
 
 /// Individual health check result.
@@ -291,6 +419,45 @@ mod tests {
         assert!(!DaemonStatus::Stopped.can_signal());
     }
 
+    #[test]
+    fn test_daemon_status_transition_table() {
+        use DaemonStatus::{Created, Paused, Running, Starting, Stopped, Stopping};
+        let failed = DaemonStatus::Failed(FailureReason::Internal);
+
+        let legal = [
+            (Created, Starting),
+            (Created, failed),
+            (Starting, Running),
+            (Starting, Stopping),
+            (Starting, failed),
+            (Running, Paused),
+            (Running, Stopping),
+            (Running, failed),
+            (Paused, Running),
+            (Paused, Stopping),
+            (Paused, failed),
+            (Stopping, Stopped),
+            (Stopping, failed),
+            (Stopped, Starting),
+            (failed, Starting),
+        ];
+        let all = [
+            Created, Starting, Running, Paused, Stopping, Stopped, failed,
+        ];
+
+        for from in all {
+            for to in all {
+                assert_eq!(
+                    from.can_transition_to(&to),
+                    legal.contains(&(from, to)),
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_daemon_status_all_variants() {
         // Test all non-terminal states
@@ -316,6 +483,7 @@ mod tests {
             FailureReason::ResourceExhausted,
             FailureReason::PolicyViolation,
             FailureReason::HealthCheckTimeout,
+            FailureReason::HealthCheckFailed,
             FailureReason::Internal,
         ] {
             let status = DaemonStatus::Failed(reason);
@@ -367,6 +535,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_signal_catchable() {
+        assert_eq!(Signal::ALL.len(), 9);
+        assert!(!Signal::Kill.is_catchable());
+        assert!(!Signal::Stop.is_catchable());
+        assert!(Signal::Term.is_catchable());
+        assert!(Signal::Hup.is_catchable());
+    }
+
     #[test]
     fn test_health_status() {
         let healthy = HealthStatus::healthy(5);
@@ -395,6 +572,24 @@ mod tests {
         assert!(unhealthy.checks[0].message.is_some());
     }
 
+    #[test]
+    fn test_health_status_reason() {
+        assert_eq!(HealthStatus::healthy(1).reason(), None);
+        assert_eq!(
+            HealthStatus::unhealthy("cache cold", 1).reason(),
+            Some("cache cold")
+        );
+    }
+
+    #[test]
+    fn test_probe_kind_display_and_serde() {
+        assert_eq!(ProbeKind::Readiness.to_string(), "readiness");
+        let json = serde_json::to_string(&ProbeKind::Startup).unwrap();
+        assert_eq!(json, "\"startup\"");
+        let kind: ProbeKind = serde_json::from_str("\"liveness\"").unwrap();
+        assert_eq!(kind, ProbeKind::Liveness);
+    }
+
     #[test]
     fn test_health_check_struct() {
         let check = HealthCheck {
@@ -534,6 +729,7 @@ mod property_tests {
             FailureReason::ResourceExhausted,
             FailureReason::PolicyViolation,
             FailureReason::HealthCheckTimeout,
+            FailureReason::HealthCheckFailed,
             FailureReason::Internal,
         ])) {
             let status = DaemonStatus::Failed(reason);
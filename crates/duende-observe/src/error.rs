@@ -18,6 +18,10 @@ pub enum ObserveError {
     #[error("export error: {0}")]
     Export(String),
 
+    /// Daemon not registered.
+    #[error("not found: {0}")]
+    NotFound(String),
+
     /// I/O error.
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -41,6 +45,12 @@ impl ObserveError {
     pub fn export(msg: impl Into<String>) -> Self {
         Self::Export(msg.into())
     }
+
+    /// Creates a not-found error.
+    #[must_use]
+    pub fn not_found(msg: impl Into<String>) -> Self {
+        Self::NotFound(msg.into())
+    }
 }
 
 #[cfg(test)]
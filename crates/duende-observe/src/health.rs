@@ -5,16 +5,23 @@
 //!
 //! # Toyota Way: Jidoka (自働化)
 //! Automatic detection and reporting of unhealthy daemons.
+//!
+//! [`HealthMonitor::spawn`] drives the checks: every `check_interval` it
+//! checks each registered daemon, broadcasts a [`HealthEvent`] and records
+//! the result with [`DaemonManager::update_health`], so restart decisions
+//! can be made by subscribers.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use duende_core::{DaemonId, HealthStatus};
-use tokio::sync::{broadcast, RwLock};
-use tokio::time::interval;
+use duende_core::checks;
+use duende_core::{DaemonId, DaemonManager, HealthProbe, HealthStatus, ProbeKind};
+use tokio::sync::{RwLock, broadcast};
+use tokio::task::JoinHandle;
 
 use crate::error::{ObserveError, Result};
+use crate::periodic::Periodic;
 
 // =============================================================================
 // HealthEvent
@@ -136,6 +143,8 @@ pub struct DaemonHealthState {
     pub consecutive_failures: u32,
     /// Consecutive success count.
     pub consecutive_successes: u32,
+    /// Consecutive failures before the current run of successes.
+    pub failures_before_successes: u32,
     /// Whether the daemon is currently considered healthy.
     pub is_healthy: bool,
     /// Total checks performed.
@@ -154,6 +163,7 @@ impl DaemonHealthState {
             last_check: None,
             consecutive_failures: 0,
             consecutive_successes: 0,
+            failures_before_successes: 0,
             is_healthy: true, // Assume healthy until proven otherwise
             total_checks: 0,
             total_failures: 0,
@@ -164,6 +174,9 @@ impl DaemonHealthState {
     pub fn record_success(&mut self, status: HealthStatus) {
         self.last_status = Some(status);
         self.last_check = Some(Instant::now());
+        if self.consecutive_successes == 0 {
+            self.failures_before_successes = self.consecutive_failures;
+        }
         self.consecutive_failures = 0;
         self.consecutive_successes += 1;
         self.total_checks += 1;
@@ -204,15 +217,32 @@ impl DaemonHealthState {
 ///
 /// Provides periodic health checking with configurable thresholds
 /// and event broadcasting for health state transitions.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let monitor = Arc::new(HealthMonitor::new(HealthConfig::new()));
+/// monitor.register_probe(id, daemon.health_probe().unwrap()).await;
+///
+/// let mut events = monitor.subscribe();
+/// let driver = Arc::clone(&monitor).spawn(Arc::clone(&manager));
+/// while let Ok(event) = events.recv().await {
+///     if let HealthEvent::Unhealthy { id, .. } = event {
+///         // restart decision
+///     }
+/// }
+/// ```
 pub struct HealthMonitor {
     /// Configuration.
     config: HealthConfig,
     /// Health state per daemon.
     states: Arc<RwLock<HashMap<DaemonId, DaemonHealthState>>>,
+    /// Probes of daemons checked in process.
+    probes: RwLock<HashMap<DaemonId, Arc<dyn HealthProbe>>>,
     /// Event broadcaster.
     event_tx: broadcast::Sender<HealthEvent>,
-    /// Whether the monitor is running.
-    running: Arc<RwLock<bool>>,
+    /// The health loop.
+    running: Periodic,
 }
 
 impl HealthMonitor {
@@ -224,8 +254,9 @@ impl HealthMonitor {
         Self {
             config,
             states: Arc::new(RwLock::new(HashMap::new())),
+            probes: RwLock::new(HashMap::new()),
             event_tx,
-            running: Arc::new(RwLock::new(false)),
+            running: Periodic::new(),
         }
     }
 
@@ -249,15 +280,31 @@ impl HealthMonitor {
 
     /// Registers a daemon for health monitoring.
     pub async fn register(&self, id: DaemonId) {
-        let mut states = self.states.write().await;
-        states.insert(id, DaemonHealthState::new(id));
+        self.states
+            .write()
+            .await
+            .insert(id, DaemonHealthState::new(id));
         tracing::debug!(id = %id, "registered daemon for health monitoring");
     }
 
+    /// Registers a daemon that is checked through `probe`.
+    ///
+    /// Daemons registered without a probe are checked through the
+    /// external liveness checks of their configuration.
+    pub async fn register_probe(&self, id: DaemonId, probe: Arc<dyn HealthProbe>) {
+        self.probes.write().await.insert(id, probe);
+        self.states
+            .write()
+            .await
+            .entry(id)
+            .or_insert_with(|| DaemonHealthState::new(id));
+        tracing::debug!(id = %id, "registered daemon probe for health monitoring");
+    }
+
     /// Unregisters a daemon from health monitoring.
     pub async fn unregister(&self, id: DaemonId) {
-        let mut states = self.states.write().await;
-        states.remove(&id);
+        self.probes.write().await.remove(&id);
+        self.states.write().await.remove(&id);
         tracing::debug!(id = %id, "unregistered daemon from health monitoring");
     }
 
@@ -296,12 +343,13 @@ impl HealthMonitor {
     /// - Updating health state
     /// - Checking thresholds
     /// - Broadcasting events
+    #[allow(clippy::similar_names)]
     pub async fn record_check(&self, id: DaemonId, status: HealthStatus) -> Result<()> {
         let mut states = self.states.write().await;
 
         let state = states
             .get_mut(&id)
-            .ok_or_else(|| ObserveError::NotFound(format!("daemon {} not registered", id)))?;
+            .ok_or_else(|| ObserveError::not_found(format!("daemon {} not registered", id)))?;
 
         let was_healthy = state.is_healthy;
 
@@ -310,12 +358,12 @@ impl HealthMonitor {
 
             // Check recovery threshold
             if !was_healthy && state.consecutive_successes >= self.config.recovery_threshold {
-                let failures_before = state.total_failures - state.consecutive_successes as u64;
+                let failures_before = state.failures_before_successes;
                 state.is_healthy = true;
 
                 let _ = self.event_tx.send(HealthEvent::Recovered {
                     id,
-                    failures_before_recovery: failures_before as u32,
+                    failures_before_recovery: failures_before,
                 });
 
                 tracing::info!(id = %id, "daemon recovered after {} failures", failures_before);
@@ -353,6 +401,7 @@ impl HealthMonitor {
                 });
             }
         }
+        drop(states);
 
         Ok(())
     }
@@ -363,7 +412,7 @@ impl HealthMonitor {
 
         let state = states
             .get_mut(&id)
-            .ok_or_else(|| ObserveError::NotFound(format!("daemon {} not registered", id)))?;
+            .ok_or_else(|| ObserveError::not_found(format!("daemon {} not registered", id)))?;
 
         state.record_failure(None);
 
@@ -382,10 +431,95 @@ impl HealthMonitor {
                 "daemon marked unhealthy due to timeouts"
             );
         }
+        drop(states);
 
         Ok(())
     }
 
+    /// Checks every registered daemon once.
+    ///
+    /// Each check is bounded by `check_timeout`; results are recorded,
+    /// broadcast and fed to [`DaemonManager::update_health`].
+    pub async fn check_all(&self, manager: &DaemonManager) {
+        let ids: Vec<DaemonId> = self.states.read().await.keys().copied().collect();
+        for id in ids {
+            self.check(manager, id).await;
+        }
+    }
+
+    /// Checks one daemon, through its probe or its external checks.
+    ///
+    /// Daemons with neither are skipped.
+    async fn check(&self, manager: &DaemonManager, id: DaemonId) {
+        let timeout = self.config.check_timeout;
+        let started = Instant::now();
+        let probe = self.probes.read().await.get(&id).cloned();
+
+        let result = if let Some(probe) = probe {
+            tokio::time::timeout(timeout, probe.health_check()).await
+        } else {
+            let Ok(config) = manager.get_config(id).await else {
+                return;
+            };
+            let external =
+                checks::run_checks(&config.health_check.checks, ProbeKind::Liveness, timeout, 0);
+            match tokio::time::timeout(timeout, external).await {
+                Ok(Some(status)) => Ok(status),
+                Ok(None) => return,
+                Err(elapsed) => Err(elapsed),
+            }
+        };
+
+        let (status, recorded) = match result {
+            Ok(status) => (status.clone(), self.record_check(id, status).await),
+            Err(_) => (
+                HealthStatus::unhealthy(
+                    format!("health check timed out after {timeout:?}"),
+                    started.elapsed().as_millis() as u64,
+                ),
+                self.record_timeout(id).await,
+            ),
+        };
+        if recorded.is_err() {
+            // Unregistered while being checked
+            return;
+        }
+
+        if let Err(e) = manager.update_health(id, status).await {
+            tracing::debug!(id = %id, error = %e, "health not recorded in manager");
+        }
+    }
+
+    /// Starts the health loop: checks every registered daemon each
+    /// `check_interval` until [`stop`](Self::stop) is called. Replaces a
+    /// running health loop.
+    pub fn spawn(self: Arc<Self>, manager: Arc<DaemonManager>) -> JoinHandle<()> {
+        let ticks = self
+            .running
+            .start("health check", self.config.check_interval);
+
+        tokio::spawn(async move {
+            let Some(mut ticks) = ticks else {
+                return;
+            };
+            while ticks.tick().await {
+                self.check_all(&manager).await;
+            }
+            tracing::debug!("health loop stopped");
+        })
+    }
+
+    /// Stops the health loop.
+    pub fn stop(&self) {
+        self.running.stop();
+    }
+
+    /// Returns true while the health loop is running.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.running.is_running()
+    }
+
     /// Returns aggregate health statistics.
     pub async fn statistics(&self) -> HealthStatistics {
         let states = self.states.read().await;
@@ -397,10 +531,10 @@ impl HealthMonitor {
         let total_checks: u64 = states.values().map(|s| s.total_checks).sum();
         let total_failures: u64 = states.values().map(|s| s.total_failures).sum();
 
-        let avg_failure_rate = if !states.is_empty() {
-            states.values().map(|s| s.failure_rate()).sum::<f64>() / states.len() as f64
-        } else {
+        let avg_failure_rate = if states.is_empty() {
             0.0
+        } else {
+            states.values().map(|s| s.failure_rate()).sum::<f64>() / states.len() as f64
         };
 
         HealthStatistics {
@@ -657,11 +791,24 @@ mod tests {
         let state = monitor.get_state(id).await.unwrap();
         assert!(!state.is_healthy); // Not recovered yet
 
-        // Second success - should recover
+        // Fail again, then recover after two successes
+        monitor
+            .record_check(id, HealthStatus::unhealthy("error", 5))
+            .await
+            .unwrap();
+        monitor.record_check(id, healthy.clone()).await.unwrap();
+        let mut events = monitor.subscribe();
         monitor.record_check(id, healthy).await.unwrap();
 
         let state = monitor.get_state(id).await.unwrap();
         assert!(state.is_healthy);
+        match events.recv().await.unwrap() {
+            HealthEvent::Recovered {
+                failures_before_recovery,
+                ..
+            } => assert_eq!(failures_before_recovery, 1), // not total_failures (2)
+            other => panic!("expected Recovered, got {other:?}"),
+        }
     }
 
     #[tokio::test]
@@ -803,4 +950,202 @@ mod tests {
             _ => panic!("Expected Healthy event"),
         }
     }
+
+    // -------------------------------------------------------------------------
+    // Health Loop Tests
+    // -------------------------------------------------------------------------
+
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use async_trait::async_trait;
+    use duende_core::{
+        CheckKind, Daemon, DaemonConfig, DaemonContext, DaemonMetrics, ExitReason, ExternalCheck,
+        RestartPolicy,
+    };
+
+    struct TestDaemon {
+        id: DaemonId,
+        metrics: DaemonMetrics,
+    }
+
+    #[async_trait]
+    impl Daemon for TestDaemon {
+        fn id(&self) -> DaemonId {
+            self.id
+        }
+        fn name(&self) -> &str {
+            "test-health"
+        }
+        async fn init(&mut self, _config: &DaemonConfig) -> duende_core::error::Result<()> {
+            Ok(())
+        }
+        async fn run(
+            &mut self,
+            _ctx: &mut DaemonContext,
+        ) -> duende_core::error::Result<ExitReason> {
+            Ok(ExitReason::Graceful)
+        }
+        async fn shutdown(&mut self, _timeout: Duration) -> duende_core::error::Result<()> {
+            Ok(())
+        }
+        async fn health_check(&self) -> HealthStatus {
+            HealthStatus::healthy(0)
+        }
+        fn metrics(&self) -> &DaemonMetrics {
+            &self.metrics
+        }
+    }
+
+    #[derive(Default)]
+    struct TestProbe {
+        unhealthy: AtomicBool,
+        delay: Option<Duration>,
+    }
+
+    #[async_trait]
+    impl HealthProbe for TestProbe {
+        async fn health_check(&self) -> HealthStatus {
+            if let Some(delay) = self.delay {
+                tokio::time::sleep(delay).await;
+            }
+            if self.unhealthy.load(Ordering::SeqCst) {
+                HealthStatus::unhealthy("probe failed", 0)
+            } else {
+                HealthStatus::healthy(0)
+            }
+        }
+    }
+
+    async fn managed(config: DaemonConfig) -> (Arc<DaemonManager>, DaemonId) {
+        let manager = Arc::new(DaemonManager::new());
+        let daemon = TestDaemon {
+            id: DaemonId::new(),
+            metrics: DaemonMetrics::new(),
+        };
+        let id = manager
+            .register(Box::new(daemon), config, RestartPolicy::Never)
+            .await
+            .unwrap();
+        (manager, id)
+    }
+
+    #[tokio::test]
+    async fn test_check_all_feeds_manager() {
+        let (manager, id) = managed(DaemonConfig::new("test-health", "/bin/test")).await;
+        let monitor = HealthMonitor::new(HealthConfig::new().with_failure_threshold(1));
+        let probe = Arc::new(TestProbe::default());
+        monitor.register_probe(id, probe.clone()).await;
+        let mut rx = monitor.subscribe();
+
+        monitor.check_all(&manager).await;
+        assert!(matches!(rx.try_recv(), Ok(HealthEvent::Healthy { .. })));
+        assert!(manager.get_health(id).await.unwrap().unwrap().is_healthy());
+
+        probe.unhealthy.store(true, Ordering::SeqCst);
+        monitor.check_all(&manager).await;
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(HealthEvent::Unhealthy {
+                failure_count: 1,
+                ..
+            })
+        ));
+        assert!(!manager.get_health(id).await.unwrap().unwrap().is_healthy());
+        assert!(!monitor.get_state(id).await.unwrap().is_healthy);
+    }
+
+    #[tokio::test]
+    async fn test_check_times_out_slow_probe() {
+        let (manager, id) = managed(DaemonConfig::new("test-health", "/bin/test")).await;
+        let monitor =
+            HealthMonitor::new(HealthConfig::new().with_check_timeout(Duration::from_millis(20)));
+        let probe = TestProbe {
+            delay: Some(Duration::from_secs(5)),
+            ..TestProbe::default()
+        };
+        monitor.register_probe(id, Arc::new(probe)).await;
+        let mut rx = monitor.subscribe();
+
+        monitor.check_all(&manager).await;
+        assert!(matches!(rx.try_recv(), Ok(HealthEvent::Timeout { .. })));
+        let health = manager.get_health(id).await.unwrap().unwrap();
+        assert!(!health.is_healthy());
+        assert!(health.reason().unwrap().contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_check_runs_external_checks_without_probe() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let mut config = DaemonConfig::new("test-health", "/bin/test");
+        config
+            .health_check
+            .checks
+            .push(ExternalCheck::new(CheckKind::Tcp { address }));
+
+        let (manager, id) = managed(config).await;
+        let monitor = HealthMonitor::with_defaults();
+        monitor.register(id).await;
+
+        monitor.check_all(&manager).await;
+        assert!(manager.get_health(id).await.unwrap().unwrap().is_healthy());
+
+        // Neither probe nor external checks: nothing to record
+        let (manager, id) = managed(DaemonConfig::new("test-health", "/bin/test")).await;
+        monitor.register(id).await;
+        monitor.check_all(&manager).await;
+        assert!(manager.get_health(id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_health_loop_spawn_and_stop() {
+        let (manager, id) = managed(DaemonConfig::new("test-health", "/bin/test")).await;
+        let monitor = Arc::new(HealthMonitor::new(
+            HealthConfig::new().with_check_interval(Duration::from_millis(10)),
+        ));
+        monitor
+            .register_probe(id, Arc::new(TestProbe::default()))
+            .await;
+
+        let handle = Arc::clone(&monitor).spawn(Arc::clone(&manager));
+        assert!(monitor.is_running());
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while manager.get_health(id).await.unwrap().is_none() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        monitor.stop();
+        assert!(!monitor.is_running());
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_health_loop_second_spawn_replaces_first() {
+        let (manager, _) = managed(DaemonConfig::new("test-health", "/bin/test")).await;
+        let monitor = Arc::new(HealthMonitor::new(
+            HealthConfig::new().with_check_interval(Duration::from_secs(3600)),
+        ));
+
+        let first = Arc::clone(&monitor).spawn(Arc::clone(&manager));
+        let second = Arc::clone(&monitor).spawn(Arc::clone(&manager));
+        tokio::time::timeout(Duration::from_secs(1), first)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(monitor.is_running());
+        assert!(!second.is_finished());
+
+        monitor.stop();
+        tokio::time::timeout(Duration::from_secs(1), second)
+            .await
+            .unwrap()
+            .unwrap();
+    }
 }
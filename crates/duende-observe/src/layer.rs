@@ -0,0 +1,279 @@
+//! Span layer - `tracing` spans as OTLP spans.
+//!
+//! [`OtlpLayer`] is a `tracing-subscriber` layer that turns every span it
+//! sees into a [`SpanData`] when the span closes, for
+//! [`OtlpExporter`] to push with the next export. Span fields become
+//! attributes; events inside a span become span events.
+//!
+//! Spans nest as in `tracing`. A root span with `trace_id` and
+//! `parent_span_id` fields, as [lifecycle spans] of a traced run have,
+//! joins that trace below that span: `init`, `run` and `shutdown` then
+//! show up inside the run's `daemon.run` span. A span whose `outcome` is
+//! `error`, `unhealthy`, `stuck` or `unsignalable`, or that has an
+//! `error` field, has error status.
+//!
+//! # Toyota Way: Visual Management (目で見る管理)
+//! What the framework does for a daemon is seen in the same trace view
+//! as what the daemon does itself.
+//!
+//! [lifecycle spans]: duende_core::spans
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use duende_core::{SpanId, TraceId};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber, span};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::otlp::{SpanData, SpanEvent, SpanStatus};
+
+/// Default number of finished spans kept until the next export.
+pub const DEFAULT_SPAN_QUEUE_CAPACITY: usize = 4096;
+
+/// Finished spans waiting for export, shared by a layer and its exporter.
+#[derive(Debug)]
+pub(crate) struct SpanQueue {
+    spans: Mutex<VecDeque<SpanData>>,
+    capacity: usize,
+}
+
+impl SpanQueue {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            spans: Mutex::new(VecDeque::new()),
+            capacity,
+        }
+    }
+
+    /// Queues a span, dropping the oldest past capacity.
+    fn push(&self, span: SpanData) {
+        if let Ok(mut spans) = self.spans.lock() {
+            if spans.len() >= self.capacity {
+                spans.pop_front();
+            }
+            spans.push_back(span);
+        }
+    }
+
+    /// Takes all queued spans, oldest first.
+    pub(crate) fn drain(&self) -> Vec<SpanData> {
+        self.spans
+            .lock()
+            .map(|mut spans| spans.drain(..).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// `tracing-subscriber` layer that queues finished spans for an
+/// [`OtlpExporter`](crate::otlp::OtlpExporter).
+///
+/// Created by [`OtlpExporter::layer`](crate::otlp::OtlpExporter::layer).
+/// It records every span it sees; add a filter to export fewer.
+#[derive(Debug, Clone)]
+pub struct OtlpLayer {
+    queue: Arc<SpanQueue>,
+}
+
+impl OtlpLayer {
+    pub(crate) const fn new(queue: Arc<SpanQueue>) -> Self {
+        Self { queue }
+    }
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let name = attrs.metadata().name();
+        let now = SystemTime::now();
+        let parent = span.parent().and_then(|parent| {
+            let extensions = parent.extensions();
+            extensions
+                .get::<SpanData>()
+                .map(|parent| (parent.trace_id, parent.span_id))
+        });
+        let mut data = SpanData::new(name, now);
+        if let Some((trace_id, parent_span_id)) = parent {
+            data.trace_id = trace_id;
+            data.parent_span_id = Some(parent_span_id);
+        }
+
+        attrs.record(&mut Fields(&mut data.attributes));
+        adopt_trace(&mut data);
+        span.extensions_mut().insert(data);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(data) = extensions.get_mut::<SpanData>() {
+            values.record(&mut Fields(&mut data.attributes));
+            adopt_trace(data);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let mut attributes = BTreeMap::new();
+        event.record(&mut Fields(&mut attributes));
+        let name = attributes
+            .remove("message")
+            .unwrap_or_else(|| event.metadata().name().to_string());
+
+        let mut extensions = span.extensions_mut();
+        if let Some(data) = extensions.get_mut::<SpanData>() {
+            data.events.push(SpanEvent {
+                name,
+                at: SystemTime::now(),
+                attributes,
+            });
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(mut data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+        data.end = SystemTime::now();
+        data.status = status(&data.attributes);
+        self.queue.push(data);
+    }
+}
+
+/// Moves a root span into the trace named by its `trace_id` and
+/// `parent_span_id` fields, once both are set.
+fn adopt_trace(data: &mut SpanData) {
+    if data.parent_span_id.is_some() {
+        return;
+    }
+    let trace_id = data
+        .attributes
+        .get("trace_id")
+        .map(|id| id.parse::<TraceId>());
+    let parent = data
+        .attributes
+        .get("parent_span_id")
+        .map(|id| id.parse::<SpanId>());
+    if let (Some(Ok(trace_id)), Some(Ok(parent))) = (trace_id, parent) {
+        data.trace_id = trace_id;
+        data.parent_span_id = Some(parent);
+        data.attributes.remove("trace_id");
+        data.attributes.remove("parent_span_id");
+    }
+}
+
+/// Returns the status a span's `outcome` and `error` fields describe.
+fn status(attributes: &BTreeMap<String, String>) -> SpanStatus {
+    let error = attributes.get("error");
+    match (attributes.get("outcome").map(String::as_str), error) {
+        (Some(outcome @ ("error" | "unhealthy" | "stuck" | "unsignalable")), error) => {
+            SpanStatus::Error(error.map_or_else(|| outcome.to_string(), Clone::clone))
+        }
+        (_, Some(error)) => SpanStatus::Error(error.clone()),
+        (Some(_), None) => SpanStatus::Ok,
+        (None, None) => SpanStatus::Unset,
+    }
+}
+
+/// Records fields as string attributes.
+struct Fields<'a>(&'a mut BTreeMap<String, String>);
+
+impl Visit for Fields<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn capture(f: impl FnOnce()) -> Vec<SpanData> {
+        let queue = Arc::new(SpanQueue::new(DEFAULT_SPAN_QUEUE_CAPACITY));
+        let subscriber = tracing_subscriber::registry().with(OtlpLayer::new(Arc::clone(&queue)));
+        tracing::subscriber::with_default(subscriber, f);
+        queue.drain()
+    }
+
+    #[test]
+    fn test_spans_nest_and_record_fields() {
+        let spans = capture(|| {
+            let outer = tracing::info_span!("outer", daemon = "api");
+            let _entered = outer.enter();
+            let inner =
+                tracing::info_span!("inner", attempt = 2_u32, outcome = tracing::field::Empty);
+            inner.in_scope(|| tracing::info!(queue = 3, "draining"));
+            inner.record("outcome", "ok");
+        });
+
+        let [inner, outer] = spans.as_slice() else {
+            panic!("expected two spans, got {spans:?}");
+        };
+        assert_eq!(outer.name, "outer");
+        assert_eq!(outer.attributes["daemon"], "api");
+        assert_eq!(outer.parent_span_id, None);
+        assert_eq!(inner.trace_id, outer.trace_id);
+        assert_eq!(inner.parent_span_id, Some(outer.span_id));
+        assert_eq!(inner.attributes["attempt"], "2");
+        assert_eq!(inner.status, SpanStatus::Ok);
+        assert_eq!(inner.events[0].name, "draining");
+        assert_eq!(inner.events[0].attributes["queue"], "3");
+        assert_eq!(outer.status, SpanStatus::Unset);
+    }
+
+    #[test]
+    fn test_root_span_adopts_recorded_trace() {
+        let trace = duende_core::TraceContext::new_root();
+        let spans = capture(|| {
+            let span = tracing::info_span!(
+                "init",
+                trace_id = tracing::field::Empty,
+                parent_span_id = tracing::field::Empty,
+                outcome = tracing::field::Empty,
+                error = tracing::field::Empty,
+            );
+            span.record("trace_id", tracing::field::display(trace.trace_id));
+            span.record("parent_span_id", tracing::field::display(trace.span_id));
+            span.record("outcome", "error").record("error", "boom");
+        });
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].trace_id, trace.trace_id);
+        assert_eq!(spans[0].parent_span_id, Some(trace.span_id));
+        assert!(!spans[0].attributes.contains_key("trace_id"));
+        assert_eq!(spans[0].status, SpanStatus::Error("boom".to_string()));
+    }
+
+    #[test]
+    fn test_queue_drops_oldest() {
+        let queue = SpanQueue::new(2);
+        for name in ["a", "b", "c"] {
+            queue.push(SpanData::new(name, SystemTime::now()));
+        }
+        let names: Vec<String> = queue.drain().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["b", "c"]);
+        assert!(queue.drain().is_empty());
+    }
+}
@@ -8,7 +8,10 @@
 //! This crate provides:
 //! - **Renacer integration**: Syscall tracing with source correlation
 //! - **ttop integration**: Real-time resource monitoring via trueno-viz collectors
-//! - **Metrics export**: Prometheus and OTLP format support
+//! - **Health monitoring**: Periodic checks with failure/recovery events
+//! - **Metrics export**: OTLP/HTTP push of metrics, lifecycle spans and
+//!   `tracing` spans, and DogStatsD over UDP (Prometheus exposition lives in
+//!   `duende_core::prometheus`)
 //!
 //! ## Iron Lotus Framework
 //!
@@ -34,9 +37,18 @@
 #![warn(missing_docs)]
 
 pub mod error;
+pub mod health;
+pub mod layer;
 pub mod monitor;
+pub mod otlp;
+mod periodic;
+pub mod statsd;
 pub mod tracer;
 
 pub use error::{ObserveError, Result};
+pub use health::{DaemonHealthState, HealthConfig, HealthEvent, HealthMonitor, HealthStatistics};
+pub use layer::OtlpLayer;
 pub use monitor::{DaemonMonitor, DaemonSnapshot, ProcessState};
+pub use otlp::{LifecycleSpans, OtlpConfig, OtlpExporter, SpanData, SpanEvent, SpanStatus};
+pub use statsd::{FlushReport, StatsdConfig, StatsdExporter};
 pub use tracer::{AnomalyKind, DaemonTracer, TraceReport};
@@ -0,0 +1,1194 @@
+//! OTLP export - push metrics and lifecycle spans to an OpenTelemetry
+//! collector.
+//!
+//! [`OtlpExporter`] speaks OTLP/HTTP with JSON bodies: metrics go to
+//! `{endpoint}/v1/metrics`, spans to `{endpoint}/v1/traces`. Batches the
+//! collector could not take (connection errors, 429, 5xx) are kept and
+//! retried on the next export, up to `max_buffered` batches.
+//!
+//! [`OtlpExporter::spawn`] drives it from a [`DaemonManager`]: every
+//! `interval` it pushes the metrics of all daemons (the series of
+//! [`DaemonMetricsSnapshot::metrics`]) and the spans built from lifecycle
+//! events by [`LifecycleSpans`]: one `daemon.run` span per run, from
+//! start to exit, with the trace and span IDs the run's events carry.
+//! Installed as a `tracing` layer, [`OtlpExporter::layer`] adds the
+//! spans of the process, such as the lifecycle spans of each phase.
+//!
+//! # Toyota Way: Visual Management (目で見る管理)
+//! One collector, one view: daemon metrics and traces land next to those
+//! of everything else.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use duende_core::checks::{HttpTarget, read_status};
+use duende_core::{
+    CustomMetric, DaemonId, DaemonManager, DaemonMetricsSnapshot, DaemonStatus, ExitReason,
+    LifecycleEvent, LifecycleEventKind, MetricValue, SpanId, TraceId,
+};
+use serde_json::{Value, json};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{Mutex, broadcast};
+use tokio::task::JoinHandle;
+
+use crate::error::{ObserveError, Result};
+use crate::layer::{DEFAULT_SPAN_QUEUE_CAPACITY, OtlpLayer, SpanQueue};
+use crate::periodic::Periodic;
+
+/// OTLP `AGGREGATION_TEMPORALITY_CUMULATIVE`.
+const CUMULATIVE: u8 = 2;
+
+/// OTLP `SPAN_KIND_INTERNAL`.
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+// =============================================================================
+// OtlpConfig
+// =============================================================================
+
+/// Configuration for OTLP export.
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// Collector base URL (`http://` only); signal paths are appended.
+    pub endpoint: String,
+    /// Interval between exports.
+    pub interval: Duration,
+    /// Timeout of one request.
+    pub timeout: Duration,
+    /// Resource attributes sent with every batch.
+    pub resource: BTreeMap<String, String>,
+    /// Failed batches kept for retry; the oldest are dropped beyond this.
+    pub max_buffered: usize,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:4318".to_string(),
+            interval: Duration::from_secs(10),
+            timeout: Duration::from_secs(10),
+            resource: BTreeMap::from([("service.name".to_string(), "duende".to_string())]),
+            max_buffered: 64,
+        }
+    }
+}
+
+impl OtlpConfig {
+    /// Creates a config for the collector at `endpoint`.
+    #[must_use]
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the export interval.
+    #[must_use]
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the request timeout.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets a resource attribute, such as `service.name`.
+    #[must_use]
+    pub fn with_resource_attribute(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.resource.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets how many failed batches are kept for retry.
+    #[must_use]
+    pub const fn with_max_buffered(mut self, max_buffered: usize) -> Self {
+        self.max_buffered = max_buffered;
+        self
+    }
+}
+
+// =============================================================================
+// SpanData
+// =============================================================================
+
+/// Outcome of a span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpanStatus {
+    /// Not set.
+    Unset,
+    /// Succeeded.
+    Ok,
+    /// Failed, with a message.
+    Error(String),
+}
+
+/// A timestamped event within a span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanEvent {
+    /// Event name.
+    pub name: String,
+    /// When it happened.
+    pub at: SystemTime,
+    /// Attributes.
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// A finished span, ready for export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanData {
+    /// Trace ID.
+    pub trace_id: TraceId,
+    /// Span ID.
+    pub span_id: SpanId,
+    /// Parent span ID, for child spans.
+    pub parent_span_id: Option<SpanId>,
+    /// Span name.
+    pub name: String,
+    /// Start time.
+    pub start: SystemTime,
+    /// End time.
+    pub end: SystemTime,
+    /// Attributes.
+    pub attributes: BTreeMap<String, String>,
+    /// Events, oldest first.
+    pub events: Vec<SpanEvent>,
+    /// Outcome.
+    pub status: SpanStatus,
+}
+
+impl SpanData {
+    /// Creates a root span of a new trace, starting and ending at `start`.
+    #[must_use]
+    pub fn new(name: impl Into<String>, start: SystemTime) -> Self {
+        Self {
+            trace_id: TraceId::random(),
+            span_id: SpanId::random(),
+            parent_span_id: None,
+            name: name.into(),
+            start,
+            end: start,
+            attributes: BTreeMap::new(),
+            events: Vec::new(),
+            status: SpanStatus::Unset,
+        }
+    }
+
+    /// Creates a child span in the trace of `parent`.
+    #[must_use]
+    pub fn child_of(parent: &Self, name: impl Into<String>, start: SystemTime) -> Self {
+        Self {
+            trace_id: parent.trace_id,
+            parent_span_id: Some(parent.span_id),
+            ..Self::new(name, start)
+        }
+    }
+
+    /// Places the span in an existing trace, e.g. that of a
+    /// [`TraceContext`](duende_core::TraceContext).
+    #[must_use]
+    pub const fn with_ids(mut self, trace_id: TraceId, span_id: SpanId) -> Self {
+        self.trace_id = trace_id;
+        self.span_id = span_id;
+        self
+    }
+
+    /// Sets an attribute.
+    #[must_use]
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+}
+
+// =============================================================================
+// LifecycleSpans
+// =============================================================================
+
+/// Returns a `daemon.run` span starting at `event`, in its trace if any.
+fn run_span(event: &LifecycleEvent) -> SpanData {
+    let span = SpanData::new("daemon.run", event.at)
+        .with_attribute("daemon", event.name.clone())
+        .with_attribute("daemon_id", event.id.to_string());
+    match (event.trace_id, event.span_id) {
+        (Some(trace_id), Some(span_id)) => span.with_ids(trace_id, span_id),
+        _ => span,
+    }
+}
+
+/// Turns lifecycle events into spans.
+///
+/// A `daemon.run` span opens on `Starting` and closes on `Exited`; it is
+/// an error if the run failed. Its IDs are those of the events, when they
+/// carry a trace. `Running` and `HealthChanged` become span events. A
+/// scheduled restart becomes a `daemon.restart` child span of the run that
+/// exited.
+#[derive(Debug, Default)]
+pub struct LifecycleSpans {
+    /// Runs in progress.
+    open: HashMap<DaemonId, SpanData>,
+    /// Last finished run of each daemon.
+    last: HashMap<DaemonId, SpanData>,
+}
+
+impl LifecycleSpans {
+    /// Creates an empty tracker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an event; returns the span it finished, if any.
+    pub fn record(&mut self, event: &LifecycleEvent) -> Option<SpanData> {
+        let id = event.id;
+        match &event.kind {
+            LifecycleEventKind::Starting => {
+                let span = run_span(event);
+                // A run whose exit was missed ends here
+                self.open.insert(id, span).map(|mut previous| {
+                    previous.end = event.at;
+                    previous
+                })
+            }
+            LifecycleEventKind::Running => {
+                self.add_event(event, "running", BTreeMap::new());
+                None
+            }
+            LifecycleEventKind::HealthChanged { health } => {
+                let attributes =
+                    BTreeMap::from([("healthy".to_string(), health.healthy.to_string())]);
+                self.add_event(event, "health_changed", attributes);
+                None
+            }
+            LifecycleEventKind::Exited { reason, status } => {
+                let mut span = self.open.remove(&id).unwrap_or_else(|| run_span(event));
+                span.end = event.at;
+                span.attributes
+                    .insert("exit.reason".to_string(), format!("{reason:?}"));
+                span.status = match (reason, status) {
+                    (ExitReason::Graceful | ExitReason::Signal(_), DaemonStatus::Stopped) => {
+                        SpanStatus::Ok
+                    }
+                    _ => SpanStatus::Error(format!("{reason:?}")),
+                };
+                self.last.insert(id, span.clone());
+                Some(span)
+            }
+            LifecycleEventKind::RestartScheduled { attempt, delay } => {
+                let last = self.last.get(&id)?;
+                Some(
+                    SpanData::child_of(last, "daemon.restart", event.at)
+                        .with_attribute("daemon", event.name.clone())
+                        .with_attribute("restart.attempt", attempt.to_string())
+                        .with_attribute("restart.delay_ms", delay.as_millis().to_string()),
+                )
+            }
+            LifecycleEventKind::Unregistered => {
+                self.last.remove(&id);
+                self.open.remove(&id).map(|mut span| {
+                    span.end = event.at;
+                    span
+                })
+            }
+            LifecycleEventKind::Registered => None,
+        }
+    }
+
+    /// Returns the number of runs in progress.
+    #[must_use]
+    pub fn open(&self) -> usize {
+        self.open.len()
+    }
+
+    fn add_event(
+        &mut self,
+        event: &LifecycleEvent,
+        name: &str,
+        attributes: BTreeMap<String, String>,
+    ) {
+        if let Some(span) = self.open.get_mut(&event.id) {
+            span.events.push(SpanEvent {
+                name: name.to_string(),
+                at: event.at,
+                attributes,
+            });
+        }
+    }
+}
+
+// =============================================================================
+// OtlpExporter
+// =============================================================================
+
+/// A request body waiting to be sent.
+#[derive(Debug)]
+struct Batch {
+    /// `v1/metrics` or `v1/traces`.
+    signal: &'static str,
+    body: String,
+}
+
+/// Why a batch was not delivered.
+enum SendError {
+    /// Worth retrying: the collector is unreachable or busy.
+    Retry(String),
+    /// The collector rejected the batch; retrying will not help.
+    Rejected(String),
+}
+
+/// OTLP/HTTP JSON exporter for metrics and spans.
+#[derive(Debug)]
+pub struct OtlpExporter {
+    config: OtlpConfig,
+    target: HttpTarget,
+    /// Batches not yet delivered, oldest first.
+    buffer: Mutex<VecDeque<Batch>>,
+    /// Spans finished by `tracing`, waiting for export.
+    traced: Arc<SpanQueue>,
+    /// The export loop.
+    running: Periodic,
+}
+
+impl OtlpExporter {
+    /// Creates an exporter.
+    ///
+    /// # Errors
+    /// Returns `ObserveError::Export` if the endpoint is not an
+    /// `http://` URL.
+    pub fn new(config: OtlpConfig) -> Result<Self> {
+        let target = HttpTarget::parse(&config.endpoint)
+            .map_err(|e| ObserveError::export(format!("invalid OTLP endpoint: {e}")))?;
+        Ok(Self {
+            config,
+            target,
+            buffer: Mutex::new(VecDeque::new()),
+            traced: Arc::new(SpanQueue::new(DEFAULT_SPAN_QUEUE_CAPACITY)),
+            running: Periodic::new(),
+        })
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &OtlpConfig {
+        &self.config
+    }
+
+    /// Returns a `tracing` layer whose finished spans this exporter pushes
+    /// with the spans of lifecycle events.
+    ///
+    /// ```rust,ignore
+    /// use tracing_subscriber::prelude::*;
+    ///
+    /// tracing_subscriber::registry().with(exporter.layer()).init();
+    /// ```
+    #[must_use]
+    pub fn layer(&self) -> OtlpLayer {
+        OtlpLayer::new(Arc::clone(&self.traced))
+    }
+
+    /// Returns the number of batches waiting for retry.
+    pub async fn buffered(&self) -> usize {
+        self.buffer.lock().await.len()
+    }
+
+    /// Encodes an `ExportMetricsServiceRequest`.
+    ///
+    /// Counters and histograms are cumulative since the daemon's metrics
+    /// were created.
+    #[must_use]
+    pub fn encode_metrics(&self, daemons: &[DaemonMetricsSnapshot], now: SystemTime) -> Value {
+        // Series of one name share a metric entry, in first-seen order
+        let mut metrics: Vec<(CustomMetric, Vec<Value>)> = Vec::new();
+        for daemon in daemons {
+            let start = daemon
+                .metrics
+                .as_ref()
+                .and_then(|snapshot| snapshot.created_at)
+                .unwrap_or(now);
+            for metric in daemon.metrics() {
+                let point = data_point(&metric, start, now);
+                match metrics.iter_mut().find(|(m, _)| m.name == metric.name) {
+                    Some((first, points)) if first.value.kind() == metric.value.kind() => {
+                        points.push(point);
+                    }
+                    Some(_) => {}
+                    None => metrics.push((metric, vec![point])),
+                }
+            }
+        }
+
+        let metrics: Vec<Value> = metrics
+            .into_iter()
+            .map(|(metric, points)| {
+                let (field, data) = match metric.value {
+                    MetricValue::Counter { .. } => (
+                        "sum",
+                        json!({
+                            "dataPoints": points,
+                            "aggregationTemporality": CUMULATIVE,
+                            "isMonotonic": true,
+                        }),
+                    ),
+                    MetricValue::Gauge { .. } => ("gauge", json!({ "dataPoints": points })),
+                    MetricValue::Histogram(_) => (
+                        "histogram",
+                        json!({
+                            "dataPoints": points,
+                            "aggregationTemporality": CUMULATIVE,
+                        }),
+                    ),
+                };
+                json!({
+                    "name": metric.name,
+                    "description": metric.help,
+                    field: data,
+                })
+            })
+            .collect();
+
+        json!({
+            "resourceMetrics": [{
+                "resource": self.resource(),
+                "scopeMetrics": [{ "scope": scope(), "metrics": metrics }],
+            }]
+        })
+    }
+
+    /// Encodes an `ExportTraceServiceRequest`.
+    #[must_use]
+    pub fn encode_spans(&self, spans: &[SpanData]) -> Value {
+        let spans: Vec<Value> = spans
+            .iter()
+            .map(|span| {
+                let mut encoded = json!({
+                    "traceId": span.trace_id.to_hex(),
+                    "spanId": span.span_id.to_hex(),
+                    "name": span.name,
+                    "kind": SPAN_KIND_INTERNAL,
+                    "startTimeUnixNano": unix_nanos(span.start),
+                    "endTimeUnixNano": unix_nanos(span.end),
+                    "attributes": attributes(&span.attributes),
+                    "events": span.events.iter().map(|event| json!({
+                        "name": event.name,
+                        "timeUnixNano": unix_nanos(event.at),
+                        "attributes": attributes(&event.attributes),
+                    })).collect::<Vec<_>>(),
+                    "status": match &span.status {
+                        SpanStatus::Unset => json!({ "code": 0 }),
+                        SpanStatus::Ok => json!({ "code": 1 }),
+                        SpanStatus::Error(message) => json!({ "code": 2, "message": message }),
+                    },
+                });
+                if let (Some(parent), Some(object)) = (span.parent_span_id, encoded.as_object_mut())
+                {
+                    object.insert("parentSpanId".to_string(), parent.to_hex().into());
+                }
+                encoded
+            })
+            .collect();
+
+        json!({
+            "resourceSpans": [{
+                "resource": self.resource(),
+                "scopeSpans": [{ "scope": scope(), "spans": spans }],
+            }]
+        })
+    }
+
+    /// Pushes the metrics of `daemons`.
+    ///
+    /// # Errors
+    /// Returns `ObserveError::Export` if this or an earlier batch could not
+    /// be delivered; undelivered batches stay buffered.
+    pub async fn export_metrics(&self, daemons: &[DaemonMetricsSnapshot]) -> Result<()> {
+        let body = self.encode_metrics(daemons, SystemTime::now());
+        self.send("v1/metrics", body.to_string()).await
+    }
+
+    /// Pushes finished spans. Does nothing if `spans` is empty.
+    ///
+    /// # Errors
+    /// Returns `ObserveError::Export` if this or an earlier batch could not
+    /// be delivered; undelivered batches stay buffered.
+    pub async fn export_spans(&self, spans: &[SpanData]) -> Result<()> {
+        if spans.is_empty() {
+            return self.flush().await;
+        }
+        let body = self.encode_spans(spans);
+        self.send("v1/traces", body.to_string()).await
+    }
+
+    /// Retries buffered batches, oldest first.
+    ///
+    /// Stops at the first batch the collector cannot take. Batches it
+    /// rejects (other 4xx responses) are dropped. Each batch is taken out
+    /// of the buffer while it is posted, so exports that arrive meanwhile
+    /// only queue behind it.
+    ///
+    /// # Errors
+    /// Returns `ObserveError::Export` if a batch could not be delivered.
+    pub async fn flush(&self) -> Result<()> {
+        loop {
+            let Some(batch) = self.buffer.lock().await.pop_front() else {
+                return Ok(());
+            };
+            match self.post(&batch).await {
+                Ok(()) => {}
+                Err(SendError::Rejected(error)) => {
+                    tracing::warn!(signal = batch.signal, error = %error, "OTLP batch rejected; dropped");
+                }
+                Err(SendError::Retry(error)) => {
+                    let buffered = self.buffer_batch(batch, true).await;
+                    return Err(ObserveError::export(format!(
+                        "{buffered} batches buffered: {error}"
+                    )));
+                }
+            }
+        }
+    }
+
+    /// Buffers a batch, then flushes.
+    async fn send(&self, signal: &'static str, body: String) -> Result<()> {
+        self.buffer_batch(Batch { signal, body }, false).await;
+        self.flush().await
+    }
+
+    /// Adds a batch to the buffer: at the front if it is being retried,
+    /// else at the back. Drops the oldest batches past `max_buffered` and
+    /// returns how many remain.
+    async fn buffer_batch(&self, batch: Batch, retry: bool) -> usize {
+        let mut buffer = self.buffer.lock().await;
+        if retry {
+            buffer.push_front(batch);
+        } else {
+            buffer.push_back(batch);
+        }
+        while buffer.len() > self.config.max_buffered.max(1) {
+            buffer.pop_front();
+            tracing::warn!("OTLP buffer full; oldest batch dropped");
+        }
+        buffer.len()
+    }
+
+    /// POSTs one batch.
+    async fn post(&self, batch: &Batch) -> std::result::Result<(), SendError> {
+        let path = format!(
+            "{}/{}",
+            self.target
+                .path
+                .split('?')
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches('/'),
+            batch.signal
+        );
+        let request = async {
+            let io = |e: std::io::Error| e.to_string();
+            let mut stream = tokio::net::TcpStream::connect(&self.target.address)
+                .await
+                .map_err(io)?;
+            let head = format!(
+                "POST {path} HTTP/1.1\r\nHost: {}\r\nUser-Agent: duende\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                self.target.host,
+                batch.body.len()
+            );
+            stream.write_all(head.as_bytes()).await.map_err(io)?;
+            stream.write_all(batch.body.as_bytes()).await.map_err(io)?;
+            read_status(&mut stream).await
+        };
+
+        let status = tokio::time::timeout(self.config.timeout, request)
+            .await
+            .map_err(|_| SendError::Retry(format!("timed out after {:?}", self.config.timeout)))?
+            .map_err(|e| SendError::Retry(format!("{}: {e}", self.target.address)))?;
+        match status {
+            200..=299 => Ok(()),
+            429 | 500..=599 => Err(SendError::Retry(format!("HTTP {status}"))),
+            _ => Err(SendError::Rejected(format!("HTTP {status}"))),
+        }
+    }
+
+    fn resource(&self) -> Value {
+        json!({ "attributes": attributes(&self.config.resource) })
+    }
+
+    /// Starts the export loop: records spans from the lifecycle events of
+    /// `manager`, and every `interval` pushes them with the metrics of all
+    /// daemons, until [`stop`](Self::stop) is called. Exports once more on
+    /// stop. Replaces a running export loop.
+    pub fn spawn(self: Arc<Self>, manager: Arc<DaemonManager>) -> JoinHandle<()> {
+        let ticks = self.running.start("OTLP export", self.config.interval);
+        let mut events = manager.subscribe();
+
+        tokio::spawn(async move {
+            let Some(mut ticks) = ticks else {
+                return;
+            };
+
+            let mut lifecycle = LifecycleSpans::new();
+            let mut spans = Vec::new();
+            loop {
+                let running = tokio::select! {
+                    running = ticks.tick() => running,
+                    event = events.recv() => {
+                        match event {
+                            Ok(event) => spans.extend(lifecycle.record(&event)),
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                tracing::warn!(skipped, "OTLP exporter missed lifecycle events");
+                            }
+                            Err(broadcast::error::RecvError::Closed) => {}
+                        }
+                        continue;
+                    }
+                };
+                self.export_all(&manager, &mut spans).await;
+                if !running {
+                    break;
+                }
+            }
+            tracing::debug!("OTLP exporter stopped");
+        })
+    }
+
+    /// Exports metrics and pending spans once; failures are logged.
+    async fn export_all(&self, manager: &DaemonManager, spans: &mut Vec<SpanData>) {
+        let daemons = manager.metrics_snapshots().await;
+        if let Err(e) = self.export_metrics(&daemons).await {
+            tracing::warn!(error = %e, "OTLP metrics export failed");
+        }
+        spans.extend(self.traced.drain());
+        if let Err(e) = self.export_spans(&std::mem::take(spans)).await {
+            tracing::warn!(error = %e, "OTLP span export failed");
+        }
+    }
+
+    /// Stops the export loop.
+    pub fn stop(&self) {
+        self.running.stop();
+    }
+
+    /// Returns true while the export loop is running.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.running.is_running()
+    }
+}
+
+/// Encodes one series as an OTLP data point.
+fn data_point(metric: &CustomMetric, start: SystemTime, now: SystemTime) -> Value {
+    let attributes = attributes(&metric.labels);
+    match &metric.value {
+        MetricValue::Counter { value } => json!({
+            "attributes": attributes,
+            "startTimeUnixNano": unix_nanos(start),
+            "timeUnixNano": unix_nanos(now),
+            "asInt": value.to_string(),
+        }),
+        MetricValue::Gauge { value } => json!({
+            "attributes": attributes,
+            "timeUnixNano": unix_nanos(now),
+            "asDouble": value,
+        }),
+        MetricValue::Histogram(histogram) => {
+            // OTLP counts per bucket, not cumulatively
+            let mut below = 0;
+            let mut counts: Vec<String> = histogram
+                .buckets
+                .iter()
+                .map(|bucket| {
+                    let count = bucket.count.saturating_sub(below);
+                    below = bucket.count;
+                    count.to_string()
+                })
+                .collect();
+            counts.push(histogram.count.saturating_sub(below).to_string());
+            json!({
+                "attributes": attributes,
+                "startTimeUnixNano": unix_nanos(start),
+                "timeUnixNano": unix_nanos(now),
+                "count": histogram.count.to_string(),
+                "sum": histogram.sum,
+                "bucketCounts": counts,
+                "explicitBounds": histogram.buckets.iter().map(|b| b.le).collect::<Vec<_>>(),
+            })
+        }
+    }
+}
+
+/// Encodes string attributes as OTLP `KeyValue`s.
+fn attributes(attributes: &BTreeMap<String, String>) -> Vec<Value> {
+    attributes
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+        .collect()
+}
+
+fn scope() -> Value {
+    json!({ "name": "duende", "version": env!("CARGO_PKG_VERSION") })
+}
+
+/// Nanoseconds since the Unix epoch, as a string (OTLP JSON encodes
+/// 64-bit integers as strings).
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use duende_core::{
+        Daemon, DaemonConfig, DaemonContext, DaemonMetrics, HealthStatus, Platform, RestartPolicy,
+        Supervisor, TraceContext,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// A request the stand-in collector received.
+    #[derive(Debug)]
+    struct Received {
+        path: String,
+        content_type: String,
+        body: Value,
+    }
+
+    /// Stand-in collector: answers with `statuses` in turn (then 200) and
+    /// forwards every request.
+    async fn collector(
+        statuses: &'static [u16],
+    ) -> (String, tokio::sync::mpsc::UnboundedReceiver<Received>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let served = Arc::new(AtomicUsize::new(0));
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut data = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let received = loop {
+                    let n = stream.read(&mut chunk).await.unwrap();
+                    data.extend_from_slice(&chunk[..n]);
+                    let text = String::from_utf8_lossy(&data).to_string();
+                    let Some((head, body)) = text.split_once("\r\n\r\n") else {
+                        continue;
+                    };
+                    let header = |name: &str| {
+                        head.lines()
+                            .find_map(|line| line.strip_prefix(name))
+                            .unwrap_or_default()
+                            .trim()
+                            .to_string()
+                    };
+                    let length: usize = header("Content-Length:").parse().unwrap();
+                    if body.len() < length {
+                        continue;
+                    }
+                    break Received {
+                        path: head.split_whitespace().nth(1).unwrap().to_string(),
+                        content_type: header("Content-Type:"),
+                        body: serde_json::from_str(body).unwrap(),
+                    };
+                };
+
+                let index = served.fetch_add(1, Ordering::SeqCst);
+                let status = statuses.get(index).copied().unwrap_or(200);
+                let response = format!("HTTP/1.1 {status} Status\r\nContent-Length: 0\r\n\r\n");
+                stream.write_all(response.as_bytes()).await.unwrap();
+                let _ = tx.send(received);
+            }
+        });
+
+        (endpoint, rx)
+    }
+
+    fn daemon(metrics: &DaemonMetrics) -> DaemonMetricsSnapshot {
+        DaemonMetricsSnapshot {
+            id: DaemonId::new(),
+            name: "api".to_string(),
+            platform: Platform::Native,
+            running: true,
+            ready: true,
+            restart_count: 2,
+            metrics: Some(metrics.snapshot()),
+        }
+    }
+
+    fn find<'a>(body: &'a Value, name: &str) -> &'a Value {
+        body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|metric| metric["name"] == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_invalid_endpoint() {
+        let err = OtlpExporter::new(OtlpConfig::new("https://collector:4318")).unwrap_err();
+        assert!(err.to_string().contains("invalid OTLP endpoint"));
+    }
+
+    #[test]
+    fn test_encode_metrics() {
+        let metrics = DaemonMetrics::new();
+        metrics.record_request();
+        metrics.record_duration(Duration::from_millis(3));
+        metrics.record_duration(Duration::from_millis(20));
+        metrics
+            .gauge("queue_depth", "Requests waiting", &[("queue", "fast")])
+            .unwrap()
+            .set(4.0);
+
+        let exporter = OtlpExporter::new(
+            OtlpConfig::default().with_resource_attribute("service.name", "fleet"),
+        )
+        .unwrap();
+        let body = exporter.encode_metrics(&[daemon(&metrics)], SystemTime::now());
+
+        let resource = &body["resourceMetrics"][0]["resource"]["attributes"][0];
+        assert_eq!(resource["key"], "service.name");
+        assert_eq!(resource["value"]["stringValue"], "fleet");
+
+        let requests = find(&body, "duende_requests_total");
+        assert_eq!(requests["sum"]["isMonotonic"], true);
+        assert_eq!(requests["sum"]["aggregationTemporality"], 2);
+        let point = &requests["sum"]["dataPoints"][0];
+        assert_eq!(point["asInt"], "1");
+        // Cumulative since creation, to the nanosecond
+        assert_eq!(point["startTimeUnixNano"], unix_nanos(metrics.created_at()));
+        assert!(
+            point["attributes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|a| a["key"] == "daemon" && a["value"]["stringValue"] == "api")
+        );
+
+        let restarts = find(&body, "duende_daemon_restarts_total");
+        assert_eq!(restarts["sum"]["dataPoints"][0]["asInt"], "2");
+
+        let queue = find(&body, "queue_depth");
+        assert_eq!(queue["gauge"]["dataPoints"][0]["asDouble"], 4.0);
+
+        // Per-bucket counts, with the +Inf bucket last
+        let latency = &find(&body, "duende_request_duration_seconds")["histogram"]["dataPoints"][0];
+        assert_eq!(latency["count"], "2");
+        let counts: Vec<u64> = latency["bucketCounts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c.as_str().unwrap().parse().unwrap())
+            .collect();
+        assert_eq!(
+            counts.len(),
+            latency["explicitBounds"].as_array().unwrap().len() + 1
+        );
+        assert_eq!(counts.iter().sum::<u64>(), 2);
+    }
+
+    #[test]
+    fn test_lifecycle_spans() {
+        let id = DaemonId::new();
+        let event = |kind| LifecycleEvent::new(id, "api", kind);
+        let mut spans = LifecycleSpans::new();
+
+        assert!(
+            spans
+                .record(&event(LifecycleEventKind::Registered))
+                .is_none()
+        );
+        assert!(spans.record(&event(LifecycleEventKind::Starting)).is_none());
+        assert_eq!(spans.open(), 1);
+        assert!(spans.record(&event(LifecycleEventKind::Running)).is_none());
+        assert!(
+            spans
+                .record(&event(LifecycleEventKind::HealthChanged {
+                    health: HealthStatus::healthy(1),
+                }))
+                .is_none()
+        );
+
+        let run = spans
+            .record(&event(LifecycleEventKind::Exited {
+                reason: ExitReason::Error("boom".to_string()),
+                status: DaemonStatus::Failed(duende_core::FailureReason::Internal),
+            }))
+            .unwrap();
+        assert_eq!(spans.open(), 0);
+        assert_eq!(run.name, "daemon.run");
+        assert_eq!(run.attributes["daemon"], "api");
+        assert!(matches!(run.status, SpanStatus::Error(_)));
+        let events: Vec<&str> = run.events.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(events, ["running", "health_changed"]);
+
+        let restart = spans
+            .record(&event(LifecycleEventKind::RestartScheduled {
+                attempt: 1,
+                delay: Duration::from_millis(500),
+            }))
+            .unwrap();
+        assert_eq!(restart.trace_id, run.trace_id);
+        assert_eq!(restart.parent_span_id, Some(run.span_id));
+        assert_eq!(restart.attributes["restart.delay_ms"], "500");
+    }
+
+    #[test]
+    fn test_lifecycle_spans_follow_event_trace() {
+        let id = DaemonId::new();
+        let trace = TraceContext::new_root();
+        let event = |kind| LifecycleEvent::new(id, "api", kind).with_trace(&trace);
+        let mut spans = LifecycleSpans::new();
+
+        spans.record(&event(LifecycleEventKind::Starting));
+        let run = spans
+            .record(&event(LifecycleEventKind::Exited {
+                reason: ExitReason::Graceful,
+                status: DaemonStatus::Stopped,
+            }))
+            .unwrap();
+        assert_eq!(run.trace_id, trace.trace_id);
+        assert_eq!(run.span_id, trace.span_id);
+        assert_eq!(run.status, SpanStatus::Ok);
+    }
+
+    #[test]
+    fn test_encode_spans() {
+        let exporter = OtlpExporter::new(OtlpConfig::default()).unwrap();
+        let parent = SpanData::new("daemon.run", SystemTime::now());
+        let child = SpanData::child_of(&parent, "daemon.restart", SystemTime::now());
+        let body = exporter.encode_spans(&[parent.clone(), child]);
+
+        let spans = &body["resourceSpans"][0]["scopeSpans"][0]["spans"];
+        assert_eq!(spans[0]["traceId"].as_str().unwrap().len(), 32);
+        assert_eq!(spans[0]["spanId"].as_str().unwrap().len(), 16);
+        assert!(spans[0].get("parentSpanId").is_none());
+        assert_eq!(spans[1]["traceId"], spans[0]["traceId"]);
+        assert_eq!(spans[1]["parentSpanId"], spans[0]["spanId"]);
+        assert_eq!(spans[0]["status"]["code"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_export_to_collector() {
+        let (endpoint, mut received) = collector(&[]).await;
+        let exporter = OtlpExporter::new(OtlpConfig::new(endpoint)).unwrap();
+
+        exporter
+            .export_metrics(&[daemon(&DaemonMetrics::new())])
+            .await
+            .unwrap();
+        let request = received.recv().await.unwrap();
+        assert_eq!(request.path, "/v1/metrics");
+        assert_eq!(request.content_type, "application/json");
+        assert!(request.body["resourceMetrics"].is_array());
+
+        exporter
+            .export_spans(&[SpanData::new("daemon.run", SystemTime::now())])
+            .await
+            .unwrap();
+        let request = received.recv().await.unwrap();
+        assert_eq!(request.path, "/v1/traces");
+        assert_eq!(
+            request.body["resourceSpans"][0]["scopeSpans"][0]["spans"][0]["name"],
+            "daemon.run"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_buffering() {
+        let (endpoint, mut received) = collector(&[503, 400]).await;
+        let exporter = OtlpExporter::new(OtlpConfig::new(endpoint).with_max_buffered(2)).unwrap();
+        let daemons = [daemon(&DaemonMetrics::new())];
+
+        // 503: kept for retry
+        assert!(exporter.export_metrics(&daemons).await.is_err());
+        assert_eq!(exporter.buffered().await, 1);
+        // Retried first: 400 drops it, then the new batch gets through
+        exporter.export_metrics(&daemons).await.unwrap();
+        assert_eq!(exporter.buffered().await, 0);
+        for _ in 0..3 {
+            assert_eq!(received.recv().await.unwrap().path, "/v1/metrics");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_buffer_drops_oldest_when_unreachable() {
+        // Nothing listens on a closed port
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let exporter = OtlpExporter::new(
+            OtlpConfig::new(endpoint)
+                .with_max_buffered(2)
+                .with_timeout(Duration::from_secs(1)),
+        )
+        .unwrap();
+        for _ in 0..3 {
+            assert!(
+                exporter
+                    .export_spans(&[SpanData::new("x", SystemTime::now())])
+                    .await
+                    .is_err()
+            );
+        }
+        assert_eq!(exporter.buffered().await, 2);
+    }
+
+    struct TestDaemon {
+        id: DaemonId,
+        metrics: DaemonMetrics,
+    }
+
+    #[async_trait::async_trait]
+    impl Daemon for TestDaemon {
+        fn id(&self) -> DaemonId {
+            self.id
+        }
+        fn name(&self) -> &str {
+            "api"
+        }
+        async fn init(&mut self, _config: &DaemonConfig) -> duende_core::error::Result<()> {
+            Ok(())
+        }
+        async fn run(
+            &mut self,
+            _ctx: &mut DaemonContext,
+        ) -> duende_core::error::Result<ExitReason> {
+            Ok(ExitReason::Graceful)
+        }
+        async fn shutdown(&mut self, _timeout: Duration) -> duende_core::error::Result<()> {
+            Ok(())
+        }
+        async fn health_check(&self) -> HealthStatus {
+            HealthStatus::healthy(0)
+        }
+        fn metrics(&self) -> &DaemonMetrics {
+            &self.metrics
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_exports_manager() {
+        let (endpoint, mut received) = collector(&[]).await;
+        let manager = Arc::new(DaemonManager::new());
+        let daemon = TestDaemon {
+            id: DaemonId::new(),
+            metrics: DaemonMetrics::new(),
+        };
+        let id = manager
+            .register(
+                Box::new(daemon),
+                DaemonConfig::new("api", "/bin/api"),
+                RestartPolicy::Never,
+            )
+            .await
+            .unwrap();
+
+        let exporter = Arc::new(
+            OtlpExporter::new(OtlpConfig::new(endpoint).with_interval(Duration::from_secs(3600)))
+                .unwrap(),
+        );
+        let task = Arc::clone(&exporter).spawn(Arc::clone(&manager));
+        // The first tick exports right away
+        let request = received.recv().await.unwrap();
+        assert_eq!(request.path, "/v1/metrics");
+        assert!(request.body.to_string().contains("duende_daemon_ready"));
+
+        manager
+            .publish(id, LifecycleEventKind::Starting)
+            .await
+            .unwrap();
+        manager
+            .publish(
+                id,
+                LifecycleEventKind::Exited {
+                    reason: ExitReason::Graceful,
+                    status: DaemonStatus::Stopped,
+                },
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Stopping exports once more, with the finished run
+        exporter.stop();
+        task.await.unwrap();
+        assert!(!exporter.is_running());
+        let mut traces = None;
+        while let Ok(request) = received.try_recv() {
+            if request.path == "/v1/traces" {
+                traces = Some(request.body);
+            }
+        }
+        let traces = traces.unwrap();
+        let span = &traces["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["name"], "daemon.run");
+        assert_eq!(span["status"]["code"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_layer_exports_lifecycle_phases() {
+        let exporter = OtlpExporter::new(OtlpConfig::default()).unwrap();
+        let subscriber = tracing_subscriber::registry().with(exporter.layer());
+        // Current-thread runtime: the supervisor task sees this subscriber
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let manager = Arc::new(DaemonManager::new());
+        let id = DaemonId::new();
+        let daemon = |id| TestDaemon {
+            id,
+            metrics: DaemonMetrics::new(),
+        };
+        manager
+            .register(
+                Box::new(daemon(id)),
+                DaemonConfig::new("api", "/bin/api"),
+                RestartPolicy::Never,
+            )
+            .await
+            .unwrap();
+        let supervisor = Supervisor::new(Arc::clone(&manager));
+        supervisor.start(Box::new(daemon(id))).await.unwrap();
+        supervisor.wait(id).await.unwrap();
+        let trace = manager.trace_context(id).await.unwrap().unwrap();
+
+        // Phases of the run hang below the run's span
+        let spans = exporter.traced.drain();
+        let phases: Vec<&str> = spans
+            .iter()
+            .filter(|span| {
+                span.trace_id == trace.trace_id && span.parent_span_id == Some(trace.span_id)
+            })
+            .map(|span| span.name.as_str())
+            .collect();
+        for phase in ["init", "health_check", "run", "shutdown"] {
+            assert!(phases.contains(&phase), "{phase} missing from {phases:?}");
+        }
+
+        let run = spans.iter().find(|span| span.name == "run").unwrap();
+        assert_eq!(run.attributes["daemon.id"], id.to_string());
+        assert_eq!(run.attributes["daemon.name"], "api");
+        assert_eq!(run.attributes["restart_count"], "0");
+        assert_eq!(run.attributes["outcome"], "ok");
+        assert!(run.attributes.contains_key("platform"));
+        assert_eq!(run.status, SpanStatus::Ok);
+
+        let body = exporter.encode_spans(&spans);
+        let encoded = &body["resourceSpans"][0]["scopeSpans"][0]["spans"];
+        assert_eq!(encoded.as_array().unwrap().len(), spans.len());
+    }
+}
@@ -0,0 +1,127 @@
+//! Periodic background loops.
+//!
+//! The health monitor and the exporters each run one loop on an
+//! interval. [`Periodic`] is that loop's control: it hands out [`Ticks`],
+//! stops the loop, and tells whether it runs. Starting a loop while one
+//! runs replaces it, so an owner never runs two.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::time::{Interval, MissedTickBehavior, interval};
+
+/// Control of a periodic background loop.
+#[derive(Debug)]
+pub struct Periodic {
+    /// Generation of the running loop; 0 while none runs.
+    current: watch::Sender<u64>,
+    /// Last generation handed out.
+    generations: AtomicU64,
+}
+
+impl Periodic {
+    pub fn new() -> Self {
+        Self {
+            current: watch::Sender::new(0),
+            generations: AtomicU64::new(0),
+        }
+    }
+
+    /// Starts a loop ticking every `period`, replacing a running one.
+    ///
+    /// Returns `None`, and only stops the running loop, if `period` is
+    /// zero; `what` names the loop in the warning.
+    pub fn start(&self, what: &str, period: Duration) -> Option<Ticks> {
+        if period.is_zero() {
+            tracing::warn!("{what} interval is zero; loop not started");
+            self.stop();
+            return None;
+        }
+
+        let generation = self.generations.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.current.send_replace(generation) != 0 {
+            tracing::debug!("{what} loop replaced");
+        }
+        let mut ticks = interval(period);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Some(Ticks {
+            interval: ticks,
+            current: self.current.subscribe(),
+            generation,
+        })
+    }
+
+    /// Stops the running loop.
+    pub fn stop(&self) {
+        self.current.send_replace(0);
+    }
+
+    /// Returns true while a loop runs.
+    pub fn is_running(&self) -> bool {
+        *self.current.borrow() != 0
+    }
+}
+
+/// Ticks of one loop started by [`Periodic::start`].
+#[derive(Debug)]
+pub struct Ticks {
+    interval: Interval,
+    current: watch::Receiver<u64>,
+    generation: u64,
+}
+
+impl Ticks {
+    /// Waits for the next tick; the first is immediate.
+    ///
+    /// Returns false once the loop was stopped or replaced, and it should
+    /// end. Cancel-safe.
+    pub async fn tick(&mut self) -> bool {
+        let generation = self.generation;
+        let stopped = tokio::select! {
+            _ = self.interval.tick() => false,
+            _ = self.current.wait_for(|current| *current != generation) => true,
+        };
+        !stopped && *self.current.borrow() == generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ticks_until_stopped() {
+        let periodic = Periodic::new();
+        let mut ticks = periodic.start("test", Duration::from_millis(1)).unwrap();
+        assert!(periodic.is_running());
+        assert!(ticks.tick().await);
+        assert!(ticks.tick().await);
+
+        periodic.stop();
+        assert!(!periodic.is_running());
+        assert!(!ticks.tick().await);
+    }
+
+    #[tokio::test]
+    async fn test_start_replaces_running_loop() {
+        let periodic = Periodic::new();
+        let mut first = periodic.start("test", Duration::from_secs(3600)).unwrap();
+        assert!(first.tick().await);
+
+        let mut second = periodic.start("test", Duration::from_secs(3600)).unwrap();
+        assert!(!first.tick().await);
+        assert!(second.tick().await);
+        assert!(periodic.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_zero_interval_does_not_start() {
+        let periodic = Periodic::new();
+        let mut running = periodic.start("test", Duration::from_secs(3600)).unwrap();
+        assert!(periodic.start("test", Duration::ZERO).is_none());
+        assert!(!periodic.is_running());
+        // The running loop was stopped
+        assert!(!running.tick().await);
+    }
+}
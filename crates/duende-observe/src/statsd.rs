@@ -0,0 +1,560 @@
+//! StatsD export - push daemon metrics to a local StatsD agent over UDP.
+//!
+//! [`StatsdExporter`] sends the series of
+//! [`DaemonMetricsSnapshot::metrics`] with DogStatsD tags
+//! (`name:value|type|#key:value,...`):
+//!
+//! - counters as `c`, the increase since the previous flush;
+//! - gauges as `g`;
+//! - histograms as `<name>_count` and `<name>_sum` increases;
+//! - request latency as `duende_request_duration_ms` distribution (`d`)
+//!   samples: one line per latency bucket that gained `n` requests, with
+//!   the bucket's value and sample rate `1/n`, so the agent computes
+//!   percentiles over every request.
+//!
+//! Lines are packed into datagrams of at most `max_packet_size` bytes.
+//! Sends never block: a datagram the socket cannot take right away, or
+//! that a dead agent refuses, is counted as dropped and forgotten.
+//!
+//! # Toyota Way: Heijunka (平準化)
+//! Monitoring must not load the line it monitors: a slow or missing agent
+//! costs the daemon nothing.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use duende_core::{DaemonManager, DaemonMetricsSnapshot, MetricValue};
+use tokio::task::JoinHandle;
+
+use crate::error::{ObserveError, Result};
+use crate::periodic::Periodic;
+
+// =============================================================================
+// StatsdConfig
+// =============================================================================
+
+/// Configuration for StatsD export.
+#[derive(Debug, Clone)]
+pub struct StatsdConfig {
+    /// Agent address (`host:port`).
+    pub address: String,
+    /// Interval between flushes.
+    pub interval: Duration,
+    /// Prefix of every metric name, such as `edge.`.
+    pub prefix: String,
+    /// Tags added to every line.
+    pub tags: BTreeMap<String, String>,
+    /// Largest datagram sent, in bytes.
+    pub max_packet_size: usize,
+}
+
+impl Default for StatsdConfig {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:8125".to_string(),
+            interval: Duration::from_secs(10),
+            prefix: String::new(),
+            tags: BTreeMap::new(),
+            // Fits a 1500-byte MTU after IP and UDP headers, with room to spare
+            max_packet_size: 1432,
+        }
+    }
+}
+
+impl StatsdConfig {
+    /// Creates a config for the agent at `address`.
+    #[must_use]
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the flush interval.
+    #[must_use]
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the metric name prefix.
+    #[must_use]
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Adds a tag to every line.
+    #[must_use]
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets the largest datagram sent.
+    #[must_use]
+    pub const fn with_max_packet_size(mut self, size: usize) -> Self {
+        self.max_packet_size = size;
+        self
+    }
+}
+
+// =============================================================================
+// StatsdExporter
+// =============================================================================
+
+/// What one flush sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlushReport {
+    /// Metric lines encoded.
+    pub lines: usize,
+    /// Datagrams sent.
+    pub packets: usize,
+    /// Datagrams dropped: the socket was busy or the agent unreachable.
+    pub dropped: usize,
+}
+
+/// DogStatsD exporter over UDP.
+#[derive(Debug)]
+pub struct StatsdExporter {
+    config: StatsdConfig,
+    socket: UdpSocket,
+    /// Cumulative values at the previous flush, by series.
+    previous: Mutex<HashMap<String, f64>>,
+    /// The flush loop.
+    running: Periodic,
+}
+
+impl StatsdExporter {
+    /// Creates an exporter with a non-blocking socket connected to the
+    /// agent.
+    ///
+    /// # Errors
+    /// Returns `ObserveError::Export` if the address does not resolve, or
+    /// `ObserveError::Io` if the socket cannot be set up.
+    pub fn new(config: StatsdConfig) -> Result<Self> {
+        let address = config
+            .address
+            .to_socket_addrs()
+            .map_err(|e| ObserveError::export(format!("invalid StatsD address: {e}")))?
+            .next()
+            .ok_or_else(|| {
+                ObserveError::export(format!("StatsD address {} did not resolve", config.address))
+            })?;
+        let local = if address.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(address)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            config,
+            socket,
+            previous: Mutex::new(HashMap::new()),
+            running: Periodic::new(),
+        })
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &StatsdConfig {
+        &self.config
+    }
+
+    /// Sends the metrics of `daemons`. Never blocks.
+    ///
+    /// Increases are computed against the previous flush; series not in
+    /// `daemons` are forgotten, so pass every daemon each time.
+    pub fn flush(&self, daemons: &[DaemonMetricsSnapshot]) -> FlushReport {
+        let lines = self.encode(daemons);
+        let mut report = FlushReport {
+            lines: lines.len(),
+            ..FlushReport::default()
+        };
+        for packet in pack(&lines, self.config.max_packet_size) {
+            match self.socket.send(packet.as_bytes()) {
+                Ok(_) => report.packets += 1,
+                Err(e) => {
+                    report.dropped += 1;
+                    if e.kind() != io::ErrorKind::WouldBlock {
+                        tracing::debug!(error = %e, "StatsD datagram dropped");
+                    }
+                }
+            }
+        }
+        report
+    }
+
+    /// Encodes the metrics of `daemons` as lines, remembering cumulative
+    /// values so the next call sends increases.
+    fn encode(&self, daemons: &[DaemonMetricsSnapshot]) -> Vec<String> {
+        let mut previous = self.previous.lock().unwrap_or_else(PoisonError::into_inner);
+        // Only series still reported are kept
+        let mut current = HashMap::new();
+        let mut lines = Vec::new();
+
+        for daemon in daemons {
+            let metrics = daemon.metrics();
+            for metric in &metrics {
+                let tags = self.tags(&metric.labels);
+                let mut increase = |name: String, value: f64| {
+                    let delta = increase(&previous, &mut current, format!("{name}|{tags}"), value);
+                    self.line(&name, delta, "c", &tags)
+                };
+                match &metric.value {
+                    MetricValue::Counter { value } => {
+                        lines.push(increase(metric.name.clone(), *value as f64));
+                    }
+                    MetricValue::Gauge { value } => {
+                        lines.push(self.line(&metric.name, *value, "g", &tags));
+                    }
+                    MetricValue::Histogram(histogram) => {
+                        lines.push(increase(
+                            format!("{}_count", metric.name),
+                            histogram.count as f64,
+                        ));
+                        lines.push(increase(format!("{}_sum", metric.name), histogram.sum));
+                    }
+                }
+            }
+
+            if let Some(snapshot) = &daemon.metrics {
+                let labels: BTreeMap<String, String> = daemon
+                    .labels()
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value))
+                    .collect();
+                let tags = self.tags(&labels);
+                let latency = &snapshot.latency;
+                for (&index, &count) in &latency.buckets {
+                    let key = format!("duende_request_duration_ms@{index}|{tags}");
+                    let new = increase(&previous, &mut current, key, count as f64);
+                    if new < 1.0 {
+                        continue;
+                    }
+                    let ms = latency.bucket_value(index).as_secs_f64() * 1000.0;
+                    let kind = format!("d|@{}", 1.0 / new);
+                    lines.push(self.line("duende_request_duration_ms", ms, &kind, &tags));
+                }
+            }
+        }
+
+        *previous = current;
+        lines
+    }
+
+    /// Formats one line.
+    fn line(&self, name: &str, value: f64, kind: &str, tags: &str) -> String {
+        let name = sanitize(&format!("{}{name}", self.config.prefix));
+        if tags.is_empty() {
+            format!("{name}:{value}|{kind}")
+        } else {
+            format!("{name}:{value}|{kind}|#{tags}")
+        }
+    }
+
+    /// Formats the configured tags and `labels` as `key:value,...`;
+    /// labels win over configured tags of the same name.
+    fn tags(&self, labels: &BTreeMap<String, String>) -> String {
+        let mut tags = self.config.tags.clone();
+        tags.extend(labels.iter().map(|(k, v)| (k.clone(), v.clone())));
+        tags.iter()
+            .map(|(key, value)| format!("{}:{}", sanitize(key), sanitize(value)))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Starts the flush loop: every `interval`, sends the metrics of all
+    /// daemons of `manager`, until [`stop`](Self::stop) is called.
+    /// Replaces a running flush loop.
+    pub fn spawn(self: Arc<Self>, manager: Arc<DaemonManager>) -> JoinHandle<()> {
+        let ticks = self.running.start("StatsD flush", self.config.interval);
+
+        tokio::spawn(async move {
+            let Some(mut ticks) = ticks else {
+                return;
+            };
+            while ticks.tick().await {
+                let report = self.flush(&manager.metrics_snapshots().await);
+                if report.dropped > 0 {
+                    tracing::debug!(dropped = report.dropped, "StatsD flush dropped datagrams");
+                }
+            }
+            tracing::debug!("StatsD exporter stopped");
+        })
+    }
+
+    /// Stops the flush loop.
+    pub fn stop(&self) {
+        self.running.stop();
+    }
+
+    /// Returns true while the flush loop is running.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.running.is_running()
+    }
+}
+
+/// Returns the increase of a cumulative series since the previous flush,
+/// recording its value in `current`.
+fn increase(
+    previous: &HashMap<String, f64>,
+    current: &mut HashMap<String, f64>,
+    key: String,
+    value: f64,
+) -> f64 {
+    let last = previous.get(&key).copied().unwrap_or(0.0);
+    current.insert(key, value);
+    // A reset counter starts over
+    if value >= last { value - last } else { value }
+}
+
+/// Joins lines into datagrams of at most `max` bytes. A line longer than
+/// that goes alone.
+fn pack(lines: &[String], max: usize) -> Vec<String> {
+    let mut packets = Vec::new();
+    let mut packet = String::new();
+    for line in lines {
+        if !packet.is_empty() && packet.len() + 1 + line.len() > max {
+            packets.push(std::mem::take(&mut packet));
+        }
+        if !packet.is_empty() {
+            packet.push('\n');
+        }
+        packet.push_str(line);
+    }
+    if !packet.is_empty() {
+        packets.push(packet);
+    }
+    packets
+}
+
+/// Replaces the characters the line format reserves.
+fn sanitize(value: &str) -> String {
+    value.replace([':', '|', '@', '#', ',', '\n'], "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use duende_core::{DaemonId, DaemonMetrics, Platform};
+
+    fn agent() -> (UdpSocket, String) {
+        let agent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        agent
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let address = agent.local_addr().unwrap().to_string();
+        (agent, address)
+    }
+
+    /// Receives `packets` datagrams; returns their lines.
+    fn receive(agent: &UdpSocket, packets: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut buf = vec![0u8; 65536];
+        for _ in 0..packets {
+            let n = agent.recv(&mut buf).unwrap();
+            lines.extend(
+                String::from_utf8_lossy(&buf[..n])
+                    .lines()
+                    .map(str::to_string),
+            );
+        }
+        lines
+    }
+
+    fn daemon(id: DaemonId, metrics: &DaemonMetrics) -> DaemonMetricsSnapshot {
+        DaemonMetricsSnapshot {
+            id,
+            name: "api".to_string(),
+            platform: Platform::Native,
+            running: true,
+            ready: true,
+            restart_count: 0,
+            metrics: Some(metrics.snapshot()),
+        }
+    }
+
+    fn find<'a>(lines: &'a [String], prefix: &str) -> &'a str {
+        lines
+            .iter()
+            .find(|line| line.starts_with(prefix))
+            .unwrap_or_else(|| panic!("no line {prefix} in {lines:?}"))
+    }
+
+    #[test]
+    fn test_pack() {
+        let lines: Vec<String> = ["aaaa", "bbbb", "cccc", "dddddddddddd"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(pack(&lines, 9), ["aaaa\nbbbb", "cccc", "dddddddddddd"]);
+        assert_eq!(pack(&lines, 100).len(), 1);
+        assert!(pack(&[], 100).is_empty());
+    }
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("a:b|c@d#e,f\ng"), "a_b_c_d_e_f_g");
+    }
+
+    #[test]
+    fn test_invalid_address() {
+        assert!(StatsdExporter::new(StatsdConfig::new("not an address")).is_err());
+    }
+
+    #[test]
+    fn test_flush_sends_deltas_and_tags() {
+        let (agent, address) = agent();
+        let exporter = StatsdExporter::new(
+            StatsdConfig::new(address)
+                .with_prefix("edge.")
+                .with_tag("env", "prod"),
+        )
+        .unwrap();
+        let metrics = DaemonMetrics::new();
+        metrics.record_request();
+        metrics.record_request();
+        metrics.record_duration(Duration::from_millis(4));
+        metrics
+            .gauge("queue_depth", "Requests waiting", &[])
+            .unwrap()
+            .set(3.0);
+
+        let id = DaemonId::new();
+        let report = exporter.flush(&[daemon(id, &metrics)]);
+        assert_eq!(report.dropped, 0);
+        let lines = receive(&agent, report.packets);
+        assert_eq!(lines.len(), report.lines);
+
+        let requests = find(&lines, "edge.duende_requests_total:");
+        assert!(requests.starts_with("edge.duende_requests_total:2|c|#"));
+        assert!(requests.contains("daemon:api"));
+        assert!(requests.contains("env:prod"));
+        assert!(find(&lines, "edge.queue_depth:").starts_with("edge.queue_depth:3|g|#"));
+        assert!(find(&lines, "edge.duende_request_duration_seconds_count:").contains(":1|c"));
+        assert!(
+            find(&lines, "edge.duende_request_duration_ms:")
+                .starts_with("edge.duende_request_duration_ms:4|d|@1|#")
+        );
+
+        // Counters send the increase since the last flush
+        metrics.record_request();
+        let report = exporter.flush(&[daemon(id, &metrics)]);
+        let lines = receive(&agent, report.packets);
+        assert!(
+            find(&lines, "edge.duende_requests_total:")
+                .starts_with("edge.duende_requests_total:1|c")
+        );
+        assert!(
+            !lines
+                .iter()
+                .any(|l| l.starts_with("edge.duende_request_duration_ms:"))
+        );
+    }
+
+    #[test]
+    fn test_flush_sends_latency_distribution() {
+        let (agent, address) = agent();
+        let exporter = StatsdExporter::new(StatsdConfig::new(address)).unwrap();
+        let metrics = DaemonMetrics::new();
+        for ms in [4, 4, 20] {
+            metrics.record_duration(Duration::from_millis(ms));
+        }
+
+        let report = exporter.flush(&[daemon(DaemonId::new(), &metrics)]);
+        let lines = receive(&agent, report.packets);
+        let samples: Vec<&str> = lines
+            .iter()
+            .filter_map(|l| l.strip_prefix("duende_request_duration_ms:"))
+            .map(|l| l.split("|#").next().unwrap())
+            .collect();
+        // Two requests in the 4ms bucket, one at the 20ms maximum
+        assert_eq!(samples, ["4.095|d|@0.5", "20|d|@1"]);
+    }
+
+    #[test]
+    fn test_flush_forgets_unreported_series() {
+        let (agent, address) = agent();
+        let exporter = StatsdExporter::new(StatsdConfig::new(address)).unwrap();
+        let metrics = DaemonMetrics::new();
+        metrics.record_request();
+        let id = DaemonId::new();
+
+        let report = exporter.flush(&[daemon(id, &metrics)]);
+        receive(&agent, report.packets);
+        assert!(!exporter.previous.lock().unwrap().is_empty());
+
+        // Unregistered: its series are dropped
+        exporter.flush(&[]);
+        assert!(exporter.previous.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_flush_batches_to_packet_size() {
+        let (agent, address) = agent();
+        let exporter =
+            StatsdExporter::new(StatsdConfig::new(address).with_max_packet_size(512)).unwrap();
+
+        let report = exporter.flush(&[daemon(DaemonId::new(), &DaemonMetrics::new())]);
+        assert!(report.packets > 1);
+        let mut lines = 0;
+        for _ in 0..report.packets {
+            let mut buf = vec![0u8; 65536];
+            let n = agent.recv(&mut buf).unwrap();
+            assert!(n <= 512);
+            lines += String::from_utf8_lossy(&buf[..n]).lines().count();
+        }
+        assert_eq!(lines, report.lines);
+    }
+
+    #[test]
+    fn test_dead_agent_does_not_block() {
+        // Nothing listens on a closed port
+        let address = {
+            let (agent, address) = agent();
+            drop(agent);
+            address
+        };
+        let exporter = StatsdExporter::new(StatsdConfig::new(address)).unwrap();
+
+        let started = std::time::Instant::now();
+        for _ in 0..10 {
+            let report = exporter.flush(&[daemon(DaemonId::new(), &DaemonMetrics::new())]);
+            assert!(report.lines > 0);
+            assert!(report.packets + report.dropped > 0);
+        }
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_spawn_flushes_manager() {
+        let (agent, address) = agent();
+        let manager = Arc::new(DaemonManager::new());
+        let exporter = Arc::new(
+            StatsdExporter::new(
+                StatsdConfig::new(address).with_interval(Duration::from_secs(3600)),
+            )
+            .unwrap(),
+        );
+
+        let task = Arc::clone(&exporter).spawn(manager);
+        // The first tick flushes right away; no daemons, no lines
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        exporter.stop();
+        task.await.unwrap();
+        assert!(!exporter.is_running());
+
+        agent.set_nonblocking(true).unwrap();
+        let mut buf = [0u8; 16];
+        assert!(agent.recv(&mut buf).is_err());
+    }
+}
@@ -5,7 +5,7 @@
 
 use async_trait::async_trait;
 
-use duende_core::{Daemon, DaemonStatus, Signal};
+use duende_core::{Daemon, DaemonConfig, DaemonStatus, Signal};
 
 use crate::detect::Platform;
 use crate::error::Result;
@@ -107,6 +107,19 @@ pub trait PlatformAdapter: Send + Sync {
     /// Returns an error if spawning fails.
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> Result<DaemonHandle>;
 
+    /// Spawns a daemon on this platform using its configuration.
+    ///
+    /// Honors `binary_path`, `args`, `env`, `working_dir`, `user`/`group`
+    /// and `resources` from the config.
+    ///
+    /// # Errors
+    /// Returns an error if spawning fails.
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> Result<DaemonHandle>;
+
     /// Sends a signal to a daemon.
     ///
     /// # Errors
@@ -175,16 +175,22 @@ impl ContainerAdapter {
         args.push("--stop-timeout".to_string());
         args.push(config.shutdown_timeout.as_secs().to_string());
 
-        // Image (use binary path base name or configured image)
-        let image = format!(
-            "{}/{}:latest",
-            self.image_prefix,
-            config
-                .binary_path
-                .file_name()
-                .map_or_else(|| config.name.clone(), |n| n.to_string_lossy().to_string())
-        );
-        args.push(image);
+        // Image (configured image, or derived from binary path base name)
+        if let Some(ref image) = config.platform.container_image {
+            args.push(image.clone());
+            // Generic images need the daemon binary as the command
+            args.push(config.binary_path.display().to_string());
+        } else {
+            let image = format!(
+                "{}/{}:latest",
+                self.image_prefix,
+                config
+                    .binary_path
+                    .file_name()
+                    .map_or_else(|| config.name.clone(), |n| n.to_string_lossy().to_string())
+            );
+            args.push(image);
+        }
 
         // Command arguments
         args.extend(config.args.iter().cloned());
@@ -250,7 +256,15 @@ impl PlatformAdapter for ContainerAdapter {
     }
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> Result<DaemonHandle> {
-        let config = DaemonConfig::new(daemon.name(), "/bin/daemon");
+        let config = DaemonConfig::new(daemon.name(), "/bin/daemon"); // Placeholder
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> Result<DaemonHandle> {
         let container_name = Self::container_name(daemon.name());
         let cli = self.runtime.cli_command();
 
@@ -263,7 +277,7 @@ impl PlatformAdapter for ContainerAdapter {
             .await;
 
         // Build and run container
-        let args = self.build_run_args(&config);
+        let args = self.build_run_args(config);
         let output = Command::new(cli)
             .args(&args)
             .stdout(Stdio::piped())
@@ -444,6 +458,22 @@ mod tests {
         assert!(args.contains(&"50".to_string()));
     }
 
+    #[test]
+    fn test_build_run_args_with_image() {
+        let adapter = ContainerAdapter::new();
+        let mut config = DaemonConfig::new("api", "/usr/bin/api-server");
+        config.args = vec!["--port".to_string(), "8080".to_string()];
+        config.platform.container_image = Some("alpine:3.20".to_string());
+
+        let args = adapter.build_run_args(&config);
+
+        let image = args.iter().position(|a| a == "alpine:3.20").unwrap();
+        assert_eq!(
+            &args[image + 1..],
+            ["/usr/bin/api-server", "--port", "8080"]
+        );
+    }
+
     #[test]
     fn test_build_run_args_with_env() {
         let adapter = ContainerAdapter::new();
@@ -113,6 +113,12 @@ impl LinuxAdapter {
         if resources.pids_max > 0 {
             unit.push_str(&format!("TasksMax={}\n", resources.pids_max));
         }
+        if resources.open_files_max > 0 {
+            unit.push_str(&format!("LimitNOFILE={}\n", resources.open_files_max));
+        }
+        if resources.lock_memory {
+            unit.push_str("LimitMEMLOCK=infinity\n");
+        }
 
         // Restart policy
         let restart_directive = match &config.restart {
@@ -185,11 +191,19 @@ impl PlatformAdapter for LinuxAdapter {
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> Result<DaemonHandle> {
         let config = DaemonConfig::new(daemon.name(), "/bin/false"); // Placeholder
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> Result<DaemonHandle> {
         let unit_name = Self::unit_name(daemon.name());
         let unit_path = self.unit_dir.join(&unit_name);
 
         // Generate unit file
-        let unit_content = self.generate_unit_file(&config);
+        let unit_content = self.generate_unit_file(config);
 
         // Write unit file
         tokio::fs::write(&unit_path, &unit_content)
@@ -401,11 +415,14 @@ mod tests {
         let mut config = DaemonConfig::new("resource-daemon", "/usr/bin/test");
         config.resources.memory_bytes = 1024 * 1024 * 512; // 512MB
         config.resources.pids_max = 50;
+        config.resources.lock_memory = true;
 
         let unit = adapter.generate_unit_file(&config);
 
         assert!(unit.contains("MemoryMax="));
         assert!(unit.contains("TasksMax=50"));
+        assert!(unit.contains("LimitNOFILE=1024"));
+        assert!(unit.contains("LimitMEMLOCK=infinity"));
     }
 
     #[test]
@@ -233,11 +233,19 @@ impl PlatformAdapter for MacOSAdapter {
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> Result<DaemonHandle> {
         let config = DaemonConfig::new(daemon.name(), "/bin/false"); // Placeholder
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> Result<DaemonHandle> {
         let label = Self::service_label(daemon.name());
         let plist_path = self.daemons_dir.join(Self::plist_filename(daemon.name()));
 
         // Generate plist
-        let plist_content = self.generate_plist(&config);
+        let plist_content = self.generate_plist(config);
 
         // Write plist file
         tokio::fs::write(&plist_path, &plist_content)
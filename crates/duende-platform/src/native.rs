@@ -6,12 +6,14 @@ use async_trait::async_trait;
 use std::process::Stdio;
 use tokio::process::Command;
 
-use duende_core::{Daemon, DaemonStatus, Signal};
+use duende_core::{Daemon, DaemonConfig, DaemonStatus, Signal};
 
 use crate::adapter::{DaemonHandle, PlatformAdapter, TracerHandle};
 use crate::detect::Platform;
 use crate::error::{PlatformError, Result};
 
+#[cfg(unix)]
+use duende_core::adapters::{apply_credentials, apply_resource_limits};
 #[cfg(unix)]
 use nix::sys::signal::{Signal as NixSignal, kill as nix_kill};
 #[cfg(unix)]
@@ -46,8 +48,15 @@ impl PlatformAdapter for NativeAdapter {
     }
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> Result<DaemonHandle> {
-        let config = duende_core::DaemonConfig::new(daemon.name(), "/bin/sh");
+        let config = DaemonConfig::new(daemon.name(), "/bin/sh");
+        self.spawn_with_config(daemon, &config).await
+    }
 
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> Result<DaemonHandle> {
         // Build command
         let mut cmd = Command::new(&config.binary_path);
         cmd.args(&config.args)
@@ -60,6 +69,12 @@ impl PlatformAdapter for NativeAdapter {
             cmd.current_dir(cwd);
         }
 
+        #[cfg(unix)]
+        {
+            apply_credentials(&mut cmd, config).map_err(|e| PlatformError::spawn(e.to_string()))?;
+            apply_resource_limits(&mut cmd, &config.resources);
+        }
+
         // Spawn process
         let child = cmd
             .spawn()
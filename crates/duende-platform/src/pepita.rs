@@ -137,7 +137,9 @@ impl PepitaAdapter {
         args.push(Self::vm_name(&config.name));
 
         // vCPUs
-        let vcpus = if config.resources.cpu_quota_percent > 0.0 {
+        let vcpus = if let Some(vcpus) = config.platform.vcpus {
+            vcpus.max(1)
+        } else if config.resources.cpu_quota_percent > 0.0 {
             // Map CPU quota to vCPU count (rough approximation)
             #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
             let v = (config.resources.cpu_quota_percent / 100.0).ceil() as u32;
@@ -157,14 +159,24 @@ impl PepitaAdapter {
         args.push("--memory".to_string());
         args.push(format!("{}M", memory_mb));
 
-        // Kernel
-        if let Some(ref kernel) = self.kernel_path {
+        // Kernel (per-daemon config overrides adapter default)
+        if let Some(kernel) = config
+            .platform
+            .kernel_path
+            .as_ref()
+            .or(self.kernel_path.as_ref())
+        {
             args.push("--kernel".to_string());
             args.push(kernel.display().to_string());
         }
 
         // Root filesystem
-        if let Some(ref rootfs) = self.rootfs_path {
+        if let Some(rootfs) = config
+            .platform
+            .rootfs_path
+            .as_ref()
+            .or(self.rootfs_path.as_ref())
+        {
             args.push("--rootfs".to_string());
             args.push(rootfs.display().to_string());
         }
@@ -173,6 +185,16 @@ impl PepitaAdapter {
         args.push("--vsock".to_string());
         args.push(format!("cid={},port={}", cid, self.vsock_port));
 
+        // Environment and working directory inside the guest
+        for (key, value) in &config.env {
+            args.push("--env".to_string());
+            args.push(format!("{}={}", key, value));
+        }
+        if let Some(ref working_dir) = config.working_dir {
+            args.push("--workdir".to_string());
+            args.push(working_dir.display().to_string());
+        }
+
         // Binary to run inside VM
         args.push("--exec".to_string());
         args.push(config.binary_path.display().to_string());
@@ -228,12 +250,20 @@ impl PlatformAdapter for PepitaAdapter {
     }
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> Result<DaemonHandle> {
-        let config = DaemonConfig::new(daemon.name(), "/bin/daemon");
+        let config = DaemonConfig::new(daemon.name(), "/bin/daemon"); // Placeholder
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> Result<DaemonHandle> {
         let cid = Self::allocate_cid();
         let vm_name = Self::vm_name(daemon.name());
 
         // Build and run VM
-        let args = self.build_run_args(&config, cid);
+        let args = self.build_run_args(config, cid);
         let output = Command::new(&self.pepita_path)
             .args(&args)
             .stdout(Stdio::piped())
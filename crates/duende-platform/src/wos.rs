@@ -268,12 +268,22 @@ impl PlatformAdapter for WosAdapter {
     }
 
     async fn spawn(&self, daemon: Box<dyn Daemon>) -> Result<DaemonHandle> {
-        let config = DaemonConfig::new(daemon.name(), "/wasm/daemon.wasm");
+        let config = DaemonConfig::new(daemon.name(), "/wasm/daemon.wasm"); // Placeholder
+        self.spawn_with_config(daemon, &config).await
+    }
+
+    async fn spawn_with_config(
+        &self,
+        daemon: Box<dyn Daemon>,
+        config: &DaemonConfig,
+    ) -> Result<DaemonHandle> {
         let pid = Self::allocate_pid();
 
-        // Determine priority
-        let priority = if config.resources.cpu_quota_percent > 0.0 {
-            Self::config_to_priority(&config)
+        // Determine priority (explicit platform priority wins over CPU quota)
+        let priority = if let Some(level) = config.platform.priority {
+            Priority::from_u8(level)
+        } else if config.resources.cpu_quota_percent > 0.0 {
+            Self::config_to_priority(config)
         } else {
             self.default_priority
         };
@@ -10,6 +10,9 @@ use std::sync::RwLock;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
+use duende_core::rates::ERROR_WINDOW_SECS;
+use duende_core::{DaemonMetrics, WindowStats};
+
 /// Circuit breaker state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CircuitState {
@@ -40,6 +43,9 @@ pub struct CircuitBreaker {
     recovery_timeout: Duration,
     /// Time when circuit opened.
     opened_at: RwLock<Option<Instant>>,
+    /// Recent error rate that opens the circuit, and the minimum number
+    /// of requests in the window for it to count.
+    error_rate_threshold: Option<(f64, u64)>,
 }
 
 impl CircuitBreaker {
@@ -57,9 +63,63 @@ impl CircuitBreaker {
             success_count: AtomicU64::new(0),
             recovery_timeout,
             opened_at: RwLock::new(None),
+            error_rate_threshold: None,
         }
     }
 
+    /// Also opens the circuit when the recent error rate reaches
+    /// `max_error_rate` (0.0 to 1.0) over at least `min_requests`
+    /// requests; see [`check_metrics`](Self::check_metrics).
+    #[must_use]
+    pub const fn with_error_rate_threshold(
+        mut self,
+        max_error_rate: f64,
+        min_requests: u64,
+    ) -> Self {
+        self.error_rate_threshold = Some((max_error_rate, min_requests));
+        self
+    }
+
+    /// Opens the circuit if the last minute of `metrics` breaches the
+    /// error rate threshold, counting the trip in `metrics`.
+    ///
+    /// Returns the resulting state.
+    pub fn check_metrics(&self, metrics: &DaemonMetrics) -> CircuitState {
+        let window = metrics.recent(Duration::from_secs(ERROR_WINDOW_SECS));
+        if self.check_window(window) {
+            metrics.record_circuit_breaker_trip();
+        }
+        self.state()
+    }
+
+    /// Opens a closed circuit if `window` breaches the error rate
+    /// threshold. Returns true if this opened the circuit.
+    pub fn check_window(&self, window: WindowStats) -> bool {
+        let Some((max_error_rate, min_requests)) = self.error_rate_threshold else {
+            return false;
+        };
+        if window.requests < min_requests.max(1) || window.error_rate() < max_error_rate {
+            return false;
+        }
+
+        // Check and open under one lock, so only one caller trips it
+        let mut state = self
+            .state
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if *state != CircuitState::Closed {
+            return false;
+        }
+
+        tracing::warn!(
+            error_rate = window.error_rate(),
+            requests = window.requests,
+            "error rate threshold breached"
+        );
+        self.open_locked(&mut state);
+        true
+    }
+
     /// Returns the current circuit state.
     #[must_use]
     pub fn state(&self) -> CircuitState {
@@ -151,13 +211,16 @@ impl CircuitBreaker {
 
     /// Opens the circuit.
     fn open(&self) {
-        {
-            let mut state = self
-                .state
-                .write()
-                .unwrap_or_else(std::sync::PoisonError::into_inner);
-            *state = CircuitState::Open;
-        }
+        let mut state = self
+            .state
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        self.open_locked(&mut state);
+    }
+
+    /// Opens the circuit while holding the state lock.
+    fn open_locked(&self, state: &mut CircuitState) {
+        *state = CircuitState::Open;
 
         {
             let mut opened_at = self
@@ -499,6 +562,68 @@ mod tests {
         breaker.record_failure();
         assert_eq!(breaker.state(), CircuitState::Open);
     }
+
+    #[test]
+    fn test_error_rate_threshold_opens_circuit() {
+        let breaker = CircuitBreaker::new(u32::MAX, Duration::from_secs(30))
+            .with_error_rate_threshold(0.5, 10);
+        let window = |requests, errors| WindowStats { requests, errors };
+
+        // Too few requests to judge, then below the threshold
+        assert!(!breaker.check_window(window(4, 4)));
+        assert!(!breaker.check_window(window(20, 9)));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        assert!(breaker.check_window(window(20, 10)));
+        assert_eq!(breaker.state(), CircuitState::Open);
+        // Already open
+        assert!(!breaker.check_window(window(20, 20)));
+    }
+
+    #[test]
+    fn test_check_metrics_uses_recent_error_rate() {
+        let metrics = DaemonMetrics::new();
+        let breaker = CircuitBreaker::new(u32::MAX, Duration::from_secs(30))
+            .with_error_rate_threshold(0.2, 5);
+
+        for _ in 0..10 {
+            metrics.record_request();
+        }
+        assert_eq!(breaker.check_metrics(&metrics), CircuitState::Closed);
+
+        for _ in 0..3 {
+            metrics.record_error();
+        }
+        assert_eq!(breaker.check_metrics(&metrics), CircuitState::Open);
+        assert_eq!(metrics.circuit_breaker_trips(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_checks_trip_once() {
+        let metrics = DaemonMetrics::new();
+        let breaker = CircuitBreaker::new(u32::MAX, Duration::from_secs(30))
+            .with_error_rate_threshold(0.5, 1);
+        metrics.record_request();
+        metrics.record_error();
+
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| breaker.check_metrics(&metrics));
+            }
+        });
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(metrics.circuit_breaker_trips(), 1);
+    }
+
+    #[test]
+    fn test_no_error_rate_threshold_by_default() {
+        let breaker = CircuitBreaker::default();
+        assert!(!breaker.check_window(WindowStats {
+            requests: 100,
+            errors: 100
+        }));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
 }
 
 // Property-based tests
@@ -3,6 +3,9 @@
 //! "Stop to fix problems, to get quality right the first time."
 //! — Taiichi Ohno, Toyota Production System (1988)
 
+use std::time::Duration;
+
+use duende_core::DaemonMetrics;
 use serde::{Deserialize, Serialize};
 
 /// Jidoka gate for stop-on-error automation.
@@ -103,6 +106,70 @@ pub trait JidokaCheck: Send + Sync {
     fn name(&self) -> &str;
 }
 
+/// Stops the line when a daemon's recent error rate is too high.
+///
+/// Reads the sliding error window of [`DaemonMetrics`], so a burst is
+/// caught however long the daemon has been up. The evidence is unused.
+#[derive(Debug, Clone)]
+pub struct ErrorRateCheck {
+    metrics: DaemonMetrics,
+    max_error_rate: f64,
+    min_requests: u64,
+    window: Duration,
+}
+
+impl ErrorRateCheck {
+    /// Creates a check that fails once the error rate of the last minute
+    /// reaches `max_error_rate` (0.0 to 1.0).
+    #[must_use]
+    pub const fn new(metrics: DaemonMetrics, max_error_rate: f64) -> Self {
+        Self {
+            metrics,
+            max_error_rate,
+            min_requests: 1,
+            window: Duration::from_mins(1),
+        }
+    }
+
+    /// Sets the minimum number of requests in the window to judge.
+    #[must_use]
+    pub const fn with_min_requests(mut self, min_requests: u64) -> Self {
+        self.min_requests = min_requests;
+        self
+    }
+
+    /// Sets the window, at most a minute.
+    #[must_use]
+    pub const fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+}
+
+impl JidokaCheck for ErrorRateCheck {
+    fn verify(&self, _: &Evidence) -> Option<JidokaViolation> {
+        let stats = self.metrics.recent(self.window);
+        if stats.requests < self.min_requests.max(1) || stats.error_rate() < self.max_error_rate {
+            return None;
+        }
+
+        Some(JidokaViolation {
+            check_name: self.name().to_string(),
+            kind: ViolationKind::Invariant,
+            description: format!(
+                "error rate {:.1}% over {} requests exceeds {:.1}%",
+                stats.error_rate() * 100.0,
+                stats.requests,
+                self.max_error_rate * 100.0
+            ),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "error_rate"
+    }
+}
+
 /// Evidence for Jidoka checks.
 #[derive(Debug, Clone, Default)]
 pub struct Evidence {
@@ -447,4 +514,28 @@ mod tests {
         let count = recommendation.matches("Review invariant").count();
         assert_eq!(count, 1, "Should deduplicate recommendations");
     }
+
+    #[test]
+    fn test_error_rate_check() {
+        let metrics = DaemonMetrics::new();
+        let check = ErrorRateCheck::new(metrics.clone(), 0.5).with_min_requests(4);
+        let evidence = Evidence::new();
+
+        metrics.record_request();
+        metrics.record_error();
+        // Too few requests to judge
+        assert!(check.verify(&evidence).is_none());
+
+        for _ in 0..3 {
+            metrics.record_request();
+        }
+        metrics.record_error();
+        let violation = check.verify(&evidence).unwrap();
+        assert_eq!(violation.check_name, "error_rate");
+        assert_eq!(violation.kind, ViolationKind::Invariant);
+
+        let mut gate = JidokaGate::new(true);
+        gate.add_check(check);
+        assert!(!gate.check(&evidence).passed());
+    }
 }
@@ -45,6 +45,7 @@ pub use circuit_breaker::{CircuitBreaker, CircuitState};
 pub use error::{PolicyError, Result};
 pub use gate::{GateConfig, GateResult, QualityAnalysis, QualityGate, QualityViolation};
 pub use jidoka::{
-    CheckItem, Evidence, JidokaCheck, JidokaGate, JidokaResult, JidokaViolation, ViolationKind,
+    CheckItem, ErrorRateCheck, Evidence, JidokaCheck, JidokaGate, JidokaResult, JidokaViolation,
+    ViolationKind,
 };
 pub use limiter::{ResourceLimiter, ResourceLimits};
@@ -37,9 +37,9 @@ use std::time::{Duration, Instant};
 use async_trait::async_trait;
 use duende_core::{
     Daemon, DaemonConfig, DaemonContext, DaemonId, DaemonMetrics, ExitReason, HealthStatus,
+    SignalBridge,
 };
 use duende_platform::{MlockResult, is_memory_locked, lock_daemon_memory};
-use tokio::signal;
 
 /// Example counter daemon that increments a counter every second.
 struct CounterDaemon {
@@ -109,14 +109,14 @@ impl Daemon for CounterDaemon {
                 if is_memory_locked() { "YES" } else { "NO" }
             );
 
-            // Check for signals
-            if let Some(sig) = ctx.try_recv_signal() {
-                println!("[RUN] Received signal: {:?}", sig);
-                break;
+            // Wait one second, waking early on signals or shutdown
+            tokio::select! {
+                () = tokio::time::sleep(Duration::from_secs(1)) => {}
+                Some(sig) = ctx.recv_signal() => {
+                    // TERM/INT/QUIT cancel the shutdown token; others are informational
+                    println!("[RUN] Received signal: {:?}", sig);
+                }
             }
-
-            // Sleep for 1 second
-            tokio::time::sleep(Duration::from_secs(1)).await;
         }
 
         println!("[RUN] Main loop exiting");
@@ -216,14 +216,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create context (returns context and handle for signaling)
     let (mut ctx, handle) = DaemonContext::new(config.clone());
 
-    // Setup Ctrl+C handler
-    let handle_clone = handle.clone();
-    tokio::spawn(async move {
-        signal::ctrl_c().await.expect("Failed to listen for Ctrl+C");
-        println!();
-        println!("[SIGNAL] Ctrl+C received, initiating shutdown...");
-        let _ = handle_clone.shutdown().await;
-    });
+    // Forward OS signals (Ctrl+C, SIGTERM, SIGHUP, ...) into the context
+    let _signal_guard = SignalBridge::new().install(&handle)?;
 
     // Initialize daemon
     daemon.init(&config).await?;